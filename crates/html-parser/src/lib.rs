@@ -1,5 +1,6 @@
 #![deny(warnings)]
 use rphtml::config::ParseOptions;
+use rphtml::entity;
 use rphtml::parser::Doc;
 use rphtml::parser::NodeType;
 use rphtml::types::BoxDynError;
@@ -38,18 +39,22 @@ pub fn parse_html<MSG>(html: &str) -> Result<Option<Node<MSG>>, ParseError> {
             auto_fix_unclosed_tag: true,
             auto_fix_unexpected_endtag: true,
             auto_fix_unescaped_lt: true,
+            ..Default::default()
         },
     )?;
     process_node(doc.get_root_node().borrow().deref())
 }
 
+/// elements where the text content is taken as is, html entities are not decoded
+const RAW_TEXT_ELEMENTS: [&str; 2] = ["script", "style"];
+
+/// decode the html entities such as `&amp;` and `&lt;` back into the characters they represent
+fn decode_html_entities(chars: &[char]) -> String {
+    String::from_iter(entity::decode_chars(chars).iter())
+}
+
 fn process_node<MSG>(node: &rphtml::parser::Node) -> Result<Option<Node<MSG>>, ParseError> {
-    let content = if let Some(content) = &node.content {
-        let content = String::from_iter(content.iter());
-        Some(content)
-    } else {
-        None
-    };
+    let content = node.content.as_deref();
 
     let mut child_nodes = if let Some(childs) = &node.childs {
         childs
@@ -66,6 +71,15 @@ fn process_node<MSG>(node: &rphtml::parser::Node) -> Result<Option<Node<MSG>>, P
             let tag_name = String::from_iter(tag.borrow().name.iter());
             if let Some(html_tag) = lookup::match_tag(&tag_name) {
                 let is_self_closing = HTML_SC_TAGS.contains(&html_tag);
+                // the content of script, style, title and textarea are not parsed as child nodes
+                if let Some(content) = content.filter(|content| !content.is_empty()) {
+                    let content = if RAW_TEXT_ELEMENTS.contains(&html_tag) {
+                        String::from_iter(content.iter())
+                    } else {
+                        decode_html_entities(content)
+                    };
+                    child_nodes.push(text(content));
+                }
                 let attributes: Vec<Attribute<MSG>> = tag
                     .borrow()
                     .attrs
@@ -77,7 +91,7 @@ fn process_node<MSG>(node: &rphtml::parser::Node) -> Result<Option<Node<MSG>>, P
                                 let key = String::from_iter(key.content.iter());
                                 if let Some(attr_key) = lookup::match_attribute(&key) {
                                     let value = if let Some(value) = &attr.value {
-                                        let value = String::from_iter(value.content.iter());
+                                        let value = decode_html_entities(&value.content);
                                         AttributeValue::Simple(Value::from(value))
                                     } else {
                                         AttributeValue::Empty
@@ -106,7 +120,7 @@ fn process_node<MSG>(node: &rphtml::parser::Node) -> Result<Option<Node<MSG>>, P
         }
         NodeType::Text => {
             let content = content.expect("must have a content");
-            Ok(Some(text(content)))
+            Ok(Some(text(decode_html_entities(content))))
         }
         NodeType::AbstractRoot => {
             let child_nodes_len = child_nodes.len();
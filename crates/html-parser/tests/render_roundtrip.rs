@@ -0,0 +1,126 @@
+use sauron_core::{
+    html::{attributes::*, tags, *},
+    prelude::Render,
+    vdom::{Node, NodeTrait},
+};
+use sauron_html_parser::parse_html;
+
+/// render the node and parse it back
+fn round_trip(node: &Node<()>) -> Node<()> {
+    let html = node.render_to_string();
+    parse_html(&html).ok().flatten().expect("must parse")
+}
+
+#[test]
+fn text_with_special_characters() {
+    let node: Node<()> = p(vec![], vec![text(r#"1 < 2 && 3 > 2, "quoted" & 'single'"#)]);
+    assert_eq!(node, round_trip(&node));
+}
+
+#[test]
+fn text_that_looks_like_html() {
+    let user_input = r#"<img src=x onerror="alert(1)"><b>bold</b>"#;
+    let node: Node<()> = div(vec![], vec![text(user_input)]);
+    let parsed = round_trip(&node);
+    assert_eq!(node, parsed);
+    assert_eq!(parsed.children().len(), 1);
+    assert_eq!(parsed.children()[0].as_text(), Some(user_input));
+}
+
+#[test]
+fn attribute_values_with_quotes_and_ampersand() {
+    let node: Node<()> = a(
+        vec![
+            href("/search?q=rust&lang=en"),
+            attributes::title(r#"say "hi" & <bye>"#),
+        ],
+        vec![text("link")],
+    );
+    assert_eq!(node, round_trip(&node));
+}
+
+#[test]
+fn attribute_value_can_not_inject_attributes() {
+    let user_input = r#"x" onclick="alert(1)"#;
+    let node: Node<()> = input(vec![value(user_input)], vec![]);
+    let parsed = round_trip(&node);
+    assert_eq!(node, parsed);
+    assert_eq!(parsed.attributes().map(|attrs| attrs.len()), Some(1));
+}
+
+#[test]
+fn text_siblings_are_kept_separate() {
+    let node: Node<()> = div(vec![], vec![text("a < b"), text("b & c")]);
+    assert_eq!(node, round_trip(&node));
+}
+
+#[test]
+fn nested_elements() {
+    let node: Node<()> = article(
+        vec![class("post")],
+        vec![
+            h1(vec![], vec![text("Tom & Jerry")]),
+            p(vec![id("intro")], vec![text("<<cat>> vs <<mouse>>")]),
+            ul(
+                vec![],
+                vec![
+                    li(vec![], vec![text("1 > 0")]),
+                    li(vec![], vec![text("\"quoted\"")]),
+                ],
+            ),
+        ],
+    );
+    assert_eq!(node, round_trip(&node));
+}
+
+#[test]
+fn script_content_is_kept_as_is() {
+    let code = r#"if (a < b && c > d) { console.log("done") }"#;
+    let node: Node<()> = script(vec![], vec![text(code)]);
+    assert_eq!(node, round_trip(&node));
+}
+
+#[test]
+fn script_content_can_not_close_the_script_element() {
+    let node: Node<()> = div(
+        vec![],
+        vec![
+            script(vec![], vec![text(r#"let s = "</script><b>injected</b>";"#)]),
+            span(vec![], vec![text("after")]),
+        ],
+    );
+    let parsed = round_trip(&node);
+    let children = parsed.children();
+    assert_eq!(children.len(), 2);
+    assert_eq!(children[0].tag(), Some(&"script"));
+    assert_eq!(
+        children[0].children()[0].as_text(),
+        Some(r#"let s = "<\/script><b>injected</b>";"#)
+    );
+    assert_eq!(children[1], span(vec![], vec![text("after")]));
+}
+
+#[test]
+fn style_content_is_kept_as_is() {
+    let node: Node<()> = tags::style(vec![], vec![text("main > .card { content: \"&\"; }")]);
+    assert_eq!(node, round_trip(&node));
+}
+
+#[test]
+fn comment_can_not_close_itself() {
+    let node: Node<()> = div(
+        vec![],
+        vec![
+            comment("--><b>injected</b><!--"),
+            span(vec![], vec![text("after")]),
+        ],
+    );
+    let parsed = round_trip(&node);
+    assert_eq!(parsed, div(vec![], vec![span(vec![], vec![text("after")])]));
+}
+
+#[test]
+fn textarea_content_is_decoded() {
+    let node: Node<()> = textarea(vec![], vec![text("a < b & </textarea>")]);
+    assert_eq!(node, round_trip(&node));
+}
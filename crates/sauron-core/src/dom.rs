@@ -2,15 +2,27 @@
 //! manipulating the actual Document Object Model in the browser
 
 pub use callback::Callback;
-pub use component::{Component, Container};
+pub use component::{Component, Container, StatefulComponent};
 pub use effects::Effects;
+#[cfg(feature = "with-dom")]
+pub use http::Fetch;
+pub use http::{Http, HttpError, Method, MockTransport, Request, Response, Transport};
 pub use modifier::Modifier;
-pub use task::Task;
+#[cfg(all(feature = "with-dom", feature = "with-serde"))]
+pub use persist::{IndexedDb, WebStorage};
+#[cfg(feature = "with-serde")]
+pub use persist::{MemoryStorage, PersistError, Persistence, Storage};
+pub use router::{Params, RoutePattern, Router};
+pub use task::{AbortHandle, Task};
 
 mod callback;
 mod component;
 mod effects;
+mod http;
 mod modifier;
+#[cfg(feature = "with-serde")]
+mod persist;
+pub mod router;
 mod task;
 
 use cfg_if::cfg_if;
@@ -19,9 +31,13 @@ cfg_if! {if #[cfg(feature = "with-dom")] {
     pub use application::{Application, Measurements};
     #[cfg(feature = "custom_element")]
     pub use web_component::{register_web_component, WebComponent, WebComponentWrapper};
+    pub use backend::{
+        DomBackend, MemoryCallbackHandle, MemoryDom, MemoryListener, MemoryNode,
+        WebCallbackHandle, WebDom,
+    };
     pub use dom_patch::{DomPatch, PatchVariant};
-    pub use http::Http;
     pub use program::{MountAction, MountTarget, Program};
+    pub use hydration::HydrationMismatch;
     pub use util::{
         document, history, now, performance,
         spawn_local, window, inject_style,
@@ -29,18 +45,39 @@ cfg_if! {if #[cfg(feature = "with-dom")] {
     pub use raf::{request_animation_frame, AnimationFrameHandle};
     pub use ric::{request_idle_callback, IdleCallbackHandle, IdleDeadline};
     pub use timeout::{delay, request_timeout_callback, TimeoutCallbackHandle};
-    pub use cmd::Cmd;
+    pub use cmd::{Cmd, MsgCmd};
+    pub use scheduler::Priority;
+    pub use sub::Sub;
+    pub use events::SyntheticEvent;
+    pub use test_program::TestProgram;
+    #[cfg(feature = "with-time-travel")]
+    pub use time_travel::{RecordedMsg, Session};
+    #[cfg(feature = "with-inspector")]
+    pub use inspector::{InspectedNode, Inspector, InspectorMsg, Rect};
     use crate::dom::events::MountEvent;
 
     mod application;
+    mod backend;
     pub mod cmd;
+    mod scheduler;
+    mod sub;
     mod dom_node;
     #[cfg(feature = "custom_element")]
     mod web_component;
     mod dom_patch;
+    #[cfg(feature = "with-event-delegation")]
+    mod event_delegation;
     pub mod events;
-    mod http;
+    #[cfg(feature = "with-serde")]
+    pub mod live;
     mod program;
+    mod hydration;
+    mod headless;
+    mod test_program;
+    #[cfg(feature = "with-time-travel")]
+    mod time_travel;
+    #[cfg(feature = "with-inspector")]
+    mod inspector;
     pub mod util;
     mod raf;
     mod ric;
@@ -55,6 +92,8 @@ cfg_if! {if #[cfg(feature = "with-dom")] {
         WebEvent(web_sys::Event),
         /// custom event here follows
         MountEvent(MountEvent),
+        /// event fired without the browser
+        Synthetic(SyntheticEvent),
     }
 
 }}
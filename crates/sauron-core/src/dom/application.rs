@@ -1,5 +1,6 @@
-use crate::dom::Cmd;
+use crate::dom::{Cmd, Sub};
 use crate::vdom::Node;
+use crate::Error;
 
 /// An Application is the root component of your program.
 /// Everything that happens in your application is done here.
@@ -28,6 +29,21 @@ where
     /// Returns a node on how the component is presented.
     fn view(&self) -> Node<MSG>;
 
+    /// The long-lived event sources the application is subscribed to.
+    /// This is called after every update, the listeners are attached and detached as the
+    /// subscriptions change.
+    fn subscriptions(&self) -> Sub<MSG> {
+        Sub::none()
+    }
+
+    /// Called when the runtime fails, such as an attribute which can not be set or a patch which
+    /// can not be applied. The returned MSG is dispatched into the app, such that it can recover
+    /// or show a message. The default logs the error.
+    fn on_error(&self, error: Error) -> Option<MSG> {
+        log::error!("{error}");
+        None
+    }
+
     /// The css style for the application, will be mounted automatically by the program
     fn stylesheet() -> Vec<String> {
         vec![]
@@ -54,6 +70,7 @@ where
 /// Contains the time it took for the last app update call for the component
 /// TODO: Maybe rename to Diagnostics
 #[derive(Clone, Debug, PartialEq, Default)]
+#[cfg_attr(feature = "with-serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Measurements {
     /// The application can name this measurement to determine where this measurement is coming
     /// from.
@@ -64,6 +81,9 @@ pub struct Measurements {
     pub build_view_took: f64,
     /// Total number of patches applied on this update loop
     pub total_patches: usize,
+    /// The number of msgs which are updated into the app before this render
+    #[cfg_attr(feature = "with-serde", serde(default))]
+    pub msg_count: usize,
     /// Time it took for the patching the DOM.
     pub dom_update_took: f64,
     /// Total time it took for the component dispatch
@@ -0,0 +1,674 @@
+//! The DOM operations used by the [`Program`], behind the [`DomBackend`] trait.
+//!
+//! The [`WebDom`] is the DOM of the browser, which is the default backend of the `Program`.
+//! The [`MemoryDom`] is a document kept in memory, such that the program can be mounted,
+//! updated and patched with plain `cargo test`, without a browser.
+//!
+//! [`Program`]: crate::dom::Program
+use crate::{
+    dom::{
+        document,
+        dom_node::{add_event_listener_options, intern},
+        now, request_animation_frame, request_idle_callback, spawn_local, AnimationFrameHandle,
+        Event, IdleCallbackHandle, IdleDeadline,
+    },
+    html::attributes::ListenerOptions,
+    html::attributes::Value,
+    Error,
+};
+use futures::future::LocalBoxFuture;
+use std::{any::Any, collections::HashMap, fmt, rc::Rc};
+use wasm_bindgen::{closure::Closure, JsCast};
+use web_sys::{
+    Element, HtmlButtonElement, HtmlDataElement, HtmlDetailsElement, HtmlFieldSetElement,
+    HtmlInputElement, HtmlLiElement, HtmlLinkElement, HtmlMeterElement, HtmlOptGroupElement,
+    HtmlOptionElement, HtmlOutputElement, HtmlParamElement, HtmlProgressElement, HtmlSelectElement,
+    HtmlStyleElement, HtmlTextAreaElement, Node,
+};
+
+pub use memory::{MemoryCallbackHandle, MemoryDom, MemoryListener, MemoryNode};
+
+mod memory;
+
+/// The DOM operations which the [`Program`] uses to mount the view, apply the patches, attach
+/// the event listeners and schedule the updates.
+///
+/// The nodes are handles to the nodes in the document, cloning a node clones the handle.
+///
+/// [`Program`]: crate::dom::Program
+pub trait DomBackend: Clone + 'static {
+    /// a handle to a node of the document
+    type Node: Clone + PartialEq + fmt::Debug + 'static;
+    /// an event listener attached to a node, which is kept until it is removed
+    type Listener: 'static;
+    /// a scheduled callback, the callback is cancelled when its handle is dropped
+    type CallbackHandle: 'static;
+
+    /// create an element with the tag, in the namespace if there is one
+    fn create_element(
+        &self,
+        tag: &'static str,
+        namespace: Option<&'static str>,
+    ) -> Result<Self::Node, Error>;
+
+    /// create a text node
+    fn create_text_node(&self, text: &str) -> Self::Node;
+
+    /// create a comment node
+    fn create_comment(&self, comment: &str) -> Self::Node;
+
+    /// create a document fragment, its children are moved into the parent it is inserted to
+    fn create_document_fragment(&self) -> Self::Node;
+
+    /// the head of the document, where the styles are injected into
+    fn head(&self) -> Option<Self::Node>;
+
+    /// returns true if the node is an element
+    fn is_element(&self, node: &Self::Node) -> bool;
+
+    /// returns true if the node is a document fragment
+    fn is_document_fragment(&self, node: &Self::Node) -> bool;
+
+    /// the tag of the element in lowercase, `None` if the node is not an element
+    fn tag_name(&self, node: &Self::Node) -> Option<String>;
+
+    /// returns true if the node is a text node
+    fn is_text(&self, node: &Self::Node) -> bool;
+
+    /// returns true if the node is a comment node
+    fn is_comment(&self, node: &Self::Node) -> bool;
+
+    /// the text of a text node or a comment node, `None` for the other nodes
+    fn node_value(&self, node: &Self::Node) -> Option<String>;
+
+    /// set the text of a text node or a comment node
+    fn set_node_value(&self, node: &Self::Node, value: &str);
+
+    /// the parent of the node
+    fn parent_node(&self, node: &Self::Node) -> Option<Self::Node>;
+
+    /// the children of the node, including the text and comment nodes
+    fn child_nodes(&self, node: &Self::Node) -> Vec<Self::Node>;
+
+    /// the child of the node at the index
+    fn child_node(&self, node: &Self::Node, index: usize) -> Option<Self::Node> {
+        self.child_nodes(node).get(index).cloned()
+    }
+
+    /// the node after the node in its parent
+    fn next_sibling(&self, node: &Self::Node) -> Option<Self::Node> {
+        let siblings = self.child_nodes(&self.parent_node(node)?);
+        let index = siblings.iter().position(|sibling| sibling == node)?;
+        siblings.get(index + 1).cloned()
+    }
+
+    /// find the node at the path, which are the indexes of the children from the root
+    fn find_node(&self, root: &Self::Node, path: &[usize]) -> Option<Self::Node> {
+        path.iter()
+            .try_fold(root.clone(), |node, index| self.child_node(&node, *index))
+    }
+
+    /// returns true if the node is the ancestor or is the node itself
+    fn contains(&self, ancestor: &Self::Node, node: &Self::Node) -> bool {
+        let mut current = Some(node.clone());
+        while let Some(node) = current {
+            if node == *ancestor {
+                return true;
+            }
+            current = self.parent_node(&node);
+        }
+        false
+    }
+
+    /// returns true if the node is in the document
+    fn is_connected(&self, node: &Self::Node) -> bool;
+
+    /// the value of the attribute of the element
+    fn get_attribute(&self, element: &Self::Node, name: &str) -> Option<String>;
+
+    /// the descendant elements of the node which has the attribute, in document order
+    fn elements_with_attribute(&self, node: &Self::Node, name: &str) -> Vec<Self::Node> {
+        let mut found = vec![];
+        for child in self.child_nodes(node) {
+            if self.is_element(&child) {
+                if self.get_attribute(&child, name).is_some() {
+                    found.push(child.clone());
+                }
+                found.extend(self.elements_with_attribute(&child, name));
+            }
+        }
+        found
+    }
+
+    /// set the attribute of the element, in the namespace if there is one
+    fn set_attribute(
+        &self,
+        element: &Self::Node,
+        name: &str,
+        value: &str,
+        namespace: Option<&str>,
+    ) -> Result<(), Error>;
+
+    /// remove the attribute of the element
+    fn remove_attribute(&self, element: &Self::Node, name: &str) -> Result<(), Error>;
+
+    /// set the property of the element which is not updated along with its attribute, which
+    /// are the `value`, `checked`, `open` and `disabled` of the form elements
+    fn set_property(&self, element: &Self::Node, name: &str, value: &Value);
+
+    /// replace the content of the element with the html
+    fn set_inner_html(&self, element: &Self::Node, html: &str);
+
+    /// append the html at the end of the content of the element
+    fn append_html(&self, element: &Self::Node, html: &str) -> Result<(), Error>;
+
+    /// append the child to the parent, the child is moved if it is already in the document
+    fn append_child(&self, parent: &Self::Node, child: &Self::Node) -> Result<(), Error>;
+
+    /// insert the node into the parent before the reference node, or at the end if there is no
+    /// reference node
+    fn insert_before(
+        &self,
+        parent: &Self::Node,
+        node: &Self::Node,
+        reference: Option<&Self::Node>,
+    ) -> Result<(), Error>;
+
+    /// insert the node right after the target node
+    fn insert_after(&self, target: &Self::Node, node: &Self::Node) -> Result<(), Error>;
+
+    /// remove the child from the parent
+    fn remove_child(&self, parent: &Self::Node, child: &Self::Node) -> Result<(), Error>;
+
+    /// put the replacement node in the place of the target node
+    fn replace_with(&self, target: &Self::Node, replacement: &Self::Node) -> Result<(), Error>;
+
+    /// remove all the children of the node
+    fn clear_children(&self, node: &Self::Node) -> Result<(), Error> {
+        for child in self.child_nodes(node) {
+            self.remove_child(node, &child)?;
+        }
+        Ok(())
+    }
+
+    /// attach an open shadow root to the element, which is returned
+    fn attach_shadow(&self, host: &Self::Node) -> Result<Self::Node, Error>;
+
+    /// call the callback with the events of the target, the `passive`, `capture` and `once`
+    /// options are applied by the backend, so are the `prevent_default` and `stop_propagation`
+    fn add_event_listener(
+        &self,
+        target: &Self::Node,
+        event_name: &'static str,
+        options: &ListenerOptions,
+        callback: Rc<dyn Fn(Event)>,
+    ) -> Result<Self::Listener, Error>;
+
+    /// remove the listener from the target
+    fn remove_event_listener(
+        &self,
+        target: &Self::Node,
+        event_name: &str,
+        listener: &Self::Listener,
+    ) -> Result<(), Error>;
+
+    /// dispatch the event which is fired by the program, such as the `mount` and `unmount`
+    /// events, to the listeners of the target
+    fn dispatch_event(&self, target: &Self::Node, event_name: &str) -> Result<(), Error>;
+
+    /// the current time in milliseconds
+    fn now(&self) -> f64;
+
+    /// run the future to completion, it is polled after the current task
+    fn spawn_local(&self, work: LocalBoxFuture<'static, ()>);
+
+    /// call the callback before the next repaint
+    fn request_animation_frame(
+        &self,
+        callback: Box<dyn FnOnce()>,
+    ) -> Result<Self::CallbackHandle, Error>;
+
+    /// call the callback when the document is idle, along with the deadline of the idle
+    /// period if there is one
+    fn request_idle_callback(
+        &self,
+        callback: Box<dyn FnOnce(Option<IdleDeadline>)>,
+    ) -> Result<Self::CallbackHandle, Error>;
+}
+
+/// The DOM of the browser, using `web_sys`
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct WebDom;
+
+/// A callback scheduled in the browser, which is cancelled when dropped
+pub enum WebCallbackHandle {
+    /// a callback requested with `request_animation_frame`
+    AnimationFrame(AnimationFrameHandle),
+    /// a callback requested with `request_idle_callback`
+    IdleCallback(IdleCallbackHandle),
+}
+
+/// the node of the browser DOM, `None` when the node is of a different DOM backend
+pub(crate) fn as_web_node<N: 'static>(node: &N) -> Option<&Node> {
+    let node: &dyn Any = node;
+    node.downcast_ref::<Node>()
+}
+
+// a cache of commonly used elements, so we can clone them.
+// cloning is much faster then creating the element
+thread_local! {
+    static CACHE_ELEMENTS: HashMap<&'static str, Element> =
+        HashMap::from_iter(["div", "span", "ol", "ul", "li"].map(create_element_with_tag));
+}
+
+fn create_element_with_tag(tag: &'static str) -> (&'static str, Element) {
+    let elm = document().create_element(intern(tag)).unwrap();
+    (tag, elm)
+}
+
+/// find the element from the most created element and clone it, else create it
+/// TODO: feature gate this with `use-cached-elements`
+fn create_element(tag: &'static str) -> Result<Element, Error> {
+    CACHE_ELEMENTS.with(|map| {
+        if let Some(elm) = map.get(tag) {
+            Ok(elm.clone_node_with_deep(false)?.unchecked_into())
+        } else {
+            Ok(document().create_element(intern(tag))?)
+        }
+    })
+}
+
+impl WebDom {
+    /// explicitly call `set_checked` function on the html element
+    /// since setting the attribute to false will not unchecked it.
+    ///
+    /// There are only 2 elements where set_checked is applicable:
+    /// - input
+    /// - menuitem
+    fn set_checked(element: &Element, is_checked: bool) {
+        if let Some(input) = element.dyn_ref::<HtmlInputElement>() {
+            input.set_checked(is_checked);
+        }
+    }
+
+    /// explicitly call set_open for details
+    /// since setting the attribute `open` to false will not close it.
+    ///
+    /// TODO: HtmlDialogElement ( but it is not supported on firefox and in safari, only works on chrome)
+    ///
+    /// Applies to:
+    ///  - dialog
+    ///  - details
+    fn set_open(element: &Element, is_open: bool) {
+        if let Some(details) = element.dyn_ref::<HtmlDetailsElement>() {
+            details.set_open(is_open);
+        }
+    }
+
+    /// explicitly call on `set_disabled`
+    /// since setting the attribute `disabled` false will not enable it.
+    ///
+    /// These are 10 elements that we can call `set_disabled` function to.
+    /// - input
+    /// - button
+    /// - textarea
+    /// - style
+    /// - link
+    /// - select
+    /// - option
+    /// - optgroup
+    /// - fieldset
+    /// - menuitem
+    ///
+    /// TODO: use macro to simplify this code
+    fn set_disabled(element: &Element, is_disabled: bool) {
+        if let Some(elm) = element.dyn_ref::<HtmlInputElement>() {
+            elm.set_disabled(is_disabled);
+        } else if let Some(elm) = element.dyn_ref::<HtmlButtonElement>() {
+            elm.set_disabled(is_disabled);
+        } else if let Some(elm) = element.dyn_ref::<HtmlTextAreaElement>() {
+            elm.set_disabled(is_disabled);
+        } else if let Some(elm) = element.dyn_ref::<HtmlStyleElement>() {
+            elm.set_disabled(is_disabled);
+        } else if let Some(elm) = element.dyn_ref::<HtmlLinkElement>() {
+            elm.set_disabled(is_disabled);
+        } else if let Some(elm) = element.dyn_ref::<HtmlSelectElement>() {
+            elm.set_disabled(is_disabled);
+        } else if let Some(elm) = element.dyn_ref::<HtmlOptionElement>() {
+            elm.set_disabled(is_disabled);
+        } else if let Some(elm) = element.dyn_ref::<HtmlOptGroupElement>() {
+            elm.set_disabled(is_disabled);
+        } else if let Some(elm) = element.dyn_ref::<HtmlFieldSetElement>() {
+            elm.set_disabled(is_disabled);
+        }
+    }
+
+    /// we explicitly call the `set_value` function in the html element
+    ///
+    /// TODO: use macro to simplify this code
+    fn set_value_str(element: &Element, value: &str) {
+        if let Some(elm) = element.dyn_ref::<HtmlInputElement>() {
+            elm.set_value(value);
+        } else if let Some(elm) = element.dyn_ref::<HtmlTextAreaElement>() {
+            elm.set_value(value);
+        } else if let Some(elm) = element.dyn_ref::<HtmlSelectElement>() {
+            elm.set_value(value);
+        } else if let Some(elm) = element.dyn_ref::<HtmlOptionElement>() {
+            elm.set_value(value);
+        } else if let Some(elm) = element.dyn_ref::<HtmlButtonElement>() {
+            elm.set_value(value);
+        } else if let Some(elm) = element.dyn_ref::<HtmlDataElement>() {
+            elm.set_value(value);
+        } else if let Some(elm) = element.dyn_ref::<HtmlOutputElement>() {
+            elm.set_value(value);
+        } else if let Some(elm) = element.dyn_ref::<HtmlParamElement>() {
+            elm.set_value(value);
+        }
+    }
+
+    fn set_value_i32(element: &Element, value: i32) {
+        if let Some(elm) = element.dyn_ref::<HtmlLiElement>() {
+            elm.set_value(value);
+        }
+    }
+
+    fn set_value_f64(element: &Element, value: f64) {
+        if let Some(elm) = element.dyn_ref::<HtmlMeterElement>() {
+            elm.set_value(value);
+        } else if let Some(elm) = element.dyn_ref::<HtmlProgressElement>() {
+            elm.set_value(value);
+        }
+    }
+}
+
+impl DomBackend for WebDom {
+    type Node = Node;
+    type Listener = Closure<dyn FnMut(web_sys::Event)>;
+    type CallbackHandle = WebCallbackHandle;
+
+    fn create_element(
+        &self,
+        tag: &'static str,
+        namespace: Option<&'static str>,
+    ) -> Result<Node, Error> {
+        let element = match namespace {
+            Some(namespace) => {
+                document().create_element_ns(Some(intern(namespace)), intern(tag))?
+            }
+            None => create_element(tag)?,
+        };
+        Ok(element.into())
+    }
+
+    fn create_text_node(&self, text: &str) -> Node {
+        document().create_text_node(text).into()
+    }
+
+    fn create_comment(&self, comment: &str) -> Node {
+        document().create_comment(comment).into()
+    }
+
+    fn create_document_fragment(&self) -> Node {
+        document().create_document_fragment().into()
+    }
+
+    fn head(&self) -> Option<Node> {
+        document().head().map(Into::into)
+    }
+
+    fn is_element(&self, node: &Node) -> bool {
+        node.node_type() == Node::ELEMENT_NODE
+    }
+
+    fn is_document_fragment(&self, node: &Node) -> bool {
+        node.node_type() == Node::DOCUMENT_FRAGMENT_NODE
+    }
+
+    fn tag_name(&self, node: &Node) -> Option<String> {
+        node.dyn_ref::<Element>()
+            .map(|element| element.tag_name().to_lowercase())
+    }
+
+    fn is_text(&self, node: &Node) -> bool {
+        node.node_type() == Node::TEXT_NODE
+    }
+
+    fn is_comment(&self, node: &Node) -> bool {
+        node.node_type() == Node::COMMENT_NODE
+    }
+
+    fn node_value(&self, node: &Node) -> Option<String> {
+        node.node_value()
+    }
+
+    fn set_node_value(&self, node: &Node, value: &str) {
+        node.set_node_value(Some(value));
+    }
+
+    fn parent_node(&self, node: &Node) -> Option<Node> {
+        node.parent_node()
+    }
+
+    fn child_nodes(&self, node: &Node) -> Vec<Node> {
+        let children = node.child_nodes();
+        (0..children.length())
+            .filter_map(|i| children.item(i))
+            .collect()
+    }
+
+    fn child_node(&self, node: &Node, index: usize) -> Option<Node> {
+        node.child_nodes().item(index as u32)
+    }
+
+    fn next_sibling(&self, node: &Node) -> Option<Node> {
+        node.next_sibling()
+    }
+
+    fn contains(&self, ancestor: &Node, node: &Node) -> bool {
+        ancestor.contains(Some(node))
+    }
+
+    fn is_connected(&self, node: &Node) -> bool {
+        node.is_connected()
+    }
+
+    fn get_attribute(&self, element: &Node, name: &str) -> Option<String> {
+        element
+            .dyn_ref::<Element>()
+            .and_then(|element| element.get_attribute(intern(name)))
+    }
+
+    fn elements_with_attribute(&self, node: &Node, name: &str) -> Vec<Node> {
+        let Some(element) = node.dyn_ref::<Element>() else {
+            return vec![];
+        };
+        let descendants = element
+            .query_selector_all(&format!("[{name}]"))
+            .expect("must query the descendants");
+        (0..descendants.length())
+            .filter_map(|i| descendants.item(i))
+            .collect()
+    }
+
+    fn set_attribute(
+        &self,
+        element: &Node,
+        name: &str,
+        value: &str,
+        namespace: Option<&str>,
+    ) -> Result<(), Error> {
+        let element: &Element = element.unchecked_ref();
+        match namespace {
+            // Warning NOTE: set_attribute_ns should only be called
+            // when you meant to use a namespace
+            // using this with None will error in the browser with:
+            // NamespaceError: An attempt was made to create or change an object in a way which is incorrect with regard to namespaces
+            Some(namespace) => {
+                element.set_attribute_ns(Some(intern(namespace)), intern(name), value)?
+            }
+            None => element.set_attribute(intern(name), value)?,
+        }
+        Ok(())
+    }
+
+    fn remove_attribute(&self, element: &Node, name: &str) -> Result<(), Error> {
+        let element: &Element = element.unchecked_ref();
+        element.remove_attribute(intern(name))?;
+        Ok(())
+    }
+
+    fn set_property(&self, element: &Node, name: &str, value: &Value) {
+        let element: &Element = element.unchecked_ref();
+        match name {
+            "value" => {
+                Self::set_value_str(element, &value.to_string());
+                if let Some(value) = value.as_i32() {
+                    Self::set_value_i32(element, value);
+                }
+                if let Some(value) = value.as_f64() {
+                    Self::set_value_f64(element, value);
+                }
+            }
+            "checked" => Self::set_checked(element, value.as_bool().unwrap_or(false)),
+            "open" => Self::set_open(element, value.as_bool().unwrap_or(false)),
+            "disabled" => Self::set_disabled(element, value.as_bool().unwrap_or(false)),
+            _ => (),
+        }
+    }
+
+    fn set_inner_html(&self, element: &Node, html: &str) {
+        let element: &Element = element.unchecked_ref();
+        element.set_inner_html(html);
+    }
+
+    fn append_html(&self, element: &Node, html: &str) -> Result<(), Error> {
+        // https://developer.mozilla.org/en-US/docs/Web/API/Element/insertAdjacentHTML
+        let element: &Element = element.unchecked_ref();
+        element.insert_adjacent_html(intern("beforeend"), html)?;
+        Ok(())
+    }
+
+    fn append_child(&self, parent: &Node, child: &Node) -> Result<(), Error> {
+        parent.append_child(child)?;
+        Ok(())
+    }
+
+    fn insert_before(
+        &self,
+        parent: &Node,
+        node: &Node,
+        reference: Option<&Node>,
+    ) -> Result<(), Error> {
+        parent.insert_before(node, reference)?;
+        Ok(())
+    }
+
+    fn insert_after(&self, target: &Node, node: &Node) -> Result<(), Error> {
+        let target: &Element = target.unchecked_ref();
+        target.insert_adjacent_element(intern("afterend"), node.unchecked_ref())?;
+        Ok(())
+    }
+
+    fn remove_child(&self, parent: &Node, child: &Node) -> Result<(), Error> {
+        parent.remove_child(child)?;
+        Ok(())
+    }
+
+    fn replace_with(&self, target: &Node, replacement: &Node) -> Result<(), Error> {
+        let target: &Element = target.unchecked_ref();
+        target.replace_with_with_node_1(replacement)?;
+        Ok(())
+    }
+
+    fn clear_children(&self, node: &Node) -> Result<(), Error> {
+        while let Some(first_child) = node.first_child() {
+            node.remove_child(&first_child)?;
+        }
+        Ok(())
+    }
+
+    fn attach_shadow(&self, host: &Node) -> Result<Node, Error> {
+        let host: &Element = host.unchecked_ref();
+        let shadow_root =
+            host.attach_shadow(&web_sys::ShadowRootInit::new(web_sys::ShadowRootMode::Open))?;
+        Ok(shadow_root.unchecked_into())
+    }
+
+    fn add_event_listener(
+        &self,
+        target: &Node,
+        event_name: &'static str,
+        options: &ListenerOptions,
+        callback: Rc<dyn Fn(Event)>,
+    ) -> Result<Self::Listener, Error> {
+        let listener_options = *options;
+        let closure = Closure::new(move |event: web_sys::Event| {
+            if listener_options.prevent_default {
+                event.prevent_default();
+            }
+            if listener_options.stop_propagation {
+                event.stop_propagation();
+            }
+            callback(Event::from(event));
+        });
+        target.add_event_listener_with_callback_and_add_event_listener_options(
+            intern(event_name),
+            closure.as_ref().unchecked_ref(),
+            &add_event_listener_options(options),
+        )?;
+        Ok(closure)
+    }
+
+    fn remove_event_listener(
+        &self,
+        target: &Node,
+        event_name: &str,
+        listener: &Self::Listener,
+    ) -> Result<(), Error> {
+        // remove the listener regardless of the phase it was attached to
+        let func = listener.as_ref().unchecked_ref();
+        target.remove_event_listener_with_callback_and_bool(intern(event_name), func, false)?;
+        target.remove_event_listener_with_callback_and_bool(intern(event_name), func, true)?;
+        Ok(())
+    }
+
+    fn dispatch_event(&self, target: &Node, event_name: &str) -> Result<(), Error> {
+        let event = web_sys::Event::new(event_name)?;
+        target.dispatch_event(&event)?;
+        Ok(())
+    }
+
+    fn now(&self) -> f64 {
+        now()
+    }
+
+    fn spawn_local(&self, work: LocalBoxFuture<'static, ()>) {
+        spawn_local(work);
+    }
+
+    fn request_animation_frame(
+        &self,
+        callback: Box<dyn FnOnce()>,
+    ) -> Result<WebCallbackHandle, Error> {
+        let mut callback = Some(callback);
+        let handle = request_animation_frame(move || {
+            if let Some(callback) = callback.take() {
+                callback();
+            }
+        })?;
+        Ok(WebCallbackHandle::AnimationFrame(handle))
+    }
+
+    fn request_idle_callback(
+        &self,
+        callback: Box<dyn FnOnce(Option<IdleDeadline>)>,
+    ) -> Result<WebCallbackHandle, Error> {
+        let mut callback = Some(callback);
+        let handle = request_idle_callback(move |deadline| {
+            if let Some(callback) = callback.take() {
+                callback(Some(deadline));
+            }
+        })?;
+        Ok(WebCallbackHandle::IdleCallback(handle))
+    }
+}
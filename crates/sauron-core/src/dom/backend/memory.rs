@@ -0,0 +1,889 @@
+//! A document kept in memory, such that the [`Program`] runs natively, such as in `cargo test`.
+//!
+//! [`Program`]: crate::dom::Program
+use super::DomBackend;
+use crate::{
+    dom::{
+        events::SyntheticEvent,
+        test_program::{matches_selector_with, parse_selector},
+        Event, IdleDeadline,
+    },
+    html::attributes::ListenerOptions,
+    html::attributes::Value,
+    render::{escape_attribute_value, escape_text},
+    Error,
+};
+use futures::{
+    executor::{LocalPool, LocalSpawner},
+    future::LocalBoxFuture,
+    task::LocalSpawnExt,
+};
+use std::{
+    cell::{Cell, RefCell},
+    collections::{BTreeMap, VecDeque},
+    fmt,
+    rc::{Rc, Weak},
+};
+
+/// the elements which have no closing tag
+const VOID_ELEMENTS: [&str; 14] = [
+    "area", "base", "br", "col", "embed", "hr", "img", "input", "link", "meta", "param", "source",
+    "track", "wbr",
+];
+
+/// A document kept in memory, which is used as the [`DomBackend`] of a [`Program`] to run it
+/// without a browser.
+///
+/// The document supports querying the elements with a selector and firing [`SyntheticEvent`]s
+/// into them, which are passed to the listeners made with [`on_synthetic`]. The listeners of the
+/// web events, such as `on_click` and the lifecycle listeners such as `on_mount`, are skipped.
+/// The tasks, animation frames and idle callbacks which are scheduled by the program are run
+/// only when asked for, such as with [`MemoryDom::settle`], and the clock only moves with
+/// [`MemoryDom::advance_time`], such that the tests are deterministic.
+///
+/// The html of the safe html nodes is not parsed, it is kept as a single node.
+///
+/// [`Program`]: crate::dom::Program
+/// [`on_synthetic`]: crate::html::events::on_synthetic
+#[derive(Clone)]
+pub struct MemoryDom {
+    document: Rc<Document>,
+}
+
+struct Document {
+    html: MemoryNode,
+    head: MemoryNode,
+    body: MemoryNode,
+    /// the current time in ms
+    clock: Cell<f64>,
+    pool: RefCell<LocalPool>,
+    spawner: LocalSpawner,
+    animation_frames: RefCell<VecDeque<Scheduled<dyn FnOnce()>>>,
+    idle_callbacks: RefCell<VecDeque<Scheduled<IdleCallback>>>,
+}
+
+/// a callback which is called with the deadline of the idle period
+type IdleCallback = dyn FnOnce(Option<IdleDeadline>);
+
+/// a callback which is waiting to be run, unless its handle is dropped
+struct Scheduled<F: ?Sized> {
+    cancelled: Rc<Cell<bool>>,
+    callback: Box<F>,
+}
+
+/// A callback scheduled in the [`MemoryDom`], which is cancelled when dropped
+pub struct MemoryCallbackHandle {
+    cancelled: Rc<Cell<bool>>,
+}
+
+impl Drop for MemoryCallbackHandle {
+    fn drop(&mut self) {
+        self.cancelled.set(true);
+    }
+}
+
+/// An event listener attached to a [`MemoryNode`], the listener is no longer called when it
+/// is dropped
+pub struct MemoryListener {
+    callback: Rc<dyn Fn(Event)>,
+}
+
+/// A node of the [`MemoryDom`], cloning the node clones the handle to the same node
+#[derive(Clone)]
+pub struct MemoryNode(Rc<RefCell<NodeData>>);
+
+struct NodeData {
+    kind: NodeKind,
+    parent: Weak<RefCell<NodeData>>,
+    children: Vec<MemoryNode>,
+    listeners: Vec<AttachedListener>,
+}
+
+enum NodeKind {
+    Element {
+        tag: String,
+        namespace: Option<String>,
+        attributes: Vec<(String, String)>,
+        properties: BTreeMap<String, Value>,
+        shadow_root: Option<MemoryNode>,
+    },
+    Text(String),
+    Comment(String),
+    Fragment,
+    ShadowRoot {
+        host: Weak<RefCell<NodeData>>,
+    },
+    /// the html which is inserted as is
+    Html(String),
+}
+
+struct AttachedListener {
+    event_name: String,
+    options: ListenerOptions,
+    callback: Weak<dyn Fn(Event)>,
+}
+
+impl Default for MemoryDom {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl fmt::Debug for MemoryDom {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("MemoryDom")
+            .field("html", &self.document.html)
+            .field("clock", &self.document.clock.get())
+            .finish()
+    }
+}
+
+impl MemoryDom {
+    /// create an empty document, with a `<head>` and a `<body>`
+    pub fn new() -> Self {
+        let html = MemoryNode::element("html", None);
+        let head = MemoryNode::element("head", None);
+        let body = MemoryNode::element("body", None);
+        html.append(&head);
+        html.append(&body);
+        let pool = LocalPool::new();
+        let spawner = pool.spawner();
+        Self {
+            document: Rc::new(Document {
+                html,
+                head,
+                body,
+                clock: Cell::new(0.0),
+                pool: RefCell::new(pool),
+                spawner,
+                animation_frames: RefCell::new(VecDeque::new()),
+                idle_callbacks: RefCell::new(VecDeque::new()),
+            }),
+        }
+    }
+
+    /// the `<html>` element of the document
+    pub fn document_element(&self) -> MemoryNode {
+        self.document.html.clone()
+    }
+
+    /// the `<head>` element of the document
+    pub fn head(&self) -> MemoryNode {
+        self.document.head.clone()
+    }
+
+    /// the `<body>` element of the document
+    pub fn body(&self) -> MemoryNode {
+        self.document.body.clone()
+    }
+
+    /// returns the first element in the document which matches the selector, see
+    /// [`MemoryNode::query_selector`]
+    pub fn query_selector(&self, selector: &str) -> Option<MemoryNode> {
+        self.document.html.query_selector(selector)
+    }
+
+    /// returns all the elements in the document which matches the selector
+    pub fn query_selector_all(&self, selector: &str) -> Vec<MemoryNode> {
+        self.document.html.query_selector_all(selector)
+    }
+
+    /// Fire the event to the target node. The listeners in the capture phase are called from
+    /// the root down to the target, then the listeners of the target, then the rest bubbling
+    /// up to the root, until a listener with the `stop_propagation` option.
+    /// The tasks which are spawned by the listeners, such as the update of the user input,
+    /// are then run.
+    pub fn fire_event(&self, target: &MemoryNode, event_name: &str, event: SyntheticEvent) {
+        let path: Vec<MemoryNode> = std::iter::successors(Some(target.clone()), |node| {
+            node.parent().or_else(|| node.shadow_host())
+        })
+        .collect();
+        let event = Event::Synthetic(event);
+        let stopped = path.iter().enumerate().rev().any(|(depth, node)| {
+            node.call_listeners(event_name, &event, |options| depth == 0 || options.capture)
+        });
+        if !stopped {
+            path.iter()
+                .skip(1)
+                .any(|node| node.call_listeners(event_name, &event, |options| !options.capture));
+        }
+        self.run_until_stalled();
+    }
+
+    /// fire a click event to the first element which matches the selector
+    ///
+    /// # Panics
+    /// Panics if there is no element which matches the selector
+    pub fn click(&self, selector: &str) {
+        let target = self.expect_element(selector);
+        self.fire_event(&target, "click", SyntheticEvent::default());
+    }
+
+    /// set the value and fire an input event to the first element which matches the selector
+    ///
+    /// # Panics
+    /// Panics if there is no element which matches the selector
+    pub fn input(&self, selector: &str, value: impl ToString) {
+        let target = self.expect_element(selector);
+        let value = value.to_string();
+        target.set_property("value", Value::from(value.clone()));
+        self.fire_event(&target, "input", SyntheticEvent::with_value(value));
+    }
+
+    fn expect_element(&self, selector: &str) -> MemoryNode {
+        self.query_selector(selector)
+            .unwrap_or_else(|| panic!("no element matches the selector: {selector:?}"))
+    }
+
+    /// run the spawned tasks until none of them can make progress
+    pub fn run_until_stalled(&self) {
+        // the tasks which are spawned while the pool is running are run by the same pool
+        if let Ok(mut pool) = self.document.pool.try_borrow_mut() {
+            pool.run_until_stalled();
+        }
+    }
+
+    /// run the animation frames which are requested so far, the frames which are requested by
+    /// them are run on the next call. Returns the number of frames which are run.
+    pub fn run_animation_frames(&self) -> usize {
+        let frames = std::mem::take(&mut *self.document.animation_frames.borrow_mut());
+        let mut count = 0;
+        for frame in frames.into_iter().filter(|frame| !frame.cancelled.get()) {
+            (frame.callback)();
+            count += 1;
+        }
+        self.run_until_stalled();
+        count
+    }
+
+    /// run the idle callbacks which are requested so far, without a deadline. Returns the
+    /// number of callbacks which are run.
+    pub fn run_idle_callbacks(&self) -> usize {
+        let callbacks = std::mem::take(&mut *self.document.idle_callbacks.borrow_mut());
+        let mut count = 0;
+        for idle in callbacks.into_iter().filter(|idle| !idle.cancelled.get()) {
+            (idle.callback)(None);
+            count += 1;
+        }
+        self.run_until_stalled();
+        count
+    }
+
+    /// run the tasks, animation frames and idle callbacks until there is nothing left to run
+    pub fn settle(&self) {
+        self.run_until_stalled();
+        while self.run_animation_frames() + self.run_idle_callbacks() > 0 {}
+    }
+
+    /// move the clock forward by the milliseconds
+    pub fn advance_time(&self, ms: f64) {
+        self.document.clock.set(self.document.clock.get() + ms);
+    }
+
+    fn is_in_document(&self, node: &MemoryNode) -> bool {
+        std::iter::successors(Some(node.clone()), |node| {
+            node.parent().or_else(|| node.shadow_host())
+        })
+        .any(|ancestor| ancestor == self.document.html)
+    }
+}
+
+impl PartialEq for MemoryNode {
+    fn eq(&self, other: &Self) -> bool {
+        Rc::ptr_eq(&self.0, &other.0)
+    }
+}
+
+impl fmt::Debug for MemoryNode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("MemoryNode")
+            .field(&self.outer_html())
+            .finish()
+    }
+}
+
+impl MemoryNode {
+    fn new(kind: NodeKind) -> Self {
+        Self(Rc::new(RefCell::new(NodeData {
+            kind,
+            parent: Weak::new(),
+            children: vec![],
+            listeners: vec![],
+        })))
+    }
+
+    fn element(tag: &str, namespace: Option<&str>) -> Self {
+        Self::new(NodeKind::Element {
+            tag: tag.to_string(),
+            namespace: namespace.map(ToString::to_string),
+            attributes: vec![],
+            properties: BTreeMap::new(),
+            shadow_root: None,
+        })
+    }
+
+    /// the tag of the element in lowercase, `None` if the node is not an element
+    pub fn tag_name(&self) -> Option<String> {
+        match &self.0.borrow().kind {
+            NodeKind::Element { tag, .. } => Some(tag.to_lowercase()),
+            _ => None,
+        }
+    }
+
+    /// the namespace of the element, such as the svg namespace
+    pub fn namespace(&self) -> Option<String> {
+        match &self.0.borrow().kind {
+            NodeKind::Element { namespace, .. } => namespace.clone(),
+            _ => None,
+        }
+    }
+
+    /// returns true if the node is an element
+    pub fn is_element(&self) -> bool {
+        matches!(self.0.borrow().kind, NodeKind::Element { .. })
+    }
+
+    /// the value of the attribute of the element
+    pub fn attribute(&self, name: &str) -> Option<String> {
+        match &self.0.borrow().kind {
+            NodeKind::Element { attributes, .. } => attributes
+                .iter()
+                .find(|(attr, _)| attr == name)
+                .map(|(_, value)| value.clone()),
+            _ => None,
+        }
+    }
+
+    /// The property of the element, which are the `value`, `checked`, `open` and `disabled`
+    /// of the form elements. The property is set along with its attribute, but unlike the
+    /// attribute it is also set by the user input and kept when the attribute is removed.
+    pub fn property(&self, name: &str) -> Option<Value> {
+        match &self.0.borrow().kind {
+            NodeKind::Element { properties, .. } => properties.get(name).cloned(),
+            _ => None,
+        }
+    }
+
+    fn set_property(&self, name: &str, value: Value) {
+        if let NodeKind::Element { properties, .. } = &mut self.0.borrow_mut().kind {
+            properties.insert(name.to_string(), value);
+        }
+    }
+
+    /// the parent of the node
+    pub fn parent(&self) -> Option<MemoryNode> {
+        self.0.borrow().parent.upgrade().map(MemoryNode)
+    }
+
+    /// the children of the node, including the text and comment nodes
+    pub fn children(&self) -> Vec<MemoryNode> {
+        self.0.borrow().children.clone()
+    }
+
+    /// the shadow root which is attached to the element
+    pub fn shadow_root(&self) -> Option<MemoryNode> {
+        match &self.0.borrow().kind {
+            NodeKind::Element { shadow_root, .. } => shadow_root.clone(),
+            _ => None,
+        }
+    }
+
+    fn shadow_host(&self) -> Option<MemoryNode> {
+        match &self.0.borrow().kind {
+            NodeKind::ShadowRoot { host } => host.upgrade().map(MemoryNode),
+            _ => None,
+        }
+    }
+
+    /// the text of the node and its descendants
+    pub fn text_content(&self) -> String {
+        match &self.0.borrow().kind {
+            NodeKind::Text(text) => text.clone(),
+            NodeKind::Comment(_) | NodeKind::Html(_) => String::new(),
+            _ => self.children().iter().map(Self::text_content).collect(),
+        }
+    }
+
+    /// the html of the children of the node
+    pub fn inner_html(&self) -> String {
+        let mut buffer = String::new();
+        for child in self.0.borrow().children.iter() {
+            child.write_html(&mut buffer).expect("must write html");
+        }
+        buffer
+    }
+
+    /// the html of the node, along with its children
+    pub fn outer_html(&self) -> String {
+        let mut buffer = String::new();
+        self.write_html(&mut buffer).expect("must write html");
+        buffer
+    }
+
+    fn write_html(&self, buffer: &mut dyn fmt::Write) -> fmt::Result {
+        let data = self.0.borrow();
+        match &data.kind {
+            NodeKind::Element {
+                tag, attributes, ..
+            } => {
+                write!(buffer, "<{tag}")?;
+                for (name, value) in attributes {
+                    write!(buffer, " {name}=\"")?;
+                    escape_attribute_value(buffer, value)?;
+                    buffer.write_str("\"")?;
+                }
+                buffer.write_str(">")?;
+                if VOID_ELEMENTS.contains(&tag.as_str()) {
+                    return Ok(());
+                }
+                let is_raw_text = matches!(tag.as_str(), "script" | "style");
+                for child in data.children.iter() {
+                    match &child.0.borrow().kind {
+                        NodeKind::Text(text) if is_raw_text => buffer.write_str(text)?,
+                        _ => child.write_html(buffer)?,
+                    }
+                }
+                write!(buffer, "</{tag}>")
+            }
+            NodeKind::Text(text) => escape_text(buffer, text),
+            NodeKind::Comment(comment) => write!(buffer, "<!--{comment}-->"),
+            NodeKind::Html(html) => buffer.write_str(html),
+            NodeKind::Fragment | NodeKind::ShadowRoot { .. } => {
+                for child in data.children.iter() {
+                    child.write_html(buffer)?;
+                }
+                Ok(())
+            }
+        }
+    }
+
+    /// Returns the first descendant element which matches the selector.
+    /// The selector is a list of simple selectors separated by whitespace, matching the
+    /// descendants of the elements matched by the previous selector.
+    /// A simple selector consists of a tag, `#id`, `.class`, `[attribute]` and
+    /// `[attribute=value]`, such as `ul.todo-list li.completed input[type=checkbox]`
+    pub fn query_selector(&self, selector: &str) -> Option<MemoryNode> {
+        self.query_selector_all(selector).into_iter().next()
+    }
+
+    /// returns all the descendant elements which matches the selector, in document order
+    pub fn query_selector_all(&self, selector: &str) -> Vec<MemoryNode> {
+        let selectors = parse_selector(selector);
+        self.descendant_elements()
+            .into_iter()
+            .filter(|element| {
+                let mut elements: Vec<MemoryNode> =
+                    std::iter::successors(Some(element.clone()), MemoryNode::parent)
+                        .filter(MemoryNode::is_element)
+                        .collect();
+                elements.reverse();
+                matches_selector_with(&selectors, &elements, |selector, element| {
+                    selector.matches_element(element.tag_name().as_deref(), |name| {
+                        element.attribute(name)
+                    })
+                })
+            })
+            .collect()
+    }
+
+    fn descendant_elements(&self) -> Vec<MemoryNode> {
+        let mut found = vec![];
+        for child in self.children() {
+            if child.is_element() {
+                found.push(child.clone());
+            }
+            found.extend(child.descendant_elements());
+        }
+        found
+    }
+
+    /// detach the node from its parent
+    fn detach(&self) {
+        if let Some(parent) = self.parent() {
+            parent.0.borrow_mut().children.retain(|child| child != self);
+            self.0.borrow_mut().parent = Weak::new();
+        }
+    }
+
+    /// the nodes which are inserted when this node is inserted, the children of a fragment are
+    /// moved out of it
+    fn take_for_insert(&self) -> Vec<MemoryNode> {
+        if matches!(self.0.borrow().kind, NodeKind::Fragment) {
+            let children = std::mem::take(&mut self.0.borrow_mut().children);
+            for child in children.iter() {
+                child.0.borrow_mut().parent = Weak::new();
+            }
+            children
+        } else {
+            self.detach();
+            vec![self.clone()]
+        }
+    }
+
+    /// insert the node at the index of the children
+    fn insert_at(&self, index: usize, node: &MemoryNode) {
+        let nodes = node.take_for_insert();
+        let index = index.min(self.0.borrow().children.len());
+        for node in nodes.iter() {
+            node.0.borrow_mut().parent = Rc::downgrade(&self.0);
+        }
+        self.0.borrow_mut().children.splice(index..index, nodes);
+    }
+
+    fn append(&self, node: &MemoryNode) {
+        self.insert_at(usize::MAX, node);
+    }
+
+    fn index_of(&self, child: &MemoryNode) -> Option<usize> {
+        self.0.borrow().children.iter().position(|c| c == child)
+    }
+
+    /// call the listeners of the event which are selected with the `phase`, returns true if
+    /// the propagation of the event is stopped
+    fn call_listeners(
+        &self,
+        event_name: &str,
+        event: &Event,
+        phase: impl Fn(&ListenerOptions) -> bool,
+    ) -> bool {
+        let mut called = vec![];
+        self.0.borrow_mut().listeners.retain(|listener| {
+            let Some(callback) = listener.callback.upgrade() else {
+                return false;
+            };
+            if listener.event_name != event_name || !phase(&listener.options) {
+                return true;
+            }
+            called.push((callback, listener.options));
+            !listener.options.once
+        });
+        // the listeners are called outside of the borrow, since they can modify the node
+        let mut stopped = false;
+        for (callback, options) in called {
+            callback(event.clone());
+            stopped |= options.stop_propagation;
+        }
+        stopped
+    }
+}
+
+fn invalid(node: &MemoryNode, operation: &str) -> Error {
+    Error::Dom(format!("unable to {operation} {node:?}"))
+}
+
+fn scheduled<F: ?Sized>(callback: Box<F>) -> (Scheduled<F>, MemoryCallbackHandle) {
+    let cancelled = Rc::new(Cell::new(false));
+    let handle = MemoryCallbackHandle {
+        cancelled: Rc::clone(&cancelled),
+    };
+    (
+        Scheduled {
+            cancelled,
+            callback,
+        },
+        handle,
+    )
+}
+
+impl DomBackend for MemoryDom {
+    type Node = MemoryNode;
+    type Listener = MemoryListener;
+    type CallbackHandle = MemoryCallbackHandle;
+
+    fn create_element(
+        &self,
+        tag: &'static str,
+        namespace: Option<&'static str>,
+    ) -> Result<MemoryNode, Error> {
+        Ok(MemoryNode::element(tag, namespace))
+    }
+
+    fn create_text_node(&self, text: &str) -> MemoryNode {
+        MemoryNode::new(NodeKind::Text(text.to_string()))
+    }
+
+    fn create_comment(&self, comment: &str) -> MemoryNode {
+        MemoryNode::new(NodeKind::Comment(comment.to_string()))
+    }
+
+    fn create_document_fragment(&self) -> MemoryNode {
+        MemoryNode::new(NodeKind::Fragment)
+    }
+
+    fn head(&self) -> Option<MemoryNode> {
+        Some(self.head())
+    }
+
+    fn is_element(&self, node: &MemoryNode) -> bool {
+        node.is_element()
+    }
+
+    fn is_document_fragment(&self, node: &MemoryNode) -> bool {
+        matches!(
+            node.0.borrow().kind,
+            NodeKind::Fragment | NodeKind::ShadowRoot { .. }
+        )
+    }
+
+    fn tag_name(&self, node: &MemoryNode) -> Option<String> {
+        node.tag_name()
+    }
+
+    fn is_text(&self, node: &MemoryNode) -> bool {
+        matches!(node.0.borrow().kind, NodeKind::Text(_))
+    }
+
+    fn is_comment(&self, node: &MemoryNode) -> bool {
+        matches!(node.0.borrow().kind, NodeKind::Comment(_))
+    }
+
+    fn node_value(&self, node: &MemoryNode) -> Option<String> {
+        match &node.0.borrow().kind {
+            NodeKind::Text(value) | NodeKind::Comment(value) => Some(value.clone()),
+            _ => None,
+        }
+    }
+
+    fn set_node_value(&self, node: &MemoryNode, value: &str) {
+        if let NodeKind::Text(current) | NodeKind::Comment(current) = &mut node.0.borrow_mut().kind
+        {
+            *current = value.to_string();
+        }
+    }
+
+    fn parent_node(&self, node: &MemoryNode) -> Option<MemoryNode> {
+        node.parent()
+    }
+
+    fn child_nodes(&self, node: &MemoryNode) -> Vec<MemoryNode> {
+        node.children()
+    }
+
+    fn child_node(&self, node: &MemoryNode, index: usize) -> Option<MemoryNode> {
+        node.0.borrow().children.get(index).cloned()
+    }
+
+    fn is_connected(&self, node: &MemoryNode) -> bool {
+        self.is_in_document(node)
+    }
+
+    fn get_attribute(&self, element: &MemoryNode, name: &str) -> Option<String> {
+        element.attribute(name)
+    }
+
+    fn set_attribute(
+        &self,
+        element: &MemoryNode,
+        name: &str,
+        value: &str,
+        _namespace: Option<&str>,
+    ) -> Result<(), Error> {
+        let mut data = element.0.borrow_mut();
+        let NodeKind::Element { attributes, .. } = &mut data.kind else {
+            drop(data);
+            return Err(invalid(element, "set an attribute of"));
+        };
+        match attributes.iter_mut().find(|(attr, _)| attr == name) {
+            Some((_, existing)) => *existing = value.to_string(),
+            None => attributes.push((name.to_string(), value.to_string())),
+        }
+        Ok(())
+    }
+
+    fn remove_attribute(&self, element: &MemoryNode, name: &str) -> Result<(), Error> {
+        let mut data = element.0.borrow_mut();
+        let NodeKind::Element { attributes, .. } = &mut data.kind else {
+            drop(data);
+            return Err(invalid(element, "remove an attribute of"));
+        };
+        attributes.retain(|(attr, _)| attr != name);
+        Ok(())
+    }
+
+    fn set_property(&self, element: &MemoryNode, name: &str, value: &Value) {
+        element.set_property(name, value.clone());
+    }
+
+    fn set_inner_html(&self, element: &MemoryNode, html: &str) {
+        for child in element.children() {
+            child.detach();
+        }
+        if !html.is_empty() {
+            element.append(&MemoryNode::new(NodeKind::Html(html.to_string())));
+        }
+    }
+
+    fn append_html(&self, element: &MemoryNode, html: &str) -> Result<(), Error> {
+        element.append(&MemoryNode::new(NodeKind::Html(html.to_string())));
+        Ok(())
+    }
+
+    fn append_child(&self, parent: &MemoryNode, child: &MemoryNode) -> Result<(), Error> {
+        if self.contains(child, parent) {
+            return Err(invalid(child, "append a node into itself,"));
+        }
+        parent.append(child);
+        Ok(())
+    }
+
+    fn insert_before(
+        &self,
+        parent: &MemoryNode,
+        node: &MemoryNode,
+        reference: Option<&MemoryNode>,
+    ) -> Result<(), Error> {
+        if self.contains(node, parent) {
+            return Err(invalid(node, "insert a node into itself,"));
+        }
+        match reference {
+            Some(reference) if reference == node => Ok(()),
+            Some(reference) => {
+                // the index is taken after the node is detached, since it can be a sibling
+                node.detach();
+                let index = parent
+                    .index_of(reference)
+                    .ok_or_else(|| invalid(reference, "insert before a non child"))?;
+                parent.insert_at(index, node);
+                Ok(())
+            }
+            None => {
+                parent.append(node);
+                Ok(())
+            }
+        }
+    }
+
+    fn insert_after(&self, target: &MemoryNode, node: &MemoryNode) -> Result<(), Error> {
+        if target == node {
+            return Ok(());
+        }
+        let parent = target.parent().ok_or(Error::MissingParent)?;
+        if self.contains(node, &parent) {
+            return Err(invalid(node, "insert a node into itself,"));
+        }
+        node.detach();
+        let index = parent
+            .index_of(target)
+            .expect("the target must be a child of its parent");
+        parent.insert_at(index + 1, node);
+        Ok(())
+    }
+
+    fn remove_child(&self, parent: &MemoryNode, child: &MemoryNode) -> Result<(), Error> {
+        if child.parent().as_ref() != Some(parent) {
+            return Err(invalid(child, "remove a non child"));
+        }
+        child.detach();
+        Ok(())
+    }
+
+    fn replace_with(&self, target: &MemoryNode, replacement: &MemoryNode) -> Result<(), Error> {
+        let parent = target.parent().ok_or(Error::MissingParent)?;
+        if target == replacement {
+            return Ok(());
+        }
+        if self.contains(replacement, &parent) {
+            return Err(invalid(replacement, "insert a node into itself,"));
+        }
+        replacement.detach();
+        let index = parent
+            .index_of(target)
+            .expect("the target must be a child of its parent");
+        target.detach();
+        parent.insert_at(index, replacement);
+        Ok(())
+    }
+
+    fn attach_shadow(&self, host: &MemoryNode) -> Result<MemoryNode, Error> {
+        let shadow_root = MemoryNode::new(NodeKind::ShadowRoot {
+            host: Rc::downgrade(&host.0),
+        });
+        let mut data = host.0.borrow_mut();
+        match &mut data.kind {
+            NodeKind::Element {
+                shadow_root: attached @ None,
+                ..
+            } => {
+                *attached = Some(shadow_root.clone());
+                Ok(shadow_root)
+            }
+            _ => {
+                drop(data);
+                Err(invalid(host, "attach a shadow root to"))
+            }
+        }
+    }
+
+    fn add_event_listener(
+        &self,
+        target: &MemoryNode,
+        event_name: &'static str,
+        options: &ListenerOptions,
+        callback: Rc<dyn Fn(Event)>,
+    ) -> Result<MemoryListener, Error> {
+        target.0.borrow_mut().listeners.push(AttachedListener {
+            event_name: event_name.to_string(),
+            options: *options,
+            callback: Rc::downgrade(&callback),
+        });
+        Ok(MemoryListener { callback })
+    }
+
+    fn remove_event_listener(
+        &self,
+        target: &MemoryNode,
+        event_name: &str,
+        listener: &MemoryListener,
+    ) -> Result<(), Error> {
+        let callback = Rc::as_ptr(&listener.callback) as *const ();
+        target.0.borrow_mut().listeners.retain(|attached| {
+            attached.event_name != event_name || attached.callback.as_ptr() as *const () != callback
+        });
+        Ok(())
+    }
+
+    fn dispatch_event(&self, target: &MemoryNode, event_name: &str) -> Result<(), Error> {
+        // the events dispatched by the program don't bubble
+        let event = Event::Synthetic(SyntheticEvent::default());
+        let path: Vec<MemoryNode> =
+            std::iter::successors(Some(target.clone()), MemoryNode::parent).collect();
+        let stopped = path
+            .iter()
+            .skip(1)
+            .rev()
+            .any(|node| node.call_listeners(event_name, &event, |options| options.capture));
+        if !stopped {
+            target.call_listeners(event_name, &event, |_| true);
+        }
+        Ok(())
+    }
+
+    fn now(&self) -> f64 {
+        self.document.clock.get()
+    }
+
+    fn spawn_local(&self, work: LocalBoxFuture<'static, ()>) {
+        self.document
+            .spawner
+            .spawn_local(work)
+            .expect("must spawn the work");
+    }
+
+    fn request_animation_frame(
+        &self,
+        callback: Box<dyn FnOnce()>,
+    ) -> Result<MemoryCallbackHandle, Error> {
+        let (frame, handle) = scheduled(callback);
+        self.document.animation_frames.borrow_mut().push_back(frame);
+        Ok(handle)
+    }
+
+    fn request_idle_callback(
+        &self,
+        callback: Box<dyn FnOnce(Option<IdleDeadline>)>,
+    ) -> Result<MemoryCallbackHandle, Error> {
+        let (idle, handle) = scheduled(callback);
+        self.document.idle_callbacks.borrow_mut().push_back(idle);
+        Ok(handle)
+    }
+}
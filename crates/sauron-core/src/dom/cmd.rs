@@ -2,8 +2,11 @@
 //! when the application starts or after the application updates.
 //!
 use crate::dom::Program;
-use crate::dom::{Application, Effects, Modifier, Task};
-use wasm_bindgen_futures::spawn_local;
+use crate::dom::{
+    AbortHandle, Application, Callback, DomBackend, Effects, Modifier, Priority, Task,
+};
+use futures::future::{join_all, LocalBoxFuture};
+use std::rc::Rc;
 
 /// Cmd is a command to be executed by the system.
 /// This is returned at the init function of a component and is executed right
@@ -15,12 +18,192 @@ pub struct Cmd<APP, MSG>
 where
     MSG: 'static,
 {
-    /// the functions that would be executed when this Cmd is emited
-    #[allow(clippy::type_complexity)]
-    pub(crate) commands: Vec<Box<dyn FnOnce(Program<APP, MSG>)>>,
+    /// the commands that would be executed when this Cmd is emited
+    pub(crate) commands: Vec<Command<APP, MSG>>,
     pub(crate) modifier: Modifier,
 }
 
+/// the asynchronous part of a command, `None` if the command completes right away
+type Work = Option<LocalBoxFuture<'static, ()>>;
+
+/// dispatch the msgs into the program, with the priority of the command if it has one
+type Dispatch<MSG> = Callback<(Vec<MSG>, Option<Priority>), ()>;
+
+/// A unit of work of a Cmd
+pub(crate) enum Command<APP, MSG>
+where
+    MSG: 'static,
+{
+    /// a command which operates on the program
+    Program(Box<dyn FnOnce(Program<APP, MSG>) -> Work>),
+    /// a command which only dispatches msgs
+    Msg(MsgCommand<MSG>),
+}
+
+/// A unit of work of a [`MsgCmd`], it only dispatches msgs and can be mapped into a different MSG
+pub(crate) enum MsgCommand<MSG>
+where
+    MSG: 'static,
+{
+    /// a command which dispatches msgs with the default priority of the program
+    Msg(Box<dyn FnOnce(Dispatch<MSG>) -> Work>),
+    /// a command which dispatches msgs with the priority
+    Prioritized(Priority, Box<dyn FnOnce(Dispatch<MSG>) -> Work>),
+}
+
+/// the command which dispatches the msgs mapped with `f`
+fn map_dispatch<MSG, MSG2>(
+    g: Box<dyn FnOnce(Dispatch<MSG>) -> Work>,
+    f: Rc<dyn Fn(MSG) -> MSG2>,
+) -> Box<dyn FnOnce(Dispatch<MSG2>) -> Work>
+where
+    MSG: 'static,
+    MSG2: 'static,
+{
+    Box::new(move |dispatch: Dispatch<MSG2>| {
+        g(Callback::from(
+            move |(msgs, priority): (Vec<MSG>, Option<Priority>)| {
+                dispatch.emit((msgs.into_iter().map(|msg| f(msg)).collect(), priority))
+            },
+        ))
+    })
+}
+
+/// the dispatch which uses the priority for the msgs that don't have one yet,
+/// such that the priority of an inner command is kept
+fn prioritize<MSG>(dispatch: Dispatch<MSG>, priority: Priority) -> Dispatch<MSG>
+where
+    MSG: 'static,
+{
+    Callback::from(move |(msgs, inner): (Vec<MSG>, Option<Priority>)| {
+        dispatch.emit((msgs, inner.or(Some(priority))))
+    })
+}
+
+/// the work which stops when the handle is aborted
+fn abortable_work(
+    handle: AbortHandle,
+    work: LocalBoxFuture<'static, ()>,
+) -> LocalBoxFuture<'static, ()> {
+    Box::pin(async move {
+        handle.wrap(work).await;
+    })
+}
+
+impl<APP, MSG> Command<APP, MSG>
+where
+    MSG: 'static,
+    APP: Application<MSG> + 'static,
+{
+    /// run the command in the program, the commands which operate on the program only run
+    /// in the browser DOM
+    fn run<DOM: DomBackend>(self, program: Program<APP, MSG, DOM>) -> Work {
+        match self {
+            Command::Program(f) => match program.as_web() {
+                Some(program) => f(program),
+                None => {
+                    log::warn!("the commands of Cmd::new can only run in the browser DOM");
+                    None
+                }
+            },
+            Command::Msg(command) => command.run(Callback::from(
+                move |(msgs, priority): (Vec<MSG>, Option<Priority>)| match priority {
+                    Some(priority) => program
+                        .clone()
+                        .dispatch_multiple_with_priority(msgs, priority),
+                    None => program.clone().dispatch_multiple(msgs),
+                },
+            )),
+        }
+    }
+
+    fn with_priority(self, priority: Priority) -> Self {
+        match self {
+            Command::Msg(command) => Command::Msg(command.with_priority(priority)),
+            command => command,
+        }
+    }
+
+    fn abortable(self, handle: AbortHandle) -> Self {
+        match self {
+            Command::Program(f) => Command::Program(Box::new(move |program| {
+                if handle.is_aborted() {
+                    return None;
+                }
+                f(program).map(|work| abortable_work(handle, work))
+            })),
+            Command::Msg(command) => Command::Msg(command.abortable(handle)),
+        }
+    }
+}
+
+impl<MSG> MsgCommand<MSG>
+where
+    MSG: 'static,
+{
+    /// run the command with the dispatch, the msgs of a prioritized command carry its priority
+    pub(crate) fn run(self, dispatch: Dispatch<MSG>) -> Work {
+        match self {
+            MsgCommand::Msg(f) => f(dispatch),
+            MsgCommand::Prioritized(priority, f) => f(prioritize(dispatch, priority)),
+        }
+    }
+
+    fn map_msg<MSG2>(self, f: Rc<dyn Fn(MSG) -> MSG2>) -> MsgCommand<MSG2>
+    where
+        MSG2: 'static,
+    {
+        match self {
+            MsgCommand::Msg(g) => MsgCommand::Msg(map_dispatch(g, f)),
+            MsgCommand::Prioritized(priority, g) => {
+                MsgCommand::Prioritized(priority, map_dispatch(g, f))
+            }
+        }
+    }
+
+    fn with_priority(self, priority: Priority) -> Self {
+        match self {
+            MsgCommand::Msg(f) => MsgCommand::Prioritized(priority, f),
+            command => command,
+        }
+    }
+
+    fn abortable(self, handle: AbortHandle) -> Self {
+        match self {
+            MsgCommand::Prioritized(priority, f) => {
+                MsgCommand::Msg(f).abortable(handle).with_priority(priority)
+            }
+            MsgCommand::Msg(f) => MsgCommand::Msg(Box::new(move |dispatch: Dispatch<MSG>| {
+                if handle.is_aborted() {
+                    return None;
+                }
+                let abort = handle.clone();
+                let work = f(Callback::from(move |msgs: (Vec<MSG>, Option<Priority>)| {
+                    if !abort.is_aborted() {
+                        dispatch.emit(msgs)
+                    }
+                }));
+                work.map(|work| abortable_work(handle, work))
+            })),
+        }
+    }
+
+    /// the command which runs each group of commands after the previous group has completed,
+    /// the commands keep their priority
+    fn sequence(groups: Vec<Vec<Self>>) -> Self {
+        MsgCommand::Msg(Box::new(move |dispatch: Dispatch<MSG>| -> Work {
+            Some(Box::pin(async move {
+                for commands in groups {
+                    let works = commands
+                        .into_iter()
+                        .filter_map(|command| command.run(dispatch.clone()));
+                    join_all(works).await;
+                }
+            }))
+        }))
+    }
+}
+
 impl<APP, MSG> Cmd<APP, MSG>
 where
     MSG: 'static,
@@ -32,7 +215,10 @@ where
         F: FnOnce(Program<APP, MSG>) + 'static,
     {
         Self {
-            commands: vec![Box::new(f)],
+            commands: vec![Command::Program(Box::new(move |program| {
+                f(program);
+                None
+            }))],
             modifier: Default::default(),
         }
     }
@@ -97,10 +283,11 @@ where
     }
 
     /// Executes the Cmd
-    pub(crate) fn emit(self, program: Program<APP, MSG>) {
-        for cb in self.commands {
-            let program_clone = program.clone();
-            cb(program_clone);
+    pub(crate) fn emit<DOM: DomBackend>(self, program: Program<APP, MSG, DOM>) {
+        for command in self.commands {
+            if let Some(work) = command.run(program.clone()) {
+                program.backend.spawn_local(work);
+            }
         }
     }
 
@@ -108,11 +295,122 @@ where
     /// A single call to update the view is then executed thereafter.
     ///
     pub fn batch_msg(msg_list: impl IntoIterator<Item = MSG>) -> Self {
-        let msg_list: Vec<MSG> = msg_list.into_iter().collect();
-        Cmd::new(move |mut program| {
-            program.dispatch_multiple(msg_list);
+        Self::from(MsgCmd::batch_msg(msg_list))
+    }
+
+    /// returns true if all the commands only dispatch msgs
+    fn is_msg_only(&self) -> bool {
+        self.commands
+            .iter()
+            .all(|command| matches!(command, Command::Msg(_)))
+    }
+
+    /// the commands which only dispatch msgs, `Err` with the cmd if some of its commands
+    /// operate on the program
+    fn into_msg_cmd(self) -> Result<MsgCmd<MSG>, Self> {
+        if !self.is_msg_only() {
+            return Err(self);
+        }
+        Ok(MsgCmd {
+            commands: self
+                .commands
+                .into_iter()
+                .filter_map(|command| match command {
+                    Command::Msg(command) => Some(command),
+                    Command::Program(_) => None,
+                })
+                .collect(),
+            modifier: self.modifier,
         })
     }
+
+    /// Execute the cmds one after the other, each cmd waits for the tasks of the previous cmd
+    /// to complete before it is executed.
+    /// The commands inside each cmd are executed concurrently just like in [`Cmd::batch`].
+    ///
+    /// Note: work that is spawned inside a [`Cmd::new`] is not awaited.
+    pub fn sequence(cmds: impl IntoIterator<Item = Self>) -> Self {
+        let cmds: Vec<Self> = cmds.into_iter().collect();
+        let mut modifier = Modifier::default();
+        for cmd in cmds.iter() {
+            modifier.coalesce(&cmd.modifier);
+        }
+        if cmds.iter().all(Self::is_msg_only) {
+            let msg_cmds = cmds.into_iter().filter_map(|cmd| cmd.into_msg_cmd().ok());
+            return Self::from(MsgCmd::sequence(msg_cmds));
+        }
+        let command = Command::Program(Box::new(move |program: Program<APP, MSG>| -> Work {
+            Some(Box::pin(async move {
+                for cmd in cmds {
+                    let works = cmd
+                        .commands
+                        .into_iter()
+                        .filter_map(|command| command.run(program.clone()));
+                    join_all(works).await;
+                }
+            }))
+        }));
+        Self {
+            commands: vec![command],
+            modifier,
+        }
+    }
+
+    /// Execute the `next` cmd after the tasks of this cmd are completed
+    pub fn and_then(self, next: Self) -> Self {
+        Self::sequence([self, next])
+    }
+
+    /// Dispatch the msgs of this Cmd with the priority, instead of the default priority of the
+    /// program. Such as the results of a background task which should not delay the input.
+    ///
+    /// Note: the commands created with [`Cmd::new`] dispatch their msgs themselves and are not
+    /// affected.
+    pub fn with_priority(self, priority: Priority) -> Self {
+        Self {
+            commands: self
+                .commands
+                .into_iter()
+                .map(|command| command.with_priority(priority))
+                .collect(),
+            modifier: self.modifier,
+        }
+    }
+
+    /// Make the commands of this Cmd abortable with the returned [`AbortHandle`].
+    /// This is useful for cancelling the in-flight request which has been replaced by a newer
+    /// one, such as in search-as-you-type.
+    /// # Example
+    /// ```rust,ignore
+    /// fn update(&mut self, msg: Msg) -> Cmd<Self, Msg> {
+    ///     match msg {
+    ///         Msg::Search(query) => {
+    ///             if let Some(previous) = self.search.take() {
+    ///                 previous.abort();
+    ///             }
+    ///             let (cmd, handle) = Cmd::from(Task::new(search(query))).abortable();
+    ///             self.search = Some(handle);
+    ///             cmd
+    ///         }
+    ///         Msg::SearchResult(result) => {
+    ///             self.result = result;
+    ///             Cmd::none()
+    ///         }
+    ///     }
+    /// }
+    /// ```
+    pub fn abortable(self) -> (Self, AbortHandle) {
+        let handle = AbortHandle::default();
+        let cmd = Self {
+            commands: self
+                .commands
+                .into_iter()
+                .map(|command| command.abortable(handle.clone()))
+                .collect(),
+            modifier: self.modifier,
+        };
+        (cmd, handle)
+    }
 }
 
 impl<APP, MSG> From<Effects<MSG, ()>> for Cmd<APP, MSG>
@@ -122,17 +420,7 @@ where
 {
     /// Convert Effects that has only follow ups
     fn from(effects: Effects<MSG, ()>) -> Self {
-        // we can safely ignore the effects here
-        // as there is no content on it.
-        let Effects {
-            local,
-            external: _,
-            modifier,
-        } = effects;
-
-        let mut cmd = Cmd::batch(local.into_iter().map(Cmd::from));
-        cmd.modifier = modifier;
-        cmd
+        Self::from(MsgCmd::from(effects))
     }
 }
 
@@ -151,14 +439,196 @@ impl<APP, MSG> From<Task<MSG>> for Cmd<APP, MSG>
 where
     MSG: 'static,
     APP: Application<MSG> + 'static,
+{
+    fn from(task: Task<MSG>) -> Self {
+        Self::from(MsgCmd::from(task))
+    }
+}
+
+impl<APP, MSG> From<MsgCmd<MSG>> for Cmd<APP, MSG>
+where
+    MSG: 'static,
+    APP: Application<MSG> + 'static,
+{
+    fn from(cmd: MsgCmd<MSG>) -> Self {
+        Self {
+            commands: cmd.commands.into_iter().map(Command::Msg).collect(),
+            modifier: cmd.modifier,
+        }
+    }
+}
+
+/// MsgCmd is a command which only dispatches msgs, such as the tasks and the follow ups of the
+/// effects. Unlike [`Cmd`], it doesn't operate on the Program, as such it can be mapped into a
+/// different MSG, which is used to lift the commands of a child into its parent.
+/// It is converted into a [`Cmd`] with `Cmd::from`.
+/// # Example
+/// ```rust,ignore
+/// impl Child {
+///     fn update_child(&mut self, msg: ChildMsg) -> MsgCmd<ChildMsg> {
+///         MsgCmd::from(Task::new(fetch_page(self.page)))
+///     }
+/// }
+///
+/// impl Application<Msg> for Parent {
+///     fn update(&mut self, msg: Msg) -> Cmd<Self, Msg> {
+///         match msg {
+///             Msg::Child(msg) => Cmd::from(self.child.update_child(msg).map_msg(Msg::Child)),
+///         }
+///     }
+/// }
+/// ```
+pub struct MsgCmd<MSG>
+where
+    MSG: 'static,
+{
+    pub(crate) commands: Vec<MsgCommand<MSG>>,
+    pub(crate) modifier: Modifier,
+}
+
+impl<MSG> MsgCmd<MSG>
+where
+    MSG: 'static,
+{
+    /// Tell the runtime that there are no commands.
+    pub fn none() -> Self {
+        Self {
+            commands: vec![],
+            modifier: Default::default(),
+        }
+    }
+
+    /// Execute the cmds concurrently, just like [`Cmd::batch`]
+    pub fn batch(cmds: impl IntoIterator<Item = Self>) -> Self {
+        let mut commands = vec![];
+        let mut modifier = Modifier::default();
+        for cmd in cmds {
+            modifier.coalesce(&cmd.modifier);
+            commands.extend(cmd.commands);
+        }
+        Self { commands, modifier }
+    }
+
+    /// Dispatch the msgs, just like [`Cmd::batch_msg`]
+    pub fn batch_msg(msg_list: impl IntoIterator<Item = MSG>) -> Self {
+        let msg_list: Vec<MSG> = msg_list.into_iter().collect();
+        Self {
+            commands: vec![MsgCommand::Msg(Box::new(move |dispatch: Dispatch<MSG>| {
+                dispatch.emit((msg_list, None));
+                None
+            }))],
+            modifier: Default::default(),
+        }
+    }
+
+    /// Execute the cmds one after the other, just like [`Cmd::sequence`].
+    /// The commands keep the priority they were given with [`MsgCmd::with_priority`].
+    pub fn sequence(cmds: impl IntoIterator<Item = Self>) -> Self {
+        let mut modifier = Modifier::default();
+        let mut groups = vec![];
+        for cmd in cmds {
+            modifier.coalesce(&cmd.modifier);
+            groups.push(cmd.commands);
+        }
+        Self {
+            commands: vec![MsgCommand::sequence(groups)],
+            modifier,
+        }
+    }
+
+    /// Execute the `next` cmd after the tasks of this cmd are completed
+    pub fn and_then(self, next: Self) -> Self {
+        Self::sequence([self, next])
+    }
+
+    /// Modify the command such that it will not do an update on the view when it is executed.
+    pub fn no_render(mut self) -> Self {
+        self.modifier.should_update_view = false;
+        self
+    }
+
+    /// Map the msgs dispatched by this cmd into a different MSG,
+    /// this is used to lift the cmd of a child into the cmd of the parent.
+    /// The mapped msgs keep their priority.
+    pub fn map_msg<F, MSG2>(self, f: F) -> MsgCmd<MSG2>
+    where
+        F: Fn(MSG) -> MSG2 + 'static,
+        MSG2: 'static,
+    {
+        let f: Rc<dyn Fn(MSG) -> MSG2> = Rc::new(f);
+        MsgCmd {
+            commands: self
+                .commands
+                .into_iter()
+                .map(|command| command.map_msg(Rc::clone(&f)))
+                .collect(),
+            modifier: self.modifier,
+        }
+    }
+
+    /// Dispatch the msgs of this cmd with the priority, just like [`Cmd::with_priority`]
+    pub fn with_priority(self, priority: Priority) -> Self {
+        Self {
+            commands: self
+                .commands
+                .into_iter()
+                .map(|command| command.with_priority(priority))
+                .collect(),
+            modifier: self.modifier,
+        }
+    }
+
+    /// Make the commands of this cmd abortable, just like [`Cmd::abortable`]
+    pub fn abortable(self) -> (Self, AbortHandle) {
+        let handle = AbortHandle::default();
+        let cmd = Self {
+            commands: self
+                .commands
+                .into_iter()
+                .map(|command| command.abortable(handle.clone()))
+                .collect(),
+            modifier: self.modifier,
+        };
+        (cmd, handle)
+    }
+}
+
+impl<MSG> From<Effects<MSG, ()>> for MsgCmd<MSG>
+where
+    MSG: 'static,
+{
+    /// Convert Effects that has only follow ups
+    fn from(effects: Effects<MSG, ()>) -> Self {
+        // we can safely ignore the effects here
+        // as there is no content on it.
+        let Effects {
+            local,
+            external: _,
+            modifier,
+        } = effects;
+
+        let mut cmd = MsgCmd::batch(local.into_iter().map(MsgCmd::from));
+        cmd.modifier = modifier;
+        cmd
+    }
+}
+
+impl<MSG> From<Task<MSG>> for MsgCmd<MSG>
+where
+    MSG: 'static,
 {
     fn from(task: Task<MSG>) -> Self {
         let task = task.task;
-        Cmd::new(move |mut program| {
-            spawn_local(async move {
-                let msg = task.await;
-                program.dispatch(msg)
-            });
-        })
+        Self {
+            commands: vec![MsgCommand::Msg(Box::new(
+                move |dispatch: Dispatch<MSG>| -> Work {
+                    Some(Box::pin(async move {
+                        let msg = task.await;
+                        dispatch.emit((vec![msg], None))
+                    }))
+                },
+            ))],
+            modifier: Default::default(),
+        }
     }
 }
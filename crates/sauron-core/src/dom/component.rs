@@ -1,6 +1,12 @@
 use crate::html::attributes::{class, classes, Attribute};
 use crate::{dom::Effects, vdom::Node};
 
+pub use stateful::StatefulComponent;
+#[cfg(all(feature = "with-dom", feature = "with-inspector"))]
+pub(crate) use stateful::{ComponentApp, ComponentMsg};
+
+mod stateful;
+
 /// A component has a view and can update itself.
 ///
 /// The update function returns an effect which can contain
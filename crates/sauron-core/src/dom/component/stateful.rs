@@ -0,0 +1,267 @@
+//! Stateful components which are declared inline in the view.
+//!
+//! The component is carried in an attribute of its host element. It is mounted into the host
+//! the first time the host is created, then the component keeps its own state and updates its
+//! own view, while the external msgs are mapped into the msgs of the parent.
+//! The instances created by the subsequent views of the parent are ignored, as long as the
+//! host is not replaced. Use a `key` in the host to recreate the component when it changes.
+use crate::dom::Component;
+use crate::render::Render;
+use std::{any::TypeId, cell::RefCell, fmt, marker::PhantomData, rc::Rc};
+
+#[cfg(feature = "with-dom")]
+use crate::dom::{spawn_local, Application, Cmd, Effects, Program};
+#[cfg(feature = "with-dom")]
+use crate::vdom::{map_msg::NodeMapMsg, Node};
+#[cfg(feature = "with-dom")]
+use std::any::Any;
+
+/// A component with its own state, which is owned by the program that mounts it.
+/// Create it with [`html::component`](crate::html::component).
+pub struct StatefulComponent<MSG> {
+    type_id: TypeId,
+    mount: Rc<dyn Mount<MSG>>,
+}
+
+/// mounts the component into its host element
+trait Mount<MSG> {
+    /// render the initial view of the component
+    fn render(&self, buffer: &mut dyn fmt::Write, indent: usize, compressed: bool) -> fmt::Result;
+
+    /// mount the component into the host, the external msgs are passed into `dispatch`.
+    /// The component is unmounted when the returned value is dropped.
+    #[cfg(feature = "with-dom")]
+    fn mount(&self, host: &web_sys::Node, dispatch: Rc<dyn Fn(MSG)>) -> Option<Box<dyn Any>>;
+}
+
+/// the component which is not yet mounted
+struct ComponentMount<COMP, CMSG, XMSG, MSG> {
+    component: RefCell<Option<COMP>>,
+    #[cfg_attr(not(feature = "with-dom"), allow(dead_code))]
+    map: Rc<dyn Fn(XMSG) -> MSG>,
+    _component_msg: PhantomData<CMSG>,
+}
+
+/// maps the msgs of the mounted component
+struct MapMsg<MSG, MSG2> {
+    mount: Rc<dyn Mount<MSG>>,
+    #[cfg_attr(not(feature = "with-dom"), allow(dead_code))]
+    map: Rc<dyn Fn(MSG) -> MSG2>,
+}
+
+/// the component running as an application in its own program
+#[cfg(feature = "with-dom")]
+pub(crate) struct ComponentApp<COMP, XMSG> {
+    component: COMP,
+    emit: Rc<dyn Fn(XMSG)>,
+}
+
+/// unmounts the program of the component when dropped
+#[cfg(feature = "with-dom")]
+struct UnmountOnDrop(Box<dyn FnMut()>);
+
+/// the msg of the component app, wrapped such that the app does not overlap with the
+/// applications implemented for the web components
+#[cfg(feature = "with-dom")]
+pub(crate) struct ComponentMsg<CMSG>(pub(crate) CMSG);
+
+impl<MSG> StatefulComponent<MSG>
+where
+    MSG: 'static,
+{
+    /// wrap the component, its external msgs are mapped into MSG with `map`
+    pub fn new<COMP, CMSG, XMSG, F>(component: COMP, map: F) -> Self
+    where
+        COMP: Component<CMSG, XMSG> + 'static,
+        CMSG: 'static,
+        XMSG: 'static,
+        F: Fn(XMSG) -> MSG + 'static,
+    {
+        Self {
+            type_id: TypeId::of::<COMP>(),
+            mount: Rc::new(ComponentMount {
+                component: RefCell::new(Some(component)),
+                map: Rc::new(map),
+                _component_msg: PhantomData,
+            }),
+        }
+    }
+
+    /// map the msg of this component such that `StatefulComponent<MSG>` becomes
+    /// `StatefulComponent<MSG2>`
+    pub fn map_msg<F, MSG2>(self, f: F) -> StatefulComponent<MSG2>
+    where
+        F: Fn(MSG) -> MSG2 + 'static,
+        MSG2: 'static,
+    {
+        StatefulComponent {
+            type_id: self.type_id,
+            mount: Rc::new(MapMsg {
+                mount: self.mount,
+                map: Rc::new(f),
+            }),
+        }
+    }
+}
+
+impl<MSG> StatefulComponent<MSG> {
+    /// render the initial view of the component
+    pub(crate) fn render(
+        &self,
+        buffer: &mut dyn fmt::Write,
+        indent: usize,
+        compressed: bool,
+    ) -> fmt::Result {
+        self.mount.render(buffer, indent, compressed)
+    }
+
+    /// mount the component into the host, returns `None` if the component was already mounted
+    #[cfg(feature = "with-dom")]
+    pub(crate) fn mount(
+        &self,
+        host: &web_sys::Node,
+        dispatch: Rc<dyn Fn(MSG)>,
+    ) -> Option<Box<dyn Any>> {
+        self.mount.mount(host, dispatch)
+    }
+}
+
+impl<MSG> Clone for StatefulComponent<MSG> {
+    fn clone(&self) -> Self {
+        Self {
+            type_id: self.type_id,
+            mount: Rc::clone(&self.mount),
+        }
+    }
+}
+
+impl<MSG> fmt::Debug for StatefulComponent<MSG> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("StatefulComponent")
+            .field("type_id", &self.type_id)
+            .finish()
+    }
+}
+
+/// components of the same type are equal, such that the mounted component is kept
+impl<MSG> PartialEq for StatefulComponent<MSG> {
+    fn eq(&self, other: &Self) -> bool {
+        self.type_id == other.type_id
+    }
+}
+
+impl<COMP, CMSG, XMSG, MSG> Mount<MSG> for ComponentMount<COMP, CMSG, XMSG, MSG>
+where
+    COMP: Component<CMSG, XMSG> + 'static,
+    CMSG: 'static,
+    XMSG: 'static,
+    MSG: 'static,
+{
+    fn render(&self, buffer: &mut dyn fmt::Write, indent: usize, compressed: bool) -> fmt::Result {
+        match self.component.borrow().as_ref() {
+            Some(component) => component
+                .view()
+                .render_with_indent(buffer, indent, compressed),
+            None => Ok(()),
+        }
+    }
+
+    #[cfg(feature = "with-dom")]
+    fn mount(&self, host: &web_sys::Node, dispatch: Rc<dyn Fn(MSG)>) -> Option<Box<dyn Any>> {
+        let component = self.component.borrow_mut().take()?;
+        let map = Rc::clone(&self.map);
+        let app = ComponentApp::new(component, Rc::new(move |xmsg| dispatch(map(xmsg))));
+        let program = Program::clear_append_to_mount(app, host);
+        let mut program = std::mem::ManuallyDrop::into_inner(program);
+        Some(Box::new(UnmountOnDrop(Box::new(move || program.unmount()))))
+    }
+}
+
+impl<MSG, MSG2> Mount<MSG2> for MapMsg<MSG, MSG2>
+where
+    MSG: 'static,
+    MSG2: 'static,
+{
+    fn render(&self, buffer: &mut dyn fmt::Write, indent: usize, compressed: bool) -> fmt::Result {
+        self.mount.render(buffer, indent, compressed)
+    }
+
+    #[cfg(feature = "with-dom")]
+    fn mount(&self, host: &web_sys::Node, dispatch: Rc<dyn Fn(MSG2)>) -> Option<Box<dyn Any>> {
+        let map = Rc::clone(&self.map);
+        self.mount
+            .mount(host, Rc::new(move |msg| dispatch(map(msg))))
+    }
+}
+
+#[cfg(feature = "with-dom")]
+impl Drop for UnmountOnDrop {
+    fn drop(&mut self) {
+        (self.0)()
+    }
+}
+
+#[cfg(feature = "with-dom")]
+impl<COMP, XMSG> ComponentApp<COMP, XMSG>
+where
+    XMSG: 'static,
+{
+    /// run the component as an application, its external msgs are passed into `emit`
+    pub(crate) fn new(component: COMP, emit: Rc<dyn Fn(XMSG)>) -> Self {
+        Self { component, emit }
+    }
+
+    /// the local effects are dispatched into the component, while the external effects are
+    /// emitted to the parent
+    fn effects_to_cmd<CMSG>(&self, effects: Effects<CMSG, XMSG>) -> Cmd<Self, ComponentMsg<CMSG>>
+    where
+        COMP: Component<CMSG, XMSG> + 'static,
+        CMSG: 'static,
+    {
+        let Effects {
+            local,
+            external,
+            modifier,
+        } = effects;
+        let external = external.into_iter().map(|task| {
+            let emit = Rc::clone(&self.emit);
+            Cmd::new(move |_program| spawn_local(async move { emit(task.await) }))
+        });
+        let local = local
+            .into_iter()
+            .map(|task| Cmd::from(task.map_msg(ComponentMsg)));
+        let mut cmd = Cmd::batch(local.chain(external));
+        cmd.modifier = modifier;
+        cmd
+    }
+}
+
+#[cfg(feature = "with-dom")]
+impl<COMP, CMSG, XMSG> Application<ComponentMsg<CMSG>> for ComponentApp<COMP, XMSG>
+where
+    COMP: Component<CMSG, XMSG> + 'static,
+    CMSG: 'static,
+    XMSG: 'static,
+{
+    fn init(&mut self) -> Cmd<Self, ComponentMsg<CMSG>> {
+        let effects = self.component.init();
+        self.effects_to_cmd(effects)
+    }
+
+    fn update(&mut self, ComponentMsg(msg): ComponentMsg<CMSG>) -> Cmd<Self, ComponentMsg<CMSG>> {
+        let effects = self.component.update(msg);
+        self.effects_to_cmd(effects)
+    }
+
+    fn view(&self) -> Node<ComponentMsg<CMSG>> {
+        self.component.view().map_msg(ComponentMsg)
+    }
+
+    fn stylesheet() -> Vec<String> {
+        COMP::stylesheet()
+    }
+
+    fn style(&self) -> Vec<String> {
+        self.component.style()
+    }
+}
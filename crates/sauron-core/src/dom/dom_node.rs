@@ -1,36 +1,25 @@
 use crate::{
+    dom::backend::{as_web_node, DomBackend},
     dom::events::MountEvent,
-    dom::{self, Application, Program},
-    dom::{document, window},
+    dom::program::MountedComponents,
+    dom::{self, document, Application, Program, StatefulComponent},
     html,
-    html::attributes::{AttributeValue, Listener, SegregatedAttributes},
+    html::attributes::Value,
+    html::attributes::{Listener, ListenerOptions, SegregatedAttributes},
     vdom,
     vdom::{Attribute, Leaf, NodeTrait},
+    Error,
 };
-use js_sys::Function;
 use mt_dom::TreePath;
-use std::collections::HashMap;
-use std::{cell::Cell, collections::BTreeMap};
+use std::{cell::Cell, collections::BTreeMap, rc::Rc};
 use wasm_bindgen::{closure::Closure, JsCast, JsValue};
-use web_sys::{
-    self, Element, HtmlButtonElement, HtmlDataElement, HtmlDetailsElement, HtmlFieldSetElement,
-    HtmlInputElement, HtmlLiElement, HtmlLinkElement, HtmlMeterElement, HtmlOptGroupElement,
-    HtmlOptionElement, HtmlOutputElement, HtmlParamElement, HtmlProgressElement, HtmlSelectElement,
-    HtmlStyleElement, HtmlTextAreaElement, Node, Text,
-};
+use web_sys::{self, AddEventListenerOptions, Node, Text};
 
 /// data attribute name used in assigning the node id of an element with events
 pub(crate) const DATA_VDOM_ID: &str = "data-vdom-id";
 
 thread_local!(static NODE_ID_COUNTER: Cell<usize> = Cell::new(1));
 
-// a cache of commonly used elements, so we can clone them.
-// cloning is much faster then creating the element
-thread_local! {
-    static CACHE_ELEMENTS: HashMap<&'static str, web_sys::Element> =
-        HashMap::from_iter(["div", "span", "ol", "ul", "li"].map(create_element_with_tag));
-}
-
 #[cfg(feature = "with-interning")]
 #[inline(always)]
 pub fn intern(s: &str) -> &str {
@@ -43,26 +32,6 @@ pub fn intern(s: &str) -> &str {
     s
 }
 
-fn create_element_with_tag(tag: &'static str) -> (&'static str, web_sys::Element) {
-    let elm = document().create_element(intern(tag)).unwrap();
-    (tag, elm)
-}
-
-/// find the element from the most created element and clone it, else create it
-/// TODO: feature gate this with `use-cached-elements`
-fn create_element(tag: &'static str) -> web_sys::Element {
-    CACHE_ELEMENTS.with(|map| {
-        if let Some(elm) = map.get(tag) {
-            elm.clone_node_with_deep(false)
-                .expect("must clone node")
-                .unchecked_into()
-        } else {
-            let elm = document().create_element(intern(tag)).unwrap();
-            elm
-        }
-    })
-}
-
 /// This is the value of the data-sauron-vdom-id.
 /// Used to uniquely identify elements that contain closures so that the DomUpdater can
 /// look them up by their unique id.
@@ -76,8 +45,7 @@ fn create_unique_identifier() -> usize {
     })
 }
 
-/// A node along with all of the closures that were created for that
-/// node's events and all of it's child node's events.
+/// The functions which only work in the DOM of the browser
 impl<APP, MSG> Program<APP, MSG>
 where
     MSG: 'static,
@@ -88,52 +56,132 @@ where
         document().create_text_node(txt)
     }
 
-    fn create_document_fragment(&self, nodes: &[vdom::Node<MSG>]) -> Node {
-        let doc_fragment = document().create_document_fragment();
+    /// dispatch the mount event,
+    /// call the listener since browser don't allow asynchronous execution of
+    /// dispatching custom events (non-native browser events)
+    ///
+    pub fn dispatch_mount_event(node: &Node) -> Result<(), Error> {
+        let event_target: &web_sys::EventTarget = node.unchecked_ref();
+        event_target.dispatch_event(&MountEvent::create_web_event())?;
+        Ok(())
+    }
+
+    /// a helper method to append a node to its parent and trigger a mount event if there is any
+    pub fn append_child_and_dispatch_mount_event(
+        parent: &Node,
+        child_node: &Node,
+    ) -> Result<(), Error> {
+        parent.append_child(child_node)?;
+        Self::dispatch_mount_event(child_node)
+    }
+
+    /// attach and event listener to an event target
+    pub fn add_event_listeners(
+        &self,
+        target: &web_sys::EventTarget,
+        event_listeners: Vec<Attribute<MSG>>,
+    ) -> Result<(), JsValue> {
+        for event_attr in event_listeners.into_iter() {
+            for event_cb in event_attr.value() {
+                let listener = event_cb.as_event_listener().expect("expecting a callback");
+                let event_name = *event_attr.name();
+                let closure = self.create_event_closure(listener);
+                self.add_event_closure_with_options(
+                    target,
+                    event_name,
+                    listener.options(),
+                    closure,
+                )?;
+            }
+        }
+        Ok(())
+    }
+
+    /// add a event listener to a target element
+    pub fn add_event_listener(
+        &self,
+        event_target: &web_sys::EventTarget,
+        event_name: &str,
+        listener: &Listener<dom::Event, MSG>,
+    ) -> Result<Closure<dyn FnMut(web_sys::Event)>, JsValue> {
+        let closure = self.create_event_closure(listener);
+        event_target.add_event_listener_with_callback_and_add_event_listener_options(
+            intern(event_name),
+            closure.as_ref().unchecked_ref(),
+            &add_event_listener_options(listener.options()),
+        )?;
+        Ok(closure)
+    }
+
+    /// create the closure which dispatches the msg emitted by the listener
+    fn create_event_closure(
+        &self,
+        listener: &Listener<dom::Event, MSG>,
+    ) -> Closure<dyn FnMut(web_sys::Event)> {
+        let program = Program::downgrade(self);
+        let listener = listener.clone();
+        Closure::new(move |event: web_sys::Event| {
+            let options = listener.options();
+            if options.prevent_default {
+                event.prevent_default();
+            }
+            if options.stop_propagation {
+                event.stop_propagation();
+            }
+            let msg = listener.emit(dom::Event::from(event));
+            if let Some(mut program) = program.upgrade() {
+                program.dispatch(msg);
+            }
+        })
+    }
+}
+
+/// A node along with all of the closures that were created for that
+/// node's events and all of it's child node's events.
+impl<APP, MSG, DOM> Program<APP, MSG, DOM>
+where
+    MSG: 'static,
+    APP: Application<MSG> + 'static,
+    DOM: DomBackend,
+{
+    fn create_document_fragment(&self, nodes: &[vdom::Node<MSG>]) -> Result<DOM::Node, Error> {
+        let doc_fragment = self.backend.create_document_fragment();
         for vnode in nodes {
-            let created_node = self.create_dom_node(vnode);
-            Self::append_child_and_dispatch_mount_event(&doc_fragment, &created_node)
+            let created_node = self.create_dom_node(vnode)?;
+            self.append_and_dispatch_mount(&doc_fragment, &created_node)?;
         }
-        doc_fragment.into()
+        Ok(doc_fragment)
     }
 
-    fn create_leaf_node(&self, leaf: &Leaf) -> Node {
+    fn create_leaf_node(&self, leaf: &Leaf) -> Result<DOM::Node, Error> {
         match leaf {
-            Leaf::Text(txt) => Self::create_text_node(txt).into(),
-            Leaf::Comment(comment) => document().create_comment(comment).into(),
+            Leaf::Text(txt) => Ok(self.backend.create_text_node(txt)),
+            Leaf::Comment(comment) => Ok(self.backend.create_comment(comment)),
+            // the safe html is appended into the element in `create_dom_node`
             Leaf::SafeHtml(_safe_html) => {
-                panic!("safe html must have already been dealt in create_element node");
-            }
-            Leaf::DocType(_doctype) => {
-                panic!(
-                    "It looks like you are using doctype in the middle of an app,
-                    doctype is only used in rendering"
-                );
+                Err(Error::InvalidNode("a safe html outside of an element"))
             }
+            // the doctype is only used in rendering
+            Leaf::DocType(_doctype) => Err(Error::InvalidNode("a doctype")),
         }
     }
 
     /// Create and return a `CreatedNode` instance (containing a DOM `Node`
     /// together with potentially related closures) for this virtual node.
-    pub fn create_dom_node(&self, vnode: &vdom::Node<MSG>) -> Node {
+    pub fn create_dom_node(&self, vnode: &vdom::Node<MSG>) -> Result<DOM::Node, Error> {
         match vnode {
             vdom::Node::Leaf(leaf_node) => self.create_leaf_node(leaf_node),
             vdom::Node::Element(element_node) => {
-                let created_node = self.create_element_node(element_node);
+                let created_node = self.create_element_node(element_node)?;
                 for child in element_node.children().iter() {
                     if let Some(child_text) = child.as_safe_html() {
-                        // https://developer.mozilla.org/en-US/docs/Web/API/Element/insertAdjacentHTML
-                        let created_element: &Element = created_node.unchecked_ref();
-                        created_element
-                            .insert_adjacent_html(intern("beforeend"), child_text)
-                            .expect("must not error");
+                        self.backend.append_html(&created_node, child_text)?;
                     } else {
-                        let created_child = self.create_dom_node(child);
-
-                        Self::append_child_and_dispatch_mount_event(&created_node, &created_child);
+                        let created_child = self.create_dom_node(child)?;
+                        self.append_and_dispatch_mount(&created_node, &created_child)?;
                     }
                 }
-                created_node
+                Ok(created_node)
             }
             vdom::Node::Fragment(nodes) => self.create_document_fragment(nodes),
             // NodeList that goes here is only possible when it is the root_node,
@@ -143,72 +191,90 @@ where
         }
     }
 
-    fn is_custom_element(tag: &str) -> bool {
-        let custom_element = window().custom_elements();
-        let existing = custom_element.get(intern(tag));
-        // define the custom element only when it is not yet defined
-        !existing.is_undefined()
-    }
-
     /// Build a DOM element by recursively creating DOM nodes for this element and it's
     /// children, it's children's children, etc.
-    fn create_element_node(&self, velem: &vdom::Element<MSG>) -> Node {
-        let document = document();
-
-        if Self::is_custom_element(velem.tag()) {
-            //log::info!("This is a custom element: {}", velem.tag());
-        }
-
-        let element = if let Some(namespace) = velem.namespace() {
-            document
-                .create_element_ns(Some(intern(namespace)), intern(velem.tag()))
-                .expect("Unable to create element")
-        } else {
-            create_element(velem.tag())
-        };
+    fn create_element_node(&self, velem: &vdom::Element<MSG>) -> Result<DOM::Node, Error> {
+        let element = self
+            .backend
+            .create_element(velem.tag(), velem.namespace().copied())?;
 
         Self::set_element_attributes(
             self,
             &element,
             &velem.attributes().iter().collect::<Vec<_>>(),
-        );
+        )?;
 
-        element.into()
+        Ok(element)
     }
 
-    /// dispatch the mount event,
-    /// call the listener since browser don't allow asynchronous execution of
-    /// dispatching custom events (non-native browser events)
-    ///
-    pub fn dispatch_mount_event(node: &Node) {
-        let event_target: &web_sys::EventTarget = node.unchecked_ref();
-        assert_eq!(
-            Ok(true),
-            event_target.dispatch_event(&MountEvent::create_web_event())
-        );
+    /// dispatch the mount event to the listeners of the node
+    pub(crate) fn dispatch_mount(&self, node: &DOM::Node) -> Result<(), Error> {
+        self.backend.dispatch_event(node, "mount")
     }
 
-    /// a helper method to append a node to its parent and trigger a mount event if there is any
-    pub fn append_child_and_dispatch_mount_event(parent: &Node, child_node: &Node) {
-        parent
-            .append_child(child_node)
-            .expect("must append child node");
-        Self::dispatch_mount_event(child_node);
+    /// append the node to its parent and dispatch the mount event to it
+    pub(crate) fn append_and_dispatch_mount(
+        &self,
+        parent: &DOM::Node,
+        child_node: &DOM::Node,
+    ) -> Result<(), Error> {
+        self.backend.append_child(parent, child_node)?;
+        self.dispatch_mount(child_node)
     }
 
-    /// clear all children of the element
-    pub(crate) fn clear_children(node: &Node) {
-        while let Some(first_child) = node.first_child() {
-            node.remove_child(&first_child).expect("must remove child");
+    /// dispatch the lifecycle event such as `unmount` to the element and its descendants which
+    /// have a listener of the event, starting from the innermost descendant
+    pub(crate) fn dispatch_lifecycle_event(
+        &self,
+        element: &DOM::Node,
+        event_name: &'static str,
+    ) -> Result<(), Error> {
+        let mut targets: Vec<DOM::Node> = self
+            .backend
+            .elements_with_attribute(element, DATA_VDOM_ID)
+            .into_iter()
+            .rev()
+            .filter(|descendant| self.has_listener(descendant, event_name))
+            .collect();
+        if self.has_listener(element, event_name) {
+            targets.push(element.clone());
+        }
+        for target in targets {
+            self.backend.dispatch_event(&target, event_name)?;
         }
+        Ok(())
+    }
+
+    /// returns true if the element has a listener of the event
+    pub(crate) fn has_listener(&self, element: &DOM::Node, event_name: &str) -> bool {
+        self.vdom_id(element)
+            .and_then(|vdom_id| {
+                self.node_closures
+                    .borrow()
+                    .get(&vdom_id)
+                    .map(|closures| closures.contains_key(event_name))
+            })
+            .unwrap_or(false)
+    }
+
+    /// the unique id of the element which has listeners
+    fn vdom_id(&self, element: &DOM::Node) -> Option<usize> {
+        self.backend
+            .get_attribute(element, DATA_VDOM_ID)
+            .and_then(|vdom_id| vdom_id.parse::<usize>().ok())
     }
 
     /// set the element attribute
-    pub fn set_element_attributes(&self, element: &Element, attrs: &[&Attribute<MSG>]) {
+    pub fn set_element_attributes(
+        &self,
+        element: &DOM::Node,
+        attrs: &[&Attribute<MSG>],
+    ) -> Result<(), Error> {
         let attrs = mt_dom::merge_attributes_of_same_name(attrs);
         for att in attrs {
-            self.set_element_attribute(element, &att);
+            self.set_element_attribute(element, &att)?;
         }
+        Ok(())
     }
 
     /// set the element attribute
@@ -216,7 +282,20 @@ where
     /// Note: this is called in a loop, so setting the attributes, and style will not be on
     /// the same call, but on a subsequent call to each other. Using the if-else-if here for
     /// attributes, style, function_call.
-    pub fn set_element_attribute(&self, element: &Element, attr: &Attribute<MSG>) {
+    pub fn set_element_attribute(
+        &self,
+        element: &DOM::Node,
+        attr: &Attribute<MSG>,
+    ) -> Result<(), Error> {
+        // the lazy marker is only compared by the diff
+        if *attr.name() == html::LAZY {
+            return Ok(());
+        }
+        let set_attribute_error = |error: Error| Error::SetAttribute {
+            tag: self.backend.tag_name(element).unwrap_or_default(),
+            name: attr.name().to_string(),
+            reason: error.to_string(),
+        };
         let SegregatedAttributes {
             listeners,
             plain_values,
@@ -228,75 +307,38 @@ where
         if let Some(merged_plain_values) =
             html::attributes::merge_plain_attributes_values(&plain_values)
         {
+            let attr_name = *attr.name();
             if let Some(namespace) = attr.namespace() {
-                // Warning NOTE: set_attribute_ns should only be called
-                // when you meant to use a namespace
-                // using this with None will error in the browser with:
-                // NamespaceError: An attempt was made to create or change an object in a way which is incorrect with regard to namespaces
-                element
-                    .set_attribute_ns(
-                        Some(intern(namespace)),
-                        intern(attr.name()),
-                        &merged_plain_values,
-                    )
-                    .unwrap_or_else(|_| panic!("Error setting an attribute_ns for {element:?}"));
+                self.backend
+                    .set_attribute(element, attr_name, &merged_plain_values, Some(namespace))
+                    .map_err(set_attribute_error)?;
             } else {
-                let attr_name = attr.name();
-                match *attr_name {
+                let first_value = plain_values.first().and_then(|v| v.get_simple());
+                match attr_name {
                     "value" => {
-                        element
-                            .set_attribute(intern(attr_name), &merged_plain_values)
-                            .unwrap_or_else(|_| {
-                                panic!("Error setting an attribute for {element:?}")
-                            });
-                        Self::set_value_str(element, &merged_plain_values);
-                        Self::set_numeric_values(element, &plain_values);
-                    }
-                    "open" => {
-                        let is_open: bool = plain_values
-                            .first()
-                            .and_then(|v| v.get_simple().and_then(|v| v.as_bool()))
-                            .unwrap_or(false);
-
-                        element
-                            .set_attribute(intern(attr.name()), &is_open.to_string())
-                            .unwrap_or_else(|_| {
-                                panic!("Error setting an attribute for {element:?}")
-                            });
-                        Self::set_open(element, is_open);
+                        self.backend
+                            .set_attribute(element, attr_name, &merged_plain_values, None)
+                            .map_err(set_attribute_error)?;
+                        // the numeric values are also set to the elements such as progress
+                        let value = match first_value {
+                            Some(value) if value.as_f64().is_some() => value.clone(),
+                            _ => Value::from(merged_plain_values),
+                        };
+                        self.backend.set_property(element, attr_name, &value);
                     }
-                    "checked" => {
-                        let is_checked: bool = plain_values
-                            .first()
-                            .and_then(|av| av.get_simple().and_then(|v| v.as_bool()))
-                            .unwrap_or(false);
-
-                        element
-                            .set_attribute(intern(attr_name), &is_checked.to_string())
-                            .unwrap_or_else(|_| {
-                                panic!("Error setting an attribute for {element:?}")
-                            });
-                        Self::set_checked(element, is_checked)
-                    }
-                    "disabled" => {
-                        let is_disabled: bool = plain_values
-                            .first()
-                            .and_then(|av| av.get_simple().and_then(|v| v.as_bool()))
-                            .unwrap_or(false);
-
-                        element
-                            .set_attribute(intern(attr_name), &is_disabled.to_string())
-                            .unwrap_or_else(|_| {
-                                panic!("Error setting an attribute for {element:?}")
-                            });
-                        Self::set_disabled(element, is_disabled);
+                    "open" | "checked" | "disabled" => {
+                        let is_set: bool = first_value.and_then(|v| v.as_bool()).unwrap_or(false);
+
+                        self.backend
+                            .set_attribute(element, attr_name, &is_set.to_string(), None)
+                            .map_err(set_attribute_error)?;
+                        self.backend
+                            .set_property(element, attr_name, &Value::from(is_set));
                     }
                     _ => {
-                        element
-                            .set_attribute(intern(attr_name), &merged_plain_values)
-                            .unwrap_or_else(|_| {
-                                panic!("Error setting an attribute for {element:?}")
-                            });
+                        self.backend
+                            .set_attribute(element, attr_name, &merged_plain_values, None)
+                            .map_err(set_attribute_error)?;
                     }
                 }
             }
@@ -304,15 +346,15 @@ where
             html::attributes::merge_styles_attributes_values(&styles)
         {
             // set the styles
-            element
-                .set_attribute(intern(attr.name()), &merged_styles)
-                .unwrap_or_else(|_| panic!("Error setting an attribute_ns for {element:?}"));
+            self.backend
+                .set_attribute(element, attr.name(), &merged_styles, None)
+                .map_err(set_attribute_error)?;
         } else {
             //if the merged attribute is blank of empty when string is trimmed
             //remove the attribute
-            element
-                .remove_attribute(intern(attr.name()))
-                .expect("must remove attribute");
+            self.backend
+                .remove_attribute(element, attr.name())
+                .map_err(set_attribute_error)?;
         }
 
         // do function calls such as set_inner_html
@@ -320,229 +362,169 @@ where
             html::attributes::merge_plain_attributes_values(&function_calls)
         {
             if *attr.name() == "inner_html" {
-                element.set_inner_html(&merged_func_values);
+                self.backend.set_inner_html(element, &merged_func_values);
             }
         }
 
-        let listener_closures: BTreeMap<&'static str, Closure<dyn FnMut(web_sys::Event)>> =
-            BTreeMap::from_iter(listeners.iter().map(|listener| {
-                let event_name = attr.name();
-                let closure = self
-                    .add_event_listener(element, event_name, listener)
-                    .expect("add listener");
-
-                (*event_name, closure)
-            }));
-
-        if !listeners.is_empty() {
-            let unique_id = create_unique_identifier();
-            // set the data-sauron_vdom-id this will be read later on
-            // when it's time to remove this element and its closures and event listeners
-            element
-                .set_attribute(intern(DATA_VDOM_ID), &unique_id.to_string())
-                .expect("Could not set attribute on element");
-
-            self.node_closures
-                .borrow_mut()
-                .insert(unique_id, listener_closures);
-        }
-    }
+        let listeners: Vec<(&'static str, &Listener<dom::Event, MSG>)> = listeners
+            .into_iter()
+            .map(|listener| (*attr.name(), listener))
+            .collect();
+        self.add_element_listeners(element, &listeners)?;
 
-    /// attach and event listener to an event target
-    pub fn add_event_listeners(
-        &self,
-        target: &web_sys::EventTarget,
-        event_listeners: Vec<Attribute<MSG>>,
-    ) -> Result<(), JsValue> {
-        for event_attr in event_listeners.into_iter() {
-            for event_cb in event_attr.value() {
-                let listener = event_cb.as_event_listener().expect("expecting a callback");
-                let closure = self.add_event_listener(target, event_attr.name(), listener)?;
-                self.event_closures.borrow_mut().push(closure);
-            }
+        for component in attr.value().iter().filter_map(|v| v.as_component()) {
+            self.mount_component(element, component);
         }
         Ok(())
     }
 
-    /// add a event listener to a target element
-    pub fn add_event_listener(
-        &self,
-        event_target: &web_sys::EventTarget,
-        event_name: &str,
-        listener: &Listener<dom::Event, MSG>,
-    ) -> Result<Closure<dyn FnMut(web_sys::Event)>, JsValue> {
-        let program = Program::downgrade(&self);
-        let listener = listener.clone();
-
-        let closure: Closure<dyn FnMut(web_sys::Event)> =
-            Closure::new(move |event: web_sys::Event| {
-                let msg = listener.emit(dom::Event::from(event));
-                let mut program = program.upgrade().expect("must upgrade");
+    /// mount the stateful component into the host element, replacing the component which was
+    /// mounted into it. The external msgs of the component are dispatched into this program.
+    pub(crate) fn mount_component(&self, host: &DOM::Node, component: &StatefulComponent<MSG>) {
+        // the components are programs of the browser DOM
+        let Some(web_host) = as_web_node(host) else {
+            log::warn!("the stateful components can only be mounted in the browser DOM");
+            return;
+        };
+        let program = Program::downgrade(self);
+        let dispatch = Rc::new(move |msg| {
+            if let Some(mut program) = program.upgrade() {
                 program.dispatch(msg);
-            });
-
-        event_target.add_event_listener_with_callback(
-            intern(event_name),
-            closure.as_ref().unchecked_ref(),
-        )?;
-        Ok(closure)
-    }
-
-    /// explicitly call `set_checked` function on the html element
-    /// since setting the attribute to false will not unchecked it.
-    ///
-    /// There are only 2 elements where set_checked is applicable:
-    /// - input
-    /// - menuitem
-    fn set_checked(element: &Element, is_checked: bool) {
-        if let Some(input) = element.dyn_ref::<HtmlInputElement>() {
-            input.set_checked(is_checked);
-        }
-    }
-
-    /// explicitly call set_open for details
-    /// since setting the attribute `open` to false will not close it.
-    ///
-    /// TODO: HtmlDialogElement ( but it is not supported on firefox and in safari, only works on chrome)
-    ///
-    /// Applies to:
-    ///  - dialog
-    ///  - details
-    fn set_open(element: &Element, is_open: bool) {
-        if let Some(details) = element.dyn_ref::<HtmlDetailsElement>() {
-            details.set_open(is_open);
-        }
-    }
-
-    /// explicitly call on `set_disabled`
-    /// since setting the attribute `disabled` false will not enable it.
-    ///
-    /// These are 10 elements that we can call `set_disabled` function to.
-    /// - input
-    /// - button
-    /// - textarea
-    /// - style
-    /// - link
-    /// - select
-    /// - option
-    /// - optgroup
-    /// - fieldset
-    /// - menuitem
-    ///
-    /// TODO: use macro to simplify this code
-    fn set_disabled(element: &Element, is_disabled: bool) {
-        if let Some(elm) = element.dyn_ref::<HtmlInputElement>() {
-            elm.set_disabled(is_disabled);
-        } else if let Some(elm) = element.dyn_ref::<HtmlButtonElement>() {
-            elm.set_disabled(is_disabled);
-        } else if let Some(elm) = element.dyn_ref::<HtmlTextAreaElement>() {
-            elm.set_disabled(is_disabled);
-        } else if let Some(elm) = element.dyn_ref::<HtmlStyleElement>() {
-            elm.set_disabled(is_disabled);
-        } else if let Some(elm) = element.dyn_ref::<HtmlLinkElement>() {
-            elm.set_disabled(is_disabled);
-        } else if let Some(elm) = element.dyn_ref::<HtmlSelectElement>() {
-            elm.set_disabled(is_disabled);
-        } else if let Some(elm) = element.dyn_ref::<HtmlOptionElement>() {
-            elm.set_disabled(is_disabled);
-        } else if let Some(elm) = element.dyn_ref::<HtmlOptGroupElement>() {
-            elm.set_disabled(is_disabled);
-        } else if let Some(elm) = element.dyn_ref::<HtmlFieldSetElement>() {
-            elm.set_disabled(is_disabled);
-        }
-    }
-
-    /// we explicitly call the `set_value` function in the html element
-    ///
-    /// TODO: use macro to simplify this code
-    fn set_value_str(element: &Element, value: &str) {
-        if let Some(elm) = element.dyn_ref::<HtmlInputElement>() {
-            elm.set_value(value);
-        } else if let Some(elm) = element.dyn_ref::<HtmlTextAreaElement>() {
-            elm.set_value(value);
-        } else if let Some(elm) = element.dyn_ref::<HtmlSelectElement>() {
-            elm.set_value(value);
-        } else if let Some(elm) = element.dyn_ref::<HtmlOptionElement>() {
-            elm.set_value(value);
-        } else if let Some(elm) = element.dyn_ref::<HtmlButtonElement>() {
-            elm.set_value(value);
-        } else if let Some(elm) = element.dyn_ref::<HtmlDataElement>() {
-            elm.set_value(value);
-        } else if let Some(elm) = element.dyn_ref::<HtmlOutputElement>() {
-            elm.set_value(value);
-        } else if let Some(elm) = element.dyn_ref::<HtmlParamElement>() {
-            elm.set_value(value);
+            }
+        });
+        if let Some(mounted) = component.mount(web_host, dispatch) {
+            let mut components = self.components.borrow_mut();
+            components.retain(|(mounted_host, _)| mounted_host != host);
+            components.push((host.clone(), mounted));
+        } else {
+            log::warn!("the component in {host:?} is already mounted");
         }
     }
 
-    fn set_value_i32(element: &Element, value: i32) {
-        if let Some(elm) = element.dyn_ref::<HtmlLiElement>() {
-            elm.set_value(value);
-        }
+    /// unmount the stateful components which are mounted into the node or its descendants
+    pub(crate) fn unmount_components(&self, node: &DOM::Node) {
+        let unmounted: Vec<_> = {
+            let mut components = self.components.borrow_mut();
+            let (unmounted, retained) = components
+                .drain(..)
+                .partition(|(host, _)| self.backend.contains(node, host));
+            *components = retained;
+            unmounted
+        };
+        // the components are dropped outside of the borrow
+        drop(unmounted);
     }
 
-    fn set_value_f64(element: &Element, value: f64) {
-        if let Some(elm) = element.dyn_ref::<HtmlMeterElement>() {
-            elm.set_value(value);
-        } else if let Some(elm) = element.dyn_ref::<HtmlProgressElement>() {
-            elm.set_value(value);
+    /// attach the listeners to the element, the closures are stored in `node_closures`
+    /// keyed with a unique id which is set in the `data-vdom-id` attribute of the element.
+    /// With the `with-event-delegation` feature, only the lifecycle listeners are attached
+    /// to the element, the rest are delegated to the mount node.
+    pub(crate) fn add_element_listeners(
+        &self,
+        element: &DOM::Node,
+        listeners: &[(&'static str, &Listener<dom::Event, MSG>)],
+    ) -> Result<(), Error> {
+        #[cfg(feature = "with-event-delegation")]
+        let delegated_program = self.as_web();
+        #[cfg(feature = "with-event-delegation")]
+        let listeners = &listeners
+            .iter()
+            .filter(|(event_name, listener)| {
+                // the events are only delegated in the browser DOM
+                let Some(program) = delegated_program.as_ref() else {
+                    return true;
+                };
+                let is_delegated = Program::<APP, MSG>::is_delegated_listener(event_name, listener);
+                if is_delegated {
+                    program.delegate_event(event_name);
+                }
+                !is_delegated
+            })
+            .copied()
+            .collect::<Vec<_>>();
+        if listeners.is_empty() {
+            return Ok(());
         }
+        let listener_closures = listeners
+            .iter()
+            .map(|(event_name, listener)| {
+                let closure = self.attach_listener(element, event_name, listener)?;
+                Ok((*event_name, closure))
+            })
+            .collect::<Result<BTreeMap<&'static str, DOM::Listener>, Error>>()?;
+
+        let unique_id = create_unique_identifier();
+        // set the data-sauron_vdom-id this will be read later on
+        // when it's time to remove this element and its closures and event listeners
+        self.backend
+            .set_attribute(element, DATA_VDOM_ID, &unique_id.to_string(), None)?;
+
+        self.node_closures
+            .borrow_mut()
+            .insert(unique_id, listener_closures);
+        Ok(())
     }
 
-    /// set the element attribute value with the first numerical value found in values
-    fn set_numeric_values(element: &Element, values: &[&AttributeValue<MSG>]) {
-        let value_i32 = values
-            .first()
-            .and_then(|v| v.get_simple().and_then(|v| v.as_i32()));
-
-        let value_f64 = values
-            .first()
-            .and_then(|v| v.get_simple().and_then(|v| v.as_f64()));
-
-        if let Some(value_i32) = value_i32 {
-            Self::set_value_i32(element, value_i32);
-        }
-        if let Some(value_f64) = value_f64 {
-            Self::set_value_f64(element, value_f64);
-        }
+    /// attach the listener to the element, which dispatches the msg emitted by the listener
+    fn attach_listener(
+        &self,
+        element: &DOM::Node,
+        event_name: &'static str,
+        listener: &Listener<dom::Event, MSG>,
+    ) -> Result<DOM::Listener, Error> {
+        let program = Program::downgrade(self);
+        let callback_listener = listener.clone();
+        self.backend.add_event_listener(
+            element,
+            event_name,
+            listener.options(),
+            Rc::new(move |event: dom::Event| {
+                let Some(msg) = callback_listener.emit_event(event) else {
+                    log::warn!(
+                        "skipping the listener of {event_name}, it can only be called with a web event"
+                    );
+                    return;
+                };
+                if let Some(mut program) = program.upgrade() {
+                    program.dispatch(msg);
+                }
+            }),
+        )
     }
 
     /// remove element attribute,
     /// takes care of special case such as checked
     pub fn remove_element_attribute(
-        element: &Element,
+        &self,
+        element: &DOM::Node,
         attr: &Attribute<MSG>,
-    ) -> Result<(), JsValue> {
-        match *attr.name() {
+    ) -> Result<(), Error> {
+        let attr_name = *attr.name();
+        match attr_name {
             "value" => {
-                Self::set_value_str(element, "");
-            }
-            "open" => {
-                Self::set_open(element, false);
+                self.backend
+                    .set_property(element, attr_name, &Value::from(""));
             }
-            "checked" => {
-                Self::set_checked(element, false);
-            }
-            "disabled" => {
-                Self::set_disabled(element, false);
+            "open" | "checked" | "disabled" => {
+                self.backend
+                    .set_property(element, attr_name, &Value::from(false));
             }
             _ => (),
         }
         //actually remove the element
-        element.remove_attribute(intern(attr.name()))?;
-
-        Ok(())
+        self.backend.remove_attribute(element, attr_name)
     }
 
     /// remove all the event listeners for this node
-    pub(crate) fn remove_event_listeners(&self, node: &Element) -> Result<(), JsValue> {
-        let all_descendant_vdom_id = get_node_descendant_data_vdom_id(node);
+    pub(crate) fn remove_event_listeners(&self, node: &DOM::Node) -> Result<(), Error> {
+        let all_descendant_vdom_id =
+            get_node_descendant_data_vdom_id(&self.backend, node, &self.components.borrow());
+        self.unmount_components(node);
         let mut node_closures = self.node_closures.borrow_mut();
         for vdom_id in all_descendant_vdom_id {
             if let Some(old_closure) = node_closures.get(&vdom_id) {
                 for (event, oc) in old_closure.iter() {
-                    let func: &Function = oc.as_ref().unchecked_ref();
-                    node.remove_event_listener_with_callback(intern(event), func)?;
+                    self.backend.remove_event_listener(node, event, oc)?;
                 }
                 // remove closure active_closure in dom_updater to free up memory
                 node_closures
@@ -555,6 +537,29 @@ where
         Ok(())
     }
 
+    /// detach the listeners of the element and its descendants from each of them,
+    /// such that no dropped closure is called when the elements are left in the DOM
+    pub(crate) fn detach_node_closures(&self, element: &DOM::Node) -> Result<(), Error> {
+        let elements = self
+            .backend
+            .elements_with_attribute(element, DATA_VDOM_ID)
+            .into_iter()
+            .chain(Some(element.clone()));
+        let mut node_closures = self.node_closures.borrow_mut();
+        for element in elements {
+            let Some(vdom_id) = self.vdom_id(&element) else {
+                continue;
+            };
+            if let Some(closures) = node_closures.remove(&vdom_id) {
+                for (event, closure) in closures.iter() {
+                    self.backend
+                        .remove_event_listener(&element, event, closure)?;
+                }
+            }
+        }
+        Ok(())
+    }
+
     /// remove the event listener which matches the given event name
     /// TODO: this is iterating over the decedant nodes to find the `vdom-id`
     /// maybe we can make the dropping of closure faster
@@ -562,16 +567,16 @@ where
     pub(crate) fn remove_event_listener_with_name(
         &self,
         event_name: &'static str,
-        node: &Element,
-    ) -> Result<(), JsValue> {
-        let all_descendant_vdom_id = get_node_descendant_data_vdom_id(node);
+        node: &DOM::Node,
+    ) -> Result<(), Error> {
+        let all_descendant_vdom_id =
+            get_node_descendant_data_vdom_id(&self.backend, node, &self.components.borrow());
         let mut node_closures = self.node_closures.borrow_mut();
         for vdom_id in all_descendant_vdom_id {
             if let Some(old_closure) = node_closures.get_mut(&vdom_id) {
                 for (event, oc) in old_closure.iter() {
                     if *event == event_name {
-                        let func: &Function = oc.as_ref().unchecked_ref();
-                        node.remove_event_listener_with_callback(intern(event), func)?;
+                        self.backend.remove_event_listener(node, event, oc)?;
                     }
                 }
 
@@ -591,28 +596,23 @@ where
     }
 }
 
-pub(crate) fn find_node(node: &Node, path: &mut TreePath) -> Option<Node> {
-    if path.is_empty() {
-        Some(node.clone())
-    } else {
-        let idx = path.remove_first();
-        let children = node.child_nodes();
-        if let Some(child) = children.item(idx as u32) {
-            find_node(&child, path)
-        } else {
-            None
-        }
-    }
+/// the options of the listener which are applied by the browser
+pub(crate) fn add_event_listener_options(options: &ListenerOptions) -> AddEventListenerOptions {
+    let add_options = AddEventListenerOptions::new();
+    add_options.set_passive(options.passive);
+    add_options.set_capture(options.capture);
+    add_options.set_once(options.once);
+    add_options
 }
 
-pub(crate) fn find_all_nodes(
-    node: &Node,
+pub(crate) fn find_all_nodes<DOM: DomBackend>(
+    backend: &DOM,
+    node: &DOM::Node,
     nodes_to_find: &[(&TreePath, Option<&&'static str>)],
-) -> BTreeMap<TreePath, Node> {
-    let mut nodes_to_patch: BTreeMap<TreePath, Node> = BTreeMap::new();
+) -> BTreeMap<TreePath, DOM::Node> {
+    let mut nodes_to_patch: BTreeMap<TreePath, DOM::Node> = BTreeMap::new();
     for (path, tag) in nodes_to_find {
-        let mut traverse_path: TreePath = (*path).clone();
-        if let Some(found) = find_node(node, &mut traverse_path) {
+        if let Some(found) = backend.find_node(node, &path.path) {
             nodes_to_patch.insert((*path).clone(), found);
         } else {
             log::warn!("can not find: {:?} {:?} root_node: {:?}", path, tag, node);
@@ -622,24 +622,31 @@ pub(crate) fn find_all_nodes(
 }
 
 /// Get the "data-vdom-id" of all the desendent of this node including itself
-/// This is needed to free-up the closure that was attached ActiveClosure manually
-fn get_node_descendant_data_vdom_id(root_element: &Element) -> Vec<usize> {
+/// This is needed to free-up the closure that was attached ActiveClosure manually.
+/// The content of the host of the stateful components is skipped, since their closures are
+/// owned by the component.
+fn get_node_descendant_data_vdom_id<DOM: DomBackend>(
+    backend: &DOM,
+    root_element: &DOM::Node,
+    components: &MountedComponents<DOM::Node>,
+) -> Vec<usize> {
     let mut data_vdom_id = vec![];
 
-    if let Some(vdom_id_str) = root_element.get_attribute(intern(DATA_VDOM_ID)) {
+    if let Some(vdom_id_str) = backend.get_attribute(root_element, DATA_VDOM_ID) {
         let vdom_id = vdom_id_str
             .parse::<usize>()
             .expect("unable to parse sauron_vdom-id");
         data_vdom_id.push(vdom_id);
     }
 
-    let children = root_element.child_nodes();
-    let child_node_count = children.length();
-    for i in 0..child_node_count {
-        let child_node = children.item(i).expect("Expecting a child node");
-        if child_node.node_type() == Node::ELEMENT_NODE {
-            let child_element = child_node.unchecked_ref::<Element>();
-            let child_data_vdom_id = get_node_descendant_data_vdom_id(child_element);
+    if components.iter().any(|(host, _)| host == root_element) {
+        return data_vdom_id;
+    }
+
+    for child_node in backend.child_nodes(root_element) {
+        if backend.is_element(&child_node) {
+            let child_data_vdom_id =
+                get_node_descendant_data_vdom_id(backend, &child_node, components);
             data_vdom_id.extend(child_data_vdom_id);
         }
     }
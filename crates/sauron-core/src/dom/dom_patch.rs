@@ -1,44 +1,49 @@
+use crate::dom::backend::{DomBackend, WebDom};
 use crate::dom::dom_node::find_all_nodes;
-use crate::dom::dom_node::intern;
+use crate::dom::events::{UNMOUNT, WILL_UNMOUNT};
 use crate::dom::{Application, Program};
 use crate::vdom::{Attribute, AttributeValue, Patch, PatchType};
+use crate::Error;
+#[cfg(feature = "with-serde")]
+use crate::{
+    dom::Event,
+    vdom::{EventId, WirePatch},
+};
 use mt_dom::TreePath;
 use std::collections::BTreeMap;
-use wasm_bindgen::JsCast;
-use wasm_bindgen::JsValue;
-use web_sys::Element;
-use web_sys::Node;
+#[cfg(feature = "with-serde")]
+use std::rc::Rc;
 
 /// a Patch where the virtual nodes are all created in the document.
 /// This is necessary since the created Node  doesn't contain references
 /// as opposed to Patch which contains reference to the vdom, which makes it hard
 /// to be included in a struct
 ///
-pub struct DomPatch<MSG> {
+pub struct DomPatch<MSG, DOM: DomBackend = WebDom> {
     /// The path to traverse to get to the target_element
     pub patch_path: TreePath,
     /// the target node
-    pub target_element: Element,
+    pub target_element: DOM::Node,
     /// the patch variant
-    pub patch_variant: PatchVariant<MSG>,
+    pub patch_variant: PatchVariant<MSG, DOM>,
 }
 
 /// patch variant
-pub enum PatchVariant<MSG> {
+pub enum PatchVariant<MSG, DOM: DomBackend = WebDom> {
     /// Insert nodes before the target node
     InsertBeforeNode {
         /// nodes to be inserted before the target node
-        nodes: Vec<Node>,
+        nodes: Vec<DOM::Node>,
     },
     /// Insert nodes after the target node
     InsertAfterNode {
         /// the nodes to be inserted after the target node
-        nodes: Vec<Node>,
+        nodes: Vec<DOM::Node>,
     },
     /// Append nodes into the target node
     AppendChildren {
         /// the children nodes to be appended into the target node
-        children: Vec<Node>,
+        children: Vec<DOM::Node>,
     },
     /// Add attributes to the target node
     AddAttributes {
@@ -53,32 +58,53 @@ pub enum PatchVariant<MSG> {
     /// Replace the target node with the replacement node
     ReplaceNode {
         /// the replacement node
-        replacement: Vec<Node>,
+        replacement: Vec<DOM::Node>,
     },
     /// Remove the target node
     RemoveNode,
     /// Move the target node before the node specified in the path location
     MoveBeforeNode {
         /// before the node at this location
-        for_moving: Vec<Node>,
+        for_moving: Vec<DOM::Node>,
     },
     /// Move the target node after the node specified in the path location
     MoveAfterNode {
         /// after the node at this location
-        for_moving: Vec<Node>,
+        for_moving: Vec<DOM::Node>,
     },
 }
 
-impl<APP, MSG> Program<APP, MSG>
+impl<MSG, DOM: DomBackend> DomPatch<MSG, DOM> {
+    /// the element which is updated by this patch, this is the parent element when the patch
+    /// inserts, removes or moves the target element
+    pub(crate) fn updated_element(&self, backend: &DOM) -> Option<DOM::Node> {
+        match self.patch_variant {
+            PatchVariant::AddAttributes { .. }
+            | PatchVariant::RemoveAttributes { .. }
+            | PatchVariant::AppendChildren { .. } => Some(self.target_element.clone()),
+            PatchVariant::InsertBeforeNode { .. }
+            | PatchVariant::InsertAfterNode { .. }
+            | PatchVariant::ReplaceNode { .. }
+            | PatchVariant::RemoveNode
+            | PatchVariant::MoveBeforeNode { .. }
+            | PatchVariant::MoveAfterNode { .. } => backend
+                .parent_node(&self.target_element)
+                .filter(|parent| backend.is_element(parent)),
+        }
+    }
+}
+
+impl<APP, MSG, DOM> Program<APP, MSG, DOM>
 where
     MSG: 'static,
     APP: Application<MSG> + 'static,
+    DOM: DomBackend,
 {
     /// get the real DOM target node and make a DomPatch object for each of the Patch
     pub(crate) fn convert_patches(
         &self,
         patches: &[Patch<MSG>],
-    ) -> Result<Vec<DomPatch<MSG>>, JsValue> {
+    ) -> Result<Vec<DomPatch<MSG, DOM>>, Error> {
         let nodes_to_find: Vec<(&TreePath, Option<&&'static str>)> = patches
             .iter()
             .map(|patch| (patch.path(), patch.tag()))
@@ -90,42 +116,41 @@ where
             )
             .collect();
 
-        let nodes_lookup = find_all_nodes(
-            self.root_node
-                .borrow()
-                .as_ref()
-                .expect("must have a root node"),
-            &nodes_to_find,
-        );
+        let root_node = self.root_node.borrow().clone().ok_or(Error::NotMounted)?;
+        let nodes_lookup = find_all_nodes(&self.backend, &root_node, &nodes_to_find);
 
-        let dom_patches:Vec<DomPatch<MSG>> = patches.iter().map(|patch|{
-            let patch_path = patch.path();
-            let patch_tag = patch.tag();
-            if let Some(target_node) = nodes_lookup.get(patch_path) {
-                let target_element: &Element = target_node.unchecked_ref();
-                if let Some(tag) = patch_tag {
-                    let target_tag = target_element.tag_name().to_lowercase();
+        patches
+            .iter()
+            .map(|patch| {
+                let patch_path = patch.path();
+                let target_node =
+                    nodes_lookup
+                        .get(patch_path)
+                        .ok_or_else(|| Error::NodeNotFound {
+                            path: patch_path.path.clone(),
+                        })?;
+                if let Some(tag) = patch.tag() {
+                    let target_tag = self.backend.tag_name(target_node).unwrap_or_default();
                     if target_tag != **tag {
-                        panic!(
-                            "expecting a tag: {tag:?}, but found: {target_tag:?}"
-                        );
+                        return Err(Error::TagMismatch {
+                            path: patch_path.path.clone(),
+                            expected: tag.to_string(),
+                            found: target_tag,
+                        });
                     }
                 }
-                self.convert_patch(&nodes_lookup, target_element, patch)
-            } else {
-                unreachable!("Getting here means we didn't find the element of next node that we are supposed to patch, patch_path: {:?}, with tag: {:?}", patch_path, patch_tag);
-            }
-        }).collect();
-
-        Ok(dom_patches)
+                self.convert_patch(&nodes_lookup, target_node, patch)
+            })
+            .collect()
     }
+
     /// convert a virtual DOM Patch into a created DOM node Patch
     pub fn convert_patch(
         &self,
-        nodes_lookup: &BTreeMap<TreePath, Node>,
-        target_element: &Element,
+        nodes_lookup: &BTreeMap<TreePath, DOM::Node>,
+        target_element: &DOM::Node,
         patch: &Patch<MSG>,
-    ) -> DomPatch<MSG> {
+    ) -> Result<DomPatch<MSG, DOM>, Error> {
         let target_element = target_element.clone();
         let Patch {
             patch_path,
@@ -134,13 +159,21 @@ where
         } = patch;
 
         let patch_path = patch_path.clone();
+        let find_node = |path: &TreePath| {
+            nodes_lookup
+                .get(path)
+                .cloned()
+                .ok_or_else(|| Error::NodeNotFound {
+                    path: path.path.clone(),
+                })
+        };
 
-        match patch_type {
+        let dom_patch = match patch_type {
             PatchType::InsertBeforeNode { nodes } => {
-                let nodes: Vec<Node> = nodes
+                let nodes: Vec<DOM::Node> = nodes
                     .iter()
                     .map(|for_insert| self.create_dom_node(for_insert))
-                    .collect();
+                    .collect::<Result<_, _>>()?;
                 DomPatch {
                     patch_path,
                     target_element,
@@ -148,10 +181,10 @@ where
                 }
             }
             PatchType::InsertAfterNode { nodes } => {
-                let nodes: Vec<Node> = nodes
+                let nodes: Vec<DOM::Node> = nodes
                     .iter()
                     .map(|for_insert| self.create_dom_node(for_insert))
-                    .collect();
+                    .collect::<Result<_, _>>()?;
                 DomPatch {
                     patch_path,
                     target_element,
@@ -175,10 +208,10 @@ where
             },
 
             PatchType::ReplaceNode { replacement } => {
-                let replacement: Vec<Node> = replacement
+                let replacement: Vec<DOM::Node> = replacement
                     .iter()
                     .map(|node| self.create_dom_node(node))
-                    .collect();
+                    .collect::<Result<_, _>>()?;
                 DomPatch {
                     patch_path,
                     target_element,
@@ -191,15 +224,8 @@ where
                 patch_variant: PatchVariant::RemoveNode,
             },
             PatchType::MoveBeforeNode { nodes_path } => {
-                let for_moving: Vec<Node> = nodes_path
-                    .iter()
-                    .map(|path| {
-                        nodes_lookup
-                            .get(path)
-                            .expect("must have found the node")
-                            .clone()
-                    })
-                    .collect();
+                let for_moving: Vec<DOM::Node> =
+                    nodes_path.iter().map(find_node).collect::<Result<_, _>>()?;
                 DomPatch {
                     patch_path,
                     target_element,
@@ -207,15 +233,8 @@ where
                 }
             }
             PatchType::MoveAfterNode { nodes_path } => {
-                let for_moving: Vec<Node> = nodes_path
-                    .iter()
-                    .map(|path| {
-                        nodes_lookup
-                            .get(path)
-                            .expect("must have found the node")
-                            .clone()
-                    })
-                    .collect();
+                let for_moving: Vec<DOM::Node> =
+                    nodes_path.iter().map(find_node).collect::<Result<_, _>>()?;
                 DomPatch {
                     patch_path,
                     target_element,
@@ -223,10 +242,10 @@ where
                 }
             }
             PatchType::AppendChildren { children } => {
-                let children: Vec<Node> = children
+                let children: Vec<DOM::Node> = children
                     .iter()
                     .map(|for_insert| self.create_dom_node(for_insert))
-                    .collect();
+                    .collect::<Result<_, _>>()?;
 
                 DomPatch {
                     patch_path,
@@ -234,67 +253,89 @@ where
                     patch_variant: PatchVariant::AppendChildren { children },
                 }
             }
+        };
+        Ok(dom_patch)
+    }
+
+    /// Apply the patches which are diffed elsewhere, such as on a server or in a web worker,
+    /// into the DOM of this program.
+    ///
+    /// The event listeners of the patches call `on_event` with the id of the listener and the
+    /// event, the returned msg is dispatched into the program.
+    /// The patches are not applied to the current vdom of the program, the view of the APP
+    /// should not diff the nodes which are patched here.
+    #[cfg(feature = "with-serde")]
+    pub fn apply_wire_patches(
+        &mut self,
+        patches: &[WirePatch],
+        on_event: impl Fn(EventId, Event) -> MSG + 'static,
+    ) -> Result<(), Error> {
+        let on_event: Rc<dyn Fn(EventId, Event) -> MSG> = Rc::new(on_event);
+        let decoded: Vec<_> = patches
+            .iter()
+            .map(|patch| patch.decode(&on_event))
+            .collect::<Result<_, _>>()?;
+        let patches: Vec<Patch<MSG>> = decoded.iter().map(|patch| patch.as_patch()).collect();
+        for dom_patch in self.convert_patches(&patches)? {
+            self.apply_dom_patch(dom_patch)?;
         }
+        Ok(())
     }
 
-    pub(crate) fn apply_dom_patch(&mut self, dom_patch: DomPatch<MSG>) -> Result<(), JsValue> {
+    pub(crate) fn apply_dom_patch(&mut self, dom_patch: DomPatch<MSG, DOM>) -> Result<(), Error> {
         let DomPatch {
             patch_path,
             target_element,
             patch_variant,
         } = dom_patch;
 
+        let backend = self.backend.clone();
         match patch_variant {
             PatchVariant::InsertBeforeNode { nodes } => {
                 // we insert the node before this target element
-                if let Some(parent_target) = target_element.parent_node() {
-                    for for_insert in nodes {
-                        parent_target
-                            .insert_before(&for_insert, Some(&target_element))
-                            .expect("must remove target node");
-                        Self::dispatch_mount_event(&for_insert);
-                    }
-                } else {
-                    panic!("unable to get parent node of the target element: {target_element:?} for patching: {nodes:#?}");
+                let parent_target = backend
+                    .parent_node(&target_element)
+                    .ok_or(Error::MissingParent)?;
+                for for_insert in nodes {
+                    backend.insert_before(&parent_target, &for_insert, Some(&target_element))?;
+                    self.dispatch_mount(&for_insert)?;
                 }
             }
 
             PatchVariant::InsertAfterNode { nodes } => {
                 // we insert the node before this target element
                 for for_insert in nodes.into_iter().rev() {
-                    let created_element: &Element = for_insert
-                        .dyn_ref()
-                        .expect("only elements is supported for now");
-                    target_element
-                        .insert_adjacent_element(intern("afterend"), created_element)
-                        .expect("must insert after the target element");
-                    Self::dispatch_mount_event(&for_insert);
+                    backend.insert_after(&target_element, &for_insert)?;
+                    self.dispatch_mount(&for_insert)?;
                 }
             }
             PatchVariant::AppendChildren { children } => {
                 for child in children.into_iter() {
-                    Self::append_child_and_dispatch_mount_event(
-                        target_element.unchecked_ref(),
-                        &child,
-                    );
+                    self.append_and_dispatch_mount(&target_element, &child)?;
                 }
             }
 
             PatchVariant::AddAttributes { attrs } => {
                 let attrs: Vec<&Attribute<MSG>> = attrs.iter().collect();
-                self.set_element_attributes(&target_element, &attrs);
+                self.set_element_attributes(&target_element, &attrs)?;
             }
             PatchVariant::RemoveAttributes { attrs } => {
                 for attr in attrs.iter() {
                     for att_value in attr.value() {
                         match att_value {
                             AttributeValue::Simple(_) => {
-                                Self::remove_element_attribute(&target_element, attr)?;
+                                self.remove_element_attribute(&target_element, attr)?;
                             }
                             // it is an event listener
                             AttributeValue::EventListener(_) => {
                                 self.remove_event_listener_with_name(attr.name(), &target_element)?;
                             }
+                            AttributeValue::Component(_) => {
+                                self.components
+                                    .borrow_mut()
+                                    .retain(|(host, _)| host != &target_element);
+                                backend.clear_children(&target_element)?;
+                            }
                             AttributeValue::FunctionCall(_)
                             | AttributeValue::Style(_)
                             | AttributeValue::Empty => (),
@@ -307,48 +348,43 @@ where
             // including the associated closures of the descendant of replaced node
             // before it is actully replaced in the DOM
             PatchVariant::ReplaceNode { mut replacement } => {
-                let first_node = replacement.pop().expect("must have a first node");
-                if target_element.node_type() == Node::DOCUMENT_FRAGMENT_NODE {
+                let Some(first_node) = replacement.pop() else {
+                    return Ok(());
+                };
+                if backend.is_document_fragment(&target_element) {
                     // if we are patching a fragment mode in the top-level document
                     // it has no access to it's parent other than accessing the mount-node itself
                     if patch_path.is_empty() {
                         let mount_node = self.mount_node();
-                        Self::clear_children(&mount_node);
-                        mount_node
-                            .append_child(&first_node)
-                            .expect("must append child");
-                        Self::dispatch_mount_event(&first_node);
+                        backend.clear_children(&mount_node)?;
+                        self.append_and_dispatch_mount(&mount_node, &first_node)?;
 
                         for node in replacement.into_iter() {
-                            let node_elm: &web_sys::Element = node.unchecked_ref();
-                            mount_node.append_child(node_elm).expect("append child");
-                            Self::dispatch_mount_event(node_elm);
+                            self.append_and_dispatch_mount(&mount_node, &node)?;
                         }
                     } else {
                         // the diffing algorithmn doesn't concern with fragment, instead it test the nodes contain in the fragment as if it where a list of nodes
                         unreachable!("patching a document fragment other than the root_node should not happen");
                     }
                 } else {
-                    if target_element.node_type() == Node::ELEMENT_NODE {
+                    let is_element = backend.is_element(&target_element);
+                    if is_element {
+                        self.dispatch_lifecycle_event(&target_element, WILL_UNMOUNT)?;
+                    }
+                    backend.replace_with(&target_element, &first_node)?;
+                    if is_element {
+                        self.dispatch_lifecycle_event(&target_element, UNMOUNT)?;
                         self.remove_event_listeners(&target_element)?;
                     }
-                    //let first_node = replacement.pop().expect("must have a first node");
-                    target_element
-                        .replace_with_with_node_1(&first_node)
-                        .unwrap_or_else(|e| {
-                            panic!("unable to replace node with {first_node:?}, {e:?}");
-                        });
 
-                    Self::dispatch_mount_event(&first_node);
-
-                    let first_node_elm: &web_sys::Element = first_node.unchecked_ref();
+                    self.dispatch_mount(&first_node)?;
 
+                    let parent = backend
+                        .parent_node(&first_node)
+                        .ok_or(Error::MissingParent)?;
                     for node in replacement.into_iter() {
-                        let node_elm: &web_sys::Element = node.unchecked_ref();
-                        first_node_elm
-                            .insert_adjacent_element(intern("beforebegin"), node_elm)
-                            .expect("append child");
-                        Self::dispatch_mount_event(node_elm);
+                        backend.insert_before(&parent, &node, Some(&first_node))?;
+                        self.dispatch_mount(&node)?;
                     }
                 }
 
@@ -364,48 +400,39 @@ where
                 }
             }
             PatchVariant::RemoveNode => {
-                let parent_target = target_element
-                    .parent_node()
-                    .expect("must have a parent node");
-                parent_target
-                    .remove_child(&target_element)
-                    .expect("must remove target node");
-                if target_element.node_type() == Node::ELEMENT_NODE {
+                let is_element = backend.is_element(&target_element);
+                if is_element {
+                    self.dispatch_lifecycle_event(&target_element, WILL_UNMOUNT)?;
+                }
+                let parent_target = backend
+                    .parent_node(&target_element)
+                    .ok_or(Error::MissingParent)?;
+                backend.remove_child(&parent_target, &target_element)?;
+                if is_element {
+                    self.dispatch_lifecycle_event(&target_element, UNMOUNT)?;
                     self.remove_event_listeners(&target_element)?;
                 }
             }
             PatchVariant::MoveBeforeNode { for_moving } => {
-                if let Some(target_parent) = target_element.parent_node() {
-                    for move_node in for_moving {
-                        let move_node_parent = move_node
-                            .parent_node()
-                            .expect("node for moving must have parent");
-                        let move_node = move_node_parent
-                            .remove_child(&move_node)
-                            .expect("must remove child");
-                        target_parent
-                            .insert_before(&move_node, Some(&target_element))
-                            .expect("must insert before this node");
-                    }
-                } else {
-                    panic!("unable to get the parent node of the target element");
+                let target_parent = backend
+                    .parent_node(&target_element)
+                    .ok_or(Error::MissingParent)?;
+                for move_node in for_moving {
+                    let move_node_parent = backend
+                        .parent_node(&move_node)
+                        .ok_or(Error::MissingParent)?;
+                    backend.remove_child(&move_node_parent, &move_node)?;
+                    backend.insert_before(&target_parent, &move_node, Some(&target_element))?;
                 }
             }
 
             PatchVariant::MoveAfterNode { for_moving } => {
                 for move_node in for_moving {
-                    let move_node_parent = move_node
-                        .parent_node()
-                        .expect("node for moving must have parent");
-                    let to_move_node = move_node_parent
-                        .remove_child(&move_node)
-                        .expect("must remove child");
-
-                    let to_move_element: &web_sys::Element =
-                        to_move_node.dyn_ref().expect("an element");
-                    target_element
-                        .insert_adjacent_element(intern("afterend"), to_move_element)
-                        .expect("must insert before this node");
+                    let move_node_parent = backend
+                        .parent_node(&move_node)
+                        .ok_or(Error::MissingParent)?;
+                    backend.remove_child(&move_node_parent, &move_node)?;
+                    backend.insert_after(&target_element, &move_node)?;
                 }
             }
         }
@@ -0,0 +1,208 @@
+//! Event delegation, enabled with the `with-event-delegation` feature.
+//!
+//! Instead of attaching a closure to each element for each of its listeners, the program
+//! listens to each event type once at the mount node. When an event arrives, the path of its
+//! target relative to the root node is resolved into the node of the current view, then the
+//! listeners of the node and its ancestors are called, until one of them stops the
+//! propagation of the event. The path is resolved the same way as the hydration, where the
+//! fragments are unrolled into their parent and a safe html may be parsed into several nodes.
+//! The pending patches of a background update are applied before the path is resolved, since
+//! the current view is already replaced while its patches are applied in the next frames.
+//!
+//! The lifecycle events such as `on_mount` and `on_unmount` are still attached to the elements,
+//! since they are dispatched to the elements which are not in the document.
+//! The `current_target` of the event passed to the delegated listeners is the mount node.
+use crate::dom::events::{UNMOUNT, UPDATED, WILL_UNMOUNT};
+use crate::dom::hydration::{count_html_nodes, flatten_children};
+use crate::dom::{self, Application, Program, WebDom};
+use crate::html::attributes::{AttributeValue, Listener, ListenerOptions};
+use crate::vdom::{self, Leaf};
+use wasm_bindgen::{closure::Closure, JsCast};
+use web_sys::Node;
+
+/// the events which are dispatched by the program to the element itself
+const LIFECYCLE_EVENTS: [&str; 4] = ["mount", WILL_UNMOUNT, UNMOUNT, UPDATED];
+
+impl<APP, MSG> Program<APP, MSG>
+where
+    MSG: 'static,
+    APP: Application<MSG> + 'static,
+{
+    /// returns true if the listener is delegated to the mount node. The listeners which are
+    /// passive, in the capture phase or called once are attached to the element, since the
+    /// browser applies these options.
+    pub(crate) fn is_delegated_listener(
+        event_name: &str,
+        listener: &Listener<dom::Event, MSG>,
+    ) -> bool {
+        let options = listener.options();
+        let is_applied_by_browser = options.passive || options.capture || options.once;
+        !LIFECYCLE_EVENTS.contains(&event_name) && !is_applied_by_browser
+    }
+
+    /// listen to the event at the mount node, the listener is attached right away if the
+    /// program is already mounted, otherwise it is attached after the program is mounted
+    pub(crate) fn delegate_event(&self, event_name: &'static str) {
+        let is_new = self.delegated_events.borrow_mut().insert(event_name);
+        if is_new && !self.is_unmounted() {
+            self.attach_delegated_event(event_name);
+        }
+    }
+
+    /// attach the listeners of the events which are collected while the view is created
+    pub(crate) fn attach_delegated_events(&self) {
+        let event_names: Vec<&'static str> =
+            self.delegated_events.borrow().iter().copied().collect();
+        for event_name in event_names {
+            self.attach_delegated_event(event_name);
+        }
+    }
+
+    /// The events which bubble are handled in the bubble phase, such that the listeners of the
+    /// nested programs are called first. The events which don't bubble, such as `focus`, only
+    /// reach the mount node in the capture phase.
+    fn attach_delegated_event(&self, event_name: &'static str) {
+        let mount_node = self.mount_node();
+        for capture in [false, true] {
+            let program = Program::downgrade(self);
+            let closure: Closure<dyn FnMut(web_sys::Event)> =
+                Closure::new(move |event: web_sys::Event| {
+                    if event.bubbles() == capture {
+                        return;
+                    }
+                    if let Some(mut program) = program.upgrade() {
+                        // the patches of a background update which are still pending are applied
+                        // first, since the target is resolved in the current view
+                        if let Err(error) = program.apply_pending_patches() {
+                            program.handle_error(error);
+                            return;
+                        }
+                        let msgs = program.delegated_msgs(event_name, &event);
+                        if !msgs.is_empty() {
+                            program.dispatch_multiple(msgs);
+                        }
+                    }
+                });
+            let options = ListenerOptions {
+                capture,
+                ..Default::default()
+            };
+            if let Err(error) =
+                self.add_event_closure_with_options(&mount_node, event_name, &options, closure)
+            {
+                self.clone().handle_error(error.into());
+            }
+        }
+    }
+
+    /// call the listeners of the target of the event and its ancestors in the current view,
+    /// which must have its pending patches applied into the DOM. The bubbling stops when a listener stops the propagation of the event
+    fn delegated_msgs(&self, event_name: &str, event: &web_sys::Event) -> Vec<MSG> {
+        let Some(path) = event
+            .target()
+            .and_then(|target| self.path_from_root(target.unchecked_ref()))
+        else {
+            return vec![];
+        };
+        let depths = if event.bubbles() {
+            0..=path.len()
+        } else {
+            path.len()..=path.len()
+        };
+        let current_vdom = self.app_context.current_vdom();
+        let vnodes = vnodes_at(&self.backend, &current_vdom, &path);
+        let mut msgs = vec![];
+        for depth in depths.rev() {
+            let Some(attributes) = vnodes[depth].and_then(|node| node.attributes()) else {
+                continue;
+            };
+            let listeners = attributes
+                .iter()
+                .filter(|attr| *attr.name() == event_name)
+                .flat_map(|attr| attr.value())
+                .filter_map(AttributeValue::as_event_listener);
+            for listener in listeners {
+                let options = listener.options();
+                if options.prevent_default {
+                    event.prevent_default();
+                }
+                if options.stop_propagation {
+                    event.stop_propagation();
+                }
+                msgs.push(listener.emit(dom::Event::from(event.clone())));
+            }
+            if event.cancel_bubble() {
+                break;
+            }
+        }
+        msgs
+    }
+
+    /// the indexes of the node and its ancestors in their parent, starting from the root node.
+    /// Returns `None` if the node is not in the view of this program.
+    fn path_from_root(&self, node: &Node) -> Option<Vec<usize>> {
+        let root_node = self.root_node.borrow().clone()?;
+        // the nodes of a fragment are the children of the mount node
+        let root_node = if root_node.node_type() == Node::DOCUMENT_FRAGMENT_NODE {
+            self.mount_node()
+        } else {
+            root_node
+        };
+        let mut path = vec![];
+        let mut node = node.clone();
+        while node != root_node {
+            let parent = node.parent_node()?;
+            let siblings = parent.child_nodes();
+            let index =
+                (0..siblings.length()).position(|i| siblings.item(i).as_ref() == Some(&node))?;
+            path.push(index);
+            node = parent;
+        }
+        path.reverse();
+        Some(path)
+    }
+}
+
+/// the nodes of the view at each depth of the path of the DOM nodes, starting from the root
+/// node. The nodes which are not in the view, such as the nodes parsed from a safe html, and
+/// their descendants are `None`.
+fn vnodes_at<'a, MSG>(
+    backend: &WebDom,
+    root: &'a vdom::Node<MSG>,
+    path: &[usize],
+) -> Vec<Option<&'a vdom::Node<MSG>>> {
+    let mut vnodes = vec![Some(root)];
+    let mut current = Some(root);
+    for index in path {
+        current = current.and_then(|vnode| dom_child_at(backend, vnode, *index));
+        vnodes.push(current);
+    }
+    vnodes
+}
+
+/// the child of the node which is created at the index of the children of its DOM node
+fn dom_child_at<'a, MSG>(
+    backend: &WebDom,
+    vnode: &'a vdom::Node<MSG>,
+    index: usize,
+) -> Option<&'a vdom::Node<MSG>> {
+    let children = match vnode {
+        vdom::Node::Element(element) => element.children(),
+        vdom::Node::Fragment(nodes) | vdom::Node::NodeList(nodes) => nodes,
+        vdom::Node::Leaf(_) => return None,
+    };
+    let mut start = 0;
+    for (_, child) in flatten_children(children, &[]) {
+        let (len, is_in_view) = match child {
+            vdom::Node::Leaf(Leaf::SafeHtml(html)) => {
+                (count_html_nodes(backend, html).ok()?, false)
+            }
+            _ => (1, true),
+        };
+        if index < start + len {
+            return is_in_view.then_some(child);
+        }
+        start += len;
+    }
+    None
+}
@@ -50,6 +50,136 @@ impl MouseButton {
     }
 }
 
+/// An event fired without the browser, such as the events fired in the [`TestProgram`].
+/// It is passed to the listeners made with [`on_synthetic`], which read the values set here.
+/// The listeners of the web events such as `on_click` and `on_input` are skipped, since there is
+/// no web event to pass to them.
+///
+/// [`TestProgram`]: crate::dom::TestProgram
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(
+    feature = "with-serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(default)
+)]
+pub struct SyntheticEvent {
+    /// the value of the target element, such as the value of an input element
+    pub value: Option<String>,
+    /// the checked state of the target input element
+    pub checked: Option<bool>,
+    /// the open state of the target details element
+    pub open: Option<bool>,
+    /// the key value of a keyboard event, such as `"Enter"` or `"a"`
+    pub key: Option<String>,
+    /// the physical key of a keyboard event, such as `"KeyA"`
+    pub code: Option<String>,
+    /// whether the alt key is pressed
+    pub alt_key: bool,
+    /// whether the control key is pressed
+    pub ctrl_key: bool,
+    /// whether the shift key is pressed
+    pub shift_key: bool,
+    /// whether the meta key is pressed
+    pub meta_key: bool,
+    /// the horizontal coordinate of a mouse event in the viewport
+    pub client_x: i32,
+    /// the vertical coordinate of a mouse event in the viewport
+    pub client_y: i32,
+    /// the button pressed in a mouse event, see [`MouseButton`]
+    pub button: i16,
+}
+
+impl SyntheticEvent {
+    /// a synthetic event with the value of the target element
+    pub fn with_value(value: impl ToString) -> Self {
+        Self {
+            value: Some(value.to_string()),
+            ..Default::default()
+        }
+    }
+
+    /// a synthetic event with the checked state of the target input element
+    pub fn with_checked(checked: bool) -> Self {
+        Self {
+            checked: Some(checked),
+            ..Default::default()
+        }
+    }
+
+    /// a synthetic keyboard event of the key value, such as `"Enter"` or `"a"`
+    pub fn with_key(key: impl ToString) -> Self {
+        Self {
+            key: Some(key.to_string()),
+            ..Default::default()
+        }
+    }
+
+    /// a synthetic mouse event at the coordinate in the viewport
+    pub fn with_client_position(client_x: i32, client_y: i32) -> Self {
+        Self {
+            client_x,
+            client_y,
+            ..Default::default()
+        }
+    }
+
+    /// a synthetic event with the values of the web event and its target element, such as the
+    /// key of a keyboard event and the coordinates of a mouse event, such that it can be fired
+    /// where there is no browser, such as on the server of a live app
+    pub fn from_web_event(web_event: &web_sys::Event) -> Self {
+        let synthetic = web_event
+            .target()
+            .map(|target| Self::from_target(&target))
+            .unwrap_or_default();
+        if let Some(keyboard) = web_event.dyn_ref::<web_sys::KeyboardEvent>() {
+            Self {
+                key: Some(keyboard.key()),
+                code: Some(keyboard.code()),
+                alt_key: keyboard.alt_key(),
+                ctrl_key: keyboard.ctrl_key(),
+                shift_key: keyboard.shift_key(),
+                meta_key: keyboard.meta_key(),
+                ..synthetic
+            }
+        } else if let Some(mouse) = web_event.dyn_ref::<web_sys::MouseEvent>() {
+            Self {
+                alt_key: mouse.alt_key(),
+                ctrl_key: mouse.ctrl_key(),
+                shift_key: mouse.shift_key(),
+                meta_key: mouse.meta_key(),
+                client_x: mouse.client_x(),
+                client_y: mouse.client_y(),
+                button: mouse.button(),
+                ..synthetic
+            }
+        } else {
+            synthetic
+        }
+    }
+
+    /// a synthetic event with the values of the target element
+    fn from_target(target: &EventTarget) -> Self {
+        if let Some(input) = target.dyn_ref::<HtmlInputElement>() {
+            Self {
+                value: Some(input.value()),
+                checked: Some(input.checked()),
+                ..Default::default()
+            }
+        } else if let Some(textarea) = target.dyn_ref::<HtmlTextAreaElement>() {
+            Self::with_value(textarea.value())
+        } else if let Some(select) = target.dyn_ref::<HtmlSelectElement>() {
+            Self::with_value(select.value())
+        } else if let Some(details) = target.dyn_ref::<HtmlDetailsElement>() {
+            Self {
+                open: Some(details.open()),
+                ..Default::default()
+            }
+        } else {
+            Self::default()
+        }
+    }
+}
+
 impl Event {
     /// convert to web event
     pub fn as_web(self) -> Option<web_sys::Event> {
@@ -60,6 +190,24 @@ impl Event {
     }
 }
 
+impl From<Event> for SyntheticEvent {
+    /// the values of the event, a web event is converted with
+    /// [`SyntheticEvent::from_web_event`]
+    fn from(event: Event) -> Self {
+        match event {
+            Event::WebEvent(web_event) => SyntheticEvent::from_web_event(&web_event),
+            Event::Synthetic(synthetic_event) => synthetic_event,
+            Event::MountEvent(_) => SyntheticEvent::default(),
+        }
+    }
+}
+
+impl From<SyntheticEvent> for Event {
+    fn from(synthetic_event: SyntheticEvent) -> Self {
+        Event::Synthetic(synthetic_event)
+    }
+}
+
 impl From<MountEvent> for Event {
     fn from(mount_event: MountEvent) -> Self {
         Event::MountEvent(mount_event)
@@ -73,7 +221,7 @@ impl From<web_sys::Event> for Event {
 }
 
 impl From<web_sys::MouseEvent> for Event {
-    fn from(mouse_event: MouseEvent) -> Self {
+    fn from(mouse_event: web_sys::MouseEvent) -> Self {
         let event: web_sys::Event = mouse_event
             .dyn_into()
             .expect("Unable to cast mouse event into event");
@@ -90,13 +238,66 @@ where
     mt_dom::attr(event_name, AttributeValue::EventListener(Listener::from(f)))
 }
 
+/// an event builder of the listeners which read the web event, they are skipped for the
+/// [`SyntheticEvent`]s fired without the browser
+fn on_web<F, MSG>(event_name: &'static str, f: F) -> Attribute<MSG>
+where
+    F: Fn(Event) -> MSG + 'static,
+    MSG: 'static,
+{
+    mt_dom::attr(
+        event_name,
+        AttributeValue::EventListener(Listener::from(f).web_only()),
+    )
+}
+
+/// an event builder of the listener which is passed a [`SyntheticEvent`], this works with and
+/// without the browser, such as in the [`TestProgram`] and the live sessions.
+/// The web events of the browser are converted with [`SyntheticEvent::from_web_event`].
+/// # Example
+/// ```rust
+/// use sauron::{dom::SyntheticEvent, html::events::on_synthetic, html::*, Node};
+///
+/// enum Msg {
+///     Submit,
+///     Edit(String),
+/// }
+///
+/// let input: Node<Msg> = input(
+///     [
+///         on_synthetic("input", |event: SyntheticEvent| {
+///             Msg::Edit(event.value.unwrap_or_default())
+///         }),
+///         on_synthetic("keydown", |event: SyntheticEvent| {
+///             if event.key.as_deref() == Some("Enter") {
+///                 Msg::Submit
+///             } else {
+///                 Msg::Edit(event.value.unwrap_or_default())
+///             }
+///         }),
+///     ],
+///     [],
+/// );
+/// ```
+///
+/// [`TestProgram`]: crate::dom::TestProgram
+pub fn on_synthetic<F, MSG>(event_name: &'static str, f: F) -> Attribute<MSG>
+where
+    F: Fn(SyntheticEvent) -> MSG + 'static,
+    MSG: 'static,
+{
+    on(event_name, move |event: Event| {
+        f(SyntheticEvent::from(event))
+    })
+}
+
 /// on click event
 pub fn on_click<F, MSG>(f: F) -> Attribute<MSG>
 where
     F: Fn(MouseEvent) -> MSG + 'static,
     MSG: 'static,
 {
-    on("click", move |event: Event| f(to_mouse_event(event)))
+    on_web("click", move |event: Event| f(to_mouse_event(event)))
 }
 
 /// attach callback to the scroll event
@@ -105,7 +306,7 @@ where
     F: Fn((i32, i32)) -> MSG + 'static,
     MSG: 'static,
 {
-    on("scroll", move |event: Event| {
+    on_web("scroll", move |event: Event| {
         let web_event = event.as_web().expect("must be a web event");
         let target = web_event.target().expect("can't get target");
         if let Some(element) = target.dyn_ref::<web_sys::Element>() {
@@ -136,20 +337,66 @@ impl MountEvent {
     }
 }
 
+/// the event passed to the `on_unmount` and `on_will_unmount` listeners
+pub type UnmountEvent = MountEvent;
+
+/// the event passed to the `on_updated` listeners
+pub type UpdatedEvent = MountEvent;
+
+/// the name of the event dispatched before the element is removed from the DOM
+pub(crate) const WILL_UNMOUNT: &str = "willunmount";
+/// the name of the event dispatched after the element is removed from the DOM
+pub(crate) const UNMOUNT: &str = "unmount";
+/// the name of the event dispatched after the patches are applied to the element
+pub(crate) const UPDATED: &str = "updated";
+
+fn to_mount_event(event: Event) -> MountEvent {
+    let web_event = event.as_web().expect("must be a web event");
+    let event_target = web_event.target().expect("must have a target");
+    MountEvent {
+        target_node: event_target.unchecked_into(),
+    }
+}
+
 /// custom mount event
 pub fn on_mount<F, MSG>(f: F) -> Attribute<MSG>
 where
     F: Fn(MountEvent) -> MSG + 'static,
     MSG: 'static,
 {
-    on("mount", move |event: Event| {
-        let web_event = event.as_web().expect("must be a web event");
-        let event_target = web_event.target().expect("must have a target");
-        let me = MountEvent {
-            target_node: event_target.unchecked_into(),
-        };
-        f(me)
-    })
+    on_web("mount", move |event: Event| f(to_mount_event(event)))
+}
+
+/// custom event dispatched right before the element is removed or replaced in the DOM,
+/// the element is still attached to the document.
+/// This is also dispatched to the descendants of the removed element which has this listener.
+pub fn on_will_unmount<F, MSG>(f: F) -> Attribute<MSG>
+where
+    F: Fn(UnmountEvent) -> MSG + 'static,
+    MSG: 'static,
+{
+    on_web(WILL_UNMOUNT, move |event: Event| f(to_mount_event(event)))
+}
+
+/// custom event dispatched after the element is removed or replaced in the DOM, used for
+/// cleaning up the timers, observers and third-party widgets attached to the element.
+/// This is also dispatched to the descendants of the removed element which has this listener.
+pub fn on_unmount<F, MSG>(f: F) -> Attribute<MSG>
+where
+    F: Fn(UnmountEvent) -> MSG + 'static,
+    MSG: 'static,
+{
+    on_web(UNMOUNT, move |event: Event| f(to_mount_event(event)))
+}
+
+/// custom event dispatched after the patches of an update are applied to the element,
+/// such as its attributes changed or its children were added, removed or moved.
+pub fn on_updated<F, MSG>(f: F) -> Attribute<MSG>
+where
+    F: Fn(UpdatedEvent) -> MSG + 'static,
+    MSG: 'static,
+{
+    on_web(UPDATED, move |event: Event| f(to_mount_event(event)))
 }
 
 macro_rules! declare_events {
@@ -168,7 +415,7 @@ macro_rules! declare_events {
                     where CB: Fn($ret) -> MSG + 'static,
                           MSG: 'static,
                     {
-                        on(stringify!($event), move|event:Event|{
+                        on_web(stringify!($event), move|event:Event|{
                             cb($mapper(event))
                         })
                 }
@@ -0,0 +1,172 @@
+//! The runtime of the APP without a browser, which is shared by the [`TestProgram`] and the
+//! live sessions.
+//!
+//! [`TestProgram`]: crate::dom::TestProgram
+use crate::{
+    dom::{cmd::Command, Application, Callback, Cmd, Priority},
+    html::{self, LazyViews},
+    vdom, Error,
+};
+use futures::{
+    executor::{LocalPool, LocalSpawner},
+    task::LocalSpawnExt,
+};
+use std::{cell::RefCell, collections::VecDeque, rc::Rc};
+
+/// Runs the `update` and `view` of the APP, the tasks of the commands are run with a local
+/// executor. The msgs are not recorded, only the msgs which are waiting to be updated are kept.
+pub(crate) struct HeadlessRuntime<APP, MSG>
+where
+    MSG: 'static,
+{
+    app: APP,
+    current_vdom: vdom::Node<MSG>,
+    /// the view before the last update of the view
+    previous_vdom: Option<vdom::Node<MSG>>,
+    /// the msgs dispatched by the commands, waiting to be updated into the APP
+    pending_msgs: Rc<RefCell<VecDeque<MSG>>>,
+    skipped_commands: usize,
+    /// pass the skipped commands to [`Application::on_error`], instead of only logging them
+    report_skipped_commands: bool,
+    /// the memoized lazy subtrees of the view
+    lazy_views: Rc<RefCell<LazyViews>>,
+    pool: LocalPool,
+    spawner: LocalSpawner,
+}
+
+impl<APP, MSG> HeadlessRuntime<APP, MSG>
+where
+    MSG: 'static,
+    APP: Application<MSG> + 'static,
+{
+    /// create the runtime of the APP with its view, the `init` of the APP is not called
+    pub(crate) fn new(app: APP) -> Self {
+        let lazy_views = Rc::new(RefCell::new(LazyViews::default()));
+        let current_vdom = LazyViews::build(&lazy_views, || app.view());
+        let pool = LocalPool::new();
+        let spawner = pool.spawner();
+        let mut runtime = Self {
+            app,
+            current_vdom,
+            previous_vdom: None,
+            pending_msgs: Rc::new(RefCell::new(VecDeque::new())),
+            skipped_commands: 0,
+            report_skipped_commands: false,
+            lazy_views,
+            pool,
+            spawner,
+        };
+        runtime.report_error_boundaries();
+        runtime
+    }
+
+    /// pass the commands which operate on the `Program` to [`Application::on_error`] as
+    /// [`Error::Unsupported`], since they can not run here
+    pub(crate) fn report_skipped_commands(mut self) -> Self {
+        self.report_skipped_commands = true;
+        self
+    }
+
+    pub(crate) fn app(&self) -> &APP {
+        &self.app
+    }
+
+    pub(crate) fn view(&self) -> &vdom::Node<MSG> {
+        &self.current_vdom
+    }
+
+    pub(crate) fn previous_view(&self) -> Option<&vdom::Node<MSG>> {
+        self.previous_vdom.as_ref()
+    }
+
+    pub(crate) fn skipped_commands(&self) -> usize {
+        self.skipped_commands
+    }
+
+    /// queue the msgs, they are updated into the APP when the runtime settles
+    pub(crate) fn push_msgs(&self, msgs: impl IntoIterator<Item = MSG>) {
+        self.pending_msgs.borrow_mut().extend(msgs);
+    }
+
+    /// run the tasks until they are stalled, then take the next msg to be updated
+    pub(crate) fn next_msg(&mut self) -> Option<MSG> {
+        self.pool.run_until_stalled();
+        self.pending_msgs.borrow_mut().pop_front()
+    }
+
+    /// run the tasks and update the APP with the msgs they dispatched until there are no
+    /// more msgs, returns the number of msgs updated into the APP
+    pub(crate) fn settle(&mut self) -> usize {
+        let mut updated = 0;
+        while let Some(msg) = self.next_msg() {
+            self.update_and_emit(msg);
+            updated += 1;
+        }
+        updated
+    }
+
+    /// update the msg into the APP and its view, then run the commands of the update
+    pub(crate) fn update_and_emit(&mut self, msg: MSG) {
+        let cmd = self.update(msg);
+        self.emit(cmd);
+    }
+
+    /// update the msg into the APP and its view, the commands of the update are returned
+    pub(crate) fn update(&mut self, msg: MSG) -> Cmd<APP, MSG> {
+        let cmd = self.app.update(msg);
+        if cmd.modifier.should_update_view {
+            let mut view = LazyViews::build(&self.lazy_views, || self.app.view());
+            html::reuse_children(&mut self.current_vdom, &mut view);
+            self.previous_vdom = Some(std::mem::replace(&mut self.current_vdom, view));
+            self.report_error_boundaries();
+        }
+        cmd
+    }
+
+    /// pass the failures caught by the error boundaries to [`Application::on_error`],
+    /// the returned msgs are updated into the APP when the runtime settles
+    fn report_error_boundaries(&mut self) {
+        let caught = self.lazy_views.borrow_mut().take_caught();
+        for error in caught {
+            self.report_error(error.into());
+        }
+    }
+
+    /// pass the error to [`Application::on_error`], the returned msg is updated into the APP
+    /// when the runtime settles
+    pub(crate) fn report_error(&mut self, error: Error) {
+        if let Some(msg) = self.app.on_error(error) {
+            self.pending_msgs.borrow_mut().push_back(msg);
+        }
+    }
+
+    /// spawn the tasks of the commands, the msgs are updated right away regardless of their
+    /// priority
+    pub(crate) fn emit(&mut self, cmd: Cmd<APP, MSG>) {
+        for command in cmd.commands {
+            match command {
+                Command::Msg(command) => {
+                    let pending_msgs = Rc::clone(&self.pending_msgs);
+                    let work = command.run(Callback::from(
+                        move |(msgs, _priority): (Vec<MSG>, Option<Priority>)| {
+                            pending_msgs.borrow_mut().extend(msgs)
+                        },
+                    ));
+                    if let Some(work) = work {
+                        self.spawner.spawn_local(work).expect("must spawn the work");
+                    }
+                }
+                Command::Program(_) => {
+                    self.skipped_commands += 1;
+                    if self.report_skipped_commands {
+                        self.report_error(Error::Unsupported(
+                            "a command which operates on the Program",
+                        ));
+                    } else {
+                        log::warn!("skipping a command which operates on the Program");
+                    }
+                }
+            }
+        }
+    }
+}
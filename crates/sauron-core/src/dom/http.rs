@@ -1,52 +1,358 @@
 //! provides functions for retrieving data using http network request
-use js_sys::TypeError;
-use std::fmt::Debug;
-use wasm_bindgen::JsCast;
-use wasm_bindgen_futures::JsFuture;
-use web_sys::{RequestInit, Response};
+//!
+//! A [`Request`] is built with the method, headers, body and timeout, then sent through a
+//! [`Transport`]. In the browser the transport is [`Fetch`], while the [`MockTransport`]
+//! responds with the prepared responses so the requests can be tested without a browser.
+//!
+//! # Example
+//! ```rust
+//! use sauron::dom::{Http, HttpError, Method, MockTransport, Response};
+//! use serde::Deserialize;
+//!
+//! #[derive(Debug, PartialEq, Deserialize)]
+//! struct User {
+//!     name: String,
+//! }
+//!
+//! #[derive(Debug, PartialEq)]
+//! enum Msg {
+//!     ReceivedUser(Result<User, HttpError>),
+//! }
+//!
+//! let transport = MockTransport::new();
+//! transport.respond(Method::Get, "/users/1", Response::new(200, r#"{"name":"Alice"}"#));
+//!
+//! let task = Http::get("/users/1")
+//!     .header("Accept", "application/json")
+//!     .task_with(&transport, Response::json, Msg::ReceivedUser);
+//!
+//! let msg = futures::executor::block_on(async { task.await });
+//! assert_eq!(msg, Msg::ReceivedUser(Ok(User { name: "Alice".to_string() })));
+//! ```
+use crate::dom::{AbortHandle, Task};
+use futures::future::LocalBoxFuture;
+#[cfg(feature = "with-serde")]
+use serde::{de::DeserializeOwned, Serialize};
+use std::{fmt, time::Duration};
+
+#[cfg(feature = "with-dom")]
+pub use fetch::Fetch;
+pub use mock::MockTransport;
+
+#[cfg(feature = "with-dom")]
+mod fetch;
+mod mock;
 
 /// Provides functions for doing http network request
 #[derive(Copy, Clone, Debug)]
 pub struct Http;
 
+/// The http request method
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Method {
+    /// GET
+    Get,
+    /// HEAD
+    Head,
+    /// POST
+    Post,
+    /// PUT
+    Put,
+    /// PATCH
+    Patch,
+    /// DELETE
+    Delete,
+    /// OPTIONS
+    Options,
+}
+
+/// An http request, built with [`Http::get`], [`Http::post`] and the likes.
+#[derive(Debug, Clone)]
+pub struct Request {
+    method: Method,
+    url: String,
+    headers: Vec<(String, String)>,
+    body: Option<Vec<u8>>,
+    timeout: Option<Duration>,
+    abort_handle: Option<AbortHandle>,
+    /// error encountered while building the request, returned when the request is sent
+    error: Option<HttpError>,
+}
+
+/// The response of an http request
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Response {
+    /// the http status code
+    pub status: u16,
+    /// the headers of the response
+    pub headers: Vec<(String, String)>,
+    /// the content of the response
+    pub body: Vec<u8>,
+}
+
+/// The errors in sending the http request and decoding its response
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum HttpError {
+    /// The request failed to reach the server, such as network failure or blocked by CORS
+    #[error("network error: {0}")]
+    Network(String),
+    /// The request did not complete within the timeout
+    #[error("request timed out")]
+    Timeout,
+    /// The request was aborted
+    #[error("request aborted")]
+    Aborted,
+    /// The server responded with a status which is not successful
+    #[error("http status {}", .0.status)]
+    Status(Response),
+    /// The body of the request could not be encoded
+    #[error("unable to encode the request body: {0}")]
+    Encode(String),
+    /// The body of the response could not be decoded
+    #[error("unable to decode the response body: {0}")]
+    Decode(String),
+}
+
+/// Sends the http request and receives its response
+pub trait Transport {
+    /// send the request, the returned future is dropped when the request is aborted
+    fn send(&self, request: Request) -> LocalBoxFuture<'static, Result<Response, HttpError>>;
+}
+
 impl Http {
-    /// fetch text document from the url and decode the result with the supplied
-    pub async fn fetch_text(url: &str) -> Result<String, TypeError> {
-        let response = Self::fetch_with_request_init(url, None).await?;
-
-        let response_promise = response.text().expect("must be a promise text");
-
-        let response_text = JsFuture::from(response_promise)
-            .await
-            .expect("must not error")
-            .as_string()
-            .expect("must be a text");
-
-        Ok(response_text)
-    }
-
-    /// API for fetching http rest request
-    pub async fn fetch_with_request_init(
-        url: &str,
-        request_init: Option<RequestInit>,
-    ) -> Result<Response, TypeError> {
-        let window = web_sys::window().expect("should a refernce to window");
-
-        let fetch_promise = if let Some(ref request_init) = request_init {
-            window.fetch_with_str_and_init(url, request_init)
-        } else {
-            window.fetch_with_str(url)
-        };
-
-        match JsFuture::from(fetch_promise).await {
-            Ok(result) => {
-                let response: Response = result.unchecked_into();
-                Ok(response)
+    /// create a GET request to the url
+    pub fn get(url: impl ToString) -> Request {
+        Request::new(Method::Get, url)
+    }
+
+    /// create a HEAD request to the url
+    pub fn head(url: impl ToString) -> Request {
+        Request::new(Method::Head, url)
+    }
+
+    /// create a POST request to the url
+    pub fn post(url: impl ToString) -> Request {
+        Request::new(Method::Post, url)
+    }
+
+    /// create a PUT request to the url
+    pub fn put(url: impl ToString) -> Request {
+        Request::new(Method::Put, url)
+    }
+
+    /// create a PATCH request to the url
+    pub fn patch(url: impl ToString) -> Request {
+        Request::new(Method::Patch, url)
+    }
+
+    /// create a DELETE request to the url
+    pub fn delete(url: impl ToString) -> Request {
+        Request::new(Method::Delete, url)
+    }
+}
+
+impl Method {
+    /// the method name used in the http request
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Method::Get => "GET",
+            Method::Head => "HEAD",
+            Method::Post => "POST",
+            Method::Put => "PUT",
+            Method::Patch => "PATCH",
+            Method::Delete => "DELETE",
+            Method::Options => "OPTIONS",
+        }
+    }
+}
+
+impl fmt::Display for Method {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+impl Request {
+    /// create a request with the method to the url
+    pub fn new(method: Method, url: impl ToString) -> Self {
+        Self {
+            method,
+            url: url.to_string(),
+            headers: vec![],
+            body: None,
+            timeout: None,
+            abort_handle: None,
+            error: None,
+        }
+    }
+
+    /// add a header to the request
+    pub fn header(mut self, name: impl ToString, value: impl ToString) -> Self {
+        self.headers.push((name.to_string(), value.to_string()));
+        self
+    }
+
+    /// set the content of the request
+    pub fn body(mut self, body: impl Into<Vec<u8>>) -> Self {
+        self.body = Some(body.into());
+        self
+    }
+
+    /// set the content of the request to the value serialized as json
+    #[cfg(feature = "with-serde")]
+    pub fn json<T: Serialize>(self, value: &T) -> Self {
+        match serde_json::to_vec(value) {
+            Ok(body) => self.header("Content-Type", "application/json").body(body),
+            Err(e) => self.with_error(HttpError::Encode(e.to_string())),
+        }
+    }
+
+    /// set the content of the request to the value serialized as url encoded form
+    #[cfg(feature = "with-serde")]
+    pub fn form<T: Serialize>(self, value: &T) -> Self {
+        match serde_urlencoded::to_string(value) {
+            Ok(body) => self
+                .header("Content-Type", "application/x-www-form-urlencoded")
+                .body(body),
+            Err(e) => self.with_error(HttpError::Encode(e.to_string())),
+        }
+    }
+
+    /// fail the request with [`HttpError::Timeout`] when the response is not received within
+    /// the timeout
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    /// fail the request with [`HttpError::Aborted`] when the handle is aborted
+    pub fn abort_handle(mut self, abort_handle: &AbortHandle) -> Self {
+        self.abort_handle = Some(abort_handle.clone());
+        self
+    }
+
+    #[cfg(feature = "with-serde")]
+    fn with_error(mut self, error: HttpError) -> Self {
+        self.error = Some(error);
+        self
+    }
+
+    /// the method of the request
+    pub fn method(&self) -> Method {
+        self.method
+    }
+
+    /// the url of the request
+    pub fn url(&self) -> &str {
+        &self.url
+    }
+
+    /// the headers of the request
+    pub fn headers(&self) -> &[(String, String)] {
+        &self.headers
+    }
+
+    /// the content of the request
+    pub fn body_bytes(&self) -> Option<&[u8]> {
+        self.body.as_deref()
+    }
+
+    /// the timeout of the request
+    pub fn timeout_duration(&self) -> Option<Duration> {
+        self.timeout
+    }
+
+    /// send the request with the transport
+    pub fn send_with(
+        mut self,
+        transport: &impl Transport,
+    ) -> LocalBoxFuture<'static, Result<Response, HttpError>> {
+        if let Some(error) = self.error.take() {
+            return Box::pin(async move { Err(error) });
+        }
+        match self.abort_handle.take() {
+            Some(abort_handle) if abort_handle.is_aborted() => {
+                Box::pin(async { Err(HttpError::Aborted) })
             }
-            Err(err) => {
-                let type_error: TypeError = err.unchecked_into();
-                Err(type_error)
+            Some(abort_handle) => {
+                let response = abort_handle.wrap(transport.send(self));
+                Box::pin(async move { response.await.unwrap_or(Err(HttpError::Aborted)) })
             }
+            None => transport.send(self),
         }
     }
+
+    /// create a task which sends the request with the transport, then decodes the successful
+    /// response with `decode` and maps the result into a MSG with `f`.
+    /// Responses with status which is not successful are returned as [`HttpError::Status`].
+    pub fn task_with<T, MSG, D, F>(self, transport: &impl Transport, decode: D, f: F) -> Task<MSG>
+    where
+        D: FnOnce(&Response) -> Result<T, HttpError> + 'static,
+        F: FnOnce(Result<T, HttpError>) -> MSG + 'static,
+        MSG: 'static,
+    {
+        let response = self.send_with(transport);
+        Task::new(async move {
+            let result = response.await.and_then(|response| {
+                if response.is_success() {
+                    decode(&response)
+                } else {
+                    Err(HttpError::Status(response))
+                }
+            });
+            f(result)
+        })
+    }
+}
+
+impl Response {
+    /// create a response with the status and the body
+    pub fn new(status: u16, body: impl Into<Vec<u8>>) -> Self {
+        Self {
+            status,
+            headers: vec![],
+            body: body.into(),
+        }
+    }
+
+    /// add a header to the response
+    pub fn header(mut self, name: impl ToString, value: impl ToString) -> Self {
+        self.headers.push((name.to_string(), value.to_string()));
+        self
+    }
+
+    /// returns true if the status is in the range of 200-299
+    pub fn is_success(&self) -> bool {
+        (200..300).contains(&self.status)
+    }
+
+    /// return the value of the header, the name is case insensitive
+    pub fn header_value(&self, name: &str) -> Option<&str> {
+        self.headers
+            .iter()
+            .find(|(header, _)| header.eq_ignore_ascii_case(name))
+            .map(|(_, value)| value.as_str())
+    }
+
+    /// decode the body as utf8 text
+    pub fn text(&self) -> Result<String, HttpError> {
+        String::from_utf8(self.body.clone()).map_err(|e| HttpError::Decode(e.to_string()))
+    }
+
+    /// decode the body as json
+    #[cfg(feature = "with-serde")]
+    pub fn json<T: DeserializeOwned>(&self) -> Result<T, HttpError> {
+        serde_json::from_slice(&self.body).map_err(|e| HttpError::Decode(e.to_string()))
+    }
+
+    /// decode the body as url encoded form
+    #[cfg(feature = "with-serde")]
+    pub fn form<T: DeserializeOwned>(&self) -> Result<T, HttpError> {
+        serde_urlencoded::from_bytes(&self.body).map_err(|e| HttpError::Decode(e.to_string()))
+    }
+
+    /// the raw bytes of the body
+    pub fn bytes(&self) -> Result<Vec<u8>, HttpError> {
+        Ok(self.body.clone())
+    }
 }
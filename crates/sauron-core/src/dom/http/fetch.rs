@@ -0,0 +1,180 @@
+//! Sending the http request with the browser fetch api
+use super::{Http, HttpError, Request, Response, Transport};
+use crate::dom::{request_timeout_callback, Application, Cmd, Task};
+use futures::future::LocalBoxFuture;
+use js_sys::{Array, Uint8Array};
+use std::{cell::Cell, rc::Rc};
+use wasm_bindgen::{JsCast, JsValue};
+use wasm_bindgen_futures::JsFuture;
+use web_sys::{AbortController, Headers, RequestInit};
+
+/// A [`Transport`] which sends the request using the browser fetch api
+#[derive(Copy, Clone, Debug, Default)]
+pub struct Fetch;
+
+/// aborts the fetch when the request is dropped before it completes,
+/// such as when the request is aborted
+struct AbortOnDrop(Option<AbortController>);
+
+impl AbortOnDrop {
+    /// the request has completed, there is nothing to abort
+    fn disarm(mut self) {
+        self.0 = None;
+    }
+}
+
+impl Drop for AbortOnDrop {
+    fn drop(&mut self) {
+        if let Some(controller) = self.0.take() {
+            controller.abort();
+        }
+    }
+}
+
+impl Transport for Fetch {
+    fn send(&self, request: Request) -> LocalBoxFuture<'static, Result<Response, HttpError>> {
+        Box::pin(fetch(request))
+    }
+}
+
+async fn fetch(request: Request) -> Result<Response, HttpError> {
+    let headers = Headers::new().map_err(js_error)?;
+    for (name, value) in request.headers() {
+        headers.append(name, value).map_err(js_error)?;
+    }
+    let controller = AbortController::new().map_err(js_error)?;
+    let request_init = RequestInit::new();
+    request_init.set_method(request.method().as_str());
+    request_init.set_headers(&headers);
+    request_init.set_signal(Some(&controller.signal()));
+    if let Some(body) = request.body_bytes() {
+        request_init.set_body(&Uint8Array::from(body));
+    }
+
+    let is_timed_out = Rc::new(Cell::new(false));
+    let _timeout_handle = match request.timeout_duration() {
+        Some(timeout) => {
+            let controller = controller.clone();
+            let is_timed_out = Rc::clone(&is_timed_out);
+            let handle = request_timeout_callback(
+                move || {
+                    is_timed_out.set(true);
+                    controller.abort();
+                },
+                timeout.as_millis() as i32,
+            )
+            .map_err(js_error)?;
+            Some(handle)
+        }
+        None => None,
+    };
+    let abort_on_drop = AbortOnDrop(Some(controller));
+
+    let response = async {
+        let response = Http::fetch_with_request_init(request.url(), Some(request_init)).await?;
+        let body = JsFuture::from(response.array_buffer().map_err(js_error)?)
+            .await
+            .map_err(js_error)?;
+        let headers = js_sys::try_iter(&response.headers())
+            .map_err(js_error)?
+            .into_iter()
+            .flatten()
+            .filter_map(|entry| {
+                let entry: Array = entry.ok()?.dyn_into().ok()?;
+                Some((entry.get(0).as_string()?, entry.get(1).as_string()?))
+            })
+            .collect();
+        Ok(Response {
+            status: response.status(),
+            headers,
+            body: Uint8Array::new(&body).to_vec(),
+        })
+    }
+    .await;
+
+    abort_on_drop.disarm();
+    response.map_err(|e| {
+        if is_timed_out.get() {
+            HttpError::Timeout
+        } else {
+            e
+        }
+    })
+}
+
+fn js_error(error: JsValue) -> HttpError {
+    let message = error
+        .dyn_ref::<js_sys::Error>()
+        .map(|e| String::from(e.message()))
+        .or_else(|| error.as_string())
+        .unwrap_or_else(|| format!("{error:?}"));
+    HttpError::Network(message)
+}
+
+impl Http {
+    /// fetch text document from the url
+    pub async fn fetch_text(url: &str) -> Result<String, HttpError> {
+        let response = Self::get(url).send().await?;
+        if !response.is_success() {
+            return Err(HttpError::Status(response));
+        }
+        response.text()
+    }
+
+    /// API for fetching http rest request
+    pub async fn fetch_with_request_init(
+        url: &str,
+        request_init: Option<RequestInit>,
+    ) -> Result<web_sys::Response, HttpError> {
+        let window = web_sys::window()
+            .ok_or_else(|| HttpError::Network("there is no window to fetch from".to_string()))?;
+
+        let fetch_promise = if let Some(ref request_init) = request_init {
+            window.fetch_with_str_and_init(url, request_init)
+        } else {
+            window.fetch_with_str(url)
+        };
+
+        let response = JsFuture::from(fetch_promise).await.map_err(js_error)?;
+        response.dyn_into().map_err(js_error)
+    }
+}
+
+impl Request {
+    /// send the request using the browser fetch api
+    pub fn send(self) -> LocalBoxFuture<'static, Result<Response, HttpError>> {
+        self.send_with(&Fetch)
+    }
+
+    /// create a task which sends the request using the browser fetch api, then decodes the
+    /// successful response with `decode` and maps the result into a MSG with `f`.
+    /// See [`Request::task_with`].
+    pub fn task<T, MSG, D, F>(self, decode: D, f: F) -> Task<MSG>
+    where
+        D: FnOnce(&Response) -> Result<T, HttpError> + 'static,
+        F: FnOnce(Result<T, HttpError>) -> MSG + 'static,
+        MSG: 'static,
+    {
+        self.task_with(&Fetch, decode, f)
+    }
+}
+
+impl<APP, MSG> Cmd<APP, MSG>
+where
+    MSG: 'static,
+    APP: Application<MSG> + 'static,
+{
+    /// Send the http request, then dispatch the MSG mapped by `f` from the response decoded
+    /// with `decode`.
+    /// # Example
+    /// ```rust,ignore
+    /// Cmd::http(Http::get("/api/users"), Response::json, Msg::ReceivedUsers)
+    /// ```
+    pub fn http<T, D, F>(request: Request, decode: D, f: F) -> Self
+    where
+        D: FnOnce(&Response) -> Result<T, HttpError> + 'static,
+        F: FnOnce(Result<T, HttpError>) -> MSG + 'static,
+    {
+        Cmd::from(request.task(decode, f))
+    }
+}
@@ -0,0 +1,63 @@
+//! A transport which responds with prepared responses, used for testing without a browser
+use super::{HttpError, Method, Request, Response, Transport};
+use futures::future::LocalBoxFuture;
+use std::{cell::RefCell, rc::Rc};
+
+/// the response prepared for the requests with the method and url
+type MockResponse = (Method, String, Result<Response, HttpError>);
+
+/// A [`Transport`] which responds to the requests with the prepared responses
+/// and records the requests it received.
+/// The clones of the mock transport share the same responses and recorded requests.
+#[derive(Debug, Clone, Default)]
+pub struct MockTransport {
+    responses: Rc<RefCell<Vec<MockResponse>>>,
+    requests: Rc<RefCell<Vec<Request>>>,
+}
+
+impl MockTransport {
+    /// create a mock transport with no prepared responses
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// respond with the response to the requests with the method and url
+    pub fn respond(&self, method: Method, url: impl ToString, response: Response) {
+        self.responses
+            .borrow_mut()
+            .push((method, url.to_string(), Ok(response)));
+    }
+
+    /// fail the requests with the method and url with the error
+    pub fn fail(&self, method: Method, url: impl ToString, error: HttpError) {
+        self.responses
+            .borrow_mut()
+            .push((method, url.to_string(), Err(error)));
+    }
+
+    /// the requests that were sent through this transport
+    pub fn requests(&self) -> Vec<Request> {
+        self.requests.borrow().clone()
+    }
+}
+
+impl Transport for MockTransport {
+    fn send(&self, request: Request) -> LocalBoxFuture<'static, Result<Response, HttpError>> {
+        let response = self
+            .responses
+            .borrow()
+            .iter()
+            .rev()
+            .find(|(method, url, _)| *method == request.method() && url == request.url())
+            .map(|(_, _, response)| response.clone())
+            .unwrap_or_else(|| {
+                Err(HttpError::Network(format!(
+                    "no mock response for {} {}",
+                    request.method(),
+                    request.url()
+                )))
+            });
+        self.requests.borrow_mut().push(request);
+        Box::pin(async move { response })
+    }
+}
@@ -0,0 +1,296 @@
+//! Hydration attaches the APP to the DOM that was already rendered in the server,
+//! instead of creating the DOM nodes again.
+use crate::{
+    dom::{self, Application, DomBackend, Program},
+    html,
+    html::attributes::{Listener, SegregatedAttributes},
+    vdom,
+    vdom::Leaf,
+    Error,
+};
+use mt_dom::TreePath;
+
+/// A difference between the server rendered DOM and the view of the APP found while hydrating.
+/// The mismatched DOM node and its descendants are left as is and don't receive the event
+/// listeners of the view.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HydrationMismatch {
+    /// the path of the node in the view
+    pub path: TreePath,
+    /// the node expected by the view
+    pub expected: String,
+    /// the DOM node that was found in the server rendered html
+    pub found: String,
+}
+
+impl HydrationMismatch {
+    fn new(path: &[usize], expected: impl ToString, found: String) -> Self {
+        Self {
+            path: TreePath::new(path.to_vec()),
+            expected: expected.to_string(),
+            found,
+        }
+    }
+}
+
+impl<APP, MSG, DOM> Program<APP, MSG, DOM>
+where
+    MSG: 'static,
+    APP: Application<MSG> + 'static,
+    DOM: DomBackend,
+{
+    /// Walk the DOM node rendered in the server alongside the virtual node, attaching the event
+    /// listeners of the virtual node to the matching DOM elements.
+    /// When the virtual node is a fragment or a node list, its nodes are matched against the
+    /// children of the DOM node.
+    ///
+    /// Whitespace text nodes that were added by the pretty rendering and the separator comments
+    /// in between text nodes are removed, since the DOM patches locates the nodes by their index.
+    /// The mismatched nodes are never replaced, they are returned instead.
+    pub(crate) fn hydrate_dom_node(
+        &self,
+        vnode: &vdom::Node<MSG>,
+        node: &DOM::Node,
+    ) -> Result<Vec<HydrationMismatch>, Error> {
+        let mut mismatches = vec![];
+        match vnode {
+            vdom::Node::Fragment(nodes) | vdom::Node::NodeList(nodes) => {
+                self.hydrate_children(node, nodes, &[], &mut mismatches)?
+            }
+            vdom::Node::Element(element) => {
+                self.hydrate_element(element, node, &[], &mut mismatches)?
+            }
+            vdom::Node::Leaf(leaf) => {
+                if !self.is_matching_leaf(leaf, node) {
+                    mismatches.push(HydrationMismatch::new(
+                        &[],
+                        describe_vnode(vnode),
+                        self.describe_dom_node(Some(node)),
+                    ));
+                }
+            }
+        }
+        Ok(mismatches)
+    }
+
+    fn hydrate_element(
+        &self,
+        velem: &vdom::Element<MSG>,
+        node: &DOM::Node,
+        path: &[usize],
+        mismatches: &mut Vec<HydrationMismatch>,
+    ) -> Result<(), Error> {
+        let is_matching_tag = self
+            .backend
+            .tag_name(node)
+            .is_some_and(|tag| tag.eq_ignore_ascii_case(velem.tag()));
+        if !is_matching_tag {
+            mismatches.push(HydrationMismatch::new(
+                path,
+                format!("<{}>", velem.tag()),
+                self.describe_dom_node(Some(node)),
+            ));
+            return Ok(());
+        }
+
+        let attrs = velem.attributes().iter().collect::<Vec<_>>();
+        let merged_attrs = mt_dom::merge_attributes_of_same_name(&attrs);
+        let mut listeners: Vec<(&'static str, &Listener<dom::Event, MSG>)> = vec![];
+        let mut has_inner_html = false;
+        for attr in merged_attrs.iter() {
+            let SegregatedAttributes {
+                listeners: attr_listeners,
+                function_calls,
+                ..
+            } = html::attributes::partition_callbacks_from_plain_styles_and_func_calls(attr);
+            listeners.extend(attr_listeners.into_iter().map(|l| (*attr.name(), l)));
+            has_inner_html |= *attr.name() == "inner_html" && !function_calls.is_empty();
+        }
+        self.add_element_listeners(node, &listeners)?;
+
+        // the content of the stateful components are created again when they are mounted
+        let components: Vec<_> = merged_attrs
+            .iter()
+            .flat_map(|attr| attr.value())
+            .filter_map(|value| value.as_component())
+            .collect();
+        for component in components.iter() {
+            self.mount_component(node, component);
+        }
+
+        // the content of the inner_html is not part of the view
+        if !has_inner_html && components.is_empty() {
+            self.hydrate_children(node, velem.children(), path, mismatches)?;
+        }
+        self.dispatch_mount(node)
+    }
+
+    fn hydrate_children(
+        &self,
+        parent: &DOM::Node,
+        vchildren: &[vdom::Node<MSG>],
+        path: &[usize],
+        mismatches: &mut Vec<HydrationMismatch>,
+    ) -> Result<(), Error> {
+        let mut current = self.backend.child_node(parent, 0);
+        for (child_path, vchild) in flatten_children(vchildren, path) {
+            while let Some(node) = current
+                .clone()
+                .filter(|node| self.is_render_only(node, vchild))
+            {
+                current = self.backend.next_sibling(&node);
+                self.backend.remove_child(parent, &node)?;
+            }
+            match vchild {
+                vdom::Node::Element(element) => match current {
+                    Some(node) => {
+                        self.hydrate_element(element, &node, &child_path, mismatches)?;
+                        current = self.backend.next_sibling(&node);
+                    }
+                    None => mismatches.push(HydrationMismatch::new(
+                        &child_path,
+                        describe_vnode(vchild),
+                        self.describe_dom_node(None),
+                    )),
+                },
+                vdom::Node::Leaf(Leaf::SafeHtml(html)) => {
+                    for _ in 0..count_html_nodes(&self.backend, html)? {
+                        current = current.and_then(|node| self.backend.next_sibling(&node));
+                    }
+                }
+                vdom::Node::Leaf(Leaf::Text(txt)) if txt.is_empty() => {
+                    // empty text nodes can not be expressed in html
+                    let created_node = self.backend.create_text_node(txt);
+                    self.backend
+                        .insert_before(parent, &created_node, current.as_ref())?;
+                }
+                vdom::Node::Leaf(leaf) => match current {
+                    Some(node) if self.is_matching_leaf(leaf, &node) => {
+                        if let Leaf::Text(txt) = leaf {
+                            // the whitespace around the text may have been added by the pretty
+                            // rendering
+                            self.backend.set_node_value(&node, txt);
+                        }
+                        current = self.backend.next_sibling(&node);
+                    }
+                    _ => {
+                        mismatches.push(HydrationMismatch::new(
+                            &child_path,
+                            describe_vnode(vchild),
+                            self.describe_dom_node(current.as_ref()),
+                        ));
+                        current = current.and_then(|node| self.backend.next_sibling(&node));
+                    }
+                },
+                vdom::Node::Fragment(_) | vdom::Node::NodeList(_) => {
+                    unreachable!("fragments must have been flattened")
+                }
+            }
+        }
+        while let Some(node) = current {
+            current = self.backend.next_sibling(&node);
+            if self.is_whitespace_text(&node) || self.is_separator(&node) {
+                self.backend.remove_child(parent, &node)?;
+            } else {
+                mismatches.push(HydrationMismatch::new(
+                    path,
+                    "no more nodes",
+                    self.describe_dom_node(Some(&node)),
+                ));
+            }
+        }
+        Ok(())
+    }
+
+    /// whether the DOM node is only in the rendered html but not in the view, the separator
+    /// comments are only rendered in front of a text node
+    fn is_render_only(&self, node: &DOM::Node, vnode: &vdom::Node<MSG>) -> bool {
+        match vnode {
+            vdom::Node::Leaf(Leaf::Text(_)) => self.is_separator(node),
+            vdom::Node::Leaf(Leaf::Comment(_)) => self.is_whitespace_text(node),
+            _ => self.is_whitespace_text(node) || self.is_separator(node),
+        }
+    }
+
+    fn is_whitespace_text(&self, node: &DOM::Node) -> bool {
+        self.backend.is_text(node)
+            && self
+                .backend
+                .node_value(node)
+                .map(|txt| txt.trim().is_empty())
+                .unwrap_or(true)
+    }
+
+    fn is_separator(&self, node: &DOM::Node) -> bool {
+        self.backend.is_comment(node)
+            && self.backend.node_value(node).as_deref() == Some(html::TEXT_SEPARATOR)
+    }
+
+    fn is_matching_leaf(&self, leaf: &Leaf, node: &DOM::Node) -> bool {
+        match leaf {
+            Leaf::Text(txt) => {
+                self.backend.is_text(node)
+                    && self
+                        .backend
+                        .node_value(node)
+                        .is_some_and(|value| value.trim() == txt.trim())
+            }
+            Leaf::Comment(_) => self.backend.is_comment(node),
+            Leaf::SafeHtml(_) | Leaf::DocType(_) => true,
+        }
+    }
+
+    fn describe_dom_node(&self, node: Option<&DOM::Node>) -> String {
+        let Some(node) = node else {
+            return "nothing".to_string();
+        };
+        if let Some(tag) = self.backend.tag_name(node) {
+            format!("<{tag}>")
+        } else if self.backend.is_text(node) {
+            let text = self.backend.node_value(node).unwrap_or_default();
+            format!("text {text:?}")
+        } else if self.backend.is_comment(node) {
+            let comment = self.backend.node_value(node).unwrap_or_default();
+            format!("comment {comment:?}")
+        } else {
+            format!("{node:?}")
+        }
+    }
+}
+
+/// flatten the fragments and node list in the children, since they are unrolled when
+/// created into the DOM
+pub(crate) fn flatten_children<'a, MSG>(
+    vchildren: &'a [vdom::Node<MSG>],
+    path: &[usize],
+) -> Vec<(Vec<usize>, &'a vdom::Node<MSG>)> {
+    let mut flattened = vec![];
+    for (index, vchild) in vchildren.iter().enumerate() {
+        let child_path = [path, &[index]].concat();
+        match vchild {
+            vdom::Node::Fragment(nodes) | vdom::Node::NodeList(nodes) => {
+                flattened.extend(flatten_children(nodes, &child_path))
+            }
+            _ => flattened.push((child_path, vchild)),
+        }
+    }
+    flattened
+}
+
+/// the number of nodes the html is parsed into
+pub(crate) fn count_html_nodes<DOM: DomBackend>(backend: &DOM, html: &str) -> Result<usize, Error> {
+    let container = backend.create_element("div", None)?;
+    backend.append_html(&container, html)?;
+    Ok(backend.child_nodes(&container).len())
+}
+
+fn describe_vnode<MSG>(vnode: &vdom::Node<MSG>) -> String {
+    match vnode {
+        vdom::Node::Element(element) => format!("<{}>", element.tag()),
+        vdom::Node::Leaf(Leaf::Text(txt)) => format!("text {txt:?}"),
+        vdom::Node::Leaf(Leaf::Comment(comment)) => format!("comment {comment:?}"),
+        vdom::Node::Leaf(Leaf::SafeHtml(html)) => format!("html {html:?}"),
+        vdom::Node::Leaf(Leaf::DocType(doctype)) => format!("doctype {doctype:?}"),
+        vdom::Node::Fragment(_) | vdom::Node::NodeList(_) => "a list of nodes".to_string(),
+    }
+}
@@ -0,0 +1,432 @@
+//! An in-page developer inspector, enabled with the `with-inspector` feature.
+//!
+//! [`Program::enable_inspector`] mounts the [`Inspector`] component into the shadow root of an
+//! element appended to the body, such that the styles of the app and the inspector don't leak
+//! into each other. The program then sends its view, the dispatched msgs and the
+//! [`Measurements`] of each update into the inspector. The DOM nodes touched by the patches are
+//! flashed with an outline.
+use crate::dom::backend::as_web_node;
+use crate::dom::component::{ComponentApp, ComponentMsg};
+use crate::dom::{
+    delay, document, util::body, Application, Component, DomBackend, Effects, Measurements,
+    MountAction, MountTarget, Program,
+};
+use crate::html::attributes::{
+    class, classes_flag, merge_plain_attributes_values, merge_styles_attributes_values,
+    partition_callbacks_from_plain_styles_and_func_calls, styles, title, SegregatedAttributes,
+};
+use crate::html::{div, events::on_click, li, span, text, ul};
+use crate::vdom::{self, Leaf, Node};
+use crate::Error;
+use std::collections::VecDeque;
+use std::fmt::Debug;
+use std::rc::Rc;
+use wasm_bindgen::JsCast;
+use web_sys::Element;
+
+/// the number of msgs and measurements kept by the inspector
+const HISTORY_CAPACITY: usize = 50;
+
+/// how long the patched nodes are flashed, in milliseconds
+const FLASH_DURATION: i32 = 600;
+
+/// the total time of an update which fills the height of the timeline, in milliseconds
+const TIMELINE_SCALE: f64 = 32.0;
+
+/// the style of the inspector, which is injected into its shadow root only, such that it
+/// doesn't leak into the app
+const STYLESHEET: &str = r#"
+.inspector { font: 12px monospace; color: #222; }
+.toggle { position: fixed; right: 8px; bottom: 8px; z-index: 2147483647; padding: 4px 8px; background: #222; color: #eee; cursor: pointer; }
+.panel { position: fixed; right: 8px; bottom: 36px; z-index: 2147483647; width: 420px; max-height: 60vh; overflow: auto; background: #fafafa; border: 1px solid #ccc; }
+.panel ul { list-style: none; margin: 0; padding: 4px; border-bottom: 1px solid #ddd; }
+.tree li { cursor: pointer; white-space: nowrap; }
+.tree li.selected { background: #cde; }
+.details { padding: 4px; border-bottom: 1px solid #ddd; }
+.attributes .name { color: #a11; margin-right: 1em; }
+.listeners li { color: #15a; }
+.timeline { display: flex; align-items: flex-end; gap: 1px; height: 40px; padding: 4px; }
+.timeline .update { flex: 1; min-height: 1px; background: #6a6; }
+.flash { position: fixed; z-index: 2147483646; pointer-events: none; outline: 2px solid #e80; }
+"#;
+
+/// A node of the inspected view, flattened in the order of the tree
+#[derive(Debug, Clone, PartialEq)]
+pub struct InspectedNode {
+    /// the number of ancestors of the node
+    pub depth: usize,
+    /// the tag of the element or the content of the leaf
+    pub label: String,
+    /// the names and the values of the attributes
+    pub attributes: Vec<(String, String)>,
+    /// the event names of the listeners
+    pub listeners: Vec<String>,
+}
+
+/// The position of a patched DOM node in the viewport
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Rect {
+    /// the distance from the left of the viewport
+    pub left: f64,
+    /// the distance from the top of the viewport
+    pub top: f64,
+    /// the width of the node
+    pub width: f64,
+    /// the height of the node
+    pub height: f64,
+}
+
+/// The msgs of the inspector
+#[derive(Debug, Clone, PartialEq)]
+pub enum InspectorMsg {
+    /// the view of the inspected app was updated
+    ViewUpdated(Vec<InspectedNode>),
+    /// a msg, formatted with `Debug`, was dispatched into the inspected app
+    MsgDispatched(String),
+    /// the DOM of the inspected app was updated
+    Measured(Measurements),
+    /// the DOM nodes at these positions were patched
+    Patched(Vec<Rect>),
+    /// the flashes of the patch with this generation are done
+    FlashDone(usize),
+    /// select the node at this index of the tree, to show its attributes and listeners
+    Select(usize),
+    /// show or hide the panel
+    Toggle,
+}
+
+/// Shows the view of the inspected app, the attributes and listeners of the selected node,
+/// the last msgs and a timeline of the updates
+#[derive(Debug, Default)]
+pub struct Inspector {
+    is_open: bool,
+    tree: Vec<InspectedNode>,
+    selected: Option<usize>,
+    msgs: VecDeque<String>,
+    measurements: VecDeque<Measurements>,
+    flashes: Vec<Rect>,
+    flash_generation: usize,
+}
+
+impl Inspector {
+    /// flatten the view into the inspected nodes
+    pub fn inspect<MSG>(node: &vdom::Node<MSG>) -> Vec<InspectedNode> {
+        let mut inspected = vec![];
+        Self::inspect_node(node, 0, &mut inspected);
+        inspected
+    }
+
+    fn inspect_node<MSG>(node: &vdom::Node<MSG>, depth: usize, inspected: &mut Vec<InspectedNode>) {
+        match node {
+            vdom::Node::Element(element) => {
+                let mut attributes = vec![];
+                let mut listeners = vec![];
+                for attr in element.attributes() {
+                    let SegregatedAttributes {
+                        listeners: attr_listeners,
+                        plain_values,
+                        styles,
+                        function_calls,
+                    } = partition_callbacks_from_plain_styles_and_func_calls(attr);
+                    if !attr_listeners.is_empty() {
+                        listeners.push(attr.name().to_string());
+                    }
+                    let value = merge_plain_attributes_values(&plain_values)
+                        .or_else(|| merge_styles_attributes_values(&styles))
+                        .or_else(|| merge_plain_attributes_values(&function_calls));
+                    if let Some(value) = value {
+                        attributes.push((attr.name().to_string(), value));
+                    }
+                }
+                inspected.push(InspectedNode {
+                    depth,
+                    label: format!("<{}>", element.tag()),
+                    attributes,
+                    listeners,
+                });
+                for child in element.children() {
+                    Self::inspect_node(child, depth + 1, inspected);
+                }
+            }
+            vdom::Node::Leaf(leaf) => {
+                let label = match leaf {
+                    Leaf::Text(txt) => format!("{txt:?}"),
+                    Leaf::SafeHtml(html) => format!("html {html:?}"),
+                    Leaf::Comment(comment) => format!("<!--{comment}-->"),
+                    Leaf::DocType(doctype) => format!("<!doctype {doctype}>"),
+                };
+                inspected.push(InspectedNode {
+                    depth,
+                    label,
+                    attributes: vec![],
+                    listeners: vec![],
+                });
+            }
+            vdom::Node::Fragment(nodes) | vdom::Node::NodeList(nodes) => {
+                for node in nodes {
+                    Self::inspect_node(node, depth, inspected);
+                }
+            }
+        }
+    }
+
+    /// push the item to the history, the oldest item is dropped when it is full
+    fn push_history<T>(history: &mut VecDeque<T>, item: T) {
+        if history.len() == HISTORY_CAPACITY {
+            history.pop_front();
+        }
+        history.push_back(item);
+    }
+
+    fn view_tree(&self) -> Node<InspectorMsg> {
+        ul(
+            [class("tree")],
+            self.tree.iter().enumerate().map(|(index, node)| {
+                li(
+                    [
+                        classes_flag([("selected", self.selected == Some(index))]),
+                        styles([("padding-left", format!("{}em", node.depth))]),
+                        on_click(move |_| InspectorMsg::Select(index)),
+                    ],
+                    [text(&node.label)],
+                )
+            }),
+        )
+    }
+
+    fn view_selected(&self) -> Node<InspectorMsg> {
+        let Some(node) = self.selected.and_then(|index| self.tree.get(index)) else {
+            return div([class("details")], [text("select a node")]);
+        };
+        div(
+            [class("details")],
+            [
+                ul(
+                    [class("attributes")],
+                    node.attributes.iter().map(|(name, value)| {
+                        li([], [span([class("name")], [text(name)]), text(value)])
+                    }),
+                ),
+                ul(
+                    [class("listeners")],
+                    node.listeners
+                        .iter()
+                        .map(|event_name| li([], [text(event_name)])),
+                ),
+            ],
+        )
+    }
+
+    fn view_msgs(&self) -> Node<InspectorMsg> {
+        ul(
+            [class("msgs")],
+            self.msgs.iter().rev().map(|msg| li([], [text(msg)])),
+        )
+    }
+
+    fn view_timeline(&self) -> Node<InspectorMsg> {
+        div(
+            [class("timeline")],
+            self.measurements.iter().map(|measurements| {
+                let height = (measurements.total_time / TIMELINE_SCALE).min(1.0) * 100.0;
+                span(
+                    [
+                        class("update"),
+                        styles([("height", format!("{height}%"))]),
+                        title(format!(
+                            "{} patches in {:.1}ms",
+                            measurements.total_patches, measurements.total_time
+                        )),
+                    ],
+                    [],
+                )
+            }),
+        )
+    }
+
+    fn view_flashes(&self) -> Node<InspectorMsg> {
+        div(
+            [class("flashes")],
+            self.flashes.iter().map(|rect| {
+                div(
+                    [
+                        class("flash"),
+                        styles([
+                            ("left", format!("{}px", rect.left)),
+                            ("top", format!("{}px", rect.top)),
+                            ("width", format!("{}px", rect.width)),
+                            ("height", format!("{}px", rect.height)),
+                        ]),
+                    ],
+                    [],
+                )
+            }),
+        )
+    }
+}
+
+impl Component<InspectorMsg, ()> for Inspector {
+    fn update(&mut self, msg: InspectorMsg) -> Effects<InspectorMsg, ()> {
+        match msg {
+            InspectorMsg::ViewUpdated(tree) => {
+                if self.selected.is_some_and(|index| index >= tree.len()) {
+                    self.selected = None;
+                }
+                self.tree = tree;
+            }
+            InspectorMsg::MsgDispatched(msg) => Self::push_history(&mut self.msgs, msg),
+            InspectorMsg::Measured(measurements) => {
+                Self::push_history(&mut self.measurements, measurements)
+            }
+            InspectorMsg::Patched(rects) => {
+                self.flashes = rects;
+                self.flash_generation += 1;
+                let generation = self.flash_generation;
+                return Effects::with_local_async([async move {
+                    delay(FLASH_DURATION).await;
+                    InspectorMsg::FlashDone(generation)
+                }]);
+            }
+            InspectorMsg::FlashDone(generation) => {
+                if generation == self.flash_generation {
+                    self.flashes.clear();
+                }
+            }
+            InspectorMsg::Select(index) => self.selected = Some(index),
+            InspectorMsg::Toggle => self.is_open = !self.is_open,
+        }
+        Effects::none()
+    }
+
+    fn view(&self) -> Node<InspectorMsg> {
+        let toggle = div(
+            [class("toggle"), on_click(|_| InspectorMsg::Toggle)],
+            [text("inspector")],
+        );
+        let panel = self.is_open.then(|| {
+            div(
+                [class("panel")],
+                [
+                    self.view_tree(),
+                    self.view_selected(),
+                    self.view_msgs(),
+                    self.view_timeline(),
+                ],
+            )
+        });
+        div(
+            [class("inspector")],
+            [self.view_flashes(), toggle].into_iter().chain(panel),
+        )
+    }
+}
+
+/// the inspector program which is mounted by the inspected program
+type InspectorProgram = Program<ComponentApp<Inspector, ()>, ComponentMsg<InspectorMsg>>;
+
+/// The inspector mounted by the program.
+/// The program doesn't require the MSG to be `Debug`, so its `fmt` function is captured when
+/// the inspector is enabled.
+pub(crate) struct AttachedInspector<MSG> {
+    format_msg: fn(&MSG) -> String,
+    program: InspectorProgram,
+    host: Element,
+}
+
+impl<APP, MSG> Program<APP, MSG>
+where
+    MSG: 'static,
+    APP: Application<MSG> + 'static,
+{
+    /// mount the inspector into the shadow root of an element appended to the body
+    pub fn enable_inspector(&self) -> Result<(), Error>
+    where
+        MSG: Debug,
+    {
+        self.disable_inspector();
+        let host = document().create_element("div")?;
+        host.set_id("sauron-inspector");
+        body().append_child(&host)?;
+        let app = ComponentApp::new(Inspector::default(), Rc::new(|()| {}));
+        let mut program = Program::new(app, &host, MountAction::Append, MountTarget::ShadowRoot);
+        program.mount()?;
+        program.inject_style_to_mount(STYLESHEET);
+        *self.inspector.borrow_mut() = Some(AttachedInspector {
+            format_msg: |msg| format!("{msg:?}"),
+            program,
+            host,
+        });
+        self.inspect_view();
+        Ok(())
+    }
+}
+
+impl<APP, MSG, DOM> Program<APP, MSG, DOM>
+where
+    MSG: 'static,
+    APP: Application<MSG> + 'static,
+    DOM: DomBackend,
+{
+    /// unmount the inspector and remove its host element
+    pub fn disable_inspector(&self) {
+        if let Some(mut inspector) = self.inspector.borrow_mut().take() {
+            inspector.program.unmount();
+            inspector.host.remove();
+        }
+    }
+
+    fn send_to_inspector(&self, msg: InspectorMsg) {
+        if let Some(inspector) = self.inspector.borrow().as_ref() {
+            inspector.program.clone().dispatch(ComponentMsg(msg));
+        }
+    }
+
+    /// send the msg which is about to be updated into the APP
+    pub(crate) fn inspect_next_msg(&self) {
+        let formatted = self.inspector.borrow().as_ref().and_then(|inspector| {
+            self.app_context
+                .pending_msgs
+                .borrow()
+                .front()
+                .map(inspector.format_msg)
+        });
+        if let Some(formatted) = formatted {
+            self.send_to_inspector(InspectorMsg::MsgDispatched(formatted));
+        }
+    }
+
+    /// send the current view of the APP
+    pub(crate) fn inspect_view(&self) {
+        if self.inspector.borrow().is_some() {
+            let tree = Inspector::inspect(&self.app_context.current_vdom());
+            self.send_to_inspector(InspectorMsg::ViewUpdated(tree));
+        }
+    }
+
+    /// send the measurements of the DOM update
+    pub(crate) fn inspect_measurements(&self, measurements: &Measurements) {
+        self.send_to_inspector(InspectorMsg::Measured(measurements.clone()));
+    }
+
+    /// send the positions of the DOM nodes which were patched, the removed nodes are skipped
+    pub(crate) fn inspect_patched(&self, patched: &[DOM::Node]) {
+        if self.inspector.borrow().is_none() {
+            return;
+        }
+        let rects = patched
+            .iter()
+            .filter_map(|node| as_web_node(node)?.dyn_ref::<Element>())
+            .filter(|element| element.is_connected())
+            .map(|element| {
+                let rect = element.get_bounding_client_rect();
+                Rect {
+                    left: rect.left(),
+                    top: rect.top(),
+                    width: rect.width(),
+                    height: rect.height(),
+                }
+            })
+            .collect();
+        self.send_to_inspector(InspectorMsg::Patched(rects));
+    }
+}
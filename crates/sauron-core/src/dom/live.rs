@@ -0,0 +1,400 @@
+//! A live mode, where the APP runs on the server and its view is streamed to the browser.
+//!
+//! The [`LiveProgram`] holds the APP on the server, it runs the `update`, `view` and `diff` of
+//! the APP and sends the patches in the [wire format](crate::vdom::WirePatch). The
+//! [`LiveClient`] is a tiny app in the browser which applies the patches into the DOM and sends
+//! the events of the listeners back to the server as the [`EventId`] of the listener and the
+//! values of the target element.
+//!
+//! The protocol of a session:
+//! - the client sends [`ClientMessage::Join`] when it is connected, with the session and the
+//!   version of the view it has when it is reconnecting.
+//! - the server sends [`ServerMessage::Render`] with the whole view, unless the client already
+//!   has the latest version of the view of the session.
+//! - the events of the client are updated into the APP, the changes to the view are sent as
+//!   [`ServerMessage::Patch`], each with the next version of the view.
+//! - when a patch is missed or can not be applied, the client sends [`ClientMessage::Resync`]
+//!   and the server renders the whole view again.
+//! - the msgs of the tasks which finish later are updated when the sessions are polled, the
+//!   changes to the view are pushed to the client as [`ServerMessage::Patch`].
+use crate::{
+    dom::{headless::HeadlessRuntime, Application, Event, SyntheticEvent},
+    html::panic_message,
+    vdom::{self, EventId, WireEvents, WireNode, WirePatch},
+    Error,
+};
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::{hash_map::RandomState, BTreeMap},
+    hash::BuildHasher,
+    panic::{self, AssertUnwindSafe},
+    time::{Duration, Instant},
+};
+
+pub use client::{LiveClient, LiveMsg};
+#[cfg(feature = "with-live-server")]
+pub use server::LiveServer;
+
+mod client;
+#[cfg(feature = "with-live-server")]
+mod server;
+
+/// The messages sent from the browser to the server
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ClientMessage {
+    /// join the session, a new session is created when the session is `None` or has expired
+    Join {
+        /// the session to reconnect to
+        session: Option<String>,
+        /// the version of the view the client has, 0 when it has no view
+        version: u64,
+    },
+    /// the event of a listener in the view
+    Event {
+        /// the listener of the event
+        id: EventId,
+        /// the values of the target element of the event
+        event: SyntheticEvent,
+    },
+    /// request the whole view, when the view of the client is out of sync
+    Resync,
+}
+
+/// The messages sent from the server to the browser
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum ServerMessage {
+    /// the whole view, which replaces the view of the client
+    Render {
+        /// the session the client is joined to
+        session: String,
+        /// the version of the view
+        version: u64,
+        /// the view of the APP
+        view: WireNode,
+    },
+    /// the patches to the previous version of the view
+    Patch {
+        /// the version of the view after the patches are applied
+        version: u64,
+        /// the patches to be applied in order
+        patches: Vec<WirePatch>,
+    },
+}
+
+/// Runs the APP of a live session on the server.
+///
+/// The tasks of the APP are run with a local executor, as such they must not use the browser
+/// api. The commands which operate on the `Program` can not run on the server, they are passed
+/// to [`Application::on_error`] as [`Error::Unsupported`]. So are the events of the listeners
+/// which read the web event such as `on_click`, the listeners of the APP are made with
+/// [`on_synthetic`] instead. The view is sent to the client in reply to the messages of the
+/// client, and when the msgs of the tasks which finish later are updated in
+/// [`LiveProgram::poll`].
+///
+/// [`on_synthetic`]: crate::html::events::on_synthetic
+pub struct LiveProgram<APP, MSG>
+where
+    MSG: 'static,
+{
+    runtime: HeadlessRuntime<APP, MSG>,
+    /// the view which the client has
+    sent_view: vdom::Node<MSG>,
+    /// the view which the client has, in the wire format
+    client_view: WireNode,
+    /// the listeners of the view which the client has
+    events: WireEvents<MSG>,
+    version: u64,
+}
+
+/// A live session and the time the client has left
+struct LiveSession<APP, MSG>
+where
+    MSG: 'static,
+{
+    program: LiveProgram<APP, MSG>,
+    /// the time the client disconnected, the session is removed once it expires
+    left: Option<Instant>,
+}
+
+/// The live sessions of a server, each session runs its own APP.
+///
+/// The sessions of the clients which disconnect are kept for the [`LiveSessions::session_ttl`]
+/// such that the clients can reconnect without losing the state of the APP.
+pub struct LiveSessions<APP, MSG>
+where
+    MSG: 'static,
+{
+    new_app: Box<dyn Fn() -> APP>,
+    sessions: BTreeMap<String, LiveSession<APP, MSG>>,
+    session_ttl: Duration,
+    random_state: RandomState,
+    /// the number of sessions created, hashed into the id of the sessions
+    created: u64,
+}
+
+impl<APP, MSG> LiveProgram<APP, MSG>
+where
+    MSG: Clone + 'static,
+    APP: Application<MSG> + 'static,
+{
+    /// run the APP, nothing is sent until the client joins
+    pub fn new(mut app: APP) -> Self {
+        let cmd = app.init();
+        let mut runtime = HeadlessRuntime::new(app).report_skipped_commands();
+        runtime.emit(cmd);
+        runtime.settle();
+        let sent_view = runtime.view().clone();
+        Self {
+            runtime,
+            sent_view,
+            client_view: WireNode::Fragment(vec![]),
+            events: WireEvents::new(),
+            version: 0,
+        }
+    }
+
+    /// the APP of the session
+    pub fn app(&self) -> &APP {
+        self.runtime.app()
+    }
+
+    /// the current view of the APP
+    pub fn view(&self) -> &vdom::Node<MSG> {
+        self.runtime.view()
+    }
+
+    /// the version of the view which is last sent to the client
+    pub fn version(&self) -> u64 {
+        self.version
+    }
+
+    /// handle the message of the client, returns the messages to be sent to the client.
+    ///
+    /// A panic of the APP is not caught here, [`LiveSessions`] removes the session of the APP
+    /// which panicked.
+    pub fn handle(
+        &mut self,
+        session: &str,
+        msg: ClientMessage,
+    ) -> Result<Vec<ServerMessage>, Error> {
+        match msg {
+            ClientMessage::Join { version, .. } if version != 0 && version == self.version => {
+                Ok(vec![])
+            }
+            ClientMessage::Join { .. } | ClientMessage::Resync => Ok(vec![self.render(session)?]),
+            ClientMessage::Event { id, event } => {
+                // the listener is removed from the view, the event is from an older view
+                let Some(listener) = self.events.get(id) else {
+                    return Ok(vec![]);
+                };
+                match listener.emit_event(Event::Synthetic(event)) {
+                    Some(msg) => self.runtime.push_msgs([msg]),
+                    None => self
+                        .runtime
+                        .report_error(Error::Unsupported("a listener which reads the web event")),
+                }
+                self.runtime.settle();
+                self.patch()
+            }
+        }
+    }
+
+    /// update the msgs of the tasks which finished since the last message of the client,
+    /// returns the patches to be pushed to the client when the view is changed
+    pub fn poll(&mut self) -> Result<Vec<ServerMessage>, Error> {
+        let updated = self.runtime.settle();
+        if updated == 0 {
+            return Ok(vec![]);
+        }
+        self.patch()
+    }
+
+    /// the whole view of the APP
+    pub fn render(&mut self, session: &str) -> Result<ServerMessage, Error> {
+        self.events.clear();
+        let view = WireNode::encode(self.runtime.view(), &mut self.events)?;
+        self.sent_view = self.runtime.view().clone();
+        self.client_view = view.clone();
+        self.version += 1;
+        Ok(ServerMessage::Render {
+            session: session.to_string(),
+            version: self.version,
+            view,
+        })
+    }
+
+    /// the patches from the view which the client has to the current view of the APP
+    fn patch(&mut self) -> Result<Vec<ServerMessage>, Error> {
+        let patches = WirePatch::encode_all(
+            &vdom::diff(&self.sent_view, self.runtime.view()),
+            &mut self.events,
+        )?;
+        if patches.is_empty() {
+            return Ok(vec![]);
+        }
+        self.client_view.apply_patches(&patches)?;
+        self.events.retain_in(&self.client_view);
+        self.sent_view = self.runtime.view().clone();
+        self.version += 1;
+        Ok(vec![ServerMessage::Patch {
+            version: self.version,
+            patches,
+        }])
+    }
+}
+
+impl<APP, MSG> LiveSessions<APP, MSG>
+where
+    MSG: Clone + 'static,
+    APP: Application<MSG> + 'static,
+{
+    /// the sessions of the APP created with `new_app`, the sessions of the disconnected clients
+    /// are kept for 5 minutes
+    pub fn new(new_app: impl Fn() -> APP + 'static) -> Self {
+        Self {
+            new_app: Box::new(new_app),
+            sessions: BTreeMap::new(),
+            session_ttl: Duration::from_secs(5 * 60),
+            random_state: RandomState::new(),
+            created: 0,
+        }
+    }
+
+    /// keep the sessions of the disconnected clients for this duration
+    pub fn session_ttl(mut self, session_ttl: Duration) -> Self {
+        self.session_ttl = session_ttl;
+        self
+    }
+
+    /// the live program of the session
+    pub fn get(&self, session: &str) -> Option<&LiveProgram<APP, MSG>> {
+        self.sessions.get(session).map(|live| &live.program)
+    }
+
+    /// the number of sessions
+    pub fn len(&self) -> usize {
+        self.sessions.len()
+    }
+
+    /// returns true if there are no sessions
+    pub fn is_empty(&self) -> bool {
+        self.sessions.is_empty()
+    }
+
+    /// handle the message of the client of the session, the session of a join message is the
+    /// session in the message. A new session is created when the session is not found, such as
+    /// when it has expired, the whole view of the new session is sent to the client.
+    ///
+    /// Returns the session of the client and the messages to be sent to the client.
+    /// The session is removed when its APP panics, the other sessions keep running.
+    pub fn handle(
+        &mut self,
+        session: Option<&str>,
+        msg: ClientMessage,
+    ) -> Result<(String, Vec<ServerMessage>), Error> {
+        self.expire();
+        let session = match &msg {
+            ClientMessage::Join { session, .. } => session.as_deref(),
+            _ => session,
+        };
+        if let Some(session) = session.filter(|session| self.sessions.contains_key(*session)) {
+            let session = session.to_string();
+            let replies = self.run_session(&session, |live| {
+                live.left = None;
+                live.program.handle(&session, msg)
+            })?;
+            return Ok((session, replies));
+        }
+        let session = self.new_session_id();
+        let new_app = &self.new_app;
+        let created = panic::catch_unwind(AssertUnwindSafe(|| {
+            let mut program = LiveProgram::new(new_app());
+            let render = program.render(&session);
+            (program, render)
+        }));
+        let (program, render) = created.map_err(|payload| Error::SessionPanicked {
+            session: session.clone(),
+            message: panic_message(&*payload),
+        })?;
+        let render = render?;
+        self.sessions.insert(
+            session.clone(),
+            LiveSession {
+                program,
+                left: None,
+            },
+        );
+        Ok((session, vec![render]))
+    }
+
+    /// update the msgs of the tasks which finished in each session, returns the sessions whose
+    /// view is changed along with the patches to be pushed to their clients.
+    /// The sessions which fail are logged, and are removed when their APP panics.
+    pub fn poll(&mut self) -> Vec<(String, Vec<ServerMessage>)> {
+        self.expire();
+        let sessions: Vec<String> = self.sessions.keys().cloned().collect();
+        sessions
+            .into_iter()
+            .filter_map(
+                |session| match self.run_session(&session, |live| live.program.poll()) {
+                    Ok(pushed) if pushed.is_empty() => None,
+                    Ok(pushed) => Some((session, pushed)),
+                    Err(error) => {
+                        log::error!("unable to poll the live session: {error}");
+                        None
+                    }
+                },
+            )
+            .collect()
+    }
+
+    /// run `f` on the session, the session is removed when its APP panics.
+    /// Returns [`Error::SessionNotFound`] if there is no such session.
+    fn run_session<T>(
+        &mut self,
+        session: &str,
+        f: impl FnOnce(&mut LiveSession<APP, MSG>) -> Result<T, Error>,
+    ) -> Result<T, Error> {
+        let Some(live) = self.sessions.get_mut(session) else {
+            return Err(Error::SessionNotFound(session.to_string()));
+        };
+        match panic::catch_unwind(AssertUnwindSafe(|| f(live))) {
+            Ok(result) => result,
+            Err(payload) => {
+                self.sessions.remove(session);
+                Err(Error::SessionPanicked {
+                    session: session.to_string(),
+                    message: panic_message(&*payload),
+                })
+            }
+        }
+    }
+
+    /// the client of the session is disconnected, the session is removed when the client does
+    /// not reconnect before the session expires
+    pub fn leave(&mut self, session: &str) {
+        if let Some(live) = self.sessions.get_mut(session) {
+            live.left = Some(Instant::now());
+        }
+    }
+
+    /// remove the sessions which clients left longer than the session ttl ago
+    pub fn expire(&mut self) {
+        let session_ttl = self.session_ttl;
+        self.sessions
+            .retain(|_, live| live.left.is_none_or(|left| left.elapsed() < session_ttl));
+    }
+
+    /// a random id which is not used by the other sessions
+    fn new_session_id(&mut self) -> String {
+        loop {
+            self.created += 1;
+            let high = self.random_state.hash_one((self.created, "high"));
+            let low = self.random_state.hash_one((self.created, "low"));
+            let session = format!("{high:016x}{low:016x}");
+            if !self.sessions.contains_key(&session) {
+                return session;
+            }
+        }
+    }
+}
@@ -0,0 +1,234 @@
+//! The app in the browser which shows the view of a live session
+use crate::{
+    dom::{
+        delay,
+        live::{ClientMessage, ServerMessage},
+        Application, Cmd, Program, SyntheticEvent, Task,
+    },
+    html::div,
+    vdom::{EventId, Node, WirePatch, WirePatchType},
+};
+use wasm_bindgen::{closure::Closure, JsCast};
+use web_sys::{MessageEvent, WebSocket};
+
+/// Shows the view of the APP which runs on the server, connected with a web socket.
+///
+/// The patches sent by the server are applied into the DOM and the events of the listeners are
+/// sent back to the server. When the connection is closed the client reconnects to the same
+/// session, the view is rendered again when the client missed some of the patches.
+///
+/// # Example
+/// ```rust,no_run
+/// use sauron::{dom::live::LiveClient, Program};
+///
+/// Program::mount_to_body(LiveClient::new("ws://localhost:3030/live"));
+/// ```
+pub struct LiveClient {
+    url: String,
+    socket: Option<WebSocket>,
+    session: Option<String>,
+    /// the version of the view which is applied into the DOM, 0 when there is none
+    version: u64,
+    /// the milliseconds to wait before reconnecting
+    reconnect_delay: i32,
+}
+
+/// The msgs of the [`LiveClient`]
+#[derive(Debug, Clone)]
+pub enum LiveMsg {
+    /// the web socket is opened
+    Connected(WebSocket),
+    /// a message is received from the server
+    Received(ServerMessage),
+    /// the web socket is closed
+    Disconnected(WebSocket),
+    /// connect to the server again
+    Reconnect,
+    /// an event of the view, which is sent to the server
+    Event(EventId, SyntheticEvent),
+}
+
+impl LiveClient {
+    /// a client of the live server at the url of the web socket
+    pub fn new(url: impl ToString) -> Self {
+        Self {
+            url: url.to_string(),
+            socket: None,
+            session: None,
+            version: 0,
+            reconnect_delay: 1_000,
+        }
+    }
+
+    /// wait for these milliseconds before reconnecting when the connection is closed
+    pub fn reconnect_delay(mut self, reconnect_delay: i32) -> Self {
+        self.reconnect_delay = reconnect_delay;
+        self
+    }
+
+    /// the session of the client, once it has joined
+    pub fn session(&self) -> Option<&str> {
+        self.session.as_deref()
+    }
+
+    /// open the web socket, its events are dispatched into the program
+    fn connect(&self) -> Cmd<Self, LiveMsg> {
+        let url = self.url.clone();
+        Cmd::new(move |program: Program<Self, LiveMsg>| {
+            let socket = match WebSocket::new(&url) {
+                Ok(socket) => socket,
+                Err(error) => {
+                    log::error!("unable to connect to {url}: {error:?}");
+                    return;
+                }
+            };
+            let dispatch = |to_msg: fn(WebSocket, web_sys::Event) -> Option<LiveMsg>| {
+                let program = program.downgrade();
+                let socket = socket.clone();
+                let closure: Closure<dyn FnMut(web_sys::Event)> = Closure::new(move |event| {
+                    if let (Some(msg), Some(mut program)) =
+                        (to_msg(socket.clone(), event), program.upgrade())
+                    {
+                        program.dispatch(msg);
+                    }
+                });
+                closure
+            };
+            let listeners = [
+                (
+                    "open",
+                    dispatch(|socket, _| Some(LiveMsg::Connected(socket))),
+                ),
+                ("message", dispatch(|_, event| received(event))),
+                (
+                    "close",
+                    dispatch(|socket, _| Some(LiveMsg::Disconnected(socket))),
+                ),
+            ];
+            for (event_name, closure) in listeners {
+                program
+                    .add_event_closure(&socket, event_name, closure)
+                    .expect("must add the listener of the web socket");
+            }
+        })
+        .no_render()
+    }
+
+    /// send the message to the server, it is dropped when the client is not connected
+    fn send(&self, msg: &ClientMessage) {
+        if let Some(socket) = &self.socket {
+            send(socket, msg);
+        }
+    }
+
+    /// apply the patches into the DOM, the whole view is requested when they can not be
+    /// applied
+    fn apply(&self, patches: Vec<WirePatch>) -> Cmd<Self, LiveMsg> {
+        let socket = self.socket.clone();
+        Cmd::new(move |mut program: Program<Self, LiveMsg>| {
+            let applied = program.apply_wire_patches(&patches, |id, event| {
+                LiveMsg::Event(id, SyntheticEvent::from(event))
+            });
+            if let Err(error) = applied {
+                log::warn!("unable to apply the live patches, resyncing: {error}");
+                if let Some(socket) = socket {
+                    send(&socket, &ClientMessage::Resync);
+                }
+            }
+        })
+        .no_render()
+    }
+}
+
+impl Application<LiveMsg> for LiveClient {
+    fn init(&mut self) -> Cmd<Self, LiveMsg> {
+        self.connect()
+    }
+
+    fn update(&mut self, msg: LiveMsg) -> Cmd<Self, LiveMsg> {
+        match msg {
+            LiveMsg::Connected(socket) => {
+                self.socket = Some(socket);
+                self.send(&ClientMessage::Join {
+                    session: self.session.clone(),
+                    version: self.version,
+                });
+                Cmd::none().no_render()
+            }
+            LiveMsg::Received(ServerMessage::Render {
+                session,
+                version,
+                view,
+            }) => {
+                self.session = Some(session);
+                self.version = version;
+                self.apply(vec![WirePatch {
+                    tag: None,
+                    patch_path: vec![],
+                    patch_type: WirePatchType::ReplaceNode {
+                        replacement: vec![view],
+                    },
+                }])
+            }
+            LiveMsg::Received(ServerMessage::Patch { version, patches }) => {
+                if version != self.version + 1 {
+                    log::warn!(
+                        "missed the live patches from version {} to {version}, resyncing",
+                        self.version
+                    );
+                    self.send(&ClientMessage::Resync);
+                    return Cmd::none().no_render();
+                }
+                self.version = version;
+                self.apply(patches)
+            }
+            LiveMsg::Disconnected(socket) => {
+                if self.socket.as_ref() == Some(&socket) {
+                    self.socket = None;
+                }
+                let reconnect_delay = self.reconnect_delay;
+                Cmd::batch([
+                    Cmd::new(move |mut program: Program<Self, LiveMsg>| {
+                        if let Err(error) = program.remove_event_closures(&socket) {
+                            program.handle_error(error);
+                        }
+                    }),
+                    Cmd::from(Task::new(async move {
+                        delay(reconnect_delay).await;
+                        LiveMsg::Reconnect
+                    })),
+                ])
+                .no_render()
+            }
+            LiveMsg::Reconnect => self.connect(),
+            LiveMsg::Event(id, event) => {
+                self.send(&ClientMessage::Event { id, event });
+                Cmd::none().no_render()
+            }
+        }
+    }
+
+    /// the placeholder which is replaced with the view sent by the server
+    fn view(&self) -> Node<LiveMsg> {
+        div([], [])
+    }
+}
+
+/// the message of the server in the message event of the web socket
+fn received(event: web_sys::Event) -> Option<LiveMsg> {
+    let data = event.unchecked_ref::<MessageEvent>().data().as_string()?;
+    match serde_json::from_str(&data) {
+        Ok(msg) => Some(LiveMsg::Received(msg)),
+        Err(error) => {
+            log::warn!("invalid message from the live server: {error}");
+            None
+        }
+    }
+}
+
+fn send(socket: &WebSocket, msg: &ClientMessage) {
+    let json = serde_json::to_string(msg).expect("must serialize the live message");
+    if let Err(error) = socket.send_with_str(&json) {
+        log::warn!("unable to send to the live server: {error:?}");
+    }
+}
@@ -0,0 +1,265 @@
+//! A web socket server which runs the live sessions of an APP
+use crate::dom::{
+    live::{ClientMessage, LiveSessions, ServerMessage},
+    Application,
+};
+use crate::Error;
+use std::{
+    collections::BTreeMap,
+    io,
+    marker::PhantomData,
+    net::{TcpListener, TcpStream},
+    sync::mpsc::{self, Receiver, RecvTimeoutError, Sender},
+    thread,
+    time::Duration,
+};
+use tungstenite::{Message, WebSocket};
+
+/// Serves the live sessions of the APP over web sockets, each connection is a [`LiveClient`].
+///
+/// The sessions run in a single thread, since the APP is not required to be `Send`, each
+/// connection is read and written in its own thread. The sessions are polled every
+/// [`LiveServer::poll_interval`], the patches of the tasks which finish later are pushed to the
+/// connection of the session. A session is removed when its APP panics, the other sessions keep
+/// running.
+///
+/// # Example
+/// ```rust,no_run
+/// use sauron::{dom::live::LiveServer, *};
+/// use std::net::TcpListener;
+///
+/// struct Hello;
+///
+/// impl Application<()> for Hello {
+///     fn update(&mut self, _msg: ()) -> Cmd<Self, ()> {
+///         Cmd::none()
+///     }
+///
+///     fn view(&self) -> Node<()> {
+///         text("hello")
+///     }
+/// }
+///
+/// let listener = TcpListener::bind("127.0.0.1:3030").expect("must bind");
+/// LiveServer::new(|| Hello).serve(listener).expect("must serve");
+/// ```
+///
+/// [`LiveClient`]: crate::dom::live::LiveClient
+pub struct LiveServer<APP, MSG> {
+    new_app: Box<dyn Fn() -> APP + Send>,
+    session_ttl: Option<Duration>,
+    poll_interval: Duration,
+    _phantom_msg: PhantomData<fn() -> MSG>,
+}
+
+/// the requests from the connections to the thread of the sessions
+enum Request {
+    Message {
+        session: Option<String>,
+        msg: ClientMessage,
+        reply: Sender<Result<(String, Vec<ServerMessage>), Error>>,
+        connection: Connection,
+    },
+    Leave {
+        session: String,
+        connection: usize,
+    },
+}
+
+/// a connection, where the messages of its session are pushed
+struct Connection {
+    id: usize,
+    push: Sender<ServerMessage>,
+}
+
+impl<APP, MSG> LiveServer<APP, MSG>
+where
+    MSG: Clone + 'static,
+    APP: Application<MSG> + 'static,
+{
+    /// a server of the APP created with `new_app` for each session
+    pub fn new(new_app: impl Fn() -> APP + Send + 'static) -> Self {
+        Self {
+            new_app: Box::new(new_app),
+            session_ttl: None,
+            poll_interval: Duration::from_millis(50),
+            _phantom_msg: PhantomData,
+        }
+    }
+
+    /// keep the sessions of the disconnected clients for this duration, see
+    /// [`LiveSessions::session_ttl`]
+    pub fn session_ttl(mut self, session_ttl: Duration) -> Self {
+        self.session_ttl = Some(session_ttl);
+        self
+    }
+
+    /// poll the sessions for the msgs of the tasks which finish later at this interval,
+    /// the default is 50ms
+    pub fn poll_interval(mut self, poll_interval: Duration) -> Self {
+        self.poll_interval = poll_interval;
+        self
+    }
+
+    /// accept the web socket connections of the listener, this blocks until the listener fails
+    pub fn serve(self, listener: TcpListener) -> io::Result<()> {
+        let (requests, received) = mpsc::channel::<Request>();
+        let Self {
+            new_app,
+            session_ttl,
+            poll_interval,
+            ..
+        } = self;
+        thread::spawn(move || {
+            let mut sessions = LiveSessions::new(new_app);
+            if let Some(session_ttl) = session_ttl {
+                sessions = sessions.session_ttl(session_ttl);
+            }
+            // the connection of each session, where the patches of the session are pushed
+            let mut connections: BTreeMap<String, Connection> = BTreeMap::new();
+            loop {
+                match received.recv_timeout(poll_interval) {
+                    Ok(Request::Message {
+                        session,
+                        msg,
+                        reply,
+                        connection,
+                    }) => {
+                        let handled = sessions.handle(session.as_deref(), msg);
+                        if let Ok((joined, _)) = &handled {
+                            connections.insert(joined.clone(), connection);
+                        }
+                        // the connection is closed when the reply can not be sent
+                        let _ = reply.send(handled);
+                    }
+                    Ok(Request::Leave {
+                        session,
+                        connection,
+                    }) => {
+                        // the client may have already reconnected to its session
+                        if connections.get(&session).map(|c| c.id) == Some(connection) {
+                            connections.remove(&session);
+                            sessions.leave(&session);
+                        }
+                    }
+                    Err(RecvTimeoutError::Timeout) => (),
+                    Err(RecvTimeoutError::Disconnected) => break,
+                }
+                for (session, pushed) in sessions.poll() {
+                    let Some(connection) = connections.get(&session) else {
+                        continue;
+                    };
+                    for msg in pushed {
+                        // the connection is closed when the patches can not be pushed
+                        let _ = connection.push.send(msg);
+                    }
+                }
+            }
+        });
+        for (id, stream) in listener.incoming().enumerate() {
+            let stream = stream?;
+            let requests = requests.clone();
+            thread::spawn(move || connect(id, stream, requests, poll_interval));
+        }
+        Ok(())
+    }
+}
+
+/// read the messages of the client until the connection is closed, the messages pushed to
+/// the connection are sent in between the reads
+fn connect(id: usize, stream: TcpStream, requests: Sender<Request>, poll_interval: Duration) {
+    let mut socket = match tungstenite::accept(stream) {
+        Ok(socket) => socket,
+        Err(error) => {
+            log::warn!("unable to accept the live connection: {error}");
+            return;
+        }
+    };
+    // the reads time out, such that the pushed messages are sent while the client is idle
+    if let Err(error) = socket.get_ref().set_read_timeout(Some(poll_interval)) {
+        log::warn!("unable to set the read timeout of the live connection: {error}");
+        return;
+    }
+    let (push, pushed) = mpsc::channel();
+    let mut session = None;
+    loop {
+        let text = match socket.read() {
+            Ok(Message::Text(text)) => text,
+            Ok(Message::Close(_)) => break,
+            Ok(_) => continue,
+            Err(tungstenite::Error::Io(error))
+                if matches!(
+                    error.kind(),
+                    io::ErrorKind::WouldBlock | io::ErrorKind::TimedOut
+                ) =>
+            {
+                if !send_pushed(&mut socket, &pushed) {
+                    break;
+                }
+                continue;
+            }
+            Err(error) => {
+                log::warn!("unable to read from the live connection: {error}");
+                break;
+            }
+        };
+        let msg: ClientMessage = match serde_json::from_str(&text) {
+            Ok(msg) => msg,
+            Err(error) => {
+                log::warn!("invalid message from the live client: {error}");
+                continue;
+            }
+        };
+        let (reply, replied) = mpsc::channel();
+        let request = Request::Message {
+            session: session.clone(),
+            msg,
+            reply,
+            connection: Connection {
+                id,
+                push: push.clone(),
+            },
+        };
+        let Ok(replied) = requests.send(request).map(|_| replied.recv()) else {
+            break;
+        };
+        // the patches pushed before the reply have the older versions of the view
+        if !send_pushed(&mut socket, &pushed) {
+            break;
+        }
+        match replied {
+            Ok(Ok((joined, replies))) => {
+                session = Some(joined);
+                if !replies.iter().all(|reply| send(&mut socket, reply)) {
+                    break;
+                }
+            }
+            Ok(Err(error)) => log::error!("unable to update the live session: {error}"),
+            Err(_) => break,
+        }
+    }
+    if let Some(session) = session {
+        let _ = requests.send(Request::Leave {
+            session,
+            connection: id,
+        });
+    }
+}
+
+/// send the messages which are pushed to the connection so far, returns false when the
+/// connection is closed
+fn send_pushed(socket: &mut WebSocket<TcpStream>, pushed: &Receiver<ServerMessage>) -> bool {
+    pushed.try_iter().all(|msg| send(socket, &msg))
+}
+
+/// send the message to the client, returns false when the connection is closed
+fn send(socket: &mut WebSocket<TcpStream>, msg: &ServerMessage) -> bool {
+    let json = serde_json::to_string(msg).expect("must serialize");
+    match socket.send(Message::Text(json)) {
+        Ok(()) => true,
+        Err(error) => {
+            log::warn!("unable to send to the live connection: {error}");
+            false
+        }
+    }
+}
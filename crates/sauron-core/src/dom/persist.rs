@@ -0,0 +1,240 @@
+//! Persists the state of the app, such that it is restored when the page is reloaded.
+//!
+//! The state is serialized along with a schema version into a [`Storage`]. In the browser the
+//! storage is the [`WebStorage`], which is the `localStorage` or `sessionStorage`, or the
+//! [`IndexedDb`], while the [`MemoryStorage`] keeps the state in memory for testing without a
+//! browser.
+//!
+//! The state is restored with [`Persistence::restore`] before the program is created, such that
+//! `init` is called on the restored state. [`Program::persist`] then saves the state after the
+//! updates, at most once for each throttle duration.
+//!
+//! # Example
+//! ```rust
+//! use sauron::dom::{MemoryStorage, Persistence};
+//! use serde::{Deserialize, Serialize};
+//!
+//! #[derive(Debug, PartialEq, Serialize, Deserialize)]
+//! struct Todos {
+//!     items: Vec<String>,
+//! }
+//!
+//! let storage = MemoryStorage::new();
+//! // the version 1 of the state was a list of items
+//! storage.insert("todos", r#"{"version":1,"state":["buy milk"]}"#);
+//!
+//! let persistence = Persistence::new("todos", storage)
+//!     .version(2)
+//!     .migrate(|version, state| match version {
+//!         1 => Ok(serde_json::json!({ "items": state })),
+//!         _ => Err(format!("unknown version {version}")),
+//!     });
+//!
+//! let todos: Option<Todos> =
+//!     futures::executor::block_on(persistence.restore()).expect("must restore");
+//! assert_eq!(
+//!     todos,
+//!     Some(Todos {
+//!         items: vec!["buy milk".to_string()]
+//!     })
+//! );
+//! ```
+//!
+//! [`Program::persist`]: crate::dom::Program::persist
+use futures::future::LocalBoxFuture;
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use std::{fmt, rc::Rc, time::Duration};
+
+#[cfg(feature = "with-dom")]
+pub use indexed_db::IndexedDb;
+pub use memory::MemoryStorage;
+#[cfg(feature = "with-dom")]
+pub use web_storage::WebStorage;
+
+#[cfg(feature = "with-dom")]
+mod indexed_db;
+mod memory;
+#[cfg(feature = "with-dom")]
+mod program;
+#[cfg(feature = "with-dom")]
+mod web_storage;
+
+#[cfg(feature = "with-dom")]
+pub(crate) use program::ActivePersistence;
+
+/// the duration between the saves of the state, when it is not specified
+const DEFAULT_THROTTLE: Duration = Duration::from_millis(500);
+
+/// The errors in saving and restoring the state
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum PersistError {
+    /// The storage is not available or failed to read or write the state
+    #[error("storage error: {0}")]
+    Storage(String),
+    /// The state could not be serialized
+    #[error("unable to serialize the state: {0}")]
+    Serialize(String),
+    /// The stored state could not be deserialized
+    #[error("unable to deserialize the state: {0}")]
+    Deserialize(String),
+    /// The stored state is saved by a newer version of the app
+    #[error("the stored state has version {stored}, which is newer than version {current}")]
+    NewerVersion {
+        /// the version of the stored state
+        stored: u32,
+        /// the version of the app
+        current: u32,
+    },
+    /// The migration of the stored state failed
+    #[error("unable to migrate the state from version {from}: {reason}")]
+    Migration {
+        /// the version of the state which is migrated
+        from: u32,
+        /// the error returned by the migration
+        reason: String,
+    },
+}
+
+/// Reads and writes the serialized state with a key
+pub trait Storage {
+    /// read the value of the key, `None` if there is no value
+    fn get(&self, key: &str) -> LocalBoxFuture<'static, Result<Option<String>, PersistError>>;
+
+    /// write the value of the key
+    fn set(&self, key: &str, value: String) -> LocalBoxFuture<'static, Result<(), PersistError>>;
+
+    /// remove the value of the key
+    fn remove(&self, key: &str) -> LocalBoxFuture<'static, Result<(), PersistError>>;
+}
+
+/// the migration of the state from a version to the next version
+type Migration = dyn Fn(u32, serde_json::Value) -> Result<serde_json::Value, String>;
+
+/// Where and how the state of the app is persisted
+#[derive(Clone)]
+pub struct Persistence {
+    key: String,
+    version: u32,
+    storage: Rc<dyn Storage>,
+    migrate: Option<Rc<Migration>>,
+    throttle: Duration,
+}
+
+/// the state along with the version of its schema, as it is written in the storage
+#[derive(Serialize, Deserialize)]
+struct Stored<T> {
+    version: u32,
+    state: T,
+}
+
+impl Persistence {
+    /// persist the state with the key into the storage, the version of the state is `0`
+    pub fn new(key: impl ToString, storage: impl Storage + 'static) -> Self {
+        Self {
+            key: key.to_string(),
+            version: 0,
+            storage: Rc::new(storage),
+            migrate: None,
+            throttle: DEFAULT_THROTTLE,
+        }
+    }
+
+    /// the version of the schema of the state, increment it when the state is changed such that
+    /// the stored state of the previous version needs to be migrated
+    pub fn version(mut self, version: u32) -> Self {
+        self.version = version;
+        self
+    }
+
+    /// Migrate the stored state of an older version.
+    /// The migration is called with the version of the state and returns the state of the
+    /// next version, it is called for each version until the state is of the current version.
+    pub fn migrate<F>(mut self, migrate: F) -> Self
+    where
+        F: Fn(u32, serde_json::Value) -> Result<serde_json::Value, String> + 'static,
+    {
+        self.migrate = Some(Rc::new(migrate));
+        self
+    }
+
+    /// save the state at most once for each duration, the default is 500ms
+    pub fn throttle(mut self, throttle: Duration) -> Self {
+        self.throttle = throttle;
+        self
+    }
+
+    /// the key of the state in the storage
+    pub fn key(&self) -> &str {
+        &self.key
+    }
+
+    /// read the stored state, migrating it if it is of an older version.
+    /// Returns `None` if there is no stored state.
+    pub async fn restore<APP>(&self) -> Result<Option<APP>, PersistError>
+    where
+        APP: DeserializeOwned,
+    {
+        let Some(value) = self.storage.get(&self.key).await? else {
+            return Ok(None);
+        };
+        let Stored { version, mut state } =
+            serde_json::from_str::<Stored<serde_json::Value>>(&value)
+                .map_err(|e| PersistError::Deserialize(e.to_string()))?;
+        if version > self.version {
+            return Err(PersistError::NewerVersion {
+                stored: version,
+                current: self.version,
+            });
+        }
+        for from in version..self.version {
+            let migrate = self
+                .migrate
+                .as_ref()
+                .ok_or_else(|| PersistError::Migration {
+                    from,
+                    reason: "there is no migration".to_string(),
+                })?;
+            state =
+                migrate(from, state).map_err(|reason| PersistError::Migration { from, reason })?;
+        }
+        let app =
+            serde_json::from_value(state).map_err(|e| PersistError::Deserialize(e.to_string()))?;
+        Ok(Some(app))
+    }
+
+    /// write the state into the storage
+    pub async fn save<APP>(&self, app: &APP) -> Result<(), PersistError>
+    where
+        APP: Serialize,
+    {
+        let value = self.encode(app)?;
+        self.storage.set(&self.key, value).await
+    }
+
+    /// remove the stored state
+    pub async fn clear(&self) -> Result<(), PersistError> {
+        self.storage.remove(&self.key).await
+    }
+
+    /// serialize the state along with its version
+    fn encode<APP>(&self, app: &APP) -> Result<String, PersistError>
+    where
+        APP: Serialize,
+    {
+        serde_json::to_string(&Stored {
+            version: self.version,
+            state: app,
+        })
+        .map_err(|e| PersistError::Serialize(e.to_string()))
+    }
+}
+
+impl fmt::Debug for Persistence {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("Persistence")
+            .field("key", &self.key)
+            .field("version", &self.version)
+            .field("throttle", &self.throttle)
+            .finish()
+    }
+}
@@ -0,0 +1,130 @@
+//! Storing the state in an object store of the browser IndexedDB
+use super::{web_storage::js_error, PersistError, Storage};
+use crate::dom::window;
+use futures::future::LocalBoxFuture;
+use js_sys::Promise;
+use wasm_bindgen::{closure::Closure, JsCast, JsValue};
+use wasm_bindgen_futures::JsFuture;
+use web_sys::{IdbDatabase, IdbObjectStore, IdbRequest, IdbTransactionMode};
+
+/// A [`Storage`] which is an object store in an IndexedDB database.
+/// Unlike the [`WebStorage`](super::WebStorage) it is not limited to a few megabytes,
+/// and the reads and writes don't block the page.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct IndexedDb {
+    database: String,
+    store: String,
+}
+
+impl IndexedDb {
+    /// store the values in the object store of the database,
+    /// the database and the object store are created when they don't exist yet
+    pub fn new(database: impl ToString, store: impl ToString) -> Self {
+        Self {
+            database: database.to_string(),
+            store: store.to_string(),
+        }
+    }
+
+    /// open the database, creating the object store when the database is created
+    async fn open(database: String, store: String) -> Result<IdbDatabase, PersistError> {
+        let factory = window()
+            .indexed_db()
+            .map_err(js_error)?
+            .ok_or_else(|| PersistError::Storage("IndexedDB is not available".to_string()))?;
+        let request = factory.open_with_u32(&database, 1).map_err(js_error)?;
+        let on_upgrade_needed = Closure::once(move |event: web_sys::Event| {
+            let request: IdbRequest = event.target().expect("must have a target").unchecked_into();
+            let db: IdbDatabase = request
+                .result()
+                .expect("must have a result")
+                .unchecked_into();
+            if let Err(error) = db.create_object_store(&store) {
+                log::error!("unable to create the object store: {error:?}");
+            }
+        });
+        request.set_onupgradeneeded(Some(on_upgrade_needed.as_ref().unchecked_ref()));
+        let db = request_result(&request).await?;
+        Ok(db.unchecked_into())
+    }
+
+    /// the object store in a new transaction of the mode
+    async fn object_store(
+        database: String,
+        store: String,
+        mode: IdbTransactionMode,
+    ) -> Result<IdbObjectStore, PersistError> {
+        let db = Self::open(database, store.clone()).await?;
+        db.transaction_with_str_and_mode(&store, mode)
+            .and_then(|transaction| transaction.object_store(&store))
+            .map_err(js_error)
+    }
+}
+
+impl Storage for IndexedDb {
+    fn get(&self, key: &str) -> LocalBoxFuture<'static, Result<Option<String>, PersistError>> {
+        let (database, store, key) = (self.database.clone(), self.store.clone(), key.to_string());
+        Box::pin(async move {
+            let store = Self::object_store(database, store, IdbTransactionMode::Readonly).await?;
+            let request = store.get(&JsValue::from_str(&key)).map_err(js_error)?;
+            let value = request_result(&request).await?;
+            Ok(value.as_string())
+        })
+    }
+
+    fn set(&self, key: &str, value: String) -> LocalBoxFuture<'static, Result<(), PersistError>> {
+        let (database, store, key) = (self.database.clone(), self.store.clone(), key.to_string());
+        Box::pin(async move {
+            let store = Self::object_store(database, store, IdbTransactionMode::Readwrite).await?;
+            let request = store
+                .put_with_key(&JsValue::from_str(&value), &JsValue::from_str(&key))
+                .map_err(js_error)?;
+            request_result(&request).await?;
+            Ok(())
+        })
+    }
+
+    fn remove(&self, key: &str) -> LocalBoxFuture<'static, Result<(), PersistError>> {
+        let (database, store, key) = (self.database.clone(), self.store.clone(), key.to_string());
+        Box::pin(async move {
+            let store = Self::object_store(database, store, IdbTransactionMode::Readwrite).await?;
+            let request = store.delete(&JsValue::from_str(&key)).map_err(js_error)?;
+            request_result(&request).await?;
+            Ok(())
+        })
+    }
+}
+
+/// wait for the request to succeed, returning its result
+async fn request_result(request: &IdbRequest) -> Result<JsValue, PersistError> {
+    let mut callbacks = None;
+    let promise = Promise::new(&mut |resolve, reject| {
+        let on_success = Closure::once(move |event: web_sys::Event| {
+            let request: IdbRequest = event.target().expect("must have a target").unchecked_into();
+            let result = request.result().unwrap_or(JsValue::UNDEFINED);
+            resolve
+                .call1(&JsValue::NULL, &result)
+                .expect("must be able to call resolve");
+        });
+        let on_error = Closure::once(move |event: web_sys::Event| {
+            let request: IdbRequest = event.target().expect("must have a target").unchecked_into();
+            let error = request
+                .error()
+                .ok()
+                .flatten()
+                .map(JsValue::from)
+                .unwrap_or(JsValue::NULL);
+            reject
+                .call1(&JsValue::NULL, &error)
+                .expect("must be able to call reject");
+        });
+        request.set_onsuccess(Some(on_success.as_ref().unchecked_ref()));
+        request.set_onerror(Some(on_error.as_ref().unchecked_ref()));
+        callbacks = Some((on_success, on_error));
+    });
+    let result = JsFuture::from(promise).await.map_err(js_error);
+    request.set_onsuccess(None);
+    request.set_onerror(None);
+    drop(callbacks);
+    result
+}
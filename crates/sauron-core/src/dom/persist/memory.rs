@@ -0,0 +1,46 @@
+//! A storage which keeps the values in memory, used for testing without a browser
+use super::{PersistError, Storage};
+use futures::future::{ready, LocalBoxFuture};
+use std::{cell::RefCell, collections::BTreeMap, rc::Rc};
+
+/// A [`Storage`] which keeps the values in memory.
+/// The clones of the memory storage share the same values.
+#[derive(Debug, Clone, Default)]
+pub struct MemoryStorage {
+    values: Rc<RefCell<BTreeMap<String, String>>>,
+}
+
+impl MemoryStorage {
+    /// create an empty memory storage
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// the value of the key
+    pub fn value(&self, key: &str) -> Option<String> {
+        self.values.borrow().get(key).cloned()
+    }
+
+    /// set the value of the key, such as the state saved by a previous version of the app
+    pub fn insert(&self, key: impl ToString, value: impl ToString) {
+        self.values
+            .borrow_mut()
+            .insert(key.to_string(), value.to_string());
+    }
+}
+
+impl Storage for MemoryStorage {
+    fn get(&self, key: &str) -> LocalBoxFuture<'static, Result<Option<String>, PersistError>> {
+        Box::pin(ready(Ok(self.value(key))))
+    }
+
+    fn set(&self, key: &str, value: String) -> LocalBoxFuture<'static, Result<(), PersistError>> {
+        self.insert(key, value);
+        Box::pin(ready(Ok(())))
+    }
+
+    fn remove(&self, key: &str) -> LocalBoxFuture<'static, Result<(), PersistError>> {
+        self.values.borrow_mut().remove(key);
+        Box::pin(ready(Ok(())))
+    }
+}
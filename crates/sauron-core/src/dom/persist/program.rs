@@ -0,0 +1,128 @@
+//! Saving the state of the app of a [`Program`] after its updates
+use super::{PersistError, Persistence};
+use crate::dom::{
+    request_timeout_callback, window, Application, DomBackend, Program, TimeoutCallbackHandle,
+};
+use serde::Serialize;
+use wasm_bindgen::closure::Closure;
+
+/// The persistence of a program, saving the state after the updates
+pub(crate) struct ActivePersistence<APP> {
+    persistence: Persistence,
+    /// serialize the state of the app, it is set when the APP is known to be `Serialize`
+    encode: fn(&Persistence, &APP) -> Result<String, PersistError>,
+    /// the scheduled save of the state, the save is cancelled when the handle is dropped
+    pending_save: Option<TimeoutCallbackHandle>,
+}
+
+impl<APP, MSG, DOM> Program<APP, MSG, DOM>
+where
+    MSG: 'static,
+    APP: Application<MSG> + 'static,
+    DOM: DomBackend,
+{
+    /// schedule the state to be saved after the throttle duration, unless a save is already
+    /// scheduled, such that the updates in the meantime are saved along with it
+    pub(crate) fn schedule_persist(&self) {
+        let mut active = self.persistence.borrow_mut();
+        let Some(active) = active.as_mut() else {
+            return;
+        };
+        if active.pending_save.is_some() {
+            return;
+        }
+        let program = Program::downgrade(self);
+        let handle = request_timeout_callback(
+            move || {
+                if let Some(mut program) = program.upgrade() {
+                    program.save_persisted();
+                }
+            },
+            active.persistence.throttle.as_millis() as i32,
+        );
+        match handle {
+            Ok(handle) => active.pending_save = Some(handle),
+            Err(error) => log::error!("unable to schedule saving the state: {error:?}"),
+        }
+    }
+
+    /// save the state now, the error is passed to [`Application::on_error`]
+    pub(crate) fn save_persisted(&mut self) {
+        let (persistence, encoded) = {
+            let mut active = self.persistence.borrow_mut();
+            let Some(active) = active.as_mut() else {
+                return;
+            };
+            active.pending_save = None;
+            let encoded = (active.encode)(&active.persistence, &self.app_context.app.borrow());
+            (active.persistence.clone(), encoded)
+        };
+        let value = match encoded {
+            Ok(value) => value,
+            Err(error) => {
+                self.handle_error(error.into());
+                return;
+            }
+        };
+        let program = Program::downgrade(self);
+        self.backend.spawn_local(Box::pin(async move {
+            let saved = persistence.storage.set(&persistence.key, value).await;
+            if let (Err(error), Some(mut program)) = (saved, program.upgrade()) {
+                program.handle_error(error.into());
+            }
+        }));
+    }
+
+    /// save the scheduled state right away, such as when the program is unmounted
+    pub(crate) fn flush_persisted(&mut self) {
+        let has_pending_save = self
+            .persistence
+            .borrow()
+            .as_ref()
+            .is_some_and(|active| active.pending_save.is_some());
+        if has_pending_save {
+            self.save_persisted();
+        }
+    }
+}
+
+impl<APP, MSG> Program<APP, MSG>
+where
+    MSG: 'static,
+    APP: Application<MSG> + Serialize + 'static,
+{
+    /// Save the state of the app into the storage of the persistence after the updates.
+    ///
+    /// The state is serialized and saved at most once for each throttle duration, outside of
+    /// the dispatch of the msgs. The scheduled save is done right away when the page is hidden
+    /// or the program is unmounted, such that the last updates are not lost on reload.
+    /// The errors in saving the state are passed to [`Application::on_error`].
+    ///
+    /// The state is restored with [`Persistence::restore`] before the program is created.
+    pub fn persist(&self, persistence: Persistence) {
+        *self.persistence.borrow_mut() = Some(ActivePersistence {
+            persistence,
+            encode: Persistence::encode::<APP>,
+            pending_save: None,
+        });
+        // the listener is attached once, it flushes the persistence which is active
+        let window = window();
+        if self.has_event_closure(&window, "pagehide") {
+            return;
+        }
+        let program = Program::downgrade(self);
+        let on_page_hide: Closure<dyn FnMut(web_sys::Event)> = Closure::new(move |_| {
+            if let Some(mut program) = program.upgrade() {
+                program.flush_persisted();
+            }
+        });
+        if let Err(error) = self.add_event_closure(&window, "pagehide", on_page_hide) {
+            log::error!("unable to listen to pagehide: {error:?}");
+        }
+    }
+
+    /// stop saving the state, the stored state is kept
+    pub fn disable_persist(&self) {
+        *self.persistence.borrow_mut() = None;
+    }
+}
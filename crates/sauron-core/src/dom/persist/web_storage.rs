@@ -0,0 +1,54 @@
+//! Storing the state with the browser `localStorage` and `sessionStorage`
+use super::{PersistError, Storage};
+use crate::dom::window;
+use futures::future::{ready, LocalBoxFuture};
+use wasm_bindgen::JsValue;
+
+/// A [`Storage`] which is the `localStorage` or the `sessionStorage` of the browser
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WebStorage {
+    /// the storage which is kept when the browser is closed
+    Local,
+    /// the storage which is cleared when the page session ends
+    Session,
+}
+
+impl WebStorage {
+    fn storage(&self) -> Result<web_sys::Storage, PersistError> {
+        let storage = match self {
+            Self::Local => window().local_storage(),
+            Self::Session => window().session_storage(),
+        };
+        storage
+            .map_err(js_error)?
+            .ok_or_else(|| PersistError::Storage(format!("{self:?} storage is not available")))
+    }
+}
+
+impl Storage for WebStorage {
+    fn get(&self, key: &str) -> LocalBoxFuture<'static, Result<Option<String>, PersistError>> {
+        let value = self
+            .storage()
+            .and_then(|storage| storage.get_item(key).map_err(js_error));
+        Box::pin(ready(value))
+    }
+
+    fn set(&self, key: &str, value: String) -> LocalBoxFuture<'static, Result<(), PersistError>> {
+        let result = self
+            .storage()
+            .and_then(|storage| storage.set_item(key, &value).map_err(js_error));
+        Box::pin(ready(result))
+    }
+
+    fn remove(&self, key: &str) -> LocalBoxFuture<'static, Result<(), PersistError>> {
+        let result = self
+            .storage()
+            .and_then(|storage| storage.remove_item(key).map_err(js_error));
+        Box::pin(ready(result))
+    }
+}
+
+/// the storage error of the exception thrown by the browser, such as the quota is exceeded
+pub(super) fn js_error(error: JsValue) -> PersistError {
+    PersistError::Storage(crate::Error::from(error).to_string())
+}
@@ -1,49 +1,87 @@
+use crate::dom::backend::{DomBackend, WebDom};
+use crate::dom::dom_node::{add_event_listener_options, intern};
+use crate::dom::events::{UNMOUNT, UPDATED, WILL_UNMOUNT};
+#[cfg(feature = "with-inspector")]
+use crate::dom::inspector::AttachedInspector;
+#[cfg(feature = "with-serde")]
+use crate::dom::persist::ActivePersistence;
 use crate::dom::program::app_context::WeakContext;
-#[cfg(feature = "with-raf")]
-use crate::dom::request_animation_frame;
-#[cfg(feature = "with-ric")]
-use crate::dom::request_idle_callback;
-use crate::dom::{document, now, IdleDeadline, Measurements, Modifier};
-use crate::dom::{util::body, AnimationFrameHandle, Application, DomPatch, IdleCallbackHandle};
-use crate::html::{self, attributes::class, text};
+use crate::dom::scheduler::{CallbackHandles, Priority, Scheduler};
+use crate::dom::sub::ActiveSubs;
+#[cfg(feature = "with-time-travel")]
+use crate::dom::time_travel::TimeTravel;
+use crate::dom::{util::body, Application, DomPatch};
+use crate::dom::{Cmd, IdleDeadline, Measurements, Modifier};
+use crate::html::{
+    self,
+    attributes::{class, ListenerOptions},
+    text,
+};
 use crate::vdom;
 use crate::vdom::diff;
+use crate::Error;
 use app_context::AppContext;
 use std::collections::hash_map::DefaultHasher;
-use std::collections::BTreeMap;
 use std::collections::VecDeque;
+use std::collections::{BTreeMap, BTreeSet};
 use std::hash::{Hash, Hasher};
 use std::mem::ManuallyDrop;
 use std::{
-    any::TypeId,
+    any::{Any, TypeId},
     cell::{Ref, RefCell, RefMut},
     rc::Rc,
     rc::Weak,
 };
 use wasm_bindgen::closure::Closure;
 use wasm_bindgen::{JsCast, JsValue};
-use web_sys::{self, Element, Node};
 
 mod app_context;
 
-pub(crate) type Closures = Vec<Closure<dyn FnMut(web_sys::Event)>>;
+pub(crate) type Closures = Vec<EventClosure>;
+
+/// a listener attached to an event target outside of the app's view, such as the window
+pub(crate) struct EventClosure {
+    target: web_sys::EventTarget,
+    event_name: &'static str,
+    capture: bool,
+    closure: Closure<dyn FnMut(web_sys::Event)>,
+}
+
+impl EventClosure {
+    /// detach the listener from its event target
+    fn remove(self) -> Result<(), Error> {
+        self.target.remove_event_listener_with_callback_and_bool(
+            intern(self.event_name),
+            self.closure.as_ref().unchecked_ref(),
+            self.capture,
+        )?;
+        Ok(())
+    }
+}
 
 /// Program handle the lifecycle of the APP
-pub struct Program<APP, MSG>
+///
+/// The program is mounted into the DOM of the browser by default, the `DOM` can be a different
+/// [`DomBackend`] such as the [`MemoryDom`](crate::dom::MemoryDom) to run it without a browser.
+pub struct Program<APP, MSG, DOM = WebDom>
 where
     MSG: 'static,
+    DOM: DomBackend,
 {
     pub(crate) app_context: AppContext<APP, MSG>,
 
+    /// the DOM where the APP is mounted into
+    pub(crate) backend: DOM,
+
     /// the first element of the app view, where the patch is generated is relative to
-    pub(crate) root_node: Rc<RefCell<Option<Node>>>,
+    pub(crate) root_node: Rc<RefCell<Option<DOM::Node>>>,
 
     /// the actual DOM element where the APP is mounted to.
-    mount_node: Rc<RefCell<Node>>,
+    mount_node: Rc<RefCell<DOM::Node>>,
 
     /// The closures that are currently attached to all the nodes used in the Application
     /// We keep these around so that they don't get dropped (and thus stop working);
-    pub node_closures: Rc<RefCell<ActiveClosure>>,
+    pub node_closures: Rc<RefCell<ActiveClosure<DOM::Listener>>>,
 
     /// specify how the root node is mounted into the mount node
     mount_procedure: MountProcedure,
@@ -52,30 +90,74 @@ where
     /// for optimization purposes to avoid sluggishness of the app, when a patch
     /// can not be run in 1 execution due to limited remaining time deadline
     /// it will be put into the pending patches to be executed on the next run.
-    pending_patches: Rc<RefCell<VecDeque<DomPatch<MSG>>>>,
+    pub(crate) pending_patches: Rc<RefCell<VecDeque<DomPatch<MSG, DOM>>>>,
 
-    /// store the Closure used in request_idle_callback calls
-    idle_callback_handles: Rc<RefCell<Vec<IdleCallbackHandle>>>,
-    /// store the Closure used in request_animation_frame calls
-    animation_frame_handles: Rc<RefCell<Vec<AnimationFrameHandle>>>,
+    /// the handles of the request_idle_callback and request_animation_frame calls which have
+    /// not run yet
+    pub(crate) callback_handles: Rc<RefCell<CallbackHandles<DOM::CallbackHandle>>>,
 
     /// event listener closures
     pub(crate) event_closures: Rc<RefCell<Closures>>,
+
+    /// the stateful components declared in the view, mounted into their host element
+    pub(crate) components: Rc<RefCell<MountedComponents<DOM::Node>>>,
+
+    /// the subscriptions of the app which are currently running
+    subscriptions: Rc<RefCell<ActiveSubs<MSG>>>,
+
+    /// the style elements injected by this program, removed when unmounted
+    injected_styles: Rc<RefCell<Vec<DOM::Node>>>,
+
+    /// the mount node which is replaced by the app's root node with [`MountAction::Replace`],
+    /// it is put back when the program is unmounted
+    replaced_node: Rc<RefCell<Option<DOM::Node>>>,
+
+    /// the event types which are listened to at the mount node and delegated to the listeners
+    /// in the view
+    pub(crate) delegated_events: Rc<RefCell<BTreeSet<&'static str>>>,
+
+    /// the dispatched msgs which are waiting to be updated, by priority
+    pub(crate) scheduler: Rc<RefCell<Scheduler<MSG>>>,
+
+    /// saves the state of the app after the updates, when it is persisted
+    #[cfg(feature = "with-serde")]
+    pub(crate) persistence: Rc<RefCell<Option<ActivePersistence<APP>>>>,
+
+    /// the recorded msgs and snapshots of the app, when the time travel is enabled
+    #[cfg(feature = "with-time-travel")]
+    pub(crate) time_travel: Rc<RefCell<Option<TimeTravel<APP, MSG>>>>,
+
+    /// the inspector overlay, when it is enabled
+    #[cfg(feature = "with-inspector")]
+    pub(crate) inspector: Rc<RefCell<Option<AttachedInspector<MSG>>>>,
 }
 
-pub struct WeakProgram<APP, MSG>
+pub struct WeakProgram<APP, MSG, DOM = WebDom>
 where
     MSG: 'static,
+    DOM: DomBackend,
 {
     pub(crate) app_context: WeakContext<APP, MSG>,
-    pub(crate) root_node: Weak<RefCell<Option<Node>>>,
-    mount_node: Weak<RefCell<Node>>,
-    pub node_closures: Weak<RefCell<ActiveClosure>>,
+    backend: DOM,
+    pub(crate) root_node: Weak<RefCell<Option<DOM::Node>>>,
+    mount_node: Weak<RefCell<DOM::Node>>,
+    pub node_closures: Weak<RefCell<ActiveClosure<DOM::Listener>>>,
     mount_procedure: MountProcedure,
-    pending_patches: Weak<RefCell<VecDeque<DomPatch<MSG>>>>,
-    idle_callback_handles: Weak<RefCell<Vec<IdleCallbackHandle>>>,
-    animation_frame_handles: Weak<RefCell<Vec<AnimationFrameHandle>>>,
+    pending_patches: Weak<RefCell<VecDeque<DomPatch<MSG, DOM>>>>,
+    callback_handles: Weak<RefCell<CallbackHandles<DOM::CallbackHandle>>>,
     pub(crate) event_closures: Weak<RefCell<Closures>>,
+    pub(crate) components: Weak<RefCell<MountedComponents<DOM::Node>>>,
+    subscriptions: Weak<RefCell<ActiveSubs<MSG>>>,
+    injected_styles: Weak<RefCell<Vec<DOM::Node>>>,
+    replaced_node: Weak<RefCell<Option<DOM::Node>>>,
+    pub(crate) delegated_events: Weak<RefCell<BTreeSet<&'static str>>>,
+    pub(crate) scheduler: Weak<RefCell<Scheduler<MSG>>>,
+    #[cfg(feature = "with-serde")]
+    pub(crate) persistence: Weak<RefCell<Option<ActivePersistence<APP>>>>,
+    #[cfg(feature = "with-time-travel")]
+    pub(crate) time_travel: Weak<RefCell<Option<TimeTravel<APP, MSG>>>>,
+    #[cfg(feature = "with-inspector")]
+    pub(crate) inspector: Weak<RefCell<Option<AttachedInspector<MSG>>>>,
 }
 
 /// Closures that we are holding on to to make sure that they don't get invalidated after a
@@ -83,8 +165,12 @@ where
 ///
 /// The usize is a unique identifier that is associated with the DOM element that this closure is
 /// attached to.
-pub type ActiveClosure =
-    BTreeMap<usize, BTreeMap<&'static str, Closure<dyn FnMut(web_sys::Event)>>>;
+pub type ActiveClosure<L = Closure<dyn FnMut(web_sys::Event)>> =
+    BTreeMap<usize, BTreeMap<&'static str, L>>;
+
+/// The stateful components along with the host element they are mounted into.
+/// The component is unmounted when it is dropped.
+pub(crate) type MountedComponents<N> = Vec<(N, Box<dyn Any>)>;
 
 /// specify how the App is mounted to the DOM
 #[derive(Clone, Copy)]
@@ -95,6 +181,11 @@ pub enum MountAction {
     ClearAppend,
     /// replace the target mount node with the APP's root node
     Replace,
+    /// the target mount node is the APP's root node which was already rendered in the server,
+    /// the event listeners are attached to it instead of creating the DOM nodes again.
+    /// When the APP's view is a fragment or node list, it is matched against the children of
+    /// the mount node instead.
+    Hydrate,
 }
 
 /// specify whether to attach the Node in shadow_root
@@ -113,117 +204,150 @@ struct MountProcedure {
     target: MountTarget,
 }
 
-impl<APP, MSG> WeakProgram<APP, MSG>
+impl<APP, MSG, DOM> WeakProgram<APP, MSG, DOM>
 where
     MSG: 'static,
+    DOM: DomBackend,
 {
     ///
-    pub fn upgrade(&self) -> Option<Program<APP, MSG>> {
-        if let Some(app_context) = self.app_context.upgrade() {
-            if let Some(root_node) = self.root_node.upgrade() {
-                if let Some(mount_node) = self.mount_node.upgrade() {
-                    if let Some(node_closures) = self.node_closures.upgrade() {
-                        if let Some(pending_patches) = self.pending_patches.upgrade() {
-                            if let Some(idle_callback_handles) =
-                                self.idle_callback_handles.upgrade()
-                            {
-                                if let Some(animation_frame_handles) =
-                                    self.animation_frame_handles.upgrade()
-                                {
-                                    if let Some(event_closures) = self.event_closures.upgrade() {
-                                        return Some(Program {
-                                            app_context,
-                                            root_node,
-                                            mount_node,
-                                            node_closures,
-                                            mount_procedure: self.mount_procedure,
-                                            pending_patches,
-                                            idle_callback_handles,
-                                            animation_frame_handles,
-                                            event_closures,
-                                        });
-                                    }
-                                }
-                            }
-                        }
-                    }
-                }
-            }
-        }
-        None
+    pub fn upgrade(&self) -> Option<Program<APP, MSG, DOM>> {
+        Some(Program {
+            app_context: self.app_context.upgrade()?,
+            backend: self.backend.clone(),
+            root_node: self.root_node.upgrade()?,
+            mount_node: self.mount_node.upgrade()?,
+            node_closures: self.node_closures.upgrade()?,
+            mount_procedure: self.mount_procedure,
+            pending_patches: self.pending_patches.upgrade()?,
+            callback_handles: self.callback_handles.upgrade()?,
+            event_closures: self.event_closures.upgrade()?,
+            components: self.components.upgrade()?,
+            subscriptions: self.subscriptions.upgrade()?,
+            injected_styles: self.injected_styles.upgrade()?,
+            replaced_node: self.replaced_node.upgrade()?,
+            delegated_events: self.delegated_events.upgrade()?,
+            scheduler: self.scheduler.upgrade()?,
+            #[cfg(feature = "with-serde")]
+            persistence: self.persistence.upgrade()?,
+            #[cfg(feature = "with-time-travel")]
+            time_travel: self.time_travel.upgrade()?,
+            #[cfg(feature = "with-inspector")]
+            inspector: self.inspector.upgrade()?,
+        })
     }
 }
 
-impl<APP, MSG> Clone for WeakProgram<APP, MSG>
+impl<APP, MSG, DOM> Clone for WeakProgram<APP, MSG, DOM>
 where
     MSG: 'static,
+    DOM: DomBackend,
 {
     fn clone(&self) -> Self {
         WeakProgram {
             app_context: self.app_context.clone(),
+            backend: self.backend.clone(),
             root_node: Weak::clone(&self.root_node),
             mount_node: Weak::clone(&self.mount_node),
             node_closures: Weak::clone(&self.node_closures),
             mount_procedure: self.mount_procedure,
             pending_patches: Weak::clone(&self.pending_patches),
-            idle_callback_handles: Weak::clone(&self.idle_callback_handles),
-            animation_frame_handles: Weak::clone(&self.animation_frame_handles),
+            callback_handles: Weak::clone(&self.callback_handles),
             event_closures: Weak::clone(&self.event_closures),
+            components: Weak::clone(&self.components),
+            subscriptions: Weak::clone(&self.subscriptions),
+            injected_styles: Weak::clone(&self.injected_styles),
+            replaced_node: Weak::clone(&self.replaced_node),
+            delegated_events: Weak::clone(&self.delegated_events),
+            scheduler: Weak::clone(&self.scheduler),
+            #[cfg(feature = "with-serde")]
+            persistence: Weak::clone(&self.persistence),
+            #[cfg(feature = "with-time-travel")]
+            time_travel: Weak::clone(&self.time_travel),
+            #[cfg(feature = "with-inspector")]
+            inspector: Weak::clone(&self.inspector),
         }
     }
 }
 
-impl<APP, MSG> Program<APP, MSG>
+impl<APP, MSG, DOM> Program<APP, MSG, DOM>
 where
     MSG: 'static,
+    DOM: DomBackend,
 {
     ///
-    pub fn downgrade(&self) -> WeakProgram<APP, MSG> {
+    pub fn downgrade(&self) -> WeakProgram<APP, MSG, DOM> {
         WeakProgram {
             app_context: AppContext::downgrade(&self.app_context),
+            backend: self.backend.clone(),
             root_node: Rc::downgrade(&self.root_node),
             mount_node: Rc::downgrade(&self.mount_node),
             node_closures: Rc::downgrade(&self.node_closures),
             mount_procedure: self.mount_procedure,
             pending_patches: Rc::downgrade(&self.pending_patches),
-            idle_callback_handles: Rc::downgrade(&self.idle_callback_handles),
-            animation_frame_handles: Rc::downgrade(&self.animation_frame_handles),
+            callback_handles: Rc::downgrade(&self.callback_handles),
             event_closures: Rc::downgrade(&self.event_closures),
+            components: Rc::downgrade(&self.components),
+            subscriptions: Rc::downgrade(&self.subscriptions),
+            injected_styles: Rc::downgrade(&self.injected_styles),
+            replaced_node: Rc::downgrade(&self.replaced_node),
+            delegated_events: Rc::downgrade(&self.delegated_events),
+            scheduler: Rc::downgrade(&self.scheduler),
+            #[cfg(feature = "with-serde")]
+            persistence: Rc::downgrade(&self.persistence),
+            #[cfg(feature = "with-time-travel")]
+            time_travel: Rc::downgrade(&self.time_travel),
+            #[cfg(feature = "with-inspector")]
+            inspector: Rc::downgrade(&self.inspector),
         }
     }
 }
 
-impl<APP, MSG> Clone for Program<APP, MSG>
+impl<APP, MSG, DOM> Clone for Program<APP, MSG, DOM>
 where
     MSG: 'static,
+    DOM: DomBackend,
 {
     fn clone(&self) -> Self {
         Program {
             app_context: self.app_context.clone(),
+            backend: self.backend.clone(),
             root_node: Rc::clone(&self.root_node),
             mount_node: Rc::clone(&self.mount_node),
             node_closures: Rc::clone(&self.node_closures),
             mount_procedure: self.mount_procedure,
             pending_patches: Rc::clone(&self.pending_patches),
-            idle_callback_handles: Rc::clone(&self.idle_callback_handles),
-            animation_frame_handles: Rc::clone(&self.animation_frame_handles),
+            callback_handles: Rc::clone(&self.callback_handles),
             event_closures: Rc::clone(&self.event_closures),
+            components: Rc::clone(&self.components),
+            subscriptions: Rc::clone(&self.subscriptions),
+            injected_styles: Rc::clone(&self.injected_styles),
+            replaced_node: Rc::clone(&self.replaced_node),
+            delegated_events: Rc::clone(&self.delegated_events),
+            scheduler: Rc::clone(&self.scheduler),
+            #[cfg(feature = "with-serde")]
+            persistence: Rc::clone(&self.persistence),
+            #[cfg(feature = "with-time-travel")]
+            time_travel: Rc::clone(&self.time_travel),
+            #[cfg(feature = "with-inspector")]
+            inspector: Rc::clone(&self.inspector),
         }
     }
 }
 
-impl<APP, MSG> Drop for Program<APP, MSG>
+impl<APP, MSG, DOM> Drop for Program<APP, MSG, DOM>
 where
     MSG: 'static,
+    DOM: DomBackend,
 {
     fn drop(&mut self) {
         // program is dropped
     }
 }
 
-impl<APP, MSG> Program<APP, MSG>
+impl<APP, MSG, DOM> Program<APP, MSG, DOM>
 where
     MSG: 'static,
+    DOM: DomBackend,
 {
     /// get a reference to the APP
     pub fn app(&self) -> Ref<'_, APP> {
@@ -234,6 +358,11 @@ where
     pub fn app_mut(&self) -> RefMut<'_, APP> {
         self.app_context.app.borrow_mut()
     }
+
+    /// the DOM where the APP is mounted into
+    pub fn backend(&self) -> &DOM {
+        &self.backend
+    }
 }
 
 impl<APP, MSG> Program<APP, MSG>
@@ -249,56 +378,7 @@ where
         action: MountAction,
         target: MountTarget,
     ) -> Self {
-        Program {
-            app_context: AppContext::new(app),
-            root_node: Rc::new(RefCell::new(None)),
-            mount_node: Rc::new(RefCell::new(mount_node.clone())),
-            node_closures: Rc::new(RefCell::new(ActiveClosure::new())),
-            mount_procedure: MountProcedure { action, target },
-            pending_patches: Rc::new(RefCell::new(VecDeque::new())),
-            idle_callback_handles: Rc::new(RefCell::new(vec![])),
-            animation_frame_handles: Rc::new(RefCell::new(vec![])),
-            event_closures: Rc::new(RefCell::new(vec![])),
-        }
-    }
-
-
-    /// executed after the program has been mounted
-    fn after_mounted(&mut self) {
-        // call the init of the component
-        let cmd = self.app_context.init_app();
-        cmd.emit(self.clone());
-
-        // inject the app's dynamic style after the emitting the init function and it's effects
-        self.inject_dynamic_style();
-    }
-
-    fn app_hash() -> u64 {
-        let type_id = TypeId::of::<APP>();
-        let mut hasher = DefaultHasher::new();
-        type_id.hash(&mut hasher);
-        hasher.finish()
-    }
-
-    fn inject_stylesheet(&mut self) {
-        let static_style = self.app_context.static_style();
-        if !static_style.is_empty() {
-            let class_names = format!("static {}", Self::app_hash());
-            self.inject_style(class_names, &static_style);
-        }
-    }
-
-    fn inject_dynamic_style(&mut self) {
-        let dynamic_style = self.app_context.dynamic_style();
-        if !dynamic_style.is_empty() {
-            let class_names = format!("dynamic {}", Self::app_hash());
-            self.inject_style(class_names, &dynamic_style);
-        }
-    }
-
-    /// return the node where the app is mounted into
-    pub fn mount_node(&self) -> web_sys::Node {
-        self.mount_node.borrow().clone()
+        Self::with_backend(app, WebDom, mount_node, action, target)
     }
 
     ///  Instantiage an app and append the view to the root_node
@@ -320,7 +400,9 @@ where
     /// ```
     pub fn append_to_mount(app: APP, mount_node: &web_sys::Node) -> ManuallyDrop<Self> {
         let mut program = Self::new(app, mount_node, MountAction::Append, MountTarget::MountNode);
-        program.mount();
+        if let Err(error) = program.mount() {
+            program.handle_error(error);
+        }
         ManuallyDrop::new(program)
     }
 
@@ -348,10 +430,43 @@ where
             MountAction::Replace,
             MountTarget::MountNode,
         );
-        program.mount();
+        if let Err(error) = program.mount() {
+            program.handle_error(error);
+        }
         ManuallyDrop::new(program)
     }
 
+    /// Attach the app to the server rendered html, the mount node being the root node of the
+    /// app's view. The differences between the server rendered html and the app's view are
+    /// logged as warnings.
+    /// # Example
+    /// ```rust,ignore
+    /// # use sauron::prelude::*;
+    /// # use sauron::document;
+    /// struct App{}
+    /// # impl Application<()> for App{
+    /// #     fn view(&self) -> Node<()>{
+    /// #         main(vec![], vec![text("hello")])
+    /// #     }
+    /// #     fn update(&mut self, _: ()) -> Cmd<Self, ()> {
+    /// #         Cmd::none()
+    /// #     }
+    /// # }
+    /// let mount = document().query_selector("main").ok().flatten().unwrap();
+    /// Program::hydrate_mount(App{}, &mount);
+    /// ```
+    pub fn hydrate_mount(app: APP, mount_node: &web_sys::Node) -> ManuallyDrop<Self> {
+        let mut program = Self::new(
+            app,
+            mount_node,
+            MountAction::Hydrate,
+            MountTarget::MountNode,
+        );
+        if let Err(error) = program.mount() {
+            program.handle_error(error);
+        }
+        ManuallyDrop::new(program)
+    }
 
     /// clear the existing children of the mount before mounting the app
     pub fn clear_append_to_mount(app: APP, mount_node: &web_sys::Node) -> ManuallyDrop<Self> {
@@ -361,7 +476,9 @@ where
             MountAction::ClearAppend,
             MountTarget::MountNode,
         );
-        program.mount();
+        if let Err(error) = program.mount() {
+            program.handle_error(error);
+        }
         ManuallyDrop::new(program)
     }
 
@@ -395,6 +512,181 @@ where
         Self::append_to_mount(app, &body())
     }
 
+    /// attach the listener to an event target outside of the app's view,
+    /// it is detached when the program is unmounted
+    pub(crate) fn add_event_closure(
+        &self,
+        target: &web_sys::EventTarget,
+        event_name: &'static str,
+        closure: Closure<dyn FnMut(web_sys::Event)>,
+    ) -> Result<(), JsValue> {
+        self.add_event_closure_with_options(
+            target,
+            event_name,
+            &ListenerOptions::default(),
+            closure,
+        )
+    }
+
+    /// attach the listener with the passive, capture and once options
+    pub(crate) fn add_event_closure_with_options(
+        &self,
+        target: &web_sys::EventTarget,
+        event_name: &'static str,
+        options: &ListenerOptions,
+        closure: Closure<dyn FnMut(web_sys::Event)>,
+    ) -> Result<(), JsValue> {
+        target.add_event_listener_with_callback_and_add_event_listener_options(
+            intern(event_name),
+            closure.as_ref().unchecked_ref(),
+            &add_event_listener_options(options),
+        )?;
+        self.event_closures.borrow_mut().push(EventClosure {
+            target: target.clone(),
+            event_name,
+            capture: options.capture,
+            closure,
+        });
+        Ok(())
+    }
+
+    /// returns true if a listener of the event is attached to the event target with
+    /// [`Program::add_event_closure`]
+    pub(crate) fn has_event_closure(
+        &self,
+        target: &web_sys::EventTarget,
+        event_name: &str,
+    ) -> bool {
+        self.event_closures.borrow().iter().any(|event_closure| {
+            event_closure.target == *target && event_closure.event_name == event_name
+        })
+    }
+
+    /// detach the listeners which are attached to the event target with
+    /// [`Program::add_event_closure`]
+    pub(crate) fn remove_event_closures(&self, target: &web_sys::EventTarget) -> Result<(), Error> {
+        let removed: Vec<EventClosure> = {
+            let mut event_closures = self.event_closures.borrow_mut();
+            let (removed, retained) = event_closures
+                .drain(..)
+                .partition(|event_closure| event_closure.target == *target);
+            *event_closures = retained;
+            removed
+        };
+        for event_closure in removed {
+            event_closure.remove()?;
+        }
+        Ok(())
+    }
+}
+
+impl<APP, MSG, DOM> Program<APP, MSG, DOM>
+where
+    MSG: 'static,
+    APP: Application<MSG> + 'static,
+    DOM: DomBackend,
+{
+    /// Create the program which is mounted into the mount node of the DOM backend, but doesn't
+    /// mount it yet.
+    /// # Example
+    /// ```rust,ignore
+    /// # use sauron::prelude::*;
+    /// # use sauron::dom::{MemoryDom, MountAction, MountTarget};
+    /// let dom = MemoryDom::new();
+    /// let mut program = Program::with_backend(
+    ///     App::default(),
+    ///     dom.clone(),
+    ///     &dom.body(),
+    ///     MountAction::Append,
+    ///     MountTarget::MountNode,
+    /// );
+    /// program.mount().unwrap();
+    /// dom.click("button");
+    /// ```
+    pub fn with_backend(
+        app: APP,
+        backend: DOM,
+        mount_node: &DOM::Node,
+        action: MountAction,
+        target: MountTarget,
+    ) -> Self {
+        Program {
+            app_context: AppContext::new(app),
+            backend,
+            root_node: Rc::new(RefCell::new(None)),
+            mount_node: Rc::new(RefCell::new(mount_node.clone())),
+            node_closures: Rc::new(RefCell::new(ActiveClosure::new())),
+            mount_procedure: MountProcedure { action, target },
+            pending_patches: Rc::new(RefCell::new(VecDeque::new())),
+            callback_handles: Rc::new(RefCell::new(CallbackHandles::default())),
+            event_closures: Rc::new(RefCell::new(vec![])),
+            components: Rc::new(RefCell::new(vec![])),
+            subscriptions: Rc::new(RefCell::new(ActiveSubs::new())),
+            injected_styles: Rc::new(RefCell::new(vec![])),
+            replaced_node: Rc::new(RefCell::new(None)),
+            delegated_events: Rc::new(RefCell::new(BTreeSet::new())),
+            scheduler: Rc::new(RefCell::new(Scheduler::default())),
+            #[cfg(feature = "with-serde")]
+            persistence: Rc::new(RefCell::new(None)),
+            #[cfg(feature = "with-time-travel")]
+            time_travel: Rc::new(RefCell::new(None)),
+            #[cfg(feature = "with-inspector")]
+            inspector: Rc::new(RefCell::new(None)),
+        }
+    }
+
+    /// this program with the DOM of the browser, `None` when it uses a different DOM backend.
+    /// This is used for the features which only work in the browser.
+    pub(crate) fn as_web(&self) -> Option<Program<APP, MSG>> {
+        let program: &dyn Any = self;
+        program.downcast_ref::<Program<APP, MSG>>().cloned()
+    }
+
+    /// executed after the program has been mounted
+    fn after_mounted(&mut self) {
+        self.report_error_boundaries();
+        #[cfg(feature = "with-event-delegation")]
+        if let Some(program) = self.as_web() {
+            program.attach_delegated_events();
+        }
+
+        // call the init of the component
+        let cmd = self.app_context.init_app();
+        cmd.emit(self.clone());
+        self.update_subscriptions();
+
+        // inject the app's dynamic style after the emitting the init function and it's effects
+        self.inject_dynamic_style();
+    }
+
+    fn app_hash() -> u64 {
+        let type_id = TypeId::of::<APP>();
+        let mut hasher = DefaultHasher::new();
+        type_id.hash(&mut hasher);
+        hasher.finish()
+    }
+
+    fn inject_stylesheet(&mut self) {
+        let static_style = self.app_context.static_style();
+        if !static_style.is_empty() {
+            let class_names = format!("static {}", Self::app_hash());
+            self.inject_style(class_names, &static_style);
+        }
+    }
+
+    fn inject_dynamic_style(&mut self) {
+        let dynamic_style = self.app_context.dynamic_style();
+        if !dynamic_style.is_empty() {
+            let class_names = format!("dynamic {}", Self::app_hash());
+            self.inject_style(class_names, &dynamic_style);
+        }
+    }
+
+    /// return the node where the app is mounted into
+    pub fn mount_node(&self) -> DOM::Node {
+        self.mount_node.borrow().clone()
+    }
+
     /// executed right before the app is mounted to the dom
     pub fn pre_mount(&mut self) {
         self.inject_stylesheet();
@@ -402,107 +694,124 @@ where
 
     /// each element and it's descendant in the vdom is created into
     /// an actual DOM node.
-    pub fn mount(&mut self) {
+    pub fn mount(&mut self) -> Result<(), Error> {
         self.pre_mount();
-        let created_node = self.create_dom_node(&self.app_context.current_vdom());
 
-        let mount_node: web_sys::Node = match self.mount_procedure.target {
-            MountTarget::MountNode => self.mount_node.borrow().clone(),
+        let mount_node = match self.mount_procedure.target {
+            MountTarget::MountNode => self.mount_node(),
             MountTarget::ShadowRoot => {
-                let mount_element: web_sys::Element =
-                    self.mount_node.borrow().clone().unchecked_into();
-                mount_element
-                    .attach_shadow(&web_sys::ShadowRootInit::new(web_sys::ShadowRootMode::Open))
-                    .expect("unable to attached shadow");
-                let mount_shadow = mount_element.shadow_root().expect("must have a shadow");
-
-                *self.mount_node.borrow_mut() = mount_shadow.unchecked_into();
-                self.mount_node.borrow().clone()
+                let mount_shadow = self.backend.attach_shadow(&self.mount_node())?;
+                *self.mount_node.borrow_mut() = mount_shadow.clone();
+                mount_shadow
             }
         };
 
-        match self.mount_procedure.action {
+        let root_node = match self.mount_procedure.action {
             MountAction::Append => {
-                Self::append_child_and_dispatch_mount_event(&mount_node, &created_node);
+                let created_node = self.create_dom_node(&self.app_context.current_vdom())?;
+                self.append_and_dispatch_mount(&mount_node, &created_node)?;
+                created_node
             }
             MountAction::ClearAppend => {
-                Self::clear_children(&mount_node);
-                Self::append_child_and_dispatch_mount_event(&mount_node, &created_node);
+                let created_node = self.create_dom_node(&self.app_context.current_vdom())?;
+                self.backend.clear_children(&mount_node)?;
+                self.append_and_dispatch_mount(&mount_node, &created_node)?;
+                created_node
             }
             MountAction::Replace => {
-                let mount_element: &Element = mount_node.unchecked_ref();
-                mount_element
-                    .replace_with_with_node_1(&created_node)
-                    .expect("Could not append child to mount");
-                Self::dispatch_mount_event(&created_node);
-                *self.mount_node.borrow_mut() = created_node.clone()
+                let created_node = self.create_dom_node(&self.app_context.current_vdom())?;
+                self.backend.replace_with(&mount_node, &created_node)?;
+                self.dispatch_mount(&created_node)?;
+                *self.replaced_node.borrow_mut() = Some(mount_node);
+                *self.mount_node.borrow_mut() = created_node.clone();
+                created_node
             }
-        }
-        *self.root_node.borrow_mut() = Some(created_node);
+            MountAction::Hydrate => {
+                let mismatches =
+                    self.hydrate_dom_node(&self.app_context.current_vdom(), &mount_node)?;
+                for mismatch in mismatches {
+                    log::warn!("hydration mismatch: {mismatch:?}");
+                }
+                mount_node
+            }
+        };
+        *self.root_node.borrow_mut() = Some(root_node);
         self.after_mounted();
-    }
-
-    #[cfg(feature = "with-ric")]
-    fn dispatch_pending_msgs_with_ric(&mut self) -> Result<(), JsValue> {
-        let program = Program::downgrade(&self);
-        let handle = request_idle_callback(move |deadline| {
-            let mut program = program.upgrade().expect("must upgrade");
-            program
-                .dispatch_pending_msgs(Some(deadline))
-                .expect("must execute")
-        })
-        .expect("must execute");
-        self.idle_callback_handles.borrow_mut().push(handle);
         Ok(())
     }
 
     /// executes pending msgs by calling the app update method with the msgs
     /// as parameters.
-    /// If there is no deadline specified all the pending messages are executed
-    fn dispatch_pending_msgs(&mut self, deadline: Option<IdleDeadline>) -> Result<(), JsValue> {
+    /// If there is no deadline specified all the pending messages are executed,
+    /// otherwise the remaining msgs are left pending when the deadline is reached.
+    pub(crate) fn dispatch_pending_msgs(
+        &mut self,
+        deadline: Option<IdleDeadline>,
+    ) -> Result<(), Error> {
         if !self.app_context.has_pending_msgs() {
             return Ok(());
         }
-        let mut did_complete = true;
-        while self.app_context.dispatch_pending_msg() {
+        loop {
+            #[cfg(feature = "with-time-travel")]
+            let msg_to_record = self.msg_to_record();
+            #[cfg(feature = "with-inspector")]
+            self.inspect_next_msg();
+            if !self.app_context.dispatch_pending_msg() {
+                break;
+            }
+            #[cfg(feature = "with-time-travel")]
+            if let Some(msg) = msg_to_record {
+                self.record_update(msg);
+            }
             // break only if a deadline is supplied
             if let Some(deadline) = &deadline {
-                if deadline.did_timeout() {
-                    did_complete = false;
+                if deadline.time_remaining() <= 0.0 {
                     break;
                 }
             }
         }
-        if !did_complete {
-            #[cfg(feature = "with-ric")]
-            self.dispatch_pending_msgs_with_ric()
-                .expect("must complete");
-        }
         Ok(())
     }
 
     /// update the browser DOM to reflect the APP's  view
-    pub fn update_dom(&mut self, modifier: &Modifier) -> Result<Measurements, JsValue> {
-        let t1 = now();
+    pub fn update_dom(&mut self, modifier: &Modifier) -> Result<Measurements, Error> {
+        self.update_dom_until(modifier, None)
+    }
+
+    /// update the browser DOM to reflect the APP's view, the patches which are not applied
+    /// before the `until` timestamp are left pending
+    fn update_dom_until(
+        &mut self,
+        modifier: &Modifier,
+        until: Option<f64>,
+    ) -> Result<Measurements, Error> {
+        let t1 = self.backend.now();
         // a new view is created due to the app update
         let view = self.app_context.view();
-        let t2 = now();
+        let t2 = self.backend.now();
+        self.report_error_boundaries();
 
         let node_count = view.node_count();
 
         // update the last DOM node tree with this new view
-        let total_patches = self.update_dom_with_vdom(view).expect("must not error");
-        let t3 = now();
+        let total_patches = self.patch_dom_until(view, until)?;
+        let t3 = self.backend.now();
 
         let strong_count = self.app_context.strong_count();
         let weak_count = self.app_context.weak_count();
         let root_node_count = Rc::strong_count(&self.root_node);
-        assert_eq!(strong_count, root_node_count);
+        if strong_count != root_node_count {
+            return Err(Error::LeakedReference {
+                app: strong_count,
+                program: root_node_count,
+            });
+        }
         let measurements = Measurements {
             name: modifier.measurement_name.to_string(),
             node_count,
             build_view_took: t2 - t1,
             total_patches,
+            msg_count: 0,
             dom_update_took: t3 - t2,
             total_time: t3 - t1,
             strong_count,
@@ -518,7 +827,10 @@ where
         Ok(measurements)
     }
 
-    fn create_dom_patch(&self, new_vdom: &vdom::Node<MSG>) -> Vec<DomPatch<MSG>> {
+    fn create_dom_patch(
+        &self,
+        new_vdom: &vdom::Node<MSG>,
+    ) -> Result<Vec<DomPatch<MSG, DOM>>, Error> {
         let current_vdom = self.app_context.current_vdom();
         let patches = diff(&current_vdom, &new_vdom);
         #[cfg(all(feature = "with-debug", feature = "log-patches"))]
@@ -526,59 +838,96 @@ where
             log::debug!("There are {} patches", patches.len());
             log::debug!("patches: {patches:#?}");
         }
-        let dom_patches = self
-            .convert_patches(&patches)
-            .expect("must convert patches");
-        dom_patches
+        self.convert_patches(&patches)
     }
 
     /// patch the DOM to reflect the App's view
     ///
     /// Note: This is in another function so as to allow tests to use this shared code
-    pub fn update_dom_with_vdom(&mut self, new_vdom: vdom::Node<MSG>) -> Result<usize, JsValue> {
-        let dom_patches = self.create_dom_patch(&new_vdom);
+    pub fn update_dom_with_vdom(&mut self, new_vdom: vdom::Node<MSG>) -> Result<usize, Error> {
+        self.patch_dom_until(new_vdom, None)
+    }
+
+    /// patch the DOM to reflect the view, the patches which are not applied before the `until`
+    /// timestamp are left pending
+    fn patch_dom_until(
+        &mut self,
+        new_vdom: vdom::Node<MSG>,
+        until: Option<f64>,
+    ) -> Result<usize, Error> {
+        // the patches are created against the DOM, which must have the previous patches applied
+        self.apply_pending_patches()?;
+        let dom_patches = self.create_dom_patch(&new_vdom)?;
         let total_patches = dom_patches.len();
         self.pending_patches.borrow_mut().extend(dom_patches);
-
-        #[cfg(feature = "with-raf")]
-        self.apply_pending_patches_with_raf().expect("raf");
-
-        #[cfg(not(feature = "with-raf"))]
-        self.apply_pending_patches().expect("raf");
+        self.apply_pending_patches_until(until)?;
 
         self.app_context.set_current_dom(new_vdom);
+        #[cfg(feature = "with-inspector")]
+        self.inspect_view();
         Ok(total_patches)
     }
 
-    #[cfg(feature = "with-raf")]
-    fn apply_pending_patches_with_raf(&mut self) -> Result<(), JsValue> {
-        let program = Program::downgrade(&self);
-        let handle = request_animation_frame(move || {
-            let mut program = program.upgrade().expect("must upgrade");
-            program.apply_pending_patches().expect("must not error");
-        })
-        .expect("must execute");
-        self.animation_frame_handles.borrow_mut().push(handle);
-        Ok(())
+    /// apply the pending patches into the DOM
+    pub(crate) fn apply_pending_patches(&mut self) -> Result<(), Error> {
+        self.apply_pending_patches_until(None)
     }
 
-    /// apply the pending patches into the DOM
-    fn apply_pending_patches(&mut self) -> Result<(), JsValue> {
+    /// apply the pending patches into the DOM, the patches which are not applied before the
+    /// `until` timestamp are left pending
+    pub(crate) fn apply_pending_patches_until(&mut self, until: Option<f64>) -> Result<(), Error> {
         if self.pending_patches.borrow().is_empty() {
             return Ok(());
         }
-        let dom_patches: Vec<DomPatch<MSG>> = self.pending_patches.borrow_mut().drain(..).collect();
-        for dom_patch in dom_patches {
-            self.apply_dom_patch(dom_patch)
-                .expect("must apply dom patch");
+        let mut updated_elements: Vec<DOM::Node> = vec![];
+        #[cfg(feature = "with-inspector")]
+        let mut patched_elements: Vec<DOM::Node> = vec![];
+        loop {
+            let dom_patch = self.pending_patches.borrow_mut().pop_front();
+            let Some(dom_patch) = dom_patch else {
+                break;
+            };
+            #[cfg(feature = "with-inspector")]
+            patched_elements.push(dom_patch.target_element.clone());
+            if let Some(updated_element) = dom_patch.updated_element(&self.backend) {
+                if !updated_elements.contains(&updated_element) {
+                    updated_elements.push(updated_element);
+                }
+            }
+            self.apply_dom_patch(dom_patch)?;
+            if until.is_some_and(|until| self.backend.now() >= until) {
+                break;
+            }
+        }
+        #[cfg(feature = "with-inspector")]
+        self.inspect_patched(&patched_elements);
+        // the updated event is dispatched after the patches are applied,
+        // the elements that were removed in the patches are skipped
+        for element in updated_elements {
+            if self.backend.is_connected(&element) && self.has_listener(&element, UPDATED) {
+                self.backend.dispatch_event(&element, UPDATED)?;
+            }
         }
         Ok(())
     }
 
     /// execute DOM changes in order to reflect the APP's view into the browser representation
-    fn dispatch_dom_changes(&mut self, modifier: &Modifier) {
+    fn dispatch_dom_changes(&mut self, modifier: &Modifier, until: Option<f64>, msg_count: usize) {
         #[allow(unused_variables)]
-        let measurements = self.update_dom(modifier).expect("must update dom");
+        let measurements = match self.update_dom_until(modifier, until) {
+            Ok(measurements) => Measurements {
+                msg_count,
+                ..measurements
+            },
+            Err(error) => {
+                self.handle_error(error);
+                return;
+            }
+        };
+        #[cfg(feature = "with-time-travel")]
+        self.record_measurements(&measurements);
+        #[cfg(feature = "with-inspector")]
+        self.inspect_measurements(&measurements);
 
         #[cfg(feature = "with-measure")]
         // tell the app about the performance measurement and only if there was patches applied
@@ -588,134 +937,246 @@ where
         }
     }
 
-    #[cfg(feature = "with-ric")]
-    fn dispatch_inner_with_ric(&self) {
-        let program = Program::downgrade(&self);
-        let handle = request_idle_callback(move |deadline| {
-            if let Some(mut program) = program.upgrade() {
-                program.dispatch_inner(Some(deadline));
-            } else {
-                log::warn!("unable to upgrade program.. maybe try again next time..");
-            }
-        })
-        .expect("must execute");
-        self.idle_callback_handles.borrow_mut().push(handle);
-    }
-
-    #[allow(unused)]
-    #[cfg(feature = "with-raf")]
-    fn dispatch_inner_with_raf(&self) {
-        let program = Program::downgrade(&self);
-        let handle = request_animation_frame(move || {
-            let mut program = program.upgrade().expect("must upgrade");
-            program.dispatch_inner(None);
-        })
-        .expect("must execute");
-        self.animation_frame_handles.borrow_mut().push(handle);
-    }
-
-    fn dispatch_inner_with_priority_ric(&self) {
-        #[cfg(feature = "with-ric")]
-        self.dispatch_inner_with_ric();
-        #[cfg(not(feature = "with-ric"))]
-        {
-            #[cfg(feature = "with-raf")]
-            self.dispatch_inner_with_raf();
-
-            #[cfg(not(feature = "with-raf"))]
-            {
-                let program = Program::downgrade(&self);
-                wasm_bindgen_futures::spawn_local(async move {
-                    if let Some(mut program) = program.upgrade() {
-                        program.dispatch_inner(None);
-                    } else {
-                        log::warn!(
-                            "unable to upgrade program here, in dispatch_inner_with_priority_ric"
-                        );
-                    }
-                })
-            }
-        }
-    }
-
     /// This is called when an event is triggered in the html DOM.
     /// The sequence of things happening here:
-    /// - The app component update is executed.
+    /// - The app component update is executed with the msgs of the priority.
     /// - The returned Cmd from the component update is then emitted.
     /// - The view is reconstructed with the new state of the app.
-    /// - The dom is updated with the newly reconstructed view.
-    fn dispatch_inner(&mut self, deadline: Option<IdleDeadline>) {
-        self.dispatch_pending_msgs(deadline)
-            .expect("must dispatch msgs");
-        // ensure that all pending msgs are all dispatched already
-        if self.app_context.has_pending_msgs() {
-            self.dispatch_pending_msgs(None)
-                .expect("must dispatch all pending msgs");
-        }
-        if self.app_context.has_pending_msgs() {
-            panic!("Can not proceed until previous pending msgs are dispatched..");
+    /// - The dom is updated with the newly reconstructed view, the patches of the background
+    ///   updates are applied in slices of the frame budget.
+    pub(crate) fn dispatch_inner(&mut self, priority: Priority, deadline: Option<IdleDeadline>) {
+        let msg_count = self.dispatch_scheduled_msgs(priority, deadline);
+        debug_assert!(
+            !self.app_context.has_pending_msgs(),
+            "all the pending msgs must be dispatched or scheduled"
+        );
+        // the msgs were already updated, such as with `dispatch_sync`
+        if msg_count == 0 {
+            return;
         }
 
         let cmd = self.app_context.batch_pending_cmds();
 
-        if !self.pending_patches.borrow().is_empty() {
-            log::error!(
-                "BEFORE DOM updates there are still Remaining pending patches: {}",
-                self.pending_patches.borrow().len()
-            );
+        if cmd.modifier.should_update_view {
+            self.dispatch_dom_changes(&cmd.modifier, self.patch_deadline(priority), msg_count);
         }
 
-        if cmd.modifier.should_update_view {
-            self.dispatch_dom_changes(&cmd.modifier);
+        // the remaining patches are applied in the next frames, the cmd is emitted after them
+        if priority == Priority::Background && !self.pending_patches.borrow().is_empty() {
+            self.apply_pending_patches_in_frames(cmd);
+            return;
         }
 
         // Ensure all pending patches are applied before emiting the Cmd from update
         if !self.pending_patches.borrow().is_empty() {
-            self.apply_pending_patches()
-                .expect("applying pending patches..");
+            if let Err(error) = self.apply_pending_patches() {
+                self.handle_error(error);
+            }
         }
 
-        if !self.pending_patches.borrow().is_empty() {
-            log::error!(
-                "Remaining pending patches: {}",
-                self.pending_patches.borrow().len()
-            );
-            panic!(
-                "There are still pending patches.. can not emit cmd, if all pending patches
-            has not been applied yet!"
-            );
+        // the cmd is not emitted, since its effects may depend on the view being updated
+        let pending_patches = self.pending_patches.borrow().len();
+        if pending_patches > 0 {
+            self.handle_error(Error::PendingPatches(pending_patches));
+            return;
         }
 
+        self.finish_dispatch(cmd);
+    }
+
+    /// emit the cmd of the updates once their patches are applied
+    pub(crate) fn finish_dispatch(&mut self, cmd: Cmd<APP, MSG>) {
+        self.update_subscriptions();
+        #[cfg(feature = "with-serde")]
+        self.schedule_persist();
         cmd.emit(self.clone());
     }
 
+    /// start and stop the subscriptions as they are changed by the update
+    fn update_subscriptions(&self) {
+        let sub = self.app_context.app.borrow().subscriptions();
+        let program = Program::downgrade(self);
+        let errors = self.subscriptions.borrow_mut().update(
+            sub,
+            Rc::new(move |msg| {
+                if let Some(mut program) = program.upgrade() {
+                    program.dispatch(msg);
+                }
+            }),
+        );
+        for error in errors {
+            self.clone().handle_error(error);
+        }
+    }
+
     /// Inject a style to the global document
     fn inject_style(&mut self, class_names: String, style: &str) {
         let style_node = html::tags::style([class(class_names)], [text(style)]);
-        let created_node = self.create_dom_node(&style_node);
-
-        let head = document().head().expect("must have a head");
-        head.append_child(&created_node).expect("must append style");
+        let injected = self.create_dom_node(&style_node).and_then(|created_node| {
+            let head = self.backend.head().ok_or(Error::MissingHead)?;
+            self.backend.append_child(&head, &created_node)?;
+            Ok(created_node)
+        });
+        match injected {
+            Ok(created_node) => self.injected_styles.borrow_mut().push(created_node),
+            Err(error) => self.handle_error(error),
+        }
     }
 
     /// inject style element to the mount node
     pub fn inject_style_to_mount(&mut self, style: &str) {
         let style_node = html::tags::style([], [text(style)]);
-        let created_node = self.create_dom_node(&style_node);
+        let injected = self.create_dom_node(&style_node).and_then(|created_node| {
+            self.backend
+                .append_child(&self.mount_node(), &created_node)?;
+            Ok(created_node)
+        });
+        match injected {
+            Ok(created_node) => self.injected_styles.borrow_mut().push(created_node),
+            Err(error) => self.handle_error(error),
+        }
+    }
 
-        self.mount_node
-            .borrow_mut()
-            .append_child(&created_node)
-            .expect("could not append child to mount shadow");
+    /// pass the error to [`Application::on_error`], the returned msg is dispatched
+    pub(crate) fn handle_error(&mut self, error: Error) {
+        let msg = self.app_context.app.borrow().on_error(error);
+        if let Some(msg) = msg {
+            self.dispatch(msg);
+        }
+    }
+
+    /// pass the failures caught by the error boundaries while building the view to
+    /// [`Application::on_error`]
+    fn report_error_boundaries(&mut self) {
+        let caught = self.app_context.lazy_views.borrow_mut().take_caught();
+        for error in caught {
+            self.handle_error(error.into());
+        }
+    }
+
+    /// returns true if the program is unmounted
+    pub fn is_unmounted(&self) -> bool {
+        self.root_node.borrow().is_none()
+    }
+
+    /// Unmount the app from the DOM.
+    ///
+    /// The unmount events are dispatched to the view, then the subscriptions, the stateful
+    /// components and the listeners are dropped, the pending idle callbacks and animation frames
+    /// are cancelled and the injected styles are removed.
+    /// The mount node is restored according to the [`MountAction`] it was mounted with:
+    /// - `Append` and `ClearAppend` removes the view from the mount node
+    /// - `Replace` puts back the node which was replaced
+    /// - `Hydrate` leaves the markup in place, without the listeners
+    ///
+    /// The msgs which are dispatched after the program is unmounted, including the ones emitted
+    /// by the unmount events, are ignored. The DOM operations which fail are passed to
+    /// [`Application::on_error`].
+    pub fn unmount(&mut self) {
+        let Some(root_node) = self.root_node.borrow_mut().take() else {
+            log::warn!("the program is already unmounted");
+            return;
+        };
+        let mount_node = self.mount_node();
+        // the fragment is emptied when appended, its nodes are the children of the mount node
+        let is_fragment = self.backend.is_document_fragment(&root_node) || root_node == mount_node;
+        let view_nodes: Vec<DOM::Node> = if is_fragment {
+            self.backend
+                .child_nodes(&mount_node)
+                .into_iter()
+                .filter(|child| !self.injected_styles.borrow().contains(child))
+                .collect()
+        } else {
+            vec![root_node.clone()]
+        };
+        let view_elements: Vec<&DOM::Node> = view_nodes
+            .iter()
+            .filter(|node| self.backend.is_element(node))
+            .collect();
+
+        // the unmount goes on when a step fails, the errors are passed to the app at the end
+        let mut errors = vec![];
+        for element in view_elements.iter() {
+            if let Err(error) = self.dispatch_lifecycle_event(element, WILL_UNMOUNT) {
+                errors.push(error);
+            }
+        }
+        #[cfg(feature = "with-serde")]
+        {
+            self.flush_persisted();
+            *self.persistence.borrow_mut() = None;
+        }
+        *self.subscriptions.borrow_mut() = ActiveSubs::new();
+        let components = std::mem::take(&mut *self.components.borrow_mut());
+        drop(components);
+        #[cfg(feature = "with-inspector")]
+        self.disable_inspector();
+
+        let event_closures = std::mem::take(&mut *self.event_closures.borrow_mut());
+        for event_closure in event_closures {
+            if let Err(error) = event_closure.remove() {
+                errors.push(error);
+            }
+        }
+        self.delegated_events.borrow_mut().clear();
+        // the handles cancel the callbacks when dropped
+        self.callback_handles.borrow_mut().clear();
+        self.pending_patches.borrow_mut().clear();
+        self.app_context.pending_msgs.borrow_mut().clear();
+        self.scheduler.borrow_mut().clear();
+        self.app_context.pending_cmds.borrow_mut().clear();
+
+        for style in self.injected_styles.borrow_mut().drain(..) {
+            if let Some(parent) = self.backend.parent_node(&style) {
+                if let Err(error) = self.backend.remove_child(&parent, &style) {
+                    errors.push(error);
+                }
+            }
+        }
+
+        match self.mount_procedure.action {
+            MountAction::Append | MountAction::ClearAppend => {
+                for node in view_nodes.iter() {
+                    if let Some(parent) = self.backend.parent_node(node) {
+                        if let Err(error) = self.backend.remove_child(&parent, node) {
+                            errors.push(error);
+                        }
+                    }
+                }
+            }
+            MountAction::Replace => {
+                let replaced_node = self.replaced_node.borrow_mut().take();
+                if let Some(replaced_node) = replaced_node {
+                    match self.backend.replace_with(&root_node, &replaced_node) {
+                        Ok(()) => *self.mount_node.borrow_mut() = replaced_node,
+                        Err(error) => errors.push(error),
+                    }
+                }
+            }
+            MountAction::Hydrate => (),
+        }
+        for element in view_elements {
+            let detached = self
+                .dispatch_lifecycle_event(element, UNMOUNT)
+                .and_then(|_| self.detach_node_closures(element));
+            if let Err(error) = detached {
+                errors.push(error);
+            }
+        }
+        self.node_closures.borrow_mut().clear();
+        // the program is already unmounted, the msgs returned by the app are ignored
+        for error in errors {
+            self.handle_error(error);
+        }
     }
 
-    /// dispatch multiple MSG
+    /// dispatch multiple MSG, with the default priority
     pub fn dispatch_multiple(&mut self, msgs: impl IntoIterator<Item = MSG>) {
-        self.app_context.push_msgs(msgs);
-        self.dispatch_inner_with_priority_ric();
+        let priority = self.scheduler.borrow().default_priority();
+        self.dispatch_multiple_with_priority(msgs, priority);
     }
 
-    /// dispatch a single msg
+    /// dispatch a single msg, with the default priority
     pub fn dispatch(&mut self, msg: MSG) {
         self.dispatch_multiple([msg])
     }
@@ -1,6 +1,7 @@
 #[cfg(feature = "with-measure")]
 use crate::dom::Measurements;
 use crate::dom::{Application, Cmd};
+use crate::html::{self, LazyViews};
 use crate::vdom;
 use std::{
     cell::{Ref, RefCell},
@@ -29,6 +30,9 @@ where
 
     /// pending cmds that hasn't been emited yet
     pub(crate) pending_cmds: Rc<RefCell<VecDeque<Cmd<APP, MSG>>>>,
+
+    /// the memoized lazy subtrees of the view
+    pub(crate) lazy_views: Rc<RefCell<LazyViews>>,
 }
 
 pub(crate) struct WeakContext<APP, MSG>
@@ -39,6 +43,7 @@ where
     pub(crate) current_vdom: Weak<RefCell<vdom::Node<MSG>>>,
     pub(crate) pending_msgs: Weak<RefCell<VecDeque<MSG>>>,
     pub(crate) pending_cmds: Weak<RefCell<VecDeque<Cmd<APP, MSG>>>>,
+    pub(crate) lazy_views: Weak<RefCell<LazyViews>>,
 }
 
 impl<APP, MSG> WeakContext<APP, MSG>
@@ -50,12 +55,15 @@ where
             if let Some(current_vdom) = self.current_vdom.upgrade() {
                 if let Some(pending_msgs) = self.pending_msgs.upgrade() {
                     if let Some(pending_cmds) = self.pending_cmds.upgrade() {
-                        return Some(AppContext {
-                            app,
-                            current_vdom,
-                            pending_msgs,
-                            pending_cmds,
-                        });
+                        if let Some(lazy_views) = self.lazy_views.upgrade() {
+                            return Some(AppContext {
+                                app,
+                                current_vdom,
+                                pending_msgs,
+                                pending_cmds,
+                                lazy_views,
+                            });
+                        }
                     }
                 }
             }
@@ -74,6 +82,7 @@ where
             current_vdom: Weak::clone(&self.current_vdom),
             pending_msgs: Weak::clone(&self.pending_msgs),
             pending_cmds: Weak::clone(&self.pending_cmds),
+            lazy_views: Weak::clone(&self.lazy_views),
         }
     }
 }
@@ -88,6 +97,7 @@ where
             current_vdom: Rc::downgrade(&this.current_vdom),
             pending_msgs: Rc::downgrade(&this.pending_msgs),
             pending_cmds: Rc::downgrade(&this.pending_cmds),
+            lazy_views: Rc::downgrade(&this.lazy_views),
         }
     }
     pub fn strong_count(&self) -> usize {
@@ -114,6 +124,7 @@ where
             current_vdom: Rc::clone(&self.current_vdom),
             pending_msgs: Rc::clone(&self.pending_msgs),
             pending_cmds: Rc::clone(&self.pending_cmds),
+            lazy_views: Rc::clone(&self.lazy_views),
         }
     }
 }
@@ -124,12 +135,14 @@ where
     APP: Application<MSG> + 'static,
 {
     pub fn new(app: APP) -> Self {
-        let view = app.view();
+        let lazy_views = Rc::new(RefCell::new(LazyViews::default()));
+        let view = LazyViews::build(&lazy_views, || app.view());
         Self {
             app: Rc::new(RefCell::new(app)),
             current_vdom: Rc::new(RefCell::new(view)),
             pending_msgs: Rc::new(RefCell::new(VecDeque::new())),
             pending_cmds: Rc::new(RefCell::new(VecDeque::new())),
+            lazy_views,
         }
     }
     pub fn init_app(&self) -> Cmd<APP, MSG> {
@@ -137,7 +150,7 @@ where
     }
 
     pub fn view(&self) -> vdom::Node<MSG> {
-        self.app.borrow().view()
+        LazyViews::build(&self.lazy_views, || self.app.borrow().view())
     }
     pub fn dynamic_style(&self) -> String {
         self.app.borrow().style().join("")
@@ -147,8 +160,12 @@ where
         APP::stylesheet().join("")
     }
 
-    pub fn set_current_dom(&mut self, new_vdom: vdom::Node<MSG>) {
-        *self.current_vdom.borrow_mut() = new_vdom;
+    /// set the new vdom as the current vdom, the reused lazy subtrees of the new vdom
+    /// take their children from the current vdom
+    pub fn set_current_dom(&mut self, mut new_vdom: vdom::Node<MSG>) {
+        let mut current_vdom = self.current_vdom.borrow_mut();
+        html::reuse_children(&mut current_vdom, &mut new_vdom);
+        *current_vdom = new_vdom;
     }
 
     pub fn current_vdom(&self) -> Ref<'_, vdom::Node<MSG>> {
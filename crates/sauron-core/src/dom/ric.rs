@@ -63,7 +63,7 @@ impl IdleDeadline {
     pub fn time_remaining(&self) -> f64 {
         match self {
             Self::Real(deadline) => deadline.time_remaining(),
-            Self::Polyfill { start } => 0.0_f64.max(50. - now() - start),
+            Self::Polyfill { start } => 0.0_f64.max(50. - (now() - start)),
         }
     }
 
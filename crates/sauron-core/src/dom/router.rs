@@ -0,0 +1,294 @@
+//! Client side routing.
+//!
+//! A [`Router`] matches an url against a list of path patterns such as `/users/:id/*rest`
+//! and converts the matched parameters into the user's typed route.
+//! Matching is done in pure rust, so the same router can be used in the server to pick the
+//! route to render for server-side rendering.
+//!
+//! When the `with-dom` feature is enabled, the navigation is done with the browser History API
+//! through [`Cmd::push_url`](crate::dom::Cmd::push_url),
+//! [`Cmd::replace_url`](crate::dom::Cmd::replace_url) and the changes in the url are dispatched
+//! into the app using [`Program::on_url_change`](crate::dom::Program::on_url_change).
+//!
+//! # Example
+//! ```rust
+//! use sauron::dom::Router;
+//!
+//! #[derive(Debug, PartialEq)]
+//! enum Route {
+//!     Home,
+//!     User(u32),
+//!     Search { query: String, page: usize },
+//!     Files(String),
+//! }
+//!
+//! let router = Router::new()
+//!     .route("/", |_| Some(Route::Home))
+//!     .route("/users/:id", |params| Some(Route::User(params.parse("id")?)))
+//!     .route("/search", |params| {
+//!         Some(Route::Search {
+//!             query: params.query("q")?.to_string(),
+//!             page: params.query_parse("page").unwrap_or(1),
+//!         })
+//!     })
+//!     .route("/files/*path", |params| Some(Route::Files(params.param("path")?.to_string())));
+//!
+//! assert_eq!(router.recognize("/"), Some(Route::Home));
+//! assert_eq!(router.recognize("/users/42"), Some(Route::User(42)));
+//! assert_eq!(router.recognize("/users/abc"), None);
+//! assert_eq!(
+//!     router.recognize("/search?q=hello%20world"),
+//!     Some(Route::Search { query: "hello world".to_string(), page: 1 })
+//! );
+//! assert_eq!(
+//!     router.recognize("/files/docs/readme.md"),
+//!     Some(Route::Files("docs/readme.md".to_string()))
+//! );
+//! ```
+use crate::dom::Callback;
+use std::{collections::BTreeMap, str::FromStr};
+
+#[cfg(feature = "with-dom")]
+pub use navigation::current_url;
+
+#[cfg(feature = "with-dom")]
+mod navigation;
+
+/// the name used for the captured path of a wildcard that has no name, ie: `/files/*`
+pub const WILDCARD: &str = "*";
+
+/// A segment of a path pattern
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Segment {
+    /// matches the segment exactly, ie: `users` in `/users/:id`
+    Static(String),
+    /// captures the segment, ie: `:id` in `/users/:id`
+    Param(String),
+    /// captures the rest of the path, ie: `*path` in `/files/*path`
+    Wildcard(String),
+}
+
+/// A path pattern such as `/users/:id/posts/*rest`.
+///
+/// - `:name` captures a single path segment
+/// - `*name` captures the rest of the path, it must be the last segment.
+///   The name can be omitted in which case the captured path is stored under [`WILDCARD`].
+/// - any other segment must match exactly.
+///
+/// Trailing slashes are ignored.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RoutePattern {
+    segments: Vec<Segment>,
+}
+
+/// The parameters captured from matching an url against a [`RoutePattern`]
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct Params {
+    path: BTreeMap<String, String>,
+    query: BTreeMap<String, String>,
+    fragment: Option<String>,
+}
+
+/// Router matches an url against the routes in the order they were added
+/// and returns the first route that matches.
+pub struct Router<ROUTE> {
+    routes: Vec<(RoutePattern, Callback<Params, Option<ROUTE>>)>,
+}
+
+impl RoutePattern {
+    /// parse the path pattern
+    /// # Panics
+    /// if the wildcard segment is not the last segment of the pattern
+    pub fn new(pattern: &str) -> Self {
+        let segments: Vec<Segment> = path_segments(pattern)
+            .map(|segment| {
+                if let Some(name) = segment.strip_prefix(':') {
+                    Segment::Param(name.to_string())
+                } else if let Some(name) = segment.strip_prefix('*') {
+                    if name.is_empty() {
+                        Segment::Wildcard(WILDCARD.to_string())
+                    } else {
+                        Segment::Wildcard(name.to_string())
+                    }
+                } else {
+                    Segment::Static(percent_decode(segment))
+                }
+            })
+            .collect();
+
+        let wildcard = segments
+            .iter()
+            .position(|segment| matches!(segment, Segment::Wildcard(_)));
+        if let Some(wildcard) = wildcard {
+            assert_eq!(
+                wildcard,
+                segments.len() - 1,
+                "wildcard must be the last segment in the route pattern: {pattern}"
+            );
+        }
+        Self { segments }
+    }
+
+    /// match the path against this pattern, returning the captured path parameters
+    pub fn match_path(&self, path: &str) -> Option<BTreeMap<String, String>> {
+        let mut captured = BTreeMap::new();
+        let mut path_segments = path_segments(path);
+        for segment in self.segments.iter() {
+            match segment {
+                Segment::Static(expected) => {
+                    if percent_decode(path_segments.next()?) != *expected {
+                        return None;
+                    }
+                }
+                Segment::Param(name) => {
+                    captured.insert(name.to_string(), percent_decode(path_segments.next()?));
+                }
+                Segment::Wildcard(name) => {
+                    let rest: Vec<String> = path_segments.by_ref().map(percent_decode).collect();
+                    captured.insert(name.to_string(), rest.join("/"));
+                }
+            }
+        }
+        // the path has more segments than the pattern
+        if path_segments.next().is_some() {
+            return None;
+        }
+        Some(captured)
+    }
+
+    /// match the url, which may contain a query and a fragment, against this pattern
+    pub fn match_url(&self, url: &str) -> Option<Params> {
+        let (path, query, fragment) = split_url(url);
+        let path = self.match_path(path)?;
+        Some(Params {
+            path,
+            query: query.map(parse_query).unwrap_or_default(),
+            fragment: fragment.map(percent_decode),
+        })
+    }
+}
+
+impl Params {
+    /// return the path parameter captured with `name`
+    pub fn param(&self, name: &str) -> Option<&str> {
+        self.path.get(name).map(|v| v.as_str())
+    }
+
+    /// parse the path parameter captured with `name` into the type `T`
+    pub fn parse<T: FromStr>(&self, name: &str) -> Option<T> {
+        self.param(name).and_then(|v| v.parse().ok())
+    }
+
+    /// return the value of the query string `name`
+    pub fn query(&self, name: &str) -> Option<&str> {
+        self.query.get(name).map(|v| v.as_str())
+    }
+
+    /// parse the value of the query string `name` into the type `T`
+    pub fn query_parse<T: FromStr>(&self, name: &str) -> Option<T> {
+        self.query(name).and_then(|v| v.parse().ok())
+    }
+
+    /// return the fragment of the url, the text right after the `#` sign
+    pub fn fragment(&self) -> Option<&str> {
+        self.fragment.as_deref()
+    }
+}
+
+impl<ROUTE> Router<ROUTE> {
+    /// create a router with no routes
+    pub fn new() -> Self {
+        Self { routes: vec![] }
+    }
+
+    /// add a route which matches the path `pattern`.
+    /// The function `f` converts the captured parameters into the route,
+    /// returning `None` will make the router try the next route.
+    pub fn route<F>(mut self, pattern: &str, f: F) -> Self
+    where
+        F: Fn(Params) -> Option<ROUTE> + 'static,
+    {
+        self.routes
+            .push((RoutePattern::new(pattern), Callback::from(f)));
+        self
+    }
+
+    /// return the first route that matches the url
+    pub fn recognize(&self, url: &str) -> Option<ROUTE> {
+        self.routes
+            .iter()
+            .find_map(|(pattern, f)| pattern.match_url(url).and_then(|params| f.emit(params)))
+    }
+}
+
+impl<ROUTE> Default for Router<ROUTE> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<ROUTE> Clone for Router<ROUTE> {
+    fn clone(&self) -> Self {
+        Self {
+            routes: self.routes.clone(),
+        }
+    }
+}
+
+/// the non-empty segments of the path
+fn path_segments(path: &str) -> impl Iterator<Item = &str> {
+    path.split('/').filter(|segment| !segment.is_empty())
+}
+
+/// split the url into its path, query and fragment
+fn split_url(url: &str) -> (&str, Option<&str>, Option<&str>) {
+    let (url, fragment) = match url.split_once('#') {
+        Some((url, fragment)) => (url, Some(fragment)),
+        None => (url, None),
+    };
+    match url.split_once('?') {
+        Some((path, query)) => (path, Some(query), fragment),
+        None => (url, None, fragment),
+    }
+}
+
+/// parse the query string `a=1&b=2` into key value pairs
+fn parse_query(query: &str) -> BTreeMap<String, String> {
+    query
+        .split('&')
+        .filter(|pair| !pair.is_empty())
+        .map(|pair| {
+            let (key, value) = pair.split_once('=').unwrap_or((pair, ""));
+            (
+                percent_decode(&key.replace('+', " ")),
+                percent_decode(&value.replace('+', " ")),
+            )
+        })
+        .collect()
+}
+
+/// decode the percent encoded characters such as `%20`,
+/// invalid sequences are kept as is.
+fn percent_decode(s: &str) -> String {
+    let bytes = s.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        let hex = bytes
+            .get(i + 1..i + 3)
+            .filter(|hex| hex.iter().all(u8::is_ascii_hexdigit))
+            .and_then(|hex| std::str::from_utf8(hex).ok())
+            .and_then(|hex| u8::from_str_radix(hex, 16).ok());
+        match (bytes[i], hex) {
+            (b'%', Some(byte)) => {
+                decoded.push(byte);
+                i += 3;
+            }
+            (byte, _) => {
+                decoded.push(byte);
+                i += 1;
+            }
+        }
+    }
+    String::from_utf8_lossy(&decoded).into_owned()
+}
@@ -0,0 +1,157 @@
+//! Navigation with the browser History API
+use super::Router;
+use crate::dom::{document, dom_node::intern, history, window, Application, Cmd, Program};
+use wasm_bindgen::{closure::Closure, JsCast, JsValue};
+use web_sys::{Element, MouseEvent, Url};
+
+/// return the current url of the browser, the path followed by the query and the fragment
+pub fn current_url() -> String {
+    let location = window().location();
+    let pathname = location.pathname().expect("must have a pathname");
+    let search = location.search().expect("must have a search");
+    let hash = location.hash().expect("must have a hash");
+    format!("{pathname}{search}{hash}")
+}
+
+/// push the url into the browser history and notify the `popstate` listeners,
+/// since the browser doesn't fire `popstate` when calling `pushState` or `replaceState`
+fn navigate(url: &str, replace: bool) {
+    let history = history();
+    if replace {
+        history
+            .replace_state_with_url(&JsValue::NULL, "", Some(url))
+            .expect("must replace state");
+    } else {
+        history
+            .push_state_with_url(&JsValue::NULL, "", Some(url))
+            .expect("must push state");
+    }
+    let popstate = web_sys::Event::new(intern("popstate")).expect("must create popstate event");
+    window()
+        .dispatch_event(&popstate)
+        .expect("must dispatch popstate");
+}
+
+/// resolve the `href` of the link relative to the current location, returning the path, query
+/// and fragment of the url if it points to the same origin as the current page
+fn same_origin_url(href: &str) -> Option<String> {
+    let location = window().location();
+    let url = Url::new_with_base(href, &location.href().ok()?).ok()?;
+    if url.origin() != location.origin().ok()? {
+        return None;
+    }
+    Some(format!("{}{}{}", url.pathname(), url.search(), url.hash()))
+}
+
+/// return the url the link is pointing to, if the click on it can be handled by the router
+fn intercepted_link(event: &MouseEvent) -> Option<String> {
+    let is_modified = event.meta_key() || event.ctrl_key() || event.shift_key() || event.alt_key();
+    // let the browser handle the clicks that opens the link in a new tab or window
+    if event.default_prevented() || event.button() != 0 || is_modified {
+        return None;
+    }
+    let target: Element = event.target()?.dyn_into().ok()?;
+    let link = target.closest(intern("a[href]")).ok()??;
+    if link.has_attribute(intern("download")) {
+        return None;
+    }
+    if let Some(link_target) = link.get_attribute(intern("target")) {
+        if !link_target.is_empty() && link_target != "_self" {
+            return None;
+        }
+    }
+    let url = same_origin_url(&link.get_attribute(intern("href"))?)?;
+    // links that only changes the fragment are left for the browser to scroll into
+    let location = window().location();
+    let current = format!("{}{}", location.pathname().ok()?, location.search().ok()?);
+    match url.split_once('#') {
+        Some((path, _fragment)) if path == current => None,
+        _ => Some(url),
+    }
+}
+
+impl<APP, MSG> Cmd<APP, MSG>
+where
+    MSG: 'static,
+    APP: Application<MSG> + 'static,
+{
+    /// Push the url into the browser history.
+    /// The listener set in [`Program::on_url_change`] is notified of the new url.
+    pub fn push_url(url: impl ToString) -> Self {
+        let url = url.to_string();
+        Cmd::new(move |_program| navigate(&url, false)).no_render()
+    }
+
+    /// Replace the current url in the browser history, without adding a new entry to it.
+    /// The listener set in [`Program::on_url_change`] is notified of the new url.
+    pub fn replace_url(url: impl ToString) -> Self {
+        let url = url.to_string();
+        Cmd::new(move |_program| navigate(&url, true)).no_render()
+    }
+
+    /// Go back to the previous url in the browser history
+    pub fn back() -> Self {
+        Cmd::new(|_program| history().back().expect("must go back")).no_render()
+    }
+
+    /// Go forward to the next url in the browser history
+    pub fn forward() -> Self {
+        Cmd::new(|_program| history().forward().expect("must go forward")).no_render()
+    }
+}
+
+impl<APP, MSG> Program<APP, MSG>
+where
+    MSG: 'static,
+    APP: Application<MSG> + 'static,
+{
+    /// Dispatch the MSG returned by `cb` whenever the url of the browser changes.
+    /// This is triggered by the back and forward buttons of the browser,
+    /// [`Cmd::push_url`], [`Cmd::replace_url`] and the clicks on links intercepted
+    /// with [`Program::intercept_links`].
+    pub fn on_url_change<F>(&self, mut cb: F)
+    where
+        F: FnMut(String) -> MSG + 'static,
+    {
+        let program = Program::downgrade(self);
+        let closure: Closure<dyn FnMut(web_sys::Event)> = Closure::new(move |_| {
+            let msg = cb(current_url());
+            if let Some(mut program) = program.upgrade() {
+                program.dispatch(msg);
+            }
+        });
+        self.add_event_closure(&window(), "popstate", closure)
+            .expect("popstate callback");
+    }
+
+    /// Dispatch the MSG returned by `cb` with the route recognized by the `router`
+    /// whenever the url of the browser changes, `None` is passed when no route matches the url.
+    pub fn on_route_change<ROUTE, F>(&self, router: Router<ROUTE>, mut cb: F)
+    where
+        ROUTE: 'static,
+        F: FnMut(Option<ROUTE>) -> MSG + 'static,
+    {
+        self.on_url_change(move |url| cb(router.recognize(&url)))
+    }
+
+    /// Intercept the clicks on `<a>` elements that links to the same origin, and instead of
+    /// loading the page, the url is pushed into the browser history and the listener set in
+    /// [`Program::on_url_change`] is notified.
+    ///
+    /// Links that are opened in a new tab or window, downloads and links that only changes the
+    /// fragment of the current url are left to the browser.
+    pub fn intercept_links(&self) {
+        let closure: Closure<dyn FnMut(web_sys::Event)> =
+            Closure::new(move |event: web_sys::Event| {
+                let Some(mouse_event) = event.dyn_ref::<MouseEvent>() else {
+                    return;
+                };
+                if let Some(url) = intercepted_link(mouse_event) {
+                    event.prevent_default();
+                    navigate(&url, false);
+                }
+            });
+        self.add_event_closure(&document(), "click", closure)
+            .expect("click callback");
+    }
+}
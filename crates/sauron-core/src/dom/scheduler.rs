@@ -0,0 +1,354 @@
+//! Schedules the updates of the msgs dispatched into a [`Program`] according to their
+//! [`Priority`].
+//!
+//! - [`Priority::UserInput`] msgs are updated in a microtask, right after the event handler.
+//! - [`Priority::Animation`] msgs are updated in the next animation frame.
+//! - [`Priority::Background`] msgs are updated when the browser is idle, for as long as the idle
+//!   period lasts. Their patches are applied in slices of the frame budget, one slice per
+//!   animation frame, such that the browser handles the input in between.
+//!
+//! The msgs with a higher priority which are queued when a lower priority update runs are
+//! updated first.
+//!
+//! The msgs dispatched before their scheduled update runs are coalesced into that update, such
+//! that a burst of msgs is rendered with a single diff and patch of the view.
+use crate::dom::{Application, Cmd, DomBackend, IdleDeadline, Program};
+use std::collections::{BTreeMap, BTreeSet, VecDeque};
+
+/// the time in ms the patches of the background updates are applied in each frame, when it is
+/// not specified
+const DEFAULT_FRAME_BUDGET: f64 = 8.0;
+
+/// The priority of the dispatched msgs, which determines when they are updated into the app
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Priority {
+    /// msgs from the interaction of the user, such as clicks and key presses,
+    /// are updated right away
+    UserInput,
+    /// msgs which changes what is shown in the next frame, such as animations and drag
+    Animation,
+    /// msgs which can wait, such as the results of background work,
+    /// are updated when the browser is idle and yields to input
+    Background,
+}
+
+impl Priority {
+    /// all the priorities, from the highest to the lowest
+    const ALL: [Self; 3] = [Self::UserInput, Self::Animation, Self::Background];
+}
+
+impl Default for Priority {
+    /// the priority of the msgs which are dispatched without a priority,
+    /// `with-ric` defaults to [`Priority::Background`] and `with-raf` to [`Priority::Animation`]
+    fn default() -> Self {
+        if cfg!(feature = "with-ric") {
+            Self::Background
+        } else if cfg!(feature = "with-raf") {
+            Self::Animation
+        } else {
+            Self::UserInput
+        }
+    }
+}
+
+/// The msgs which are waiting to be updated into the app, by priority
+pub(crate) struct Scheduler<MSG> {
+    queues: BTreeMap<Priority, VecDeque<MSG>>,
+    /// the priorities which have an update scheduled
+    scheduled: BTreeSet<Priority>,
+    default_priority: Priority,
+    /// the time in ms the patches of the background updates are applied in each frame
+    frame_budget: f64,
+}
+
+impl<MSG> Default for Scheduler<MSG> {
+    fn default() -> Self {
+        Self {
+            queues: BTreeMap::new(),
+            scheduled: BTreeSet::new(),
+            default_priority: Priority::default(),
+            frame_budget: DEFAULT_FRAME_BUDGET,
+        }
+    }
+}
+
+/// The handles of the requested animation frames and idle callbacks which have not run yet.
+/// The callback removes its own handle when it runs, the rest are cancelled when dropped.
+pub(crate) struct CallbackHandles<H> {
+    next_id: usize,
+    handles: BTreeMap<usize, H>,
+}
+
+impl<H> Default for CallbackHandles<H> {
+    fn default() -> Self {
+        Self {
+            next_id: 0,
+            handles: BTreeMap::new(),
+        }
+    }
+}
+
+impl<H> CallbackHandles<H> {
+    /// the id of the handle of the next requested callback
+    fn next_id(&mut self) -> usize {
+        let id = self.next_id;
+        self.next_id += 1;
+        id
+    }
+
+    fn insert(&mut self, id: usize, handle: H) {
+        self.handles.insert(id, handle);
+    }
+
+    fn remove(&mut self, id: usize) -> Option<H> {
+        self.handles.remove(&id)
+    }
+
+    /// cancel all the callbacks which have not run yet
+    pub(crate) fn clear(&mut self) {
+        self.handles.clear();
+    }
+}
+
+impl<MSG> Scheduler<MSG> {
+    /// the msgs dispatched without a priority
+    pub(crate) fn default_priority(&self) -> Priority {
+        self.default_priority
+    }
+
+    /// remove all the queued msgs
+    pub(crate) fn clear(&mut self) {
+        self.queues.clear();
+        self.scheduled.clear();
+    }
+
+    fn queue(&mut self, priority: Priority) -> &mut VecDeque<MSG> {
+        self.queues.entry(priority).or_default()
+    }
+}
+
+impl<APP, MSG, DOM> Program<APP, MSG, DOM>
+where
+    MSG: 'static,
+    APP: Application<MSG> + 'static,
+    DOM: DomBackend,
+{
+    /// dispatch the msgs with the priority, instead of the default priority
+    pub fn dispatch_multiple_with_priority(
+        &mut self,
+        msgs: impl IntoIterator<Item = MSG>,
+        priority: Priority,
+    ) {
+        if self.is_unmounted() {
+            log::warn!("the msgs are ignored, since the program is unmounted");
+            return;
+        }
+        self.scheduler.borrow_mut().queue(priority).extend(msgs);
+        self.schedule_dispatch(priority);
+    }
+
+    /// dispatch the msg with the priority, instead of the default priority
+    pub fn dispatch_with_priority(&mut self, msg: MSG, priority: Priority) {
+        self.dispatch_multiple_with_priority([msg], priority)
+    }
+
+    /// Update the msg into the app and patch the DOM right away, instead of in the scheduled
+    /// update. The msgs which are waiting for the scheduled update of the user input are
+    /// updated along with it.
+    ///
+    /// This is useful when the DOM must reflect the msg before the event handler returns,
+    /// such as to focus an element which is created by the msg.
+    pub fn dispatch_sync(&mut self, msg: MSG) {
+        if self.is_unmounted() {
+            log::warn!("the msg is ignored, since the program is unmounted");
+            return;
+        }
+        self.scheduler
+            .borrow_mut()
+            .queue(Priority::UserInput)
+            .push_back(msg);
+        self.dispatch_inner(Priority::UserInput, None);
+    }
+
+    /// set the priority of the msgs which are dispatched without a priority, such as the msgs
+    /// of the event listeners in the view
+    pub fn set_default_priority(&self, priority: Priority) {
+        self.scheduler.borrow_mut().default_priority = priority;
+    }
+
+    /// set the time in ms the patches of the background updates are applied in each frame,
+    /// the default is 8ms
+    pub fn set_frame_budget(&self, frame_budget: f64) {
+        self.scheduler.borrow_mut().frame_budget = frame_budget;
+    }
+
+    /// schedule the update of the msgs of the priority, unless it is already scheduled
+    fn schedule_dispatch(&self, priority: Priority) {
+        if !self.scheduler.borrow_mut().scheduled.insert(priority) {
+            return;
+        }
+        let program = Program::downgrade(self);
+        let id = self.callback_handles.borrow_mut().next_id();
+        let scheduled = match priority {
+            Priority::UserInput => {
+                self.backend.spawn_local(Box::pin(async move {
+                    if let Some(mut program) = program.upgrade() {
+                        program.run_scheduled(priority, None);
+                    }
+                }));
+                Ok(())
+            }
+            Priority::Animation => self
+                .backend
+                .request_animation_frame(Box::new(move || {
+                    if let Some(mut program) = program.upgrade() {
+                        program.callback_handles.borrow_mut().remove(id);
+                        program.run_scheduled(priority, None);
+                    }
+                }))
+                .map(|handle| self.callback_handles.borrow_mut().insert(id, handle)),
+            Priority::Background => self
+                .backend
+                .request_idle_callback(Box::new(move |deadline| {
+                    if let Some(mut program) = program.upgrade() {
+                        program.callback_handles.borrow_mut().remove(id);
+                        program.run_scheduled(priority, deadline);
+                    }
+                }))
+                .map(|handle| self.callback_handles.borrow_mut().insert(id, handle)),
+        };
+        if let Err(error) = scheduled {
+            self.scheduler.borrow_mut().scheduled.remove(&priority);
+            self.clone().handle_error(error);
+        }
+    }
+
+    /// run the scheduled update of the priority, the msgs dispatched from here on are
+    /// updated in the next scheduled update
+    fn run_scheduled(&mut self, priority: Priority, deadline: Option<IdleDeadline>) {
+        self.scheduler.borrow_mut().scheduled.remove(&priority);
+        self.dispatch_inner(priority, deadline);
+    }
+
+    /// update the queued msgs of the priority and of the higher priorities into the app.
+    /// The background msgs are updated until the idle period ends, the rest are updated in the
+    /// next idle period.
+    /// Returns the number of msgs which are updated.
+    pub(crate) fn dispatch_scheduled_msgs(
+        &mut self,
+        priority: Priority,
+        mut deadline: Option<IdleDeadline>,
+    ) -> usize {
+        let mut msg_count = 0;
+        for current in Priority::ALL.into_iter().filter(|p| *p <= priority) {
+            let msgs = std::mem::take(self.scheduler.borrow_mut().queue(current));
+            msg_count += msgs.len();
+            self.app_context.push_msgs(msgs);
+            let deadline = match current {
+                Priority::Background => deadline.take(),
+                _ => None,
+            };
+            if let Err(error) = self.dispatch_pending_msgs(deadline) {
+                self.handle_error(error);
+            }
+            if self.app_context.has_pending_msgs() {
+                let remaining: Vec<MSG> = self
+                    .app_context
+                    .pending_msgs
+                    .borrow_mut()
+                    .drain(..)
+                    .collect();
+                msg_count -= remaining.len();
+                let mut scheduler = self.scheduler.borrow_mut();
+                let queue = scheduler.queue(current);
+                for msg in remaining.into_iter().rev() {
+                    queue.push_front(msg);
+                }
+                drop(scheduler);
+                self.schedule_dispatch(current);
+            }
+        }
+        msg_count
+    }
+
+    /// the timestamp until which the patches of the priority are applied in this frame,
+    /// `None` if all the patches are applied right away
+    pub(crate) fn patch_deadline(&self, priority: Priority) -> Option<f64> {
+        match priority {
+            Priority::Background => Some(self.backend.now() + self.scheduler.borrow().frame_budget),
+            Priority::UserInput | Priority::Animation => None,
+        }
+    }
+
+    /// apply the pending patches in slices of the frame budget, one slice in each animation
+    /// frame, then finish the dispatch with the cmd once all the patches are applied
+    pub(crate) fn apply_pending_patches_in_frames(&mut self, cmd: Cmd<APP, MSG>) {
+        let program = Program::downgrade(self);
+        let id = self.callback_handles.borrow_mut().next_id();
+        let handle = self.backend.request_animation_frame(Box::new(move || {
+            let Some(mut program) = program.upgrade() else {
+                return;
+            };
+            program.callback_handles.borrow_mut().remove(id);
+            let until = program.patch_deadline(Priority::Background);
+            if let Err(error) = program.apply_pending_patches_until(until) {
+                program.handle_error(error);
+                return;
+            }
+            if program.pending_patches.borrow().is_empty() {
+                program.finish_dispatch(cmd);
+            } else {
+                program.apply_pending_patches_in_frames(cmd);
+            }
+        }));
+        match handle {
+            Ok(handle) => self.callback_handles.borrow_mut().insert(id, handle),
+            Err(error) => self.handle_error(error),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{
+        dom::{MemoryDom, MountAction, MountTarget},
+        html::*,
+        vdom::Node,
+    };
+
+    struct Counter(usize);
+
+    impl Application<usize> for Counter {
+        fn update(&mut self, msg: usize) -> Cmd<Self, usize> {
+            self.0 += msg;
+            Cmd::none()
+        }
+
+        fn view(&self) -> Node<usize> {
+            div([], [text(self.0)])
+        }
+    }
+
+    #[test]
+    fn handles_are_removed_when_the_callbacks_run() {
+        let dom = MemoryDom::new();
+        let mut program = Program::with_backend(
+            Counter(0),
+            dom.clone(),
+            &dom.body(),
+            MountAction::Append,
+            MountTarget::MountNode,
+        );
+        program.mount().expect("must mount");
+        dom.settle();
+        for _ in 0..10 {
+            program.dispatch_with_priority(1, Priority::Animation);
+            program.dispatch_with_priority(1, Priority::Background);
+            assert!(!program.callback_handles.borrow().handles.is_empty());
+            dom.settle();
+        }
+        assert_eq!(program.app().0, 20);
+        assert!(program.callback_handles.borrow().handles.is_empty());
+    }
+}
@@ -0,0 +1,467 @@
+//! Subscriptions to the long-lived event sources, such as intervals and window events.
+//!
+//! The program calls [`Application::subscriptions`](crate::dom::Application::subscriptions)
+//! after every update and compares it with the running subscriptions by their key.
+//! The new subscriptions are started, the ones which are no longer returned are stopped and
+//! the listeners are detached, while the ones with the same key keep running.
+//! The subscriptions to the same source are told apart by their order, a source of the same key
+//! which emits a different type of payload is restarted.
+//! The sources which fail to start are passed to
+//! [`Application::on_error`](crate::dom::Application::on_error).
+//!
+//! # Example
+//! ```rust,no_run
+//! use sauron::{dom::Sub, *};
+//!
+//! enum Msg {
+//!     Tick,
+//!     Resized(i32, i32),
+//! }
+//!
+//! struct Clock {
+//!     running: bool,
+//! }
+//!
+//! impl Application<Msg> for Clock {
+//!     fn update(&mut self, _msg: Msg) -> Cmd<Self, Msg> {
+//!         Cmd::none()
+//!     }
+//!
+//!     fn view(&self) -> Node<Msg> {
+//!         text("clock")
+//!     }
+//!
+//!     fn subscriptions(&self) -> Sub<Msg> {
+//!         Sub::batch([
+//!             Sub::on_resize(Msg::Resized),
+//!             if self.running {
+//!                 Sub::interval(1000, || Msg::Tick)
+//!             } else {
+//!                 Sub::none()
+//!             },
+//!         ])
+//!     }
+//! }
+//! ```
+use crate::dom::{dom_node::intern, util, window, AbortHandle, Event};
+use crate::Error;
+use futures::{Stream, StreamExt};
+use std::{
+    any::{Any, TypeId},
+    cell::RefCell,
+    collections::BTreeMap,
+    rc::Rc,
+};
+use wasm_bindgen::{closure::Closure, JsCast};
+
+/// the value emitted by the source of a subscription
+type Payload = Box<dyn Any>;
+
+/// maps the payload of the source into a msg
+type MapPayload<MSG> = Rc<dyn Fn(Payload) -> MSG>;
+
+/// passes the payload of the source into the program
+type Emit = Rc<dyn Fn(Payload)>;
+
+/// stops the source when dropped
+type Guard = Box<dyn Any>;
+
+/// A set of subscriptions to long-lived event sources.
+/// Each subscription is identified by its key, which is derived from the source.
+pub struct Sub<MSG> {
+    subscriptions: Vec<Subscription<MSG>>,
+}
+
+struct Subscription<MSG> {
+    key: String,
+    /// the type of the payloads emitted by the source
+    payload: TypeId,
+    /// starts the source, passing the payloads into the emit function.
+    /// The source is stopped when the returned guard is dropped.
+    start: Box<dyn FnOnce(Emit) -> Result<Guard, Error>>,
+    map: MapPayload<MSG>,
+}
+
+/// The subscriptions which are currently running, keyed by their key, the type of their payload
+/// and the order among the subscriptions with the same key and payload
+pub(crate) struct ActiveSubs<MSG> {
+    running: BTreeMap<(String, TypeId, usize), Running<MSG>>,
+}
+
+struct Running<MSG> {
+    /// the map is replaced with the one from the latest subscriptions
+    map: Rc<RefCell<MapPayload<MSG>>>,
+    /// `None` if the source failed to start, it is not started again while it is subscribed
+    _guard: Option<Guard>,
+}
+
+/// removes the event listener when dropped
+struct ListenerGuard {
+    target: web_sys::EventTarget,
+    event_name: &'static str,
+    closure: Closure<dyn FnMut(web_sys::Event)>,
+}
+
+/// clears the interval when dropped
+struct IntervalGuard {
+    handle: i32,
+    _closure: Closure<dyn FnMut()>,
+}
+
+/// aborts the task when dropped
+struct AbortGuard(AbortHandle);
+
+impl<MSG> Sub<MSG>
+where
+    MSG: 'static,
+{
+    /// no subscriptions
+    pub fn none() -> Self {
+        Self {
+            subscriptions: vec![],
+        }
+    }
+
+    /// combine the subscriptions into one.
+    /// The subscriptions with the same key are told apart by their order in the batch.
+    pub fn batch(subs: impl IntoIterator<Item = Self>) -> Self {
+        Self {
+            subscriptions: subs.into_iter().flat_map(|sub| sub.subscriptions).collect(),
+        }
+    }
+
+    /// create a subscription from a source identified with the key
+    fn new<T, S, F>(key: String, start: S, f: F) -> Self
+    where
+        T: 'static,
+        S: FnOnce(Emit) -> Result<Guard, Error> + 'static,
+        F: Fn(T) -> MSG + 'static,
+    {
+        Self {
+            subscriptions: vec![Subscription {
+                key,
+                payload: TypeId::of::<T>(),
+                start: Box::new(start),
+                map: Rc::new(move |payload: Payload| {
+                    // the running source is only kept for the subscription of the same payload type
+                    f(*payload
+                        .downcast::<T>()
+                        .expect("must be the payload of the source"))
+                }),
+            }],
+        }
+    }
+
+    /// emits the msg every `interval` milliseconds
+    pub fn interval<F>(interval: i32, f: F) -> Self
+    where
+        F: Fn() -> MSG + 'static,
+    {
+        Self::new(
+            format!("interval:{interval}"),
+            move |emit| {
+                let closure: Closure<dyn FnMut()> = Closure::new(move || emit(Box::new(())));
+                let handle = window().set_interval_with_callback_and_timeout_and_arguments_0(
+                    closure.as_ref().unchecked_ref(),
+                    interval,
+                )?;
+                Ok(Box::new(IntervalGuard {
+                    handle,
+                    _closure: closure,
+                }))
+            },
+            move |()| f(),
+        )
+    }
+
+    /// emits the msg when the event is dispatched to the window
+    pub fn window_event<F>(event_name: &'static str, f: F) -> Self
+    where
+        F: Fn(Event) -> MSG + 'static,
+    {
+        Self::new(
+            format!("window:{event_name}"),
+            move |emit| {
+                Ok(Box::new(ListenerGuard::new(
+                    &window(),
+                    event_name,
+                    move |event| emit(Box::new(event)),
+                )?))
+            },
+            move |event: web_sys::Event| f(Event::from(event)),
+        )
+    }
+
+    /// emits the msg with the width and height of the window when it is resized
+    pub fn on_resize<F>(f: F) -> Self
+    where
+        F: Fn(i32, i32) -> MSG + 'static,
+    {
+        Self::new(
+            "resize".to_string(),
+            move |emit| {
+                Ok(Box::new(ListenerGuard::new(
+                    &window(),
+                    "resize",
+                    move |_| emit(Box::new(util::get_window_size())),
+                )?))
+            },
+            move |(width, height)| f(width, height),
+        )
+    }
+
+    /// emits the msg with the hash of the location when it is changed
+    pub fn on_hashchange<F>(f: F) -> Self
+    where
+        F: Fn(String) -> MSG + 'static,
+    {
+        Self::new(
+            "hashchange".to_string(),
+            move |emit| {
+                Ok(Box::new(ListenerGuard::new(
+                    &window(),
+                    "hashchange",
+                    move |_| emit(Box::new(util::get_location_hash())),
+                )?))
+            },
+            f,
+        )
+    }
+
+    /// emits the msg with whether the media query matches, when it is subscribed and each time
+    /// it changes
+    pub fn media_query<F>(query: &str, f: F) -> Self
+    where
+        F: Fn(bool) -> MSG + 'static,
+    {
+        let query = query.to_string();
+        Self::new(
+            format!("media:{query}"),
+            move |emit| {
+                let media_query = window()
+                    .match_media(&query)?
+                    .ok_or_else(|| Error::Js(format!("not a valid media query: {query}")))?;
+                emit(Box::new(media_query.matches()));
+                let list = media_query.clone();
+                Ok(Box::new(ListenerGuard::new(
+                    &media_query,
+                    "change",
+                    move |_| emit(Box::new(list.matches())),
+                )?))
+            },
+            f,
+        )
+    }
+
+    /// emits the msg with whether the document is visible each time the visibility changes
+    pub fn on_visibility_change<F>(f: F) -> Self
+    where
+        F: Fn(bool) -> MSG + 'static,
+    {
+        Self::new(
+            "visibility".to_string(),
+            move |emit| {
+                let document = util::document();
+                let target = document.clone();
+                Ok(Box::new(ListenerGuard::new(
+                    &target,
+                    "visibilitychange",
+                    move |_| emit(Box::new(!document.hidden())),
+                )?))
+            },
+            f,
+        )
+    }
+
+    /// emits the msg with whether the browser is online each time it goes online or offline
+    pub fn on_online_change<F>(f: F) -> Self
+    where
+        F: Fn(bool) -> MSG + 'static,
+    {
+        Self::new(
+            "online".to_string(),
+            move |emit| {
+                let emit_offline = Rc::clone(&emit);
+                Ok(Box::new([
+                    ListenerGuard::new(&window(), "online", move |_| emit(Box::new(true)))?,
+                    ListenerGuard::new(&window(), "offline", move |_| {
+                        emit_offline(Box::new(false))
+                    })?,
+                ]))
+            },
+            f,
+        )
+    }
+
+    /// emits the msg for each item of the stream, the stream is created with `create` when it is
+    /// subscribed and is dropped when it is no longer in the subscriptions
+    pub fn stream<T, S, C, F>(key: &str, create: C, f: F) -> Self
+    where
+        T: 'static,
+        S: Stream<Item = T> + 'static,
+        C: FnOnce() -> S + 'static,
+        F: Fn(T) -> MSG + 'static,
+    {
+        Self::new(
+            format!("stream:{key}"),
+            move |emit| {
+                let handle = AbortHandle::default();
+                let stream = create();
+                let task = handle.wrap(async move {
+                    futures::pin_mut!(stream);
+                    while let Some(item) = stream.next().await {
+                        emit(Box::new(item));
+                    }
+                });
+                util::spawn_local(async move {
+                    task.await;
+                });
+                Ok(Box::new(AbortGuard(handle)))
+            },
+            f,
+        )
+    }
+
+    /// map the msg of this subscription such that `Sub<MSG>` becomes `Sub<MSG2>`
+    pub fn map_msg<F, MSG2>(self, f: F) -> Sub<MSG2>
+    where
+        F: Fn(MSG) -> MSG2 + 'static,
+        MSG2: 'static,
+    {
+        let f = Rc::new(f);
+        Sub {
+            subscriptions: self
+                .subscriptions
+                .into_iter()
+                .map(|subscription| {
+                    let f = Rc::clone(&f);
+                    let map = subscription.map;
+                    Subscription {
+                        key: subscription.key,
+                        payload: subscription.payload,
+                        start: subscription.start,
+                        map: Rc::new(move |payload| f(map(payload))),
+                    }
+                })
+                .collect(),
+        }
+    }
+
+    /// returns true if there are no subscriptions
+    pub fn is_empty(&self) -> bool {
+        self.subscriptions.is_empty()
+    }
+
+    /// the keys of the subscriptions
+    pub fn keys(&self) -> impl Iterator<Item = &str> {
+        self.subscriptions.iter().map(|sub| sub.key.as_str())
+    }
+}
+
+impl<MSG> Default for Sub<MSG>
+where
+    MSG: 'static,
+{
+    fn default() -> Self {
+        Self::none()
+    }
+}
+
+impl<MSG> ActiveSubs<MSG>
+where
+    MSG: 'static,
+{
+    pub(crate) fn new() -> Self {
+        Self {
+            running: BTreeMap::new(),
+        }
+    }
+
+    /// start the subscriptions which are not yet running and stop the ones which are no
+    /// longer in `sub`. The msgs of the subscriptions are passed into `dispatch`.
+    /// Returns the errors of the sources which failed to start.
+    pub(crate) fn update(&mut self, sub: Sub<MSG>, dispatch: Rc<dyn Fn(MSG)>) -> Vec<Error> {
+        let mut running = BTreeMap::new();
+        let mut occurrences: BTreeMap<(String, TypeId), usize> = BTreeMap::new();
+        let mut errors = vec![];
+        for subscription in sub.subscriptions {
+            let occurrence = occurrences
+                .entry((subscription.key.clone(), subscription.payload))
+                .or_default();
+            let key = (subscription.key, subscription.payload, *occurrence);
+            *occurrence += 1;
+            let active = match self.running.remove(&key) {
+                Some(active) => {
+                    *active.map.borrow_mut() = subscription.map;
+                    active
+                }
+                None => {
+                    let map = Rc::new(RefCell::new(subscription.map));
+                    let emit_map = Rc::clone(&map);
+                    let dispatch = Rc::clone(&dispatch);
+                    let started = (subscription.start)(Rc::new(move |payload| {
+                        let map = Rc::clone(&emit_map.borrow());
+                        dispatch(map(payload))
+                    }));
+                    let guard = match started {
+                        Ok(guard) => Some(guard),
+                        Err(error) => {
+                            errors.push(error);
+                            None
+                        }
+                    };
+                    Running { map, _guard: guard }
+                }
+            };
+            running.insert(key, active);
+        }
+        // the subscriptions which are left are stopped when dropped
+        self.running = running;
+        errors
+    }
+}
+
+impl ListenerGuard {
+    fn new(
+        target: &web_sys::EventTarget,
+        event_name: &'static str,
+        f: impl FnMut(web_sys::Event) + 'static,
+    ) -> Result<Self, Error> {
+        let closure: Closure<dyn FnMut(web_sys::Event)> = Closure::new(f);
+        target.add_event_listener_with_callback(
+            intern(event_name),
+            closure.as_ref().unchecked_ref(),
+        )?;
+        Ok(Self {
+            target: target.clone(),
+            event_name,
+            closure,
+        })
+    }
+}
+
+impl Drop for ListenerGuard {
+    fn drop(&mut self) {
+        if let Err(error) = self.target.remove_event_listener_with_callback(
+            intern(self.event_name),
+            self.closure.as_ref().unchecked_ref(),
+        ) {
+            log::warn!(
+                "unable to remove the {} listener of the subscription: {error:?}",
+                self.event_name
+            );
+        }
+    }
+}
+
+impl Drop for IntervalGuard {
+    fn drop(&mut self) {
+        window().clear_interval_with_handle(self.handle);
+    }
+}
+
+impl Drop for AbortGuard {
+    fn drop(&mut self) {
+        self.0.abort();
+    }
+}
@@ -1,5 +1,8 @@
-use std::future::Future;
+use std::cell::{Cell, RefCell};
+use std::future::{Future, IntoFuture};
 use std::pin::Pin;
+use std::rc::Rc;
+use std::task::{Context, Poll, Waker};
 
 /// Task is used to do asynchronous operations
 pub struct Task<MSG> {
@@ -32,6 +35,15 @@ where
     }
 }
 
+impl<MSG> IntoFuture for Task<MSG> {
+    type Output = MSG;
+    type IntoFuture = Pin<Box<dyn Future<Output = MSG>>>;
+
+    fn into_future(self) -> Self::IntoFuture {
+        self.task
+    }
+}
+
 impl<F, MSG> From<F> for Task<MSG>
 where
     F: Future<Output = MSG> + 'static,
@@ -41,3 +53,68 @@ where
         Task::new(f)
     }
 }
+
+/// A handle to abort the in-flight asynchronous operations, such as the commands of a
+/// [`Cmd`](crate::dom::Cmd) created with `Cmd::abortable` and http requests.
+///
+/// The operations that are not yet started are skipped, the in-flight ones are dropped
+/// and their msgs are not dispatched.
+#[derive(Debug, Clone, Default)]
+pub struct AbortHandle {
+    inner: Rc<AbortState>,
+}
+
+#[derive(Debug, Default)]
+struct AbortState {
+    is_aborted: Cell<bool>,
+    wakers: RefCell<Vec<Waker>>,
+}
+
+/// A future which resolves to `None` right away when the handle is aborted
+pub(crate) struct Abortable<T> {
+    future: Pin<Box<dyn Future<Output = T>>>,
+    handle: AbortHandle,
+}
+
+impl AbortHandle {
+    /// abort the operations
+    pub fn abort(&self) {
+        self.inner.is_aborted.set(true);
+        for waker in self.inner.wakers.borrow_mut().drain(..) {
+            waker.wake();
+        }
+    }
+
+    /// returns true if the operations has been aborted
+    pub fn is_aborted(&self) -> bool {
+        self.inner.is_aborted.get()
+    }
+
+    /// make the future abortable with this handle
+    pub(crate) fn wrap<T>(&self, future: impl Future<Output = T> + 'static) -> Abortable<T> {
+        Abortable {
+            future: Box::pin(future),
+            handle: self.clone(),
+        }
+    }
+}
+
+impl<T> Future for Abortable<T> {
+    type Output = Option<T>;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<T>> {
+        if self.handle.is_aborted() {
+            return Poll::Ready(None);
+        }
+        match self.future.as_mut().poll(cx) {
+            Poll::Ready(output) => Poll::Ready(Some(output)),
+            Poll::Pending => {
+                let mut wakers = self.handle.inner.wakers.borrow_mut();
+                if !wakers.iter().any(|waker| waker.will_wake(cx.waker())) {
+                    wakers.push(cx.waker().clone());
+                }
+                Poll::Pending
+            }
+        }
+    }
+}
@@ -0,0 +1,455 @@
+//! A headless program which runs the APP without a browser, used for testing the APP with
+//! plain `cargo test`.
+//!
+//! # Example
+//! ```rust
+//! use sauron::{dom::TestProgram, html::events::on_synthetic, *};
+//!
+//! #[derive(Debug, Clone, PartialEq)]
+//! enum Msg {
+//!     Increment,
+//! }
+//!
+//! #[derive(Default)]
+//! struct Counter {
+//!     count: i32,
+//! }
+//!
+//! impl Application<Msg> for Counter {
+//!     fn update(&mut self, msg: Msg) -> Cmd<Self, Msg> {
+//!         match msg {
+//!             Msg::Increment => self.count += 1,
+//!         }
+//!         Cmd::none()
+//!     }
+//!
+//!     fn view(&self) -> Node<Msg> {
+//!         div(
+//!             [],
+//!             [
+//!                 span([class("count")], [text(self.count)]),
+//!                 button([on_synthetic("click", |_| Msg::Increment)], [text("+")]),
+//!             ],
+//!         )
+//!     }
+//! }
+//!
+//! let mut program = TestProgram::new(Counter::default());
+//! program.click("button");
+//! assert_eq!(program.text_content(".count").as_deref(), Some("1"));
+//! assert_eq!(program.dispatched_msgs(), [Msg::Increment]);
+//! ```
+#[cfg(feature = "with-time-travel")]
+use crate::dom::Session;
+use crate::{
+    dom::{events::SyntheticEvent, headless::HeadlessRuntime, Application, Event},
+    html::attributes::{merge_plain_attributes_values, AttributeValue},
+    render::Render,
+    vdom::{self, Leaf, Patch},
+};
+
+/// Runs the `init`, `update` and `view` of the APP without a browser.
+///
+/// The msgs are dispatched into the APP, the [`Cmd`]s returned by the APP are executed and
+/// the [`Task`]s are run to completion with a local executor by calling [`TestProgram::settle`].
+/// The events are fired into the listeners of the view as [`SyntheticEvent`]s.
+///
+/// Since there is no browser:
+/// - the commands created with [`Cmd::new`], which operate on the [`Program`], are skipped
+///   and counted in [`TestProgram::skipped_commands`].
+/// - the tasks must not use the browser api, such as `delay`, use a
+///   [`MockTransport`] for the http requests.
+/// - only the listeners made with [`on_synthetic`] or `on` are called, they can only read the
+///   values set in the [`SyntheticEvent`]. The listeners of the web events such as `on_click`,
+///   `on_input` and `on_mount` are skipped.
+///
+/// [`Cmd`]: crate::dom::Cmd
+/// [`Cmd::new`]: crate::dom::Cmd::new
+/// [`Task`]: crate::dom::Task
+/// [`Program`]: crate::dom::Program
+/// [`MockTransport`]: crate::dom::MockTransport
+/// [`on_synthetic`]: crate::html::events::on_synthetic
+pub struct TestProgram<APP, MSG>
+where
+    MSG: 'static,
+{
+    runtime: HeadlessRuntime<APP, MSG>,
+    /// the msgs that were updated into the APP
+    dispatched_msgs: Vec<MSG>,
+}
+
+/// a simple selector of an element, such as `button.primary#save[type=submit]`
+#[derive(Debug, Default)]
+pub(crate) struct SimpleSelector {
+    tag: Option<String>,
+    id: Option<String>,
+    classes: Vec<String>,
+    attributes: Vec<(String, Option<String>)>,
+}
+
+impl<APP, MSG> TestProgram<APP, MSG>
+where
+    MSG: Clone + 'static,
+    APP: Application<MSG> + 'static,
+{
+    /// create a test program of the APP, the `init` of the APP is called and its commands are
+    /// run until there is nothing to do
+    pub fn new(mut app: APP) -> Self {
+        let cmd = app.init();
+        let mut program = Self::with_app(app);
+        program.runtime.emit(cmd);
+        program.settle();
+        program
+    }
+
+    /// create a test program of the APP, then update the APP with the msgs of the recorded
+    /// session. The `init` of the APP is called, but the commands of the `init` and of the
+    /// recorded msgs are not executed, since the msgs they dispatched are in the session.
+    #[cfg(feature = "with-time-travel")]
+    pub fn replay(mut app: APP, session: &Session<MSG>) -> Self {
+        let _cmd = app.init();
+        let mut program = Self::with_app(app);
+        for recorded in &session.msgs {
+            program.dispatched_msgs.push(recorded.msg.clone());
+            let _cmd = program.runtime.update(recorded.msg.clone());
+        }
+        program
+    }
+
+    fn with_app(app: APP) -> Self {
+        Self {
+            runtime: HeadlessRuntime::new(app),
+            dispatched_msgs: vec![],
+        }
+    }
+
+    /// the APP of this program
+    pub fn app(&self) -> &APP {
+        self.runtime.app()
+    }
+
+    /// the current view of the APP
+    pub fn view(&self) -> &vdom::Node<MSG> {
+        self.runtime.view()
+    }
+
+    /// render the current view of the APP into html
+    pub fn html(&self) -> String {
+        self.view().render_to_string()
+    }
+
+    /// the msgs that were dispatched into the APP, in the order they were updated
+    pub fn dispatched_msgs(&self) -> &[MSG] {
+        &self.dispatched_msgs
+    }
+
+    /// the number of commands which were skipped since they operate on the `Program`
+    pub fn skipped_commands(&self) -> usize {
+        self.runtime.skipped_commands()
+    }
+
+    /// the patches from the view before the last update to the current view
+    pub fn patches(&self) -> Vec<Patch<'_, MSG>> {
+        match self.runtime.previous_view() {
+            Some(previous_vdom) => vdom::diff(previous_vdom, self.view()),
+            None => vec![],
+        }
+    }
+
+    /// dispatch the msg into the APP, then run the commands until there is nothing to do
+    pub fn dispatch(&mut self, msg: MSG) {
+        self.dispatch_multiple([msg]);
+    }
+
+    /// dispatch the msgs into the APP, then run the commands until there is nothing to do
+    pub fn dispatch_multiple(&mut self, msgs: impl IntoIterator<Item = MSG>) {
+        self.runtime.push_msgs(msgs);
+        self.settle();
+    }
+
+    /// run the tasks and update the APP with the msgs they dispatched until there are no
+    /// more msgs and the remaining tasks are waiting for something that can not happen here
+    pub fn settle(&mut self) {
+        while let Some(msg) = self.runtime.next_msg() {
+            self.dispatched_msgs.push(msg.clone());
+            self.runtime.update_and_emit(msg);
+        }
+    }
+
+    /// returns the first element in the view which matches the selector.
+    /// The selector is a list of simple selectors separated by whitespace, matching the
+    /// descendants of the elements matched by the previous selector.
+    /// A simple selector consists of a tag, `#id`, `.class`, `[attribute]` and
+    /// `[attribute=value]`, such as `ul.todo-list li.completed input[type=checkbox]`
+    pub fn find(&self, selector: &str) -> Option<&vdom::Node<MSG>> {
+        self.find_path(selector)
+            .and_then(|path| node_at(self.view(), &path))
+    }
+
+    /// returns all the elements in the view which matches the selector
+    pub fn find_all(&self, selector: &str) -> Vec<&vdom::Node<MSG>> {
+        let selectors = parse_selector(selector);
+        let mut found = vec![];
+        visit_elements(self.view(), &mut vec![], &mut |ancestors, _| {
+            if matches_selector(&selectors, ancestors) {
+                found.push(*ancestors.last().expect("must have the element"));
+            }
+        });
+        found
+    }
+
+    /// returns the innermost element whose text content is the text, ignoring the whitespace
+    /// around it
+    pub fn find_by_text(&self, text: &str) -> Option<&vdom::Node<MSG>> {
+        find_by_text(self.view(), text.trim())
+    }
+
+    /// returns the text content of the first element which matches the selector
+    pub fn text_content(&self, selector: &str) -> Option<String> {
+        self.find(selector).map(text_content)
+    }
+
+    /// fire a click event to the first element which matches the selector
+    pub fn click(&mut self, selector: &str) {
+        self.fire_event(selector, "click", SyntheticEvent::default());
+    }
+
+    /// set the value and fire an input event to the first element which matches the selector
+    pub fn input(&mut self, selector: &str, value: impl ToString) {
+        self.fire_event(selector, "input", SyntheticEvent::with_value(value));
+    }
+
+    /// set the checked state and fire an input event to the first element which matches the
+    /// selector
+    pub fn check(&mut self, selector: &str, checked: bool) {
+        self.fire_event(selector, "input", SyntheticEvent::with_checked(checked));
+    }
+
+    /// fire the event to the first element which matches the selector, the event bubbles up
+    /// to the listeners of its ancestors, until a listener with the `stop_propagation` option.
+    /// The msgs from the listeners are then dispatched into the APP.
+    ///
+    /// # Panics
+    /// Panics if there is no element which matches the selector
+    pub fn fire_event(&mut self, selector: &str, event_name: &str, event: SyntheticEvent) {
+        let path = self
+            .find_path(selector)
+            .unwrap_or_else(|| panic!("no element matches the selector: {selector:?}"));
+        let mut msgs: Vec<MSG> = vec![];
+        let ancestors = (0..=path.len())
+            .rev()
+            .filter_map(|depth| node_at(self.view(), &path[..depth]))
+            .filter_map(|node| node.attributes());
+        for attributes in ancestors {
+            let listeners: Vec<_> = attributes
+                .iter()
+                .filter(|attr| *attr.name() == event_name)
+                .flat_map(|attr| attr.value())
+                .filter_map(AttributeValue::as_event_listener)
+                .collect();
+            for listener in listeners.iter() {
+                match listener.emit_event(Event::from(event.clone())) {
+                    Some(msg) => msgs.push(msg),
+                    None => log::warn!(
+                        "skipping the listener of {event_name}, it can only be called with a web event"
+                    ),
+                }
+            }
+            if listeners
+                .iter()
+                .any(|listener| listener.options().stop_propagation)
+            {
+                break;
+            }
+        }
+        self.dispatch_multiple(msgs);
+    }
+
+    fn find_path(&self, selector: &str) -> Option<Vec<usize>> {
+        let selectors = parse_selector(selector);
+        let mut found = None;
+        visit_elements(self.view(), &mut vec![], &mut |ancestors, path| {
+            if found.is_none() && matches_selector(&selectors, ancestors) {
+                found = Some(path.to_vec());
+            }
+        });
+        found
+    }
+}
+
+/// the nodes inside an element, fragment or node list
+fn child_nodes<MSG>(node: &vdom::Node<MSG>) -> &[vdom::Node<MSG>] {
+    match node {
+        vdom::Node::Element(element) => element.children(),
+        vdom::Node::Fragment(nodes) | vdom::Node::NodeList(nodes) => nodes,
+        vdom::Node::Leaf(_) => &[],
+    }
+}
+
+fn node_at<'a, MSG>(node: &'a vdom::Node<MSG>, path: &[usize]) -> Option<&'a vdom::Node<MSG>> {
+    match path.split_first() {
+        Some((idx, rest)) => node_at(child_nodes(node).get(*idx)?, rest),
+        None => Some(node),
+    }
+}
+
+/// visit the elements in document order, with the element and its ancestor elements and the
+/// path of the element
+fn visit_elements<'a, MSG>(
+    node: &'a vdom::Node<MSG>,
+    ancestors: &mut Vec<&'a vdom::Node<MSG>>,
+    f: &mut impl FnMut(&[&'a vdom::Node<MSG>], &[usize]),
+) {
+    fn visit<'a, MSG>(
+        node: &'a vdom::Node<MSG>,
+        ancestors: &mut Vec<&'a vdom::Node<MSG>>,
+        path: &mut Vec<usize>,
+        f: &mut impl FnMut(&[&'a vdom::Node<MSG>], &[usize]),
+    ) {
+        let is_element = node.is_element();
+        if is_element {
+            ancestors.push(node);
+            f(ancestors, path);
+        }
+        for (idx, child) in child_nodes(node).iter().enumerate() {
+            path.push(idx);
+            visit(child, ancestors, path, f);
+            path.pop();
+        }
+        if is_element {
+            ancestors.pop();
+        }
+    }
+    visit(node, ancestors, &mut vec![], f)
+}
+
+fn find_by_text<'a, MSG>(node: &'a vdom::Node<MSG>, text: &str) -> Option<&'a vdom::Node<MSG>> {
+    let innermost = child_nodes(node)
+        .iter()
+        .find_map(|child| find_by_text(child, text));
+    innermost.or_else(|| (node.is_element() && text_content(node).trim() == text).then_some(node))
+}
+
+fn text_content<MSG>(node: &vdom::Node<MSG>) -> String {
+    match node {
+        vdom::Node::Leaf(Leaf::Text(text)) => text.to_string(),
+        vdom::Node::Leaf(_) => String::new(),
+        _ => child_nodes(node).iter().map(text_content).collect(),
+    }
+}
+
+/// the plain value of the attribute of the element, the values of the same attribute name are
+/// joined with whitespace
+fn attribute_value<MSG>(node: &vdom::Node<MSG>, name: &str) -> Option<String> {
+    let values: Vec<&AttributeValue<MSG>> = node
+        .attributes()?
+        .iter()
+        .filter(|attr| *attr.name() == name)
+        .flat_map(|attr| attr.value())
+        .collect();
+    merge_plain_attributes_values(&values)
+}
+
+pub(crate) fn parse_selector(selector: &str) -> Vec<SimpleSelector> {
+    selector
+        .split_whitespace()
+        .map(|simple| {
+            let mut parsed = SimpleSelector::default();
+            let mut rest = simple;
+            while !rest.is_empty() {
+                let (prefix, body) = match rest.as_bytes()[0] {
+                    b'#' | b'.' | b'[' => rest.split_at(1),
+                    _ => ("", rest),
+                };
+                let end = match prefix {
+                    "[" => body.find(']').map(|i| i + 1).unwrap_or(body.len()),
+                    _ => body.find(['#', '.', '[']).unwrap_or(body.len()),
+                };
+                let (part, remaining) = body.split_at(end);
+                match prefix {
+                    "#" => parsed.id = Some(part.to_string()),
+                    "." => parsed.classes.push(part.to_string()),
+                    "[" => {
+                        let part = part.trim_end_matches(']');
+                        parsed.attributes.push(match part.split_once('=') {
+                            Some((name, value)) => (
+                                name.to_string(),
+                                Some(value.trim_matches(['"', '\'']).to_string()),
+                            ),
+                            None => (part.to_string(), None),
+                        });
+                    }
+                    _ => parsed.tag = Some(part.to_string()),
+                }
+                rest = remaining;
+            }
+            parsed
+        })
+        .collect()
+}
+
+impl SimpleSelector {
+    fn matches<MSG>(&self, node: &vdom::Node<MSG>) -> bool {
+        self.matches_element(node.tag().copied(), |name| attribute_value(node, name))
+    }
+
+    /// returns true if the element with the tag and the attributes matches this selector,
+    /// the text nodes has no tag and are never matched
+    pub(crate) fn matches_element(
+        &self,
+        tag: Option<&str>,
+        attribute: impl Fn(&str) -> Option<String>,
+    ) -> bool {
+        let tag_matches = match (&self.tag, tag) {
+            (Some(tag), Some(node_tag)) => tag.eq_ignore_ascii_case(node_tag),
+            (None, Some(_)) => true,
+            (_, None) => false,
+        };
+        let id_matches = match &self.id {
+            Some(id) => attribute("id").as_deref() == Some(id.as_str()),
+            None => true,
+        };
+        let class_value = attribute("class").unwrap_or_default();
+        let class_matches = self
+            .classes
+            .iter()
+            .all(|class| class_value.split_whitespace().any(|c| c == class));
+        let attributes_match =
+            self.attributes
+                .iter()
+                .all(|(name, value)| match (attribute(name), value) {
+                    (Some(actual), Some(value)) => actual == *value,
+                    (Some(_), None) => true,
+                    (None, _) => false,
+                });
+        tag_matches && id_matches && class_matches && attributes_match
+    }
+}
+
+/// the last element must match the last selector, while the rest of the selectors match its
+/// ancestors in order
+fn matches_selector<MSG>(selectors: &[SimpleSelector], elements: &[&vdom::Node<MSG>]) -> bool {
+    matches_selector_with(selectors, elements, |selector, node| selector.matches(node))
+}
+
+/// match the elements with the selectors, each element being matched with `matches`
+pub(crate) fn matches_selector_with<T>(
+    selectors: &[SimpleSelector],
+    elements: &[T],
+    matches: impl Fn(&SimpleSelector, &T) -> bool,
+) -> bool {
+    let Some((last_selector, ancestor_selectors)) = selectors.split_last() else {
+        return false;
+    };
+    let Some((element, ancestors)) = elements.split_last() else {
+        return false;
+    };
+    if !matches(last_selector, element) {
+        return false;
+    }
+    let mut ancestors = ancestors.iter().rev();
+    ancestor_selectors
+        .iter()
+        .rev()
+        .all(|selector| ancestors.any(|ancestor| matches(selector, ancestor)))
+}
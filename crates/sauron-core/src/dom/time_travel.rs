@@ -0,0 +1,208 @@
+//! Time-travel debugging, enabled with the `with-time-travel` feature.
+//!
+//! Once [`Program::enable_time_travel`] is called, each msg is recorded as it is updated into
+//! the APP, along with the time of the update, the measurements of the DOM update which
+//! rendered it and a snapshot of the APP after the update.
+//! The program can then step back and forth through the recorded msgs, the APP is restored from
+//! the snapshot and its view is patched into the DOM, the commands are not executed again.
+//!
+//! The recorded msgs can be exported as a JSON [`Session`], which is replayed in a test with
+//! [`TestProgram::replay`](crate::dom::TestProgram::replay).
+use crate::dom::{Application, DomBackend, Measurements, Program};
+use crate::Error;
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+
+/// a msg which was updated into the APP
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RecordedMsg<MSG> {
+    /// the msg
+    pub msg: MSG,
+    /// the time the msg was updated into the APP, in milliseconds since the page was loaded
+    pub timestamp: f64,
+    /// the measurements of the DOM update which rendered the msg, `None` if it is not rendered yet
+    pub measurements: Option<Measurements>,
+}
+
+/// the msgs recorded by the program, in the order they were updated into the APP
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Session<MSG> {
+    /// the recorded msgs
+    pub msgs: Vec<RecordedMsg<MSG>>,
+}
+
+impl<MSG> Session<MSG> {
+    /// serialize the session into JSON
+    pub fn to_json(&self) -> Result<String, serde_json::Error>
+    where
+        MSG: Serialize,
+    {
+        serde_json::to_string(self)
+    }
+
+    /// deserialize the session from JSON
+    pub fn from_json(json: &str) -> Result<Self, serde_json::Error>
+    where
+        MSG: DeserializeOwned,
+    {
+        serde_json::from_str(json)
+    }
+}
+
+/// The recorded msgs and the snapshots of the APP.
+/// The program doesn't require the APP and MSG to be `Clone`, so their `clone` functions are
+/// captured when the time travel is enabled.
+pub(crate) struct TimeTravel<APP, MSG> {
+    clone_app: fn(&APP) -> APP,
+    clone_msg: fn(&MSG) -> MSG,
+    /// the APP before the first recorded msg
+    initial_app: APP,
+    /// the recorded msgs along with the APP after each of them was updated
+    history: Vec<(RecordedMsg<MSG>, APP)>,
+    /// the number of recorded msgs which are reflected in the APP
+    position: usize,
+    /// the number of msgs at the end of the history which are not rendered yet
+    unmeasured: usize,
+}
+
+impl<APP, MSG, DOM> Program<APP, MSG, DOM>
+where
+    MSG: 'static,
+    APP: Application<MSG> + 'static,
+    DOM: DomBackend,
+{
+    /// a copy of the msg which is about to be updated into the APP, if the time travel is enabled
+    pub(crate) fn msg_to_record(&self) -> Option<MSG> {
+        let time_travel = self.time_travel.borrow();
+        let time_travel = time_travel.as_ref()?;
+        self.app_context
+            .pending_msgs
+            .borrow()
+            .front()
+            .map(time_travel.clone_msg)
+    }
+
+    /// record the msg along with a snapshot of the APP after it was updated.
+    /// The msgs after the current position are discarded, as the APP branches off from there.
+    pub(crate) fn record_update(&self, msg: MSG) {
+        let mut time_travel = self.time_travel.borrow_mut();
+        let Some(time_travel) = time_travel.as_mut() else {
+            return;
+        };
+        let app = (time_travel.clone_app)(&self.app_context.app.borrow());
+        time_travel.history.truncate(time_travel.position);
+        time_travel.unmeasured = time_travel.unmeasured.min(time_travel.history.len()) + 1;
+        time_travel.history.push((
+            RecordedMsg {
+                msg,
+                timestamp: self.backend.now(),
+                measurements: None,
+            },
+            app,
+        ));
+        time_travel.position = time_travel.history.len();
+    }
+
+    /// set the measurements of the DOM update to the msgs which were not rendered yet
+    pub(crate) fn record_measurements(&self, measurements: &Measurements) {
+        let mut time_travel = self.time_travel.borrow_mut();
+        let Some(time_travel) = time_travel.as_mut() else {
+            return;
+        };
+        let unmeasured = time_travel.unmeasured;
+        for (recorded, _) in time_travel.history.iter_mut().rev().take(unmeasured) {
+            recorded.measurements = Some(measurements.clone());
+        }
+        time_travel.unmeasured = 0;
+    }
+}
+
+impl<APP, MSG, DOM> Program<APP, MSG, DOM>
+where
+    MSG: Clone + 'static,
+    APP: Application<MSG> + Clone + 'static,
+    DOM: DomBackend,
+{
+    /// start recording the msgs which are updated into the APP, starting from the current
+    /// state of the APP. The previously recorded msgs are discarded.
+    pub fn enable_time_travel(&self) {
+        *self.time_travel.borrow_mut() = Some(TimeTravel {
+            clone_app: APP::clone,
+            clone_msg: MSG::clone,
+            initial_app: self.app().clone(),
+            history: vec![],
+            position: 0,
+            unmeasured: 0,
+        });
+    }
+
+    /// stop recording the msgs, the APP stays at its current state
+    pub fn disable_time_travel(&self) {
+        *self.time_travel.borrow_mut() = None;
+    }
+
+    /// the number of recorded msgs which are reflected in the APP.
+    /// It is less than the number of recorded msgs when the program stepped back.
+    pub fn time_travel_position(&self) -> usize {
+        self.time_travel
+            .borrow()
+            .as_ref()
+            .map(|time_travel| time_travel.position)
+            .unwrap_or(0)
+    }
+
+    /// the recorded msgs, including the msgs after the current position
+    pub fn session(&self) -> Session<MSG> {
+        let msgs = self
+            .time_travel
+            .borrow()
+            .as_ref()
+            .map(|time_travel| {
+                time_travel
+                    .history
+                    .iter()
+                    .map(|(recorded, _)| recorded.clone())
+                    .collect()
+            })
+            .unwrap_or_default();
+        Session { msgs }
+    }
+
+    /// export the recorded msgs as JSON
+    pub fn export_session(&self) -> Result<String, serde_json::Error>
+    where
+        MSG: Serialize,
+    {
+        self.session().to_json()
+    }
+
+    /// restore the APP to the state after the first `position` recorded msgs were updated and
+    /// patch its view into the DOM. Position `0` is the APP when the time travel was enabled.
+    pub fn travel_to(&mut self, position: usize) -> Result<(), Error> {
+        let app = {
+            let mut time_travel = self.time_travel.borrow_mut();
+            let Some(time_travel) = time_travel.as_mut() else {
+                log::warn!("the time travel is not enabled");
+                return Ok(());
+            };
+            time_travel.position = position.min(time_travel.history.len());
+            match time_travel.position.checked_sub(1) {
+                Some(index) => time_travel.history[index].1.clone(),
+                None => time_travel.initial_app.clone(),
+            }
+        };
+        *self.app_mut() = app;
+        let view = self.app_context.view();
+        self.update_dom_with_vdom(view)?;
+        Ok(())
+    }
+
+    /// undo the last msg which is reflected in the APP
+    pub fn step_back(&mut self) -> Result<(), Error> {
+        self.travel_to(self.time_travel_position().saturating_sub(1))
+    }
+
+    /// redo the msg after the current position
+    pub fn step_forward(&mut self) -> Result<(), Error> {
+        self.travel_to(self.time_travel_position() + 1)
+    }
+}
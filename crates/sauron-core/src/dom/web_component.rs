@@ -163,7 +163,10 @@ where
 
     /// called when the web component is mounted
     pub fn connected_callback(&mut self) {
-        self.program.mount();
+        if let Err(error) = self.program.mount() {
+            self.program.handle_error(error);
+            return;
+        }
         let static_style = <APP as Application<MSG>>::stylesheet().join("");
         self.program.inject_style_to_mount(&static_style);
         let dynamic_style =
@@ -174,9 +177,9 @@ where
             .app
             .borrow_mut()
             .connected_callback();
-        self.program
-            .update_dom(&Modifier::default())
-            .expect("must update dom");
+        if let Err(error) = self.program.update_dom(&Modifier::default()) {
+            self.program.handle_error(error);
+        }
     }
 
     /// called when the web component is removed
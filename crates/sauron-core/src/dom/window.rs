@@ -18,7 +18,9 @@ where
     }
 
     /// Creates a Cmd in which the MSG will be emitted
-    /// whenever the browser is resized
+    /// whenever the browser is resized.
+    /// The listener is only removed when the program is unmounted, use
+    /// [`Sub::on_resize`](crate::dom::Sub::on_resize) in the subscriptions of the app instead.
     pub fn on_resize<F>(&self, mut cb: F)
     where
         F: FnMut(i32, i32) -> MSG + Clone + 'static,
@@ -27,18 +29,17 @@ where
         let closure: Closure<dyn FnMut(web_sys::Event)> = Closure::new(move |_| {
             let (window_width, window_height) = util::get_window_size();
             let msg = cb(window_width, window_height);
-            let mut program = program.upgrade().expect("must upgrade");
-            program.dispatch(msg);
+            if let Some(mut program) = program.upgrade() {
+                program.dispatch(msg);
+            }
         });
-        window()
-            .add_event_listener_with_callback(intern("resize"), closure.as_ref().unchecked_ref())
+        self.add_event_closure(&window(), "resize", closure)
             .expect("resize callback");
-        self.event_closures.borrow_mut().push(closure);
     }
 
-    /// TODO: only executed once, since the Task Future is droped once done
-    /// TODO: this should be a stream, instead of just one-time future
-    /// a variant of resize task, but instead of returning Cmd, it is returning Task
+    /// a variant of resize task, but instead of returning Cmd, it is returning Task.
+    /// This is only executed once, since the Task Future is dropped once done.
+    #[deprecated(note = "use `Sub::on_resize` in `Application::subscriptions` instead")]
     pub fn on_resize_task<F>(mut cb: F) -> Task<MSG>
     where
         F: FnMut(i32, i32) -> MSG + Clone + 'static,
@@ -63,7 +64,10 @@ where
     }
 
     /// attached a callback and will be triggered when the hash portion of the window location
-    /// url is changed
+    /// url is changed.
+    /// The listener is only removed when the program is unmounted, use
+    /// [`Sub::on_hashchange`](crate::dom::Sub::on_hashchange) in the subscriptions of the app
+    /// instead.
     pub fn on_hashchange<F>(&self, mut cb: F)
     where
         F: FnMut(String) -> MSG + 'static,
@@ -72,10 +76,11 @@ where
         let closure: Closure<dyn FnMut(web_sys::Event)> = Closure::new(move |_| {
             let hash = util::get_location_hash();
             let msg = cb(hash);
-            let mut program = program.upgrade().expect("must upgrade");
-            program.dispatch(msg);
+            if let Some(mut program) = program.upgrade() {
+                program.dispatch(msg);
+            }
         });
-        window().set_onhashchange(Some(closure.as_ref().unchecked_ref()));
-        self.event_closures.borrow_mut().push(closure);
+        self.add_event_closure(&window(), "hashchange", closure)
+            .expect("hashchange callback");
     }
 }
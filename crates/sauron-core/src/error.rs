@@ -0,0 +1,110 @@
+//! The errors of the DOM runtime, such as failures in mounting the app and patching the DOM.
+//!
+//! The errors are passed into [`Application::on_error`](crate::dom::Application::on_error)
+//! instead of panicking, such that the app can recover or show a message.
+
+/// The errors of the DOM runtime
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum Error {
+    /// An exception is thrown by the browser
+    #[error("javascript error: {0}")]
+    Js(String),
+    /// The attribute could not be set on the element, such as an invalid attribute name
+    #[error("unable to set the attribute `{name}` of `<{tag}>`: {reason}")]
+    SetAttribute {
+        /// the tag of the element
+        tag: String,
+        /// the name of the attribute
+        name: String,
+        /// the exception thrown by the browser
+        reason: String,
+    },
+    /// The DOM node at the path of a patch is not found
+    #[error("unable to find the node at {path:?} to patch")]
+    NodeNotFound {
+        /// the path of the node from the root node
+        path: Vec<usize>,
+    },
+    /// The tag of the DOM element at the path of a patch is not the tag in the patch
+    #[error("expecting a `<{expected}>` at {path:?}, but found `<{found}>`")]
+    TagMismatch {
+        /// the path of the node from the root node
+        path: Vec<usize>,
+        /// the tag in the patch
+        expected: String,
+        /// the tag of the DOM element
+        found: String,
+    },
+    /// The node has no parent, which is needed to insert, replace or remove it
+    #[error("the node has no parent")]
+    MissingParent,
+    /// The document has no head, which the styles are injected into
+    #[error("the document has no head")]
+    MissingHead,
+    /// The node of the view can not be created into the DOM, such as a doctype in the middle of
+    /// the view
+    #[error("{0} can not be created into the DOM")]
+    InvalidNode(&'static str),
+    /// The app or the view of the program is referenced outside of the clones of the program,
+    /// such that they are not dropped along with the program
+    #[error("the app is referenced {app} times, but the program is referenced {program} times")]
+    LeakedReference {
+        /// the references to the app and the view
+        app: usize,
+        /// the references to the root node, one for each clone of the program
+        program: usize,
+    },
+    /// The program is unmounted or its root node is not yet created
+    #[error("the program is not mounted")]
+    NotMounted,
+    /// The patches could not all be applied before the cmd of the update is emitted
+    #[error("there are still {0} pending patches")]
+    PendingPatches(usize),
+    /// A stateful component can not be encoded into the wire format, since it runs in the
+    /// program which mounts it
+    #[error("a stateful component can not be encoded into a wire patch")]
+    WireComponent,
+    /// The name of a tag or attribute in a wire patch could not be decoded, since too many
+    /// distinct names which are not html or svg names are decoded
+    #[error("unable to decode the name `{0}`, too many distinct names are decoded")]
+    WireName(String),
+    /// The APP of a live session panicked, the session is removed while the other sessions
+    /// keep running
+    #[error("the live session `{session}` panicked: {message}")]
+    SessionPanicked {
+        /// the id of the session
+        session: String,
+        /// the message of the panic
+        message: String,
+    },
+    /// There is no live session of the id, such as a session which has expired
+    #[error("the live session `{0}` is not found")]
+    SessionNotFound(String),
+    /// The operation is not supported by the runtime of the app, such as a command which
+    /// operates on the `Program` in a live session
+    #[error("{0} is not supported by this runtime")]
+    Unsupported(&'static str),
+    /// A DOM operation failed in a DOM backend other than the browser, such as removing a node
+    /// from a parent which doesn't contain it
+    #[error("DOM error: {0}")]
+    Dom(String),
+    /// The view inside an error boundary panicked or failed, the fallback is rendered instead
+    #[error(transparent)]
+    ErrorBoundary(#[from] crate::html::ViewError),
+    /// The state of the app could not be saved into the storage
+    #[cfg(feature = "with-serde")]
+    #[error(transparent)]
+    Persist(#[from] crate::dom::PersistError),
+}
+
+#[cfg(feature = "with-dom")]
+impl From<wasm_bindgen::JsValue> for Error {
+    fn from(value: wasm_bindgen::JsValue) -> Self {
+        use wasm_bindgen::JsCast;
+        let message = match value.dyn_ref::<js_sys::Error>() {
+            Some(error) => String::from(error.message()),
+            None => value.as_string().unwrap_or_else(|| format!("{value:?}")),
+        };
+        Self::Js(message)
+    }
+}
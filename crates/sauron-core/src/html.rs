@@ -1,10 +1,20 @@
 //! Provides functions and macros to build html elements
-use crate::vdom::{leaf, Attribute, Node, NodeTrait};
+use crate::dom::{Component, StatefulComponent};
+use crate::vdom::{leaf, Attribute, AttributeValue, Node, NodeTrait};
+#[cfg(all(feature = "with-dom", feature = "with-serde"))]
+pub(crate) use error_boundary::panic_message;
+pub use error_boundary::{error_boundary, try_error_boundary, ViewError};
+pub use lazy::lazy;
+pub(crate) use lazy::LAZY;
+#[cfg(feature = "with-dom")]
+pub(crate) use lazy::{reuse_children, LazyViews};
 pub use mt_dom::{element, element_ns};
 pub use tags::{commons::*, self_closing::*, *};
 
 #[macro_use]
 pub mod attributes;
+mod error_boundary;
+mod lazy;
 #[cfg(feature = "with-lookup")]
 pub mod lookup;
 pub mod tags;
@@ -55,6 +65,13 @@ where
     }
 }
 
+/// the content of the comment which separates 2 adjacent text nodes
+pub(crate) const TEXT_SEPARATOR: &str = "separator";
+
+/// the name of the attribute which carries the stateful component of its host element,
+/// it is not rendered nor set into the DOM
+pub(crate) const COMPONENT: &str = "sauron-component";
+
 /// Creates an html element with the element tag name and namespace
 /// This is specifically used for creating svg element where a namespace is needed, otherwise the
 /// browser will not render it correctly.
@@ -80,7 +97,7 @@ pub fn html_element<MSG>(
     for child in children {
         if let Some(last) = corrected_children.last() {
             if last.is_text() {
-                corrected_children.push(comment("separator"));
+                corrected_children.push(comment(TEXT_SEPARATOR));
             }
         }
         corrected_children.push(child);
@@ -88,6 +105,60 @@ pub fn html_element<MSG>(
     element_ns(namespace, tag, attrs, corrected_children, self_closing)
 }
 
+/// Creates a host element where the stateful component is mounted into.
+/// The component keeps its own state, the external msgs it emits are mapped into the msgs of
+/// the parent with `map`.
+///
+/// The component is created only when the host is created, the instances in the subsequent
+/// views are ignored. Set a `key` in the `attrs` to recreate the component when the key changes.
+/// # Examples
+/// ```rust
+/// use sauron::{*, html::component};
+///
+/// #[derive(Default)]
+/// struct Counter(i32);
+///
+/// impl Component<i32, ()> for Counter {
+///     fn update(&mut self, msg: i32) -> Effects<i32, ()> {
+///         self.0 += msg;
+///         Effects::none()
+///     }
+///     fn view(&self) -> Node<i32> {
+///         button([], [text(self.0)])
+///     }
+/// }
+///
+/// let html: Node<()> = component([key("counter")], Counter(3), |_: ()| ());
+/// assert_eq!(
+///     html.render_to_string(),
+///     r#"<div class="Counter" key="counter"><button>3</button></div>"#
+/// );
+/// ```
+pub fn component<COMP, CMSG, XMSG, MSG, F>(
+    attrs: impl IntoIterator<Item = Attribute<MSG>>,
+    component: COMP,
+    map: F,
+) -> Node<MSG>
+where
+    COMP: Component<CMSG, XMSG> + 'static,
+    CMSG: 'static,
+    XMSG: 'static,
+    MSG: 'static,
+    F: Fn(XMSG) -> MSG + 'static,
+{
+    let component = StatefulComponent::new(component, map);
+    div(
+        [attributes::class(COMP::component_name())]
+            .into_iter()
+            .chain(attrs)
+            .chain([mt_dom::attr(
+                COMPONENT,
+                AttributeValue::Component(component),
+            )]),
+        [],
+    )
+}
+
 /// creates a text node using a formatter
 /// # Examples
 /// ```rust
@@ -7,7 +7,7 @@ pub use crate::{dom::Event, vdom::Attribute};
 pub use attribute_macros::commons::*;
 pub use attribute_macros::*;
 pub use attribute_value::AttributeValue;
-pub use listener::Listener;
+pub use listener::{Listener, ListenerModifiers, ListenerOptions};
 pub use special::{key, replace, skip, skip_criteria, Special};
 pub use style::Style;
 pub use value::Value;
@@ -1,4 +1,5 @@
 use crate::{
+    dom::StatefulComponent,
     html::attributes::{Style, Value},
     vdom::Listener,
 };
@@ -14,6 +15,8 @@ pub enum AttributeValue<MSG> {
     Style(Vec<Style>),
     /// Event Listener
     EventListener(Listener<MSG>),
+    /// a stateful component which is mounted into the element
+    Component(StatefulComponent<MSG>),
     /// no value
     Empty,
 }
@@ -27,6 +30,7 @@ impl<MSG> Clone for AttributeValue<MSG> {
             AttributeValue::Simple(this) => AttributeValue::Simple(this.clone()),
             AttributeValue::Style(this) => AttributeValue::Style(this.clone()),
             AttributeValue::EventListener(this) => AttributeValue::EventListener(this.clone()),
+            AttributeValue::Component(this) => AttributeValue::Component(this.clone()),
             AttributeValue::Empty => AttributeValue::Empty,
         }
     }
@@ -41,6 +45,7 @@ impl<MSG> Debug for AttributeValue<MSG> {
             AttributeValue::Simple(this) => this.fmt(f),
             AttributeValue::Style(this) => this.fmt(f),
             AttributeValue::EventListener(this) => this.fmt(f),
+            AttributeValue::Component(this) => this.fmt(f),
             AttributeValue::Empty => write!(f, "Empty"),
         }
     }
@@ -59,6 +64,7 @@ impl<MSG> PartialEq for AttributeValue<MSG> {
             (AttributeValue::EventListener(this), AttributeValue::EventListener(other)) => {
                 this == other
             }
+            (AttributeValue::Component(this), AttributeValue::Component(other)) => this == other,
             (AttributeValue::Empty, AttributeValue::Empty) => true,
             (_, _) => false,
         }
@@ -142,6 +148,14 @@ impl<MSG> AttributeValue<MSG> {
         matches!(self, Self::FunctionCall(_))
     }
 
+    /// return the component if the attribute value is a stateful component
+    pub fn as_component(&self) -> Option<&StatefulComponent<MSG>> {
+        match self {
+            Self::Component(component) => Some(component),
+            _ => None,
+        }
+    }
+
     /// returns true if this attribute value is empty
     pub fn is_empty(&self) -> bool {
         matches!(self, Self::Empty)
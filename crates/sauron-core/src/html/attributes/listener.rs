@@ -1,5 +1,9 @@
 //! Callbacks contains function that can be called at a later time.
 //! This is used in containing an event listener attached to an DOM element.
+use crate::{
+    dom::Event,
+    vdom::{Attribute, AttributeValue},
+};
 use std::{any::TypeId, fmt, rc::Rc};
 
 /// A generic sized representation of a function that can be
@@ -32,6 +36,60 @@ pub struct Listener<IN, OUT> {
     event_type_id: TypeId,
     /// the type_id of the return type of this callback when executed.
     msg_type_id: TypeId,
+    /// how the listener is attached to the DOM element
+    options: ListenerOptions,
+    /// the listener reads the web event of the browser, it is not called with a
+    /// `SyntheticEvent`
+    web_only: bool,
+}
+
+/// The options of how the listener is attached, along with what is done to the event
+/// before the listener is called.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "with-serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ListenerOptions {
+    /// the listener never calls `prevent_default`, which lets the browser scroll right away
+    pub passive: bool,
+    /// the listener is called in the capture phase, before the listeners of the descendants
+    pub capture: bool,
+    /// the listener is removed after it is called once
+    pub once: bool,
+    /// `prevent_default` is called on the event before the listener is called
+    pub prevent_default: bool,
+    /// `stop_propagation` is called on the event before the listener is called
+    pub stop_propagation: bool,
+}
+
+/// Set the options of the event listeners in the attribute, such as
+/// `on_submit(|_| Msg::Submit).prevent_default()`.
+pub trait ListenerModifiers: Sized {
+    /// set the options of the listeners with `f`
+    fn with_options(self, f: impl Fn(&mut ListenerOptions)) -> Self;
+
+    /// the listener never calls `prevent_default`
+    fn passive(self) -> Self {
+        self.with_options(|options| options.passive = true)
+    }
+
+    /// the listener is called in the capture phase
+    fn capture(self) -> Self {
+        self.with_options(|options| options.capture = true)
+    }
+
+    /// the listener is removed after it is called once
+    fn once(self) -> Self {
+        self.with_options(|options| options.once = true)
+    }
+
+    /// call `prevent_default` on the event before the listener is called
+    fn prevent_default(self) -> Self {
+        self.with_options(|options| options.prevent_default = true)
+    }
+
+    /// call `stop_propagation` on the event before the listener is called
+    fn stop_propagation(self) -> Self {
+        self.with_options(|options| options.stop_propagation = true)
+    }
 }
 
 impl<IN, F, OUT> From<F> for Listener<IN, OUT>
@@ -46,6 +104,8 @@ where
             func_type_id: TypeId::of::<F>(),
             event_type_id: TypeId::of::<IN>(),
             msg_type_id: TypeId::of::<OUT>(),
+            options: ListenerOptions::default(),
+            web_only: false,
         }
     }
 }
@@ -59,8 +119,8 @@ impl<IN, OUT> fmt::Debug for Listener<IN, OUT> {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         write!(
             f,
-            "in: {:?}, out: {:?}, func: {:?}",
-            self.event_type_id, self.msg_type_id, self.func_type_id
+            "in: {:?}, out: {:?}, func: {:?}, options: {:?}",
+            self.event_type_id, self.msg_type_id, self.func_type_id, self.options
         )
     }
 }
@@ -81,11 +141,73 @@ where
         F: Fn(OUT) -> MSG2 + Clone + 'static,
         MSG2: 'static,
     {
+        let options = self.options;
+        let web_only = self.web_only;
         let cb = move |input| {
             let out = self.emit(input);
             cb2(out)
         };
-        Listener::from(cb)
+        Listener {
+            web_only,
+            ..Listener::from(cb).with_options(|opts| *opts = options)
+        }
+    }
+
+    /// the options of how the listener is attached
+    pub fn options(&self) -> &ListenerOptions {
+        &self.options
+    }
+
+    /// mark the listener as reading the web event of the browser
+    #[cfg(feature = "with-dom")]
+    pub(crate) fn web_only(mut self) -> Self {
+        self.web_only = true;
+        self
+    }
+
+    /// returns true if the listener can only be called with the web events of the browser,
+    /// such as the listeners of `on_click` and `on_input`, they are skipped for the
+    /// `SyntheticEvent`s fired without the browser
+    pub fn is_web_only(&self) -> bool {
+        self.web_only
+    }
+}
+
+impl<MSG> Listener<Event, MSG>
+where
+    MSG: 'static,
+{
+    /// call the listener with the event, `None` if the listener reads the web event of the
+    /// browser while the event is fired without the browser
+    pub fn emit_event(&self, event: Event) -> Option<MSG> {
+        #[cfg(feature = "with-dom")]
+        if self.web_only && !matches!(event, Event::WebEvent(_)) {
+            return None;
+        }
+        Some(self.emit(event))
+    }
+}
+
+impl<IN, OUT> ListenerModifiers for Listener<IN, OUT> {
+    fn with_options(mut self, f: impl Fn(&mut ListenerOptions)) -> Self {
+        f(&mut self.options);
+        self
+    }
+}
+
+impl<MSG> ListenerModifiers for Attribute<MSG> {
+    fn with_options(mut self, f: impl Fn(&mut ListenerOptions)) -> Self {
+        self.value = self
+            .value
+            .into_iter()
+            .map(|value| match value {
+                AttributeValue::EventListener(listener) => {
+                    AttributeValue::EventListener(listener.with_options(&f))
+                }
+                value => value,
+            })
+            .collect();
+        self
     }
 }
 
@@ -102,6 +224,8 @@ impl<IN, OUT> Clone for Listener<IN, OUT> {
             func_type_id: self.func_type_id,
             event_type_id: self.event_type_id,
             msg_type_id: self.msg_type_id,
+            options: self.options,
+            web_only: self.web_only,
         }
     }
 }
@@ -118,5 +242,6 @@ impl<IN, OUT> PartialEq for Listener<IN, OUT> {
         self.event_type_id == other.event_type_id
             && self.msg_type_id == other.msg_type_id
             && self.func_type_id == other.func_type_id
+            && self.options == other.options
     }
 }
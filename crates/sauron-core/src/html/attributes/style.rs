@@ -1,9 +1,12 @@
 use crate::html::attributes::Value;
+#[cfg(feature = "with-serde")]
+use serde::{Deserialize, Serialize};
 use std::fmt;
 
 /// css styles
 /// style can be converted into an attribute
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "with-serde", derive(Serialize, Deserialize))]
 pub struct Style {
     /// style name such as border, width, etc
     pub name: String,
@@ -1,14 +1,19 @@
+#[cfg(feature = "with-serde")]
+use serde::{Deserialize, Deserializer, Serialize};
 use std::fmt;
 
 /// Wraps different primitive variants used as values in html
 /// This is needed since html attributes can have different value types
 /// such as checked(bool), name(String), tab_index(i32)
 /// Note: memory size of Value is 32 bytes, in comparison String is 24 bytes
+/// A `Str` value is serialized as a `String` value.
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "with-serde", derive(Serialize))]
 pub enum Value {
     /// bool value
     Bool(bool),
     /// &'static str value
+    #[cfg_attr(feature = "with-serde", serde(rename = "String"))]
     Str(&'static str),
     /// String value
     String(String),
@@ -44,6 +49,62 @@ pub enum Value {
     F64(f64),
 }
 
+/// The values which are deserialized, there is no `Str` since it can not be borrowed
+#[cfg(feature = "with-serde")]
+#[derive(Deserialize)]
+#[serde(rename = "Value")]
+enum OwnedValue {
+    Bool(bool),
+    String(String),
+    Vec(Vec<Value>),
+    U8(u8),
+    U16(u16),
+    U32(u32),
+    U64(u64),
+    Usize(usize),
+    U128(u128),
+    I8(i8),
+    I16(i16),
+    I32(i32),
+    I64(i64),
+    I128(i128),
+    Isize(isize),
+    F32(f32),
+    F64(f64),
+}
+
+#[cfg(feature = "with-serde")]
+impl<'de> Deserialize<'de> for Value {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        OwnedValue::deserialize(deserializer).map(Self::from)
+    }
+}
+
+#[cfg(feature = "with-serde")]
+impl From<OwnedValue> for Value {
+    fn from(value: OwnedValue) -> Self {
+        match value {
+            OwnedValue::Bool(v) => Self::Bool(v),
+            OwnedValue::String(v) => Self::String(v),
+            OwnedValue::Vec(v) => Self::Vec(v),
+            OwnedValue::U8(v) => Self::U8(v),
+            OwnedValue::U16(v) => Self::U16(v),
+            OwnedValue::U32(v) => Self::U32(v),
+            OwnedValue::U64(v) => Self::U64(v),
+            OwnedValue::Usize(v) => Self::Usize(v),
+            OwnedValue::U128(v) => Self::U128(v),
+            OwnedValue::I8(v) => Self::I8(v),
+            OwnedValue::I16(v) => Self::I16(v),
+            OwnedValue::I32(v) => Self::I32(v),
+            OwnedValue::I64(v) => Self::I64(v),
+            OwnedValue::I128(v) => Self::I128(v),
+            OwnedValue::Isize(v) => Self::Isize(v),
+            OwnedValue::F32(v) => Self::F32(v),
+            OwnedValue::F64(v) => Self::F64(v),
+        }
+    }
+}
+
 impl Value {
     /// returns an &str reference if this value is `Str` or `String` variant
     /// Note: This doesn't convert other variant into str representation
@@ -0,0 +1,153 @@
+//! Error boundaries, which render a fallback in place of a view subtree which failed to build.
+//!
+//! The failures caught while a program builds the view are reported to
+//! [`Application::on_error`](crate::dom::Application::on_error) as
+//! [`Error::ErrorBoundary`](crate::Error::ErrorBoundary) once the program has built the view.
+//! The failures caught outside of a program, such as in server side rendering, are not kept.
+use crate::vdom::Node;
+#[cfg(feature = "with-dom")]
+use std::cell::RefCell;
+use std::{
+    any::Any,
+    fmt,
+    panic::{self, AssertUnwindSafe},
+};
+
+#[cfg(feature = "with-dom")]
+thread_local! {
+    /// the failures caught by the error boundaries of the view which is being built by a program
+    static CAUGHT: RefCell<Option<Vec<ViewError>>> = const { RefCell::new(None) };
+}
+
+/// The failure of the view inside an error boundary
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ViewError {
+    /// the message of the panic or of the returned error
+    pub message: String,
+    /// true if the view panicked, false if the view returned an error
+    pub panicked: bool,
+}
+
+impl fmt::Display for ViewError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.panicked {
+            write!(f, "the view panicked: {}", self.message)
+        } else {
+            write!(f, "the view failed: {}", self.message)
+        }
+    }
+}
+
+impl std::error::Error for ViewError {}
+
+/// Build the view, rendering the fallback instead if it panics.
+///
+/// The panic is caught when it unwinds, which is the default on native targets. On `wasm32` the
+/// panics abort unless the app is compiled with `panic = "unwind"`, use [`try_error_boundary`]
+/// to guard the views which can fail without relying on unwinding.
+/// # Examples
+/// ```rust
+/// use sauron::{*, html::error_boundary};
+///
+/// let items: Vec<i32> = vec![];
+/// let html: Node<()> = div(
+///     [],
+///     [error_boundary(
+///         |error| p([class("error")], [text(&error.message)]),
+///         || text(format!("first item: {}", items[0])),
+///     )],
+/// );
+/// assert_eq!(
+///     html.render_to_string(),
+///     r#"<div><p class="error">index out of bounds: the len is 0 but the index is 0</p></div>"#
+/// );
+/// ```
+pub fn error_boundary<MSG, FALLBACK, VIEW>(fallback: FALLBACK, view: VIEW) -> Node<MSG>
+where
+    FALLBACK: FnOnce(&ViewError) -> Node<MSG>,
+    VIEW: FnOnce() -> Node<MSG>,
+{
+    match panic::catch_unwind(AssertUnwindSafe(view)) {
+        Ok(node) => node,
+        Err(payload) => caught(
+            ViewError {
+                message: panic_message(payload.as_ref()),
+                panicked: true,
+            },
+            fallback,
+        ),
+    }
+}
+
+/// Build the view which can fail by returning an error, rendering the fallback instead when it
+/// does. The panics are caught the same way as in [`error_boundary`].
+/// # Examples
+/// ```rust
+/// use sauron::{*, html::try_error_boundary};
+///
+/// let html: Node<()> = try_error_boundary(
+///     |error| p([], [text(&error.message)]),
+///     || "forty two".parse::<i32>().map(text),
+/// );
+/// assert_eq!(html.render_to_string(), "<p>invalid digit found in string</p>");
+/// ```
+pub fn try_error_boundary<MSG, FALLBACK, VIEW, E>(fallback: FALLBACK, view: VIEW) -> Node<MSG>
+where
+    FALLBACK: FnOnce(&ViewError) -> Node<MSG>,
+    VIEW: FnOnce() -> Result<Node<MSG>, E>,
+    E: fmt::Display,
+{
+    match panic::catch_unwind(AssertUnwindSafe(view)) {
+        Ok(Ok(node)) => node,
+        Ok(Err(error)) => caught(
+            ViewError {
+                message: error.to_string(),
+                panicked: false,
+            },
+            fallback,
+        ),
+        Err(payload) => caught(
+            ViewError {
+                message: panic_message(payload.as_ref()),
+                panicked: true,
+            },
+            fallback,
+        ),
+    }
+}
+
+/// build the view, returning it along with the failures caught by the error boundaries in it
+#[cfg(feature = "with-dom")]
+pub(crate) fn catch_view_errors<T>(build: impl FnOnce() -> T) -> (T, Vec<ViewError>) {
+    let previous = CAUGHT.with(|caught| caught.replace(Some(vec![])));
+    let built = build();
+    let errors = CAUGHT.with(|caught| caught.replace(previous));
+    (built, errors.unwrap_or_default())
+}
+
+/// keep the failure to be reported and render the fallback
+fn caught<MSG, FALLBACK>(error: ViewError, fallback: FALLBACK) -> Node<MSG>
+where
+    FALLBACK: FnOnce(&ViewError) -> Node<MSG>,
+{
+    let node = fallback(&error);
+    // there is no app to report to outside of a program, such as in server side rendering
+    #[cfg(feature = "with-dom")]
+    CAUGHT.with(|caught| {
+        if let Some(caught) = caught.borrow_mut().as_mut() {
+            caught.push(error);
+        }
+    });
+    node
+}
+
+/// the message of the panic, which is the formatted string passed to `panic!`
+pub(crate) fn panic_message(payload: &(dyn Any + Send)) -> String {
+    if let Some(message) = payload.downcast_ref::<&str>() {
+        message.to_string()
+    } else if let Some(message) = payload.downcast_ref::<String>() {
+        message.clone()
+    } else {
+        "unknown panic".to_string()
+    }
+}
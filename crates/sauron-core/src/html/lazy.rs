@@ -0,0 +1,254 @@
+//! Memoized view subtrees, which are built only when their dependencies change.
+//!
+//! The subtrees of [`lazy`] are remembered by the program which builds the view. When the
+//! dependencies are the same as in the previous view, the subtree is not built, instead a copy of
+//! its root element without the children is put in the view. The diff skips the element, since
+//! its lazy marker is unchanged, and the program moves the children of the previous view into
+//! it. The marker is an attribute which is only compared by the diff, it is neither rendered nor
+//! set into the DOM.
+use crate::html::attributes::attr;
+#[cfg(feature = "with-dom")]
+use crate::html::error_boundary::catch_view_errors;
+#[cfg(feature = "with-dom")]
+use crate::html::ViewError;
+use crate::vdom::{Element, Node};
+use std::{
+    any::Any,
+    cell::RefCell,
+    collections::{BTreeMap, BTreeSet},
+    panic::Location,
+    rc::Rc,
+};
+
+/// the name of the attribute which identifies the memoized subtree, it is unchanged as long as
+/// the dependencies are the same. It is not rendered nor set into the DOM
+pub(crate) const LAZY: &str = "sauron-lazy";
+
+thread_local! {
+    /// the memoized subtrees of the view which is being built
+    static BUILDING: RefCell<Option<Rc<RefCell<LazyViews>>>> = const { RefCell::new(None) };
+}
+
+/// identifies the lazy subtree with where it is called in the view, along with the number of
+/// times it is called there, such as in a loop
+type LazyId = (&'static Location<'static>, usize);
+
+/// a memoized subtree
+struct Memo {
+    /// the dependencies the subtree is built with
+    deps: Box<dyn Any>,
+    /// the root element of the subtree, without the children
+    root: Box<dyn Any>,
+}
+
+/// The memoized subtrees of the views of a program
+#[derive(Default)]
+pub(crate) struct LazyViews {
+    memos: BTreeMap<LazyId, Memo>,
+    /// the number of times each location is called in the view which is being built
+    calls: BTreeMap<&'static Location<'static>, usize>,
+    /// the memos which are used in the view which is being built
+    used: BTreeSet<LazyId>,
+    /// the lazy marker of the next subtree which is built
+    next_id: u64,
+    /// the failures caught by the error boundaries of the views, which are not yet reported
+    #[cfg(feature = "with-dom")]
+    caught: Vec<ViewError>,
+}
+
+impl LazyViews {
+    /// build the view, the lazy subtrees in it are memoized in the lazy views.
+    /// The memos which are not used in the view are removed, the failures caught by the error
+    /// boundaries in the view are kept until they are taken with [`LazyViews::take_caught`].
+    #[cfg(feature = "with-dom")]
+    pub(crate) fn build<MSG>(
+        lazy_views: &Rc<RefCell<Self>>,
+        view: impl FnOnce() -> Node<MSG>,
+    ) -> Node<MSG> {
+        {
+            let mut lazy_views = lazy_views.borrow_mut();
+            lazy_views.calls.clear();
+            lazy_views.used.clear();
+        }
+        let previous = BUILDING.with(|building| building.replace(Some(Rc::clone(lazy_views))));
+        let (node, caught) = catch_view_errors(view);
+        BUILDING.with(|building| building.replace(previous));
+        let mut lazy_views = lazy_views.borrow_mut();
+        let used = std::mem::take(&mut lazy_views.used);
+        lazy_views.memos.retain(|id, _| used.contains(id));
+        lazy_views.caught.extend(caught);
+        node
+    }
+
+    /// take the failures caught since the last call, to be reported to the app
+    #[cfg(feature = "with-dom")]
+    pub(crate) fn take_caught(&mut self) -> Vec<ViewError> {
+        std::mem::take(&mut self.caught)
+    }
+
+    /// the id of the call at the location
+    fn next_call(&mut self, location: &'static Location<'static>) -> LazyId {
+        let count = self.calls.entry(location).or_default();
+        let id = (location, *count);
+        *count += 1;
+        id
+    }
+
+    /// the root element of the memo, if it is built with the same dependencies
+    fn reuse<MSG, DEPS>(&mut self, id: &LazyId, deps: &DEPS) -> Option<Node<MSG>>
+    where
+        MSG: 'static,
+        DEPS: PartialEq + 'static,
+    {
+        let memo = self.memos.get(id)?;
+        if memo.deps.downcast_ref::<DEPS>() != Some(deps) {
+            return None;
+        }
+        let root = memo.root.downcast_ref::<Node<MSG>>()?.clone();
+        self.used.insert(*id);
+        Some(root)
+    }
+}
+
+/// Build the subtree only when the `deps` are changed from the previous view of the program,
+/// otherwise the previous subtree is reused and the diffing of it is skipped.
+///
+/// The subtree is memoized when its root is an element, it is built everytime when it is not
+/// built by a program, such as when it is rendered on the server.
+/// # Examples
+/// ```rust
+/// use sauron::{*, html::lazy};
+///
+/// fn rows(rows: &[String]) -> Node<()> {
+///     lazy(rows.to_vec(), || {
+///         ul([], rows.iter().map(|row| li([], [text(row)])))
+///     })
+/// }
+/// ```
+#[track_caller]
+pub fn lazy<MSG, DEPS, VIEW>(deps: DEPS, view: VIEW) -> Node<MSG>
+where
+    MSG: 'static,
+    DEPS: PartialEq + 'static,
+    VIEW: FnOnce() -> Node<MSG>,
+{
+    let location = Location::caller();
+    let Some(lazy_views) = BUILDING.with(|building| building.borrow().clone()) else {
+        return view();
+    };
+    let id = lazy_views.borrow_mut().next_call(location);
+    if let Some(root) = lazy_views.borrow_mut().reuse(&id, &deps) {
+        return root;
+    }
+    // the lazy views is not borrowed while building, since the subtree can have lazy subtrees
+    let mut node = view();
+    let Node::Element(element) = &mut node else {
+        return node;
+    };
+    let mut lazy_views = lazy_views.borrow_mut();
+    let lazy_id = lazy_views.next_id;
+    lazy_views.next_id += 1;
+    element.attrs.retain(|att| att.name != LAZY);
+    element.attrs.push(attr(LAZY, lazy_id));
+    let root = Node::Element(Element {
+        namespace: element.namespace,
+        tag: element.tag,
+        attrs: element.attrs.clone(),
+        children: vec![],
+        self_closing: element.self_closing,
+    });
+    lazy_views.used.insert(id);
+    lazy_views.memos.insert(
+        id,
+        Memo {
+            deps: Box::new(deps),
+            root: Box::new(root),
+        },
+    );
+    node
+}
+
+/// Move the children of the reused subtrees from the previous view into the new view,
+/// where the reused subtrees have no children.
+#[cfg(feature = "with-dom")]
+pub(crate) fn reuse_children<MSG>(old: &mut Node<MSG>, new: &mut Node<MSG>) {
+    let mut reused = BTreeSet::new();
+    collect_reused(new, &mut reused);
+    if reused.is_empty() {
+        return;
+    }
+    let mut children = BTreeMap::new();
+    take_children(old, &reused, &mut children);
+    put_children(new, &mut children);
+}
+
+/// the lazy marker of the element
+#[cfg(feature = "with-dom")]
+fn lazy_id<MSG>(node: &Node<MSG>) -> Option<u64> {
+    let Node::Element(element) = node else {
+        return None;
+    };
+    element
+        .attrs
+        .iter()
+        .filter(|att| att.name == LAZY)
+        .flat_map(|att| att.value.iter())
+        .find_map(|value| match value.get_simple() {
+            Some(crate::html::attributes::Value::U64(id)) => Some(*id),
+            _ => None,
+        })
+}
+
+/// the lazy elements of the new view without children, which are the reused subtrees
+#[cfg(feature = "with-dom")]
+fn collect_reused<MSG>(node: &Node<MSG>, reused: &mut BTreeSet<u64>) {
+    if let Some(id) = lazy_id(node) {
+        if node.children().is_empty() {
+            reused.insert(id);
+            return;
+        }
+    }
+    for child in node.children() {
+        collect_reused(child, reused);
+    }
+}
+
+/// take the children of the reused subtrees in the old view, without descending into them
+#[cfg(feature = "with-dom")]
+fn take_children<MSG>(
+    node: &mut Node<MSG>,
+    reused: &BTreeSet<u64>,
+    children: &mut BTreeMap<u64, Vec<Node<MSG>>>,
+) {
+    if let Some(id) = lazy_id(node) {
+        if reused.contains(&id) {
+            if let Node::Element(element) = node {
+                children.insert(id, std::mem::take(&mut element.children));
+            }
+            return;
+        }
+    }
+    if let Some(node_children) = node.children_mut() {
+        for child in node_children {
+            take_children(child, reused, children);
+        }
+    }
+}
+
+/// put the children into the reused subtrees of the new view
+#[cfg(feature = "with-dom")]
+fn put_children<MSG>(node: &mut Node<MSG>, children: &mut BTreeMap<u64, Vec<Node<MSG>>>) {
+    if let Some(id) = lazy_id(node) {
+        if let Some(reused_children) = children.remove(&id) {
+            if let Node::Element(element) = node {
+                element.children = reused_children;
+            }
+            return;
+        }
+    }
+    if let Some(node_children) = node.children_mut() {
+        for child in node_children {
+            put_children(child, children);
+        }
+    }
+}
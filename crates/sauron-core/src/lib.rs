@@ -21,6 +21,7 @@ pub mod prelude {
         attributes::key,
         attributes::{
             attr, checked, class, classes, classes_flag, disabled, empty_attr, r#type, styles_flag,
+            ListenerModifiers,
         },
         br, comment,
         commons::*,
@@ -41,6 +42,7 @@ pub mod prelude {
         Attribute, AttributeValue, Element, Listener, Node, NodeTrait, Patch,
     };
     pub use mt_dom::TreePath;
+    pub use crate::dom::Router;
 
     use cfg_if::cfg_if;
     cfg_if! {if #[cfg(feature = "with-dom")] {
@@ -51,9 +53,9 @@ pub mod prelude {
         pub use wasm_bindgen::prelude::*;
         pub use serde_wasm_bindgen;
         pub use crate::html::events::*;
-        pub use crate::dom::{Application, events, Program, document, now, window, Cmd,
+        pub use crate::dom::{Application, events, Program, document, now, window, Cmd, MsgCmd,
             AnimationFrameHandle, Callback, Component, Container, Effects, Measurements, MountAction,
-            MountTarget, Task, TimeoutCallbackHandle,
+            MountTarget, Task, TimeoutCallbackHandle, AbortHandle,
         };
         #[cfg(feature = "custom_element")]
         pub use crate::dom::WebComponent;
@@ -67,7 +69,10 @@ pub mod html;
 #[macro_use]
 pub mod svg;
 pub mod dom;
-mod render;
+mod error;
+pub mod render;
 pub mod vdom;
+
+pub use error::Error;
 #[doc(hidden)]
 pub use mt_dom;
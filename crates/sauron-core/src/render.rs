@@ -2,13 +2,22 @@
 //! virtual dom into a writable buffer
 //!
 use crate::{
-    html::{attributes, attributes::SegregatedAttributes},
+    html::{attributes, attributes::SegregatedAttributes, COMPONENT, LAZY, TEXT_SEPARATOR},
     vdom::{Attribute, Element, Leaf, Node, NodeTrait},
 };
-use std::fmt;
+use std::{fmt, io};
+use stream::IoWriter;
+pub use stream::{placeholder, AsyncHtmlWriter, HtmlWriter};
+
+mod stream;
 
 const DEFAULT_INDENT_SIZE: usize = 2;
 
+/// elements where the text content is not parsed as html by the browser,
+/// therefore escaping it with html entities would change its content
+/// <https://html.spec.whatwg.org/multipage/syntax.html#raw-text-elements>
+const RAW_TEXT_ELEMENTS: [&str; 2] = ["script", "style"];
+
 /// render node, elements to a writable buffer
 pub trait Render {
     // ISSUE: sublte difference in `render` and `render_to_string`:
@@ -52,6 +61,16 @@ pub trait Render {
         buffer
     }
 
+    /// render compressed html into the io writer, the writes are not buffered
+    fn render_to_writer(&self, writer: &mut dyn io::Write) -> io::Result<()> {
+        let mut buffer = IoWriter {
+            writer,
+            error: None,
+        };
+        self.render_compressed(&mut buffer)
+            .map_err(|_| buffer.into_error())
+    }
+
     /// render to string with nice indention
     fn render_to_string_pretty(&self) -> String {
         let mut buffer = String::new();
@@ -87,18 +106,8 @@ impl<MSG> Render for Node<MSG> {
         match self {
             Node::Element(element) => element.render_with_indent(buffer, indent, compressed),
             Node::Leaf(leaf) => leaf.render_with_indent(buffer, indent, compressed),
-            Node::Fragment(nodes) => {
-                for node in nodes {
-                    node.render_with_indent(buffer, indent, compressed)?;
-                }
-                Ok(())
-            }
-            Node::NodeList(node_list) => {
-                for node in node_list {
-                    node.render_with_indent(buffer, indent, compressed)?;
-                }
-                Ok(())
-            }
+            Node::Fragment(nodes) => render_siblings(nodes, buffer, indent, compressed),
+            Node::NodeList(node_list) => render_siblings(node_list, buffer, indent, compressed),
         }
     }
 }
@@ -111,15 +120,16 @@ impl Render for Leaf {
         _compressed: bool,
     ) -> fmt::Result {
         match self {
-            Leaf::Text(text) => {
-                write!(buffer, "{text}")
-            }
+            Leaf::Text(text) => escape_text(buffer, text),
             Leaf::SafeHtml(html) => {
-                //TODO: html escape this one
+                // safe html is the explicit opt-out of escaping, it is written as is
                 write!(buffer, "{html}")
             }
             Leaf::Comment(comment) => {
-                write!(buffer, "<!--{comment}-->")
+                write!(buffer, "<!--")?;
+                // escaping `<` and `>` makes sure the comment can not be closed prematurely
+                escape_text(buffer, comment)?;
+                write!(buffer, "-->")
             }
             Leaf::DocType(doctype) => {
                 write!(buffer, "<!doctype {doctype}>")
@@ -163,9 +173,9 @@ impl<MSG> Render for Element<MSG> {
             mt_dom::merge_attributes_of_same_name(&ref_attrs);
 
         for attr in &merged_attributes {
-            // dont render empty attribute
+            // dont render empty attribute, the component of the host element and the lazy marker
             // TODO: must check the attribute value for empty value
-            if !attr.name().is_empty() {
+            if !attr.name().is_empty() && *attr.name() != COMPONENT && *attr.name() != LAZY {
                 write!(buffer, " ")?;
                 attr.render_with_indent(buffer, indent, compressed)?;
             }
@@ -185,14 +195,12 @@ impl<MSG> Render for Element<MSG> {
 
         // do not indent if it is only text child node
         if is_lone_child_text_node {
-            first_child
-                .unwrap()
-                .render_with_indent(buffer, indent, compressed)?;
+            render_child(self.tag(), first_child.unwrap(), buffer, indent, compressed)?;
         } else {
             // otherwise print all child nodes with each line and indented
             for child in self.children() {
                 self.maybe_indent(buffer, indent + 1, compressed)?;
-                child.render_with_indent(buffer, indent + 1, compressed)?;
+                render_child(self.tag(), child, buffer, indent + 1, compressed)?;
             }
         }
 
@@ -201,6 +209,15 @@ impl<MSG> Render for Element<MSG> {
             self.maybe_indent(buffer, indent, compressed)?;
         }
 
+        // the initial view of the stateful components are the content of their host
+        for component in merged_attributes
+            .iter()
+            .flat_map(|attr| attr.value())
+            .filter_map(|value| value.as_component())
+        {
+            component.render(buffer, indent, compressed)?;
+        }
+
         let inner_html = extract_inner_html(&merged_attributes);
         if !inner_html.is_empty() {
             write!(buffer, "{inner_html}")?;
@@ -248,16 +265,119 @@ impl<MSG> Render for Attribute<MSG> {
             if let Some(merged_plain_values) =
                 attributes::merge_plain_attributes_values(&plain_values)
             {
-                write!(buffer, "{}=\"{}\"", self.name(), merged_plain_values)?;
+                write!(buffer, "{}=\"", self.name())?;
+                escape_attribute_value(buffer, &merged_plain_values)?;
+                write!(buffer, "\"")?;
             }
             if let Some(merged_styles) = attributes::merge_styles_attributes_values(&styles) {
-                write!(buffer, "{}=\"{}\"", self.name(), merged_styles)?;
+                write!(buffer, "{}=\"", self.name())?;
+                escape_attribute_value(buffer, &merged_styles)?;
+                write!(buffer, "\"")?;
             }
         }
         Ok(())
     }
 }
 
+/// render the nodes one after the other, a separator comment is inserted in between adjacent
+/// text nodes, otherwise the browser will merge them into one text node when parsing the html
+/// and the hydration would not be able to match them with the view.
+fn render_siblings<MSG>(
+    nodes: &[Node<MSG>],
+    buffer: &mut dyn fmt::Write,
+    indent: usize,
+    compressed: bool,
+) -> fmt::Result {
+    let mut previous_is_text = false;
+    for node in nodes {
+        if previous_is_text && node.is_text() {
+            write!(buffer, "<!--{TEXT_SEPARATOR}-->")?;
+        }
+        node.render_with_indent(buffer, indent, compressed)?;
+        previous_is_text = node.is_text();
+    }
+    Ok(())
+}
+
+/// render a child node of the element with the tag `parent_tag`.
+/// Text content of raw text elements such as `<script>` and `<style>` are not html escaped
+/// since the browser doesn't decode them.
+fn render_child<MSG>(
+    parent_tag: &str,
+    child: &Node<MSG>,
+    buffer: &mut dyn fmt::Write,
+    indent: usize,
+    compressed: bool,
+) -> fmt::Result {
+    match child.as_text() {
+        Some(text) if RAW_TEXT_ELEMENTS.contains(&parent_tag) => {
+            escape_raw_text(buffer, parent_tag, text)
+        }
+        _ => child.render_with_indent(buffer, indent, compressed),
+    }
+}
+
+/// write `content` into the buffer, replacing the characters matched by `escape`
+/// with their html entity
+fn write_escaped(
+    buffer: &mut dyn fmt::Write,
+    content: &str,
+    escape: impl Fn(char) -> Option<&'static str>,
+) -> fmt::Result {
+    let mut last = 0;
+    for (i, ch) in content.char_indices() {
+        if let Some(entity) = escape(ch) {
+            buffer.write_str(&content[last..i])?;
+            buffer.write_str(entity)?;
+            last = i + ch.len_utf8();
+        }
+    }
+    buffer.write_str(&content[last..])
+}
+
+/// escape the text content of an element
+pub(crate) fn escape_text(buffer: &mut dyn fmt::Write, text: &str) -> fmt::Result {
+    write_escaped(buffer, text, |ch| match ch {
+        '&' => Some("&amp;"),
+        '<' => Some("&lt;"),
+        '>' => Some("&gt;"),
+        _ => None,
+    })
+}
+
+/// escape the value of an attribute which is enclosed in double quotes
+pub(crate) fn escape_attribute_value(buffer: &mut dyn fmt::Write, value: &str) -> fmt::Result {
+    write_escaped(buffer, value, |ch| match ch {
+        '&' => Some("&amp;"),
+        '"' => Some("&quot;"),
+        '<' => Some("&lt;"),
+        '>' => Some("&gt;"),
+        _ => None,
+    })
+}
+
+/// The content of raw text elements can not contain html entities, so instead
+/// we break the sequences that would end the element (`</script`) or start a comment (`<!--`)
+/// by inserting a backslash after the `<`, which is still valid in javascript strings and css.
+fn escape_raw_text(buffer: &mut dyn fmt::Write, tag: &str, text: &str) -> fmt::Result {
+    let mut last = 0;
+    for (i, _) in text.match_indices('<') {
+        let rest = &text[i + 1..];
+        let closes_element = rest
+            .strip_prefix('/')
+            .and_then(|rest| rest.get(..tag.len()))
+            .map(|name| name.eq_ignore_ascii_case(tag))
+            .unwrap_or(false);
+        let opens_comment = rest.starts_with("!--");
+        if closes_element || opens_comment {
+            buffer.write_str(&text[last..=i])?;
+            buffer.write_str("\\")?;
+            last = i + 1;
+        }
+    }
+    buffer.write_str(&text[last..])
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
@@ -283,6 +403,16 @@ mod test {
         );
     }
 
+    #[test]
+    fn test_render_text_siblings_in_fragment_should_be_separated_with_comments() {
+        let view: Node<()> = fragment([text("text1"), text("text2"), span([], [])]);
+
+        assert_eq!(
+            view.render_to_string(),
+            "text1<!--separator-->text2<span></span>"
+        );
+    }
+
     #[test]
     fn test_render_classes() {
         let view: Node<()> = div(vec![class("frame"), class("component")], vec![]);
@@ -306,4 +436,82 @@ mod test {
         view.render(&mut buffer).expect("must render");
         assert_eq!(expected, buffer);
     }
+
+    #[test]
+    fn test_render_text_is_escaped() {
+        let view: Node<()> = p(vec![], vec![text("<b>bold</b> & \"quoted\"")]);
+        assert_eq!(
+            view.render_to_string(),
+            "<p>&lt;b&gt;bold&lt;/b&gt; &amp; \"quoted\"</p>"
+        );
+    }
+
+    #[test]
+    fn test_render_attribute_value_is_escaped() {
+        let view: Node<()> = a(
+            vec![
+                attributes::title(r#"say "hi" & <bye>"#),
+                href("/search?q=1&lang=en"),
+            ],
+            vec![],
+        );
+        assert_eq!(
+            view.render_to_string(),
+            r#"<a title="say &quot;hi&quot; &amp; &lt;bye&gt;" href="/search?q=1&amp;lang=en"></a>"#
+        );
+    }
+
+    #[test]
+    fn test_render_style_value_is_escaped() {
+        let view: Node<()> = div(vec![styles([("font-family", r#""Fira Code""#)])], vec![]);
+        assert_eq!(
+            view.render_to_string(),
+            r#"<div style="font-family:&quot;Fira Code&quot;;"></div>"#
+        );
+    }
+
+    #[test]
+    fn test_render_script_content_is_not_escaped() {
+        let view: Node<()> = script(vec![], vec![text("if (a < b && c > d) { go(\"x\") }")]);
+        assert_eq!(
+            view.render_to_string(),
+            "<script>if (a < b && c > d) { go(\"x\") }</script>"
+        );
+    }
+
+    #[test]
+    fn test_render_script_content_can_not_close_the_element() {
+        let view: Node<()> = script(
+            vec![],
+            vec![text("let s = \"</SCRIPT><script>alert(1)</script><!--\";")],
+        );
+        assert_eq!(
+            view.render_to_string(),
+            "<script>let s = \"<\\/SCRIPT><script>alert(1)<\\/script><\\!--\";</script>"
+        );
+    }
+
+    #[test]
+    fn test_render_style_content_can_not_close_the_element() {
+        let view: Node<()> = tags::style(vec![], vec![text("a > b { content: \"</style>\" }")]);
+        assert_eq!(
+            view.render_to_string(),
+            "<style>a > b { content: \"<\\/style>\" }</style>"
+        );
+    }
+
+    #[test]
+    fn test_render_comment_can_not_close_itself() {
+        let view: Node<()> = div(vec![], vec![comment("--><script>alert(1)</script>")]);
+        assert_eq!(
+            view.render_to_string(),
+            "<div><!----&gt;&lt;script&gt;alert(1)&lt;/script&gt;--></div>"
+        );
+    }
+
+    #[test]
+    fn test_render_safe_html_is_not_escaped() {
+        let view: Node<()> = div(vec![], vec![safe_html("<b>bold</b> &amp;")]);
+        assert_eq!(view.render_to_string(), "<div><b>bold</b> &amp;</div>");
+    }
 }
@@ -0,0 +1,314 @@
+//! Streaming the rendered html into an [`io::Write`] or an [`AsyncWrite`] in chunks.
+//!
+//! The page can be written in parts, such that the server can flush the `<head>` and the first
+//! screen early. Slow sections of the page are rendered as a [`placeholder`] with a fallback,
+//! which is filled in later in the same response with [`HtmlWriter::fill`].
+//!
+//! # Example
+//! ```rust
+//! use sauron::{html::*, render::{placeholder, HtmlWriter}, *};
+//!
+//! let page: Node<()> = main([], [
+//!     h1([], [text("Users")]),
+//!     placeholder("users", p([], [text("Loading...")])),
+//! ]);
+//!
+//! let mut writer = HtmlWriter::new(vec![]);
+//! writer.write_str("<!doctype html>").unwrap();
+//! writer.write_node(&page).unwrap();
+//! writer.flush().unwrap();
+//! // the browser can now display the page with the fallback
+//!
+//! let users: Node<()> = ul([], [li([], [text("Alice")])]);
+//! writer.fill("users", &users).unwrap();
+//! let html = String::from_utf8(writer.into_inner().unwrap()).unwrap();
+//! assert!(html.contains(r#"<template id="sauron-fill-users"><ul><li>Alice</li></ul></template>"#));
+//! ```
+use crate::{html, render::Render, vdom::Node};
+use futures::io::{AsyncWrite, AsyncWriteExt};
+use std::{fmt, io};
+
+/// the number of bytes buffered before it is written into the writer
+const DEFAULT_CHUNK_SIZE: usize = 8 * 1024;
+
+/// replaces the nodes in between the placeholder comments with the content of the template
+const FILL_SCRIPT: &str = r#"<script>function sauronFill(id){var t=document.getElementById("sauron-fill-"+id);var w=document.createTreeWalker(document,NodeFilter.SHOW_COMMENT);var s=null;while(w.nextNode()){var n=w.currentNode;if(n.data==="placeholder:"+id){s=n}else if(s&&n.data==="/placeholder:"+id){var p=n.parentNode;while(s.nextSibling!==n){p.removeChild(s.nextSibling)}p.insertBefore(t.content,n);p.removeChild(s);p.removeChild(n);break}}t.parentNode.removeChild(t)}</script>"#;
+
+/// A placeholder which displays the fallback until it is filled with [`HtmlWriter::fill`] or
+/// [`AsyncHtmlWriter::fill`] with the same `id`.
+///
+/// # Panics
+/// Panics if the id contains characters other than ascii alphanumerics, `-` and `_`
+pub fn placeholder<MSG>(id: &str, fallback: Node<MSG>) -> Node<MSG> {
+    assert_valid_id(id);
+    html::fragment([
+        html::comment(format!("placeholder:{id}")),
+        fallback,
+        html::comment(format!("/placeholder:{id}")),
+    ])
+}
+
+fn assert_valid_id(id: &str) {
+    assert!(
+        !id.is_empty()
+            && id
+                .chars()
+                .all(|ch| ch.is_ascii_alphanumeric() || ch == '-' || ch == '_'),
+        "placeholder id must only contain ascii alphanumerics, `-` and `_`: {id:?}"
+    );
+}
+
+/// Writes the rendered html into an [`io::Write`] in chunks of `chunk_size` bytes.
+/// The remaining bytes are written when [`HtmlWriter::flush`] is called.
+#[derive(Debug)]
+pub struct HtmlWriter<W> {
+    writer: W,
+    chunks: Chunks,
+}
+
+/// Writes the rendered html into an [`AsyncWrite`] in chunks of `chunk_size` bytes.
+/// The remaining bytes are written when [`AsyncHtmlWriter::flush`] is called.
+///
+/// Each node is rendered into memory before its chunks are written, write the page in
+/// parts to send the first parts early.
+#[derive(Debug)]
+pub struct AsyncHtmlWriter<W> {
+    writer: W,
+    chunks: Chunks,
+}
+
+/// the buffered html which is not yet written
+#[derive(Debug)]
+struct Chunks {
+    buffer: String,
+    chunk_size: usize,
+    /// the fill script is written once, before the first fill
+    has_fill_script: bool,
+}
+
+/// buffers the rendered html, passing each full chunk into `on_chunk`
+struct ChunkWriter<'a> {
+    chunks: &'a mut Chunks,
+    on_chunk: &'a mut dyn FnMut(&str) -> io::Result<()>,
+    error: Option<io::Error>,
+}
+
+/// writes the rendered html into an io writer
+pub(crate) struct IoWriter<'a> {
+    pub(crate) writer: &'a mut dyn io::Write,
+    pub(crate) error: Option<io::Error>,
+}
+
+impl fmt::Write for ChunkWriter<'_> {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        self.chunks.buffer.push_str(s);
+        if self.chunks.buffer.len() >= self.chunks.chunk_size {
+            if let Err(e) = (self.on_chunk)(&self.chunks.buffer) {
+                self.error = Some(e);
+                return Err(fmt::Error);
+            }
+            self.chunks.buffer.clear();
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Write for IoWriter<'_> {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        self.writer.write_all(s.as_bytes()).map_err(|e| {
+            self.error = Some(e);
+            fmt::Error
+        })
+    }
+}
+
+impl IoWriter<'_> {
+    /// the io error which caused the formatting to fail
+    pub(crate) fn into_error(self) -> io::Error {
+        self.error
+            .unwrap_or_else(|| io::Error::other("formatter error"))
+    }
+}
+
+impl Chunks {
+    fn new() -> Self {
+        Self {
+            buffer: String::new(),
+            chunk_size: DEFAULT_CHUNK_SIZE,
+            has_fill_script: false,
+        }
+    }
+
+    /// render into the buffer, each full chunk is passed into `on_chunk`
+    fn render(
+        &mut self,
+        render: impl FnOnce(&mut dyn fmt::Write) -> fmt::Result,
+        on_chunk: &mut dyn FnMut(&str) -> io::Result<()>,
+    ) -> io::Result<()> {
+        let mut writer = ChunkWriter {
+            chunks: self,
+            on_chunk,
+            error: None,
+        };
+        render(&mut writer).map_err(|_| {
+            writer
+                .error
+                .take()
+                .unwrap_or_else(|| io::Error::other("formatter error"))
+        })
+    }
+
+    /// render the content of the placeholder into a template, which is then moved into the
+    /// placeholder by the fill script
+    fn render_fill<MSG>(
+        &mut self,
+        id: &str,
+        node: &Node<MSG>,
+        on_chunk: &mut dyn FnMut(&str) -> io::Result<()>,
+    ) -> io::Result<()> {
+        assert_valid_id(id);
+        let has_fill_script = std::mem::replace(&mut self.has_fill_script, true);
+        self.render(
+            |buffer| {
+                if !has_fill_script {
+                    buffer.write_str(FILL_SCRIPT)?;
+                }
+                write!(buffer, r#"<template id="sauron-fill-{id}">"#)?;
+                node.render_compressed(buffer)?;
+                write!(buffer, r#"</template><script>sauronFill("{id}")</script>"#)
+            },
+            on_chunk,
+        )
+    }
+}
+
+impl<W: io::Write> HtmlWriter<W> {
+    /// create an html writer which writes into the writer
+    pub fn new(writer: W) -> Self {
+        Self {
+            writer,
+            chunks: Chunks::new(),
+        }
+    }
+
+    /// set the number of bytes buffered before it is written into the writer
+    pub fn with_chunk_size(mut self, chunk_size: usize) -> Self {
+        self.chunks.chunk_size = chunk_size;
+        self
+    }
+
+    /// write the str as is, such as the doctype
+    pub fn write_str(&mut self, s: &str) -> io::Result<()> {
+        let writer = &mut self.writer;
+        self.chunks
+            .render(|buffer| buffer.write_str(s), &mut |chunk| {
+                writer.write_all(chunk.as_bytes())
+            })
+    }
+
+    /// render the node into the writer
+    pub fn write_node<MSG>(&mut self, node: &Node<MSG>) -> io::Result<()> {
+        let writer = &mut self.writer;
+        self.chunks
+            .render(|buffer| node.render_compressed(buffer), &mut |chunk| {
+                writer.write_all(chunk.as_bytes())
+            })
+    }
+
+    /// fill the [`placeholder`] with the `id` with the node, replacing its fallback
+    ///
+    /// # Panics
+    /// Panics if the id contains characters other than ascii alphanumerics, `-` and `_`
+    pub fn fill<MSG>(&mut self, id: &str, node: &Node<MSG>) -> io::Result<()> {
+        let writer = &mut self.writer;
+        self.chunks
+            .render_fill(id, node, &mut |chunk| writer.write_all(chunk.as_bytes()))
+    }
+
+    /// write the buffered html and flush the writer
+    pub fn flush(&mut self) -> io::Result<()> {
+        self.writer.write_all(self.chunks.buffer.as_bytes())?;
+        self.chunks.buffer.clear();
+        self.writer.flush()
+    }
+
+    /// flush the buffered html and return the writer
+    pub fn into_inner(mut self) -> io::Result<W> {
+        self.flush()?;
+        Ok(self.writer)
+    }
+}
+
+impl<W: AsyncWrite + Unpin> AsyncHtmlWriter<W> {
+    /// create an html writer which writes into the async writer
+    pub fn new(writer: W) -> Self {
+        Self {
+            writer,
+            chunks: Chunks::new(),
+        }
+    }
+
+    /// set the number of bytes buffered before it is written into the writer
+    pub fn with_chunk_size(mut self, chunk_size: usize) -> Self {
+        self.chunks.chunk_size = chunk_size;
+        self
+    }
+
+    /// write the str as is, such as the doctype
+    pub async fn write_str(&mut self, s: &str) -> io::Result<()> {
+        let mut full_chunks = vec![];
+        self.chunks.render(
+            |buffer| buffer.write_str(s),
+            &mut collect_into(&mut full_chunks),
+        )?;
+        self.write_chunks(full_chunks).await
+    }
+
+    /// render the node into the writer
+    pub async fn write_node<MSG>(&mut self, node: &Node<MSG>) -> io::Result<()> {
+        let mut full_chunks = vec![];
+        self.chunks.render(
+            |buffer| node.render_compressed(buffer),
+            &mut collect_into(&mut full_chunks),
+        )?;
+        self.write_chunks(full_chunks).await
+    }
+
+    /// fill the [`placeholder`] with the `id` with the node, replacing its fallback
+    ///
+    /// # Panics
+    /// Panics if the id contains characters other than ascii alphanumerics, `-` and `_`
+    pub async fn fill<MSG>(&mut self, id: &str, node: &Node<MSG>) -> io::Result<()> {
+        let mut full_chunks = vec![];
+        self.chunks
+            .render_fill(id, node, &mut collect_into(&mut full_chunks))?;
+        self.write_chunks(full_chunks).await
+    }
+
+    /// write the buffered html and flush the writer
+    pub async fn flush(&mut self) -> io::Result<()> {
+        let buffer = std::mem::take(&mut self.chunks.buffer);
+        self.writer.write_all(buffer.as_bytes()).await?;
+        self.writer.flush().await
+    }
+
+    /// flush the buffered html and return the writer
+    pub async fn into_inner(mut self) -> io::Result<W> {
+        self.flush().await?;
+        Ok(self.writer)
+    }
+
+    async fn write_chunks(&mut self, full_chunks: Vec<String>) -> io::Result<()> {
+        for chunk in full_chunks {
+            self.writer.write_all(chunk.as_bytes()).await?;
+        }
+        Ok(())
+    }
+}
+
+fn collect_into(full_chunks: &mut Vec<String>) -> impl FnMut(&str) -> io::Result<()> + '_ {
+    |chunk| {
+        full_chunks.push(chunk.to_string());
+        Ok(())
+    }
+}
@@ -7,12 +7,23 @@
 //!
 pub use crate::html::attributes::AttributeValue;
 use crate::{dom::Event, html::attributes};
+pub use apply::apply_patches;
 pub use leaf::Leaf;
+use mt_dom::TreePath;
 pub use node_trait::NodeTrait;
+use std::{cell::RefCell, ptr};
+#[cfg(feature = "with-serde")]
+pub use wire::{
+    EventId, WireAttribute, WireElement, WireEvents, WireNode, WirePatch, WirePatchType, WireValue,
+};
 
+mod apply;
+mod keyed;
 pub mod leaf;
 pub(crate) mod map_msg;
 mod node_trait;
+#[cfg(feature = "with-serde")]
+mod wire;
 
 /// namespace type in node, which could be change to an enum
 pub type Namespace = &'static str;
@@ -47,6 +58,28 @@ pub type Listener<MSG> = attributes::Listener<Event, MSG>;
 /// This is a sauron html specific functionality
 /// diff 2 nodes with attribute using `&'static str` instead of generic ATT
 pub fn diff<'a, MSG>(old: &'a Node<MSG>, new: &'a Node<MSG>) -> Vec<Patch<'a, MSG>>
+where
+    MSG: 'static,
+{
+    let mut patches = diff_recursive(old, new, &TreePath::root());
+    // the lazy marker of a rebuilt subtree changes, but it is not set into the DOM
+    patches.retain_mut(|patch| match &mut patch.patch_type {
+        PatchType::AddAttributes { attrs } | PatchType::RemoveAttributes { attrs } => {
+            attrs.retain(|att| att.name != crate::html::LAZY);
+            !attrs.is_empty()
+        }
+        _ => true,
+    });
+    patches
+}
+
+/// diff the nodes at the path with mt-dom, except for the elements with keyed children which
+/// are diffed in [`keyed`]
+fn diff_recursive<'a, MSG>(
+    old: &'a Node<MSG>,
+    new: &'a Node<MSG>,
+    path: &TreePath,
+) -> Vec<Patch<'a, MSG>>
 where
     MSG: 'static,
 {
@@ -56,6 +89,13 @@ where
     // check if the skip attribute is true
     // if it is true, skip diffing and no patches is created at this dom
     let skip = |old_node: &'a Node<MSG>, new_node: &'a Node<MSG>| {
+        // the lazy subtree is reused when its lazy id didn't change
+        if let (Some(new), Some(old)) = (
+            new_node.attribute_value(&crate::html::LAZY),
+            old_node.attribute_value(&crate::html::LAZY),
+        ) {
+            return new == old;
+        }
         let new_skip_criteria = new_node.attribute_value(&"skip_criteria");
         let old_skip_criteria = old_node.attribute_value(&"skip_criteria");
         // if old and new skip_criteria didn't change skip diffing this nodes
@@ -68,6 +108,9 @@ where
         }
     };
 
+    // the elements with keyed children, which mt-dom is told to replace
+    let keyed_elements = RefCell::new(vec![]);
+
     // check if the replace attribute evaluates to true,
     // if it is, a replace patch replace the old node with the new node
     // without diffing the dom tree
@@ -82,7 +125,30 @@ where
         // don't recycle when old node has event while new new doesn't have
         let forbid_recycle = old_node_has_event && !new_node_has_event;
 
-        explicit_replace_attr || forbid_recycle
+        if explicit_replace_attr || forbid_recycle {
+            return true;
+        }
+        // mt-dom replaces the keyed elements, the replace patches are then diffed by keys
+        if keyed::is_keyed(old_node, new_node) {
+            keyed_elements.borrow_mut().push((old_node, new_node));
+            return true;
+        }
+        false
     };
-    mt_dom::diff::diff_with_functions(old, new, &"key", &skip, &replace)
+    let patches = mt_dom::diff::diff_recursive(old, new, path, &"key", &skip, &replace);
+    let keyed_elements = keyed_elements.into_inner();
+    patches
+        .into_iter()
+        .flat_map(|patch| {
+            if let PatchType::ReplaceNode { replacement } = &patch.patch_type {
+                let keyed_pair = keyed_elements.iter().find(|(_, new_node)| {
+                    replacement.len() == 1 && ptr::eq(*new_node, replacement[0])
+                });
+                if let Some((Node::Element(old_element), Node::Element(new_element))) = keyed_pair {
+                    return keyed::diff_keyed(old_element, new_element, &patch.patch_path);
+                }
+            }
+            vec![patch]
+        })
+        .collect()
 }
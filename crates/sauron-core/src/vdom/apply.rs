@@ -0,0 +1,332 @@
+//! Apply the patches to a node without a browser, the same way they are applied to the DOM.
+//!
+//! The paths of the patches are of the nodes before any of the patches is applied, as such the
+//! targets of all the patches are found first, then the patches are applied in order to the
+//! nodes, which are identified by their id as they are moved around.
+use crate::{
+    vdom::{Attribute, Node, Patch, PatchType},
+    Error,
+};
+
+/// Apply the patches from [`diff`](crate::vdom::diff) to the old node, which turns it into the
+/// new node.
+///
+/// The attributes which are added are put after the remaining attributes of the element, since
+/// the order of the attributes is not kept in the patches. The node is left unchanged when any of
+/// the patches can not be applied, such as when the patches are not diffed from this node.
+/// # Example
+/// ```rust
+/// use sauron::{vdom::{apply_patches, diff}, *};
+///
+/// let old: Node<()> = ul([], [li([key(1)], [text(1)]), li([key(2)], [text(2)])]);
+/// let new: Node<()> = ul([], [li([key(2)], [text(2)]), li([key(3)], [text(3)])]);
+///
+/// let mut patched = old.clone();
+/// apply_patches(&mut patched, &diff(&old, &new)).expect("must apply");
+/// assert_eq!(patched, new);
+/// ```
+pub fn apply_patches<MSG>(node: &mut Node<MSG>, patches: &[Patch<MSG>]) -> Result<(), Error> {
+    let patches: Vec<_> = patches.iter().map(NodePatch::from_patch).collect();
+    apply(node, &patches)
+}
+
+/// A tree which the patches can be applied to
+pub(crate) trait PatchNode: Clone {
+    /// the attribute of the elements
+    type Attribute: Clone;
+
+    /// the tag of the element
+    fn tag(&self) -> Option<&str>;
+
+    /// the child nodes of the element, fragment or node list
+    fn child_nodes_mut(&mut self) -> Option<&mut Vec<Self>>;
+
+    /// the node which has the nodes when a node is replaced with more than one node
+    fn fragment(nodes: Vec<Self>) -> Self;
+
+    /// the attributes of the element
+    fn attributes_mut(&mut self) -> Option<&mut Vec<Self::Attribute>>;
+
+    /// returns true if both attributes have the same name, which replace each other
+    fn same_name(a: &Self::Attribute, b: &Self::Attribute) -> bool;
+}
+
+/// A patch with the owned nodes which are put into the tree
+pub(crate) struct NodePatch<'a, N: PatchNode> {
+    pub(crate) tag: Option<&'a str>,
+    pub(crate) patch_path: &'a [usize],
+    pub(crate) patch_type: NodePatchType<'a, N>,
+}
+
+/// What is done to the target node of a [`NodePatch`]
+pub(crate) enum NodePatchType<'a, N: PatchNode> {
+    /// insert the nodes before or after the target node
+    Insert {
+        nodes: Vec<N>,
+        after: bool,
+    },
+    AppendChildren(Vec<N>),
+    RemoveNode,
+    /// move the nodes at the paths before or after the target node
+    Move {
+        nodes_path: Vec<&'a [usize]>,
+        after: bool,
+    },
+    ReplaceNode(Vec<N>),
+    /// replace the attributes of the same names with these attributes
+    AddAttributes(Vec<N::Attribute>),
+    /// remove the attributes of the same names as these attributes
+    RemoveAttributes(Vec<N::Attribute>),
+}
+
+/// Apply the patches to the node, the node is left unchanged when any of the patches can not be
+/// applied.
+pub(crate) fn apply<N: PatchNode>(node: &mut N, patches: &[NodePatch<N>]) -> Result<(), Error> {
+    let mut next_id = 0;
+    let mut root = Slot::new(node.clone(), &mut next_id);
+    let targets = patches
+        .iter()
+        .map(|patch| root.find_target(patch))
+        .collect::<Result<Vec<_>, Error>>()?;
+    for (patch, (target, moving)) in patches.iter().zip(targets) {
+        root.apply(patch, target, moving, &mut next_id)?;
+    }
+    *node = root.into_node();
+    Ok(())
+}
+
+impl<'a, MSG> NodePatch<'a, Node<MSG>> {
+    fn from_patch(patch: &'a Patch<MSG>) -> Self {
+        let nodes = |nodes: &[&Node<MSG>]| nodes.iter().map(|node| (*node).clone()).collect();
+        let attrs = |attrs: &[&Attribute<MSG>]| attrs.iter().map(|att| (*att).clone()).collect();
+        let patch_type = match &patch.patch_type {
+            PatchType::InsertBeforeNode { nodes: inserted } => NodePatchType::Insert {
+                nodes: nodes(inserted),
+                after: false,
+            },
+            PatchType::InsertAfterNode { nodes: inserted } => NodePatchType::Insert {
+                nodes: nodes(inserted),
+                after: true,
+            },
+            PatchType::AppendChildren { children } => {
+                NodePatchType::AppendChildren(nodes(children))
+            }
+            PatchType::RemoveNode => NodePatchType::RemoveNode,
+            PatchType::MoveBeforeNode { nodes_path } => NodePatchType::Move {
+                nodes_path: nodes_path.iter().map(|path| path.path.as_slice()).collect(),
+                after: false,
+            },
+            PatchType::MoveAfterNode { nodes_path } => NodePatchType::Move {
+                nodes_path: nodes_path.iter().map(|path| path.path.as_slice()).collect(),
+                after: true,
+            },
+            PatchType::ReplaceNode { replacement } => {
+                NodePatchType::ReplaceNode(nodes(replacement))
+            }
+            PatchType::AddAttributes { attrs: added } => NodePatchType::AddAttributes(attrs(added)),
+            PatchType::RemoveAttributes { attrs: removed } => {
+                NodePatchType::RemoveAttributes(attrs(removed))
+            }
+        };
+        Self {
+            tag: patch.tag.copied(),
+            patch_path: &patch.patch_path.path,
+            patch_type,
+        }
+    }
+}
+
+impl<MSG> PatchNode for Node<MSG> {
+    type Attribute = Attribute<MSG>;
+
+    fn tag(&self) -> Option<&str> {
+        self.tag().copied()
+    }
+
+    fn child_nodes_mut(&mut self) -> Option<&mut Vec<Self>> {
+        match self {
+            Node::Element(element) => Some(&mut element.children),
+            Node::Fragment(nodes) | Node::NodeList(nodes) => Some(nodes),
+            Node::Leaf(_) => None,
+        }
+    }
+
+    fn fragment(nodes: Vec<Self>) -> Self {
+        Node::Fragment(nodes)
+    }
+
+    fn attributes_mut(&mut self) -> Option<&mut Vec<Self::Attribute>> {
+        match self {
+            Node::Element(element) => Some(&mut element.attrs),
+            _ => None,
+        }
+    }
+
+    fn same_name(a: &Self::Attribute, b: &Self::Attribute) -> bool {
+        a.name == b.name
+    }
+}
+
+/// A node with an id, such that the nodes targeted by the patches are found after the
+/// previous patches moved them around
+struct Slot<N> {
+    id: usize,
+    /// the node, without its children
+    node: N,
+    children: Vec<Slot<N>>,
+}
+
+impl<N: PatchNode> Slot<N> {
+    fn new(mut node: N, next_id: &mut usize) -> Self {
+        let id = *next_id;
+        *next_id += 1;
+        let children = node
+            .child_nodes_mut()
+            .map(std::mem::take)
+            .unwrap_or_default()
+            .into_iter()
+            .map(|child| Slot::new(child, next_id))
+            .collect();
+        Self { id, node, children }
+    }
+
+    fn new_all(nodes: &[N], next_id: &mut usize) -> Vec<Self> {
+        nodes
+            .iter()
+            .map(|node| Slot::new(node.clone(), next_id))
+            .collect()
+    }
+
+    fn into_node(self) -> N {
+        let mut node = self.node;
+        if let Some(children) = node.child_nodes_mut() {
+            *children = self.children.into_iter().map(Slot::into_node).collect();
+        }
+        node
+    }
+
+    /// the id of the target of the patch and the ids of the nodes it moves
+    fn find_target(&self, patch: &NodePatch<N>) -> Result<(usize, Vec<usize>), Error> {
+        let target = self.find_path(patch.patch_path)?;
+        if let Some(tag) = patch.tag {
+            let found = target.node.tag().unwrap_or_default();
+            if found != tag {
+                return Err(Error::TagMismatch {
+                    path: patch.patch_path.to_vec(),
+                    expected: tag.to_string(),
+                    found: found.to_string(),
+                });
+            }
+        }
+        let moving = match &patch.patch_type {
+            NodePatchType::Move { nodes_path, .. } => nodes_path
+                .iter()
+                .map(|path| self.find_path(path).map(|slot| slot.id))
+                .collect::<Result<_, _>>()?,
+            _ => vec![],
+        };
+        Ok((target.id, moving))
+    }
+
+    fn find_path(&self, path: &[usize]) -> Result<&Slot<N>, Error> {
+        let mut slot = self;
+        for idx in path {
+            slot = slot.children.get(*idx).ok_or_else(|| Error::NodeNotFound {
+                path: path.to_vec(),
+            })?;
+        }
+        Ok(slot)
+    }
+
+    fn find_mut(&mut self, id: usize) -> Option<&mut Slot<N>> {
+        if self.id == id {
+            return Some(self);
+        }
+        self.children
+            .iter_mut()
+            .find_map(|child| child.find_mut(id))
+    }
+
+    /// the parent of the slot and the index of the slot in it
+    fn find_parent_mut(&mut self, id: usize) -> Option<(&mut Slot<N>, usize)> {
+        match self.children.iter().position(|child| child.id == id) {
+            Some(idx) => Some((self, idx)),
+            None => self
+                .children
+                .iter_mut()
+                .find_map(|child| child.find_parent_mut(id)),
+        }
+    }
+
+    fn remove(&mut self, id: usize) -> Result<Slot<N>, Error> {
+        let (parent, idx) = self.find_parent_mut(id).ok_or(Error::MissingParent)?;
+        Ok(parent.children.remove(idx))
+    }
+
+    /// insert the slots into the parent of the target, before or after it
+    fn insert(&mut self, target: usize, slots: Vec<Slot<N>>, after: bool) -> Result<(), Error> {
+        let (parent, idx) = self.find_parent_mut(target).ok_or(Error::MissingParent)?;
+        let at = if after { idx + 1 } else { idx };
+        parent.children.splice(at..at, slots);
+        Ok(())
+    }
+
+    fn apply(
+        &mut self,
+        patch: &NodePatch<N>,
+        target: usize,
+        moving: Vec<usize>,
+        next_id: &mut usize,
+    ) -> Result<(), Error> {
+        let not_found = || Error::NodeNotFound {
+            path: patch.patch_path.to_vec(),
+        };
+        match &patch.patch_type {
+            NodePatchType::Insert { nodes, after } => {
+                self.insert(target, Slot::new_all(nodes, next_id), *after)?
+            }
+            NodePatchType::AppendChildren(children) => {
+                let slots = Slot::new_all(children, next_id);
+                let target = self.find_mut(target).ok_or_else(not_found)?;
+                target.children.extend(slots);
+            }
+            NodePatchType::RemoveNode => {
+                self.remove(target)?;
+            }
+            NodePatchType::Move { after, .. } => {
+                let slots = moving
+                    .into_iter()
+                    .map(|id| self.remove(id))
+                    .collect::<Result<_, _>>()?;
+                self.insert(target, slots, *after)?;
+            }
+            NodePatchType::ReplaceNode(replacement) => {
+                let mut slots = Slot::new_all(replacement, next_id);
+                if self.id == target {
+                    // the root node is replaced
+                    *self = match slots.len() {
+                        1 => slots.remove(0),
+                        _ => Slot::new(N::fragment(replacement.clone()), next_id),
+                    };
+                } else {
+                    let (parent, idx) = self.find_parent_mut(target).ok_or_else(not_found)?;
+                    parent.children.splice(idx..=idx, slots);
+                }
+            }
+            NodePatchType::AddAttributes(added) => {
+                let target = self.find_mut(target).ok_or_else(not_found)?;
+                if let Some(attrs) = target.node.attributes_mut() {
+                    attrs.retain(|att| !added.iter().any(|add| N::same_name(att, add)));
+                    attrs.extend(added.iter().cloned());
+                }
+            }
+            NodePatchType::RemoveAttributes(removed) => {
+                let target = self.find_mut(target).ok_or_else(not_found)?;
+                if let Some(attrs) = target.node.attributes_mut() {
+                    attrs.retain(|att| !removed.iter().any(|remove| N::same_name(att, remove)));
+                }
+            }
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,250 @@
+//! Diff the keyed children of an element.
+//!
+//! The keyed diff of mt-dom creates the patches at the wrong paths when the children in the
+//! middle of the list are changed or reordered, so the elements with such keyed children are
+//! diffed here instead. The paths of the patches are of the old tree: the removed children are
+//! removed, the kept children which are out of order are moved next to the children which stay in
+//! place, then the new children are inserted next to the kept children.
+use crate::vdom::{diff_recursive, AttributeValue, Element, Node, Patch};
+use mt_dom::TreePath;
+
+const KEY: &str = "key";
+
+/// returns true if the elements are diffed here, which are the elements of the same tag and key
+/// where any of the children has a key, and the children in between the same keys at both ends
+/// are changed in both the old and new children. The other keyed children are diffed by mt-dom.
+pub(super) fn is_keyed<MSG>(old: &Node<MSG>, new: &Node<MSG>) -> bool {
+    let (Node::Element(old_element), Node::Element(new_element)) = (old, new) else {
+        return false;
+    };
+    // the elements of a different tag or key are replaced, the keys are only compared for the
+    // elements with keyed children
+    old_element.tag == new_element.tag
+        && (has_keyed_children(old_element) || has_keyed_children(new_element))
+        && (!has_key(old) || !has_key(new) || same_key(old, new))
+        && has_middle(&old_element.children, &new_element.children)
+}
+
+fn has_key<MSG>(node: &Node<MSG>) -> bool {
+    node.attributes()
+        .is_some_and(|attrs| attrs.iter().any(|att| att.name == KEY))
+}
+
+fn has_keyed_children<MSG>(element: &Element<MSG>) -> bool {
+    element.children.iter().any(has_key)
+}
+
+/// returns true if the values of the keys are the same, or both nodes have no key
+fn same_key<MSG>(old: &Node<MSG>, new: &Node<MSG>) -> bool {
+    key_values(old).eq(key_values(new))
+}
+
+fn key_values<MSG>(node: &Node<MSG>) -> impl Iterator<Item = &AttributeValue<MSG>> {
+    node.attributes()
+        .unwrap_or_default()
+        .iter()
+        .filter(|att| att.name == KEY)
+        .flat_map(|att| att.value())
+}
+
+/// returns true if the keyed diff of mt-dom would diff the middle of the children, which are the
+/// old and new children left in between the children of the same keys at the start and at the end,
+/// or if the children at the end overlap the children at the start of the new children
+fn has_middle<MSG>(old_children: &[Node<MSG>], new_children: &[Node<MSG>]) -> bool {
+    let start = old_children
+        .iter()
+        .zip(new_children)
+        .take_while(|(old, new)| same_key(old, new))
+        .count();
+    if start == old_children.len() || start == new_children.len() {
+        return false;
+    }
+    // the children at the end are not matched again in the old children, but they can be in the
+    // new children when the keys are repeated
+    let end = old_children[start..]
+        .iter()
+        .rev()
+        .zip(new_children.iter().rev())
+        .take_while(|(old, new)| same_key(old, new))
+        .count();
+    new_children.len() < start + end
+        || (old_children.len() > start + end && new_children.len() > start + end)
+}
+
+/// diff the attributes and the keyed children of the elements at the path
+pub(super) fn diff_keyed<'a, MSG>(
+    old: &'a Element<MSG>,
+    new: &'a Element<MSG>,
+    path: &TreePath,
+) -> Vec<Patch<'a, MSG>>
+where
+    MSG: 'static,
+{
+    let mut patches = diff_attributes(old, new, path);
+    let old_children = &old.children;
+    let new_children = &new.children;
+
+    // the index of the old child which is kept for each new child, the children without a key
+    // are matched in their order
+    let mut kept = vec![false; old_children.len()];
+    let old_index: Vec<Option<usize>> = new_children
+        .iter()
+        .map(|new_child| {
+            let found = (0..old_children.len())
+                .find(|i| !kept[*i] && same_key(&old_children[*i], new_child));
+            if let Some(i) = found {
+                kept[i] = true;
+            }
+            found
+        })
+        .collect();
+
+    for (i, old_child) in old_children.iter().enumerate() {
+        if !kept[i] {
+            patches.push(Patch::remove_node(old_child.tag(), path.traverse(i)));
+        }
+    }
+
+    // the kept children before a child in place are moved before it
+    let in_place = in_place(&old_index);
+    let mut moving = vec![];
+    let mut last_in_place = None;
+    for (j, i) in old_index.iter().enumerate() {
+        let Some(i) = *i else { continue };
+        if in_place[j] {
+            if !moving.is_empty() {
+                patches.push(Patch::move_before_node(
+                    old_children[i].tag(),
+                    path.traverse(i),
+                    moving.drain(..).map(|m| path.traverse(m)),
+                ));
+            }
+            last_in_place = Some(i);
+        } else {
+            moving.push(i);
+        }
+    }
+    // the kept children after the last child in place are moved after it one at a time, since
+    // the nodes which are moved after the same node end up in the reverse order in the DOM
+    if let (Some(last), Some(tail)) = (last_in_place, moving.pop()) {
+        patches.push(Patch::move_after_node(
+            old_children[last].tag(),
+            path.traverse(last),
+            [path.traverse(tail)],
+        ));
+        if !moving.is_empty() {
+            patches.push(Patch::move_before_node(
+                old_children[tail].tag(),
+                path.traverse(tail),
+                moving.iter().map(|m| path.traverse(*m)),
+            ));
+        }
+    }
+
+    // the new children are inserted before the kept child after them, or after the kept child
+    // before them, which are already in their place
+    let mut start = 0;
+    while start < new_children.len() {
+        if old_index[start].is_some() {
+            start += 1;
+            continue;
+        }
+        let end = (start..new_children.len())
+            .find(|j| old_index[*j].is_some())
+            .unwrap_or(new_children.len());
+        let inserted = new_children[start..end].iter();
+        let next = old_index.get(end).copied().flatten();
+        let previous = start.checked_sub(1).and_then(|j| old_index[j]);
+        patches.push(match (next, previous) {
+            (Some(next), _) => {
+                Patch::insert_before_node(old_children[next].tag(), path.traverse(next), inserted)
+            }
+            (None, Some(previous)) => Patch::insert_after_node(
+                old_children[previous].tag(),
+                path.traverse(previous),
+                inserted.collect(),
+            ),
+            (None, None) => {
+                Patch::append_children(Some(&old.tag), path.clone(), inserted.collect())
+            }
+        });
+        start = end;
+    }
+
+    // the kept children are diffed last, such that the children which are replaced are already
+    // moved into their place
+    for (new_child, i) in new_children.iter().zip(&old_index) {
+        if let Some(i) = *i {
+            patches.extend(diff_recursive(
+                &old_children[i],
+                new_child,
+                &path.traverse(i),
+            ));
+        }
+    }
+    patches
+}
+
+/// the new children which stay in place, which are the longest run of the kept children that
+/// are in the same order as in the old children
+fn in_place(old_index: &[Option<usize>]) -> Vec<bool> {
+    // the last new child of the increasing runs of each length, and the new child before each
+    // child in its run
+    let mut tails: Vec<usize> = vec![];
+    let mut previous = vec![None; old_index.len()];
+    for (j, i) in old_index.iter().enumerate() {
+        if i.is_none() {
+            continue;
+        }
+        let len = tails.partition_point(|t| old_index[*t] < *i);
+        previous[j] = len.checked_sub(1).map(|l| tails[l]);
+        if len == tails.len() {
+            tails.push(j);
+        } else {
+            tails[len] = j;
+        }
+    }
+    let mut in_place = vec![false; old_index.len()];
+    let mut last = tails.last().copied();
+    while let Some(j) = last {
+        in_place[j] = true;
+        last = previous[j];
+    }
+    in_place
+}
+
+/// the attributes which are added or changed, and the attributes which are removed
+fn diff_attributes<'a, MSG>(
+    old: &'a Element<MSG>,
+    new: &'a Element<MSG>,
+    path: &TreePath,
+) -> Vec<Patch<'a, MSG>> {
+    if old.attrs == new.attrs {
+        return vec![];
+    }
+    let old_attrs = mt_dom::group_attributes_per_name(&old.attrs);
+    let new_attrs = mt_dom::group_attributes_per_name(&new.attrs);
+    let added: Vec<_> = new_attrs
+        .iter()
+        .filter(|(name, attrs)| {
+            !old_attrs
+                .iter()
+                .any(|(old_name, old)| old_name == name && old == attrs)
+        })
+        .flat_map(|(_, attrs)| attrs.iter().copied())
+        .collect();
+    let removed: Vec<_> = old_attrs
+        .iter()
+        .filter(|(name, _)| !new_attrs.iter().any(|(new_name, _)| new_name == name))
+        .flat_map(|(_, attrs)| attrs.iter().copied())
+        .collect();
+
+    let mut patches = vec![];
+    if !added.is_empty() {
+        patches.push(Patch::add_attributes(&old.tag, path.clone(), added));
+    }
+    if !removed.is_empty() {
+        patches.push(Patch::remove_attributes(&old.tag, path.clone(), removed));
+    }
+    patches
+}
@@ -1,7 +1,10 @@
 //! Leaf node for html dom tree
+#[cfg(feature = "with-serde")]
+use serde::{Deserialize, Serialize};
 use std::fmt;
 
 /// A leaf node value of html dom tree
+#[cfg_attr(feature = "with-serde", derive(Serialize, Deserialize))]
 pub enum Leaf {
     /// Text variant of a virtual node
     Text(String),
@@ -156,6 +156,7 @@ where
             AttributeValue::Simple(this) => AttributeValue::Simple(this),
             AttributeValue::Style(this) => AttributeValue::Style(this),
             AttributeValue::EventListener(this) => AttributeValue::EventListener(this.map_msg(cb)),
+            AttributeValue::Component(this) => AttributeValue::Component(this.map_msg(cb)),
             AttributeValue::Empty => AttributeValue::Empty,
         }
     }
@@ -0,0 +1,678 @@
+//! An owned and serializable format of the patches and nodes, which can be sent to another
+//! thread, a web worker or over the network, such that the view can be diffed on a server and
+//! patched into the DOM of the browser.
+//!
+//! The event listeners can not be sent, each listener is registered into [`WireEvents`] where
+//! the diff is done and is sent as an [`EventId`] instead. The events of the id are sent back
+//! and are passed to the registered listener.
+use crate::{
+    dom::Event,
+    html::{
+        attributes::{AttributeValue, ListenerModifiers, ListenerOptions, Style, Value},
+        lookup,
+    },
+    vdom::{
+        apply::{self, NodePatch, NodePatchType, PatchNode},
+        Attribute, Element, Leaf, Listener, Node, Patch, PatchType,
+    },
+    Error,
+};
+#[cfg(feature = "with-dom")]
+use mt_dom::TreePath;
+use serde::{Deserialize, Serialize};
+use std::{
+    cell::RefCell,
+    collections::{BTreeMap, BTreeSet},
+    rc::Rc,
+};
+
+/// the maximum number of the decoded names which are not html or svg tags and attributes, such
+/// as the event names and the custom elements, since they are leaked to be static
+const MAX_LEAKED_NAMES: usize = 1024;
+
+thread_local! {
+    /// the names of the decoded tags and attributes
+    static NAMES: RefCell<Names> = const {
+        RefCell::new(Names {
+            interned: BTreeSet::new(),
+            leaked: 0,
+        })
+    };
+}
+
+/// the static names of the decoded tags and attributes
+struct Names {
+    interned: BTreeSet<&'static str>,
+    /// the number of the names which are leaked
+    leaked: usize,
+}
+
+/// The id of an event listener which is sent over the wire
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct EventId(pub u64);
+
+/// The event listeners of the nodes which are sent over the wire, by their id
+pub struct WireEvents<MSG> {
+    listeners: BTreeMap<EventId, Listener<MSG>>,
+    next_id: u64,
+}
+
+/// An owned node which can be serialized
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum WireNode {
+    /// an element
+    Element(WireElement),
+    /// a list of nodes
+    NodeList(Vec<WireNode>),
+    /// a fragment of nodes
+    Fragment(Vec<WireNode>),
+    /// a text, comment or doctype
+    Leaf(Leaf),
+}
+
+/// An owned element which can be serialized
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct WireElement {
+    /// the namespace of the element, such as svg
+    pub namespace: Option<String>,
+    /// the tag of the element
+    pub tag: String,
+    /// the attributes of the element
+    pub attrs: Vec<WireAttribute>,
+    /// the children of the element
+    pub children: Vec<WireNode>,
+    /// whether the element is self closing, such as `<br/>`
+    pub self_closing: bool,
+}
+
+/// An owned attribute which can be serialized
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct WireAttribute {
+    /// the namespace of the attribute, such as xlink
+    pub namespace: Option<String>,
+    /// the name of the attribute
+    pub name: String,
+    /// the values of the attribute
+    pub value: Vec<WireValue>,
+}
+
+/// The value of an attribute which can be serialized
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum WireValue {
+    /// a value which is passed to a function of the element
+    FunctionCall(Value),
+    /// a simple value
+    Simple(Value),
+    /// style values
+    Style(Vec<Style>),
+    /// an event listener, which is registered in the [`WireEvents`]
+    EventListener {
+        /// the id of the registered listener
+        id: EventId,
+        /// how the listener is attached to the element
+        options: ListenerOptions,
+    },
+    /// no value
+    Empty,
+}
+
+/// An owned patch which can be serialized
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct WirePatch {
+    /// the tag of the node at the patch path
+    pub tag: Option<String>,
+    /// the path to traverse to get to the target node
+    pub patch_path: Vec<usize>,
+    /// what is done to the target node
+    pub patch_type: WirePatchType,
+}
+
+/// The patch variants, the same as [`PatchType`] with owned nodes and attributes
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum WirePatchType {
+    /// insert the nodes before the target node
+    InsertBeforeNode {
+        /// the nodes to be inserted
+        nodes: Vec<WireNode>,
+    },
+    /// insert the nodes after the target node
+    InsertAfterNode {
+        /// the nodes to be inserted
+        nodes: Vec<WireNode>,
+    },
+    /// append the children into the target node
+    AppendChildren {
+        /// the nodes to be appended
+        children: Vec<WireNode>,
+    },
+    /// remove the target node
+    RemoveNode,
+    /// move the nodes at the paths before the target node
+    MoveBeforeNode {
+        /// the paths of the nodes to be moved
+        nodes_path: Vec<Vec<usize>>,
+    },
+    /// move the nodes at the paths after the target node
+    MoveAfterNode {
+        /// the paths of the nodes to be moved
+        nodes_path: Vec<Vec<usize>>,
+    },
+    /// replace the target node with the nodes
+    ReplaceNode {
+        /// the nodes which replaces the target node
+        replacement: Vec<WireNode>,
+    },
+    /// add the attributes to the target node
+    AddAttributes {
+        /// the attributes to be added
+        attrs: Vec<WireAttribute>,
+    },
+    /// remove the attributes from the target node
+    RemoveAttributes {
+        /// the attributes to be removed
+        attrs: Vec<WireAttribute>,
+    },
+}
+
+/// A patch decoded from a [`WirePatch`], which owns the nodes referenced by its [`Patch`]
+#[cfg(feature = "with-dom")]
+pub(crate) struct DecodedPatch<MSG> {
+    tag: Option<&'static str>,
+    patch_path: TreePath,
+    patch_type: DecodedPatchType<MSG>,
+}
+
+#[cfg(feature = "with-dom")]
+enum DecodedPatchType<MSG> {
+    InsertBeforeNode(Vec<Node<MSG>>),
+    InsertAfterNode(Vec<Node<MSG>>),
+    AppendChildren(Vec<Node<MSG>>),
+    RemoveNode,
+    MoveBeforeNode(Vec<TreePath>),
+    MoveAfterNode(Vec<TreePath>),
+    ReplaceNode(Vec<Node<MSG>>),
+    AddAttributes(Vec<Attribute<MSG>>),
+    RemoveAttributes(Vec<Attribute<MSG>>),
+}
+
+/// creates the event listener of the decoded nodes from the id of the listener and the event
+type OnEvent<MSG> = Rc<dyn Fn(EventId, Event) -> MSG>;
+
+impl<MSG> Default for WireEvents<MSG> {
+    fn default() -> Self {
+        Self {
+            listeners: BTreeMap::new(),
+            next_id: 0,
+        }
+    }
+}
+
+impl<MSG> WireEvents<MSG>
+where
+    MSG: 'static,
+{
+    /// create an empty registry of listeners
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// register the listener, returning its id
+    pub fn register(&mut self, listener: &Listener<MSG>) -> EventId {
+        let id = EventId(self.next_id);
+        self.next_id += 1;
+        self.listeners.insert(id, listener.clone());
+        id
+    }
+
+    /// the listener of the id
+    pub fn get(&self, id: EventId) -> Option<&Listener<MSG>> {
+        self.listeners.get(&id)
+    }
+
+    /// call the listener of the id with the event, `None` if there is no such listener or the
+    /// listener can only be called with a web event
+    pub fn emit(&self, id: EventId, event: Event) -> Option<MSG> {
+        self.get(id).and_then(|listener| listener.emit_event(event))
+    }
+
+    /// keep only the listeners which are in the node, such as the copy of the node which is
+    /// patched with the same patches as the DOM
+    pub fn retain_in(&mut self, node: &WireNode) {
+        let mut ids = BTreeSet::new();
+        node.collect_event_ids(&mut ids);
+        self.listeners.retain(|id, _| ids.contains(id));
+    }
+
+    /// remove all the listeners
+    pub fn clear(&mut self) {
+        self.listeners.clear();
+    }
+
+    /// the number of registered listeners
+    pub fn len(&self) -> usize {
+        self.listeners.len()
+    }
+
+    /// returns true if there are no registered listeners
+    pub fn is_empty(&self) -> bool {
+        self.listeners.is_empty()
+    }
+}
+
+impl WireNode {
+    /// encode the node, the event listeners are registered into the events
+    pub fn encode<MSG: 'static>(
+        node: &Node<MSG>,
+        events: &mut WireEvents<MSG>,
+    ) -> Result<Self, Error> {
+        Ok(match node {
+            Node::Element(element) => Self::Element(WireElement::encode(element, events)?),
+            Node::NodeList(nodes) => Self::NodeList(Self::encode_all(nodes, events)?),
+            Node::Fragment(nodes) => Self::Fragment(Self::encode_all(nodes, events)?),
+            Node::Leaf(leaf) => Self::Leaf(leaf.clone()),
+        })
+    }
+
+    fn encode_all<'a, MSG: 'static>(
+        nodes: impl IntoIterator<Item = &'a Node<MSG>>,
+        events: &mut WireEvents<MSG>,
+    ) -> Result<Vec<Self>, Error> {
+        nodes
+            .into_iter()
+            .map(|node| Self::encode(node, events))
+            .collect()
+    }
+
+    /// decode into a node, the event listeners call `on_event` with their id.
+    ///
+    /// Fails with [`Error::WireName`] when there are too many distinct names which are not html
+    /// or svg tags and attributes.
+    pub fn decode<MSG: 'static>(
+        &self,
+        on_event: impl Fn(EventId, Event) -> MSG + 'static,
+    ) -> Result<Node<MSG>, Error> {
+        let on_event: OnEvent<MSG> = Rc::new(on_event);
+        self.decode_with(&on_event)
+    }
+
+    fn decode_with<MSG: 'static>(&self, on_event: &OnEvent<MSG>) -> Result<Node<MSG>, Error> {
+        Ok(match self {
+            Self::Element(element) => Node::Element(element.decode_with(on_event)?),
+            Self::NodeList(nodes) => Node::NodeList(decode_all(nodes, on_event)?),
+            Self::Fragment(nodes) => Node::Fragment(decode_all(nodes, on_event)?),
+            Self::Leaf(leaf) => Node::Leaf(leaf.clone()),
+        })
+    }
+
+    /// the children of the node
+    pub fn children(&self) -> &[WireNode] {
+        match self {
+            Self::Element(element) => &element.children,
+            Self::NodeList(nodes) | Self::Fragment(nodes) => nodes,
+            Self::Leaf(_) => &[],
+        }
+    }
+
+    fn collect_event_ids(&self, ids: &mut BTreeSet<EventId>) {
+        if let Self::Element(element) = self {
+            let values = element.attrs.iter().flat_map(|att| &att.value);
+            for value in values {
+                if let WireValue::EventListener { id, .. } = value {
+                    ids.insert(*id);
+                }
+            }
+        }
+        for child in self.children() {
+            child.collect_event_ids(ids);
+        }
+    }
+
+    /// Apply the patches to this node, the same way they are applied to the DOM.
+    ///
+    /// This keeps a copy of what is in the DOM where the patches are created, such as on a
+    /// server, without a browser.
+    pub fn apply_patches(&mut self, patches: &[WirePatch]) -> Result<(), Error> {
+        let patches: Vec<_> = patches.iter().map(NodePatch::from_wire).collect();
+        apply::apply(self, &patches)
+    }
+}
+
+fn decode_all<MSG: 'static>(
+    nodes: &[WireNode],
+    on_event: &OnEvent<MSG>,
+) -> Result<Vec<Node<MSG>>, Error> {
+    nodes
+        .iter()
+        .map(|node| node.decode_with(on_event))
+        .collect()
+}
+
+impl WireElement {
+    fn encode<MSG: 'static>(
+        element: &Element<MSG>,
+        events: &mut WireEvents<MSG>,
+    ) -> Result<Self, Error> {
+        Ok(Self {
+            namespace: element.namespace.map(str::to_string),
+            tag: element.tag.to_string(),
+            attrs: WireAttribute::encode_all(&element.attrs, events)?,
+            children: WireNode::encode_all(&element.children, events)?,
+            self_closing: element.self_closing,
+        })
+    }
+
+    fn decode_with<MSG: 'static>(&self, on_event: &OnEvent<MSG>) -> Result<Element<MSG>, Error> {
+        Ok(Element {
+            namespace: self.namespace.as_deref().map(intern_name).transpose()?,
+            tag: intern_name(&self.tag)?,
+            attrs: self
+                .attrs
+                .iter()
+                .map(|att| att.decode_with(on_event))
+                .collect::<Result<_, _>>()?,
+            children: decode_all(&self.children, on_event)?,
+            self_closing: self.self_closing,
+        })
+    }
+}
+
+impl WireAttribute {
+    fn encode_all<'a, MSG: 'static>(
+        attrs: impl IntoIterator<Item = &'a Attribute<MSG>>,
+        events: &mut WireEvents<MSG>,
+    ) -> Result<Vec<Self>, Error> {
+        attrs
+            .into_iter()
+            .map(|att| {
+                Ok(Self {
+                    namespace: att.namespace.map(str::to_string),
+                    name: att.name.to_string(),
+                    value: att
+                        .value
+                        .iter()
+                        .map(|value| WireValue::encode(value, events))
+                        .collect::<Result<_, _>>()?,
+                })
+            })
+            .collect()
+    }
+
+    fn decode_with<MSG: 'static>(&self, on_event: &OnEvent<MSG>) -> Result<Attribute<MSG>, Error> {
+        Ok(Attribute {
+            namespace: self.namespace.as_deref().map(intern_name).transpose()?,
+            name: intern_name(&self.name)?,
+            value: self
+                .value
+                .iter()
+                .map(|value| value.decode_with(on_event))
+                .collect(),
+        })
+    }
+}
+
+impl WireValue {
+    fn encode<MSG: 'static>(
+        value: &AttributeValue<MSG>,
+        events: &mut WireEvents<MSG>,
+    ) -> Result<Self, Error> {
+        Ok(match value {
+            AttributeValue::FunctionCall(value) => Self::FunctionCall(value.clone()),
+            AttributeValue::Simple(value) => Self::Simple(value.clone()),
+            AttributeValue::Style(styles) => Self::Style(styles.clone()),
+            AttributeValue::EventListener(listener) => Self::EventListener {
+                id: events.register(listener),
+                options: *listener.options(),
+            },
+            AttributeValue::Component(_) => return Err(Error::WireComponent),
+            AttributeValue::Empty => Self::Empty,
+        })
+    }
+
+    fn decode_with<MSG: 'static>(&self, on_event: &OnEvent<MSG>) -> AttributeValue<MSG> {
+        match self {
+            Self::FunctionCall(value) => AttributeValue::FunctionCall(value.clone()),
+            Self::Simple(value) => AttributeValue::Simple(value.clone()),
+            Self::Style(styles) => AttributeValue::Style(styles.clone()),
+            Self::EventListener { id, options } => {
+                let (id, options, on_event) = (*id, *options, Rc::clone(on_event));
+                let listener = Listener::from(move |event| on_event(id, event));
+                AttributeValue::EventListener(listener.with_options(|opts| *opts = options))
+            }
+            Self::Empty => AttributeValue::Empty,
+        }
+    }
+}
+
+impl WirePatch {
+    /// encode the patch, the event listeners are registered into the events
+    pub fn encode<MSG: 'static>(
+        patch: &Patch<MSG>,
+        events: &mut WireEvents<MSG>,
+    ) -> Result<Self, Error> {
+        let patch_type = match &patch.patch_type {
+            PatchType::InsertBeforeNode { nodes } => WirePatchType::InsertBeforeNode {
+                nodes: WireNode::encode_all(nodes.iter().copied(), events)?,
+            },
+            PatchType::InsertAfterNode { nodes } => WirePatchType::InsertAfterNode {
+                nodes: WireNode::encode_all(nodes.iter().copied(), events)?,
+            },
+            PatchType::AppendChildren { children } => WirePatchType::AppendChildren {
+                children: WireNode::encode_all(children.iter().copied(), events)?,
+            },
+            PatchType::RemoveNode => WirePatchType::RemoveNode,
+            PatchType::MoveBeforeNode { nodes_path } => WirePatchType::MoveBeforeNode {
+                nodes_path: nodes_path.iter().map(|path| path.path.clone()).collect(),
+            },
+            PatchType::MoveAfterNode { nodes_path } => WirePatchType::MoveAfterNode {
+                nodes_path: nodes_path.iter().map(|path| path.path.clone()).collect(),
+            },
+            PatchType::ReplaceNode { replacement } => WirePatchType::ReplaceNode {
+                replacement: WireNode::encode_all(replacement.iter().copied(), events)?,
+            },
+            PatchType::AddAttributes { attrs } => WirePatchType::AddAttributes {
+                attrs: WireAttribute::encode_all(attrs.iter().copied(), events)?,
+            },
+            PatchType::RemoveAttributes { attrs } => WirePatchType::RemoveAttributes {
+                attrs: WireAttribute::encode_all(attrs.iter().copied(), events)?,
+            },
+        };
+        Ok(Self {
+            tag: patch.tag.map(|tag| tag.to_string()),
+            patch_path: patch.patch_path.path.clone(),
+            patch_type,
+        })
+    }
+
+    /// encode the patches, the event listeners are registered into the events
+    pub fn encode_all<MSG: 'static>(
+        patches: &[Patch<MSG>],
+        events: &mut WireEvents<MSG>,
+    ) -> Result<Vec<Self>, Error> {
+        patches
+            .iter()
+            .map(|patch| Self::encode(patch, events))
+            .collect()
+    }
+
+    /// decode into a patch which owns its nodes, the event listeners call `on_event`
+    #[cfg(feature = "with-dom")]
+    pub(crate) fn decode<MSG: 'static>(
+        &self,
+        on_event: &OnEvent<MSG>,
+    ) -> Result<DecodedPatch<MSG>, Error> {
+        let nodes = |nodes: &[WireNode]| decode_all(nodes, on_event);
+        let attrs = |attrs: &[WireAttribute]| {
+            attrs
+                .iter()
+                .map(|att| att.decode_with(on_event))
+                .collect::<Result<_, _>>()
+        };
+        let paths = |paths: &[Vec<usize>]| paths.iter().cloned().map(TreePath::new).collect();
+        let patch_type = match &self.patch_type {
+            WirePatchType::InsertBeforeNode { nodes: n } => {
+                DecodedPatchType::InsertBeforeNode(nodes(n)?)
+            }
+            WirePatchType::InsertAfterNode { nodes: n } => {
+                DecodedPatchType::InsertAfterNode(nodes(n)?)
+            }
+            WirePatchType::AppendChildren { children } => {
+                DecodedPatchType::AppendChildren(nodes(children)?)
+            }
+            WirePatchType::RemoveNode => DecodedPatchType::RemoveNode,
+            WirePatchType::MoveBeforeNode { nodes_path } => {
+                DecodedPatchType::MoveBeforeNode(paths(nodes_path))
+            }
+            WirePatchType::MoveAfterNode { nodes_path } => {
+                DecodedPatchType::MoveAfterNode(paths(nodes_path))
+            }
+            WirePatchType::ReplaceNode { replacement } => {
+                DecodedPatchType::ReplaceNode(nodes(replacement)?)
+            }
+            WirePatchType::AddAttributes { attrs: a } => DecodedPatchType::AddAttributes(attrs(a)?),
+            WirePatchType::RemoveAttributes { attrs: a } => {
+                DecodedPatchType::RemoveAttributes(attrs(a)?)
+            }
+        };
+        Ok(DecodedPatch {
+            tag: self.tag.as_deref().map(intern_name).transpose()?,
+            patch_path: TreePath::new(self.patch_path.clone()),
+            patch_type,
+        })
+    }
+}
+
+#[cfg(feature = "with-dom")]
+impl<MSG> DecodedPatch<MSG> {
+    /// the patch which references the nodes of this decoded patch
+    pub(crate) fn as_patch(&self) -> Patch<'_, MSG> {
+        let patch_type = match &self.patch_type {
+            DecodedPatchType::InsertBeforeNode(nodes) => PatchType::InsertBeforeNode {
+                nodes: nodes.iter().collect(),
+            },
+            DecodedPatchType::InsertAfterNode(nodes) => PatchType::InsertAfterNode {
+                nodes: nodes.iter().collect(),
+            },
+            DecodedPatchType::AppendChildren(children) => PatchType::AppendChildren {
+                children: children.iter().collect(),
+            },
+            DecodedPatchType::RemoveNode => PatchType::RemoveNode,
+            DecodedPatchType::MoveBeforeNode(nodes_path) => PatchType::MoveBeforeNode {
+                nodes_path: nodes_path.clone(),
+            },
+            DecodedPatchType::MoveAfterNode(nodes_path) => PatchType::MoveAfterNode {
+                nodes_path: nodes_path.clone(),
+            },
+            DecodedPatchType::ReplaceNode(replacement) => PatchType::ReplaceNode {
+                replacement: replacement.iter().collect(),
+            },
+            DecodedPatchType::AddAttributes(attrs) => PatchType::AddAttributes {
+                attrs: attrs.iter().collect(),
+            },
+            DecodedPatchType::RemoveAttributes(attrs) => PatchType::RemoveAttributes {
+                attrs: attrs.iter().collect(),
+            },
+        };
+        Patch {
+            tag: self.tag.as_ref(),
+            patch_path: self.patch_path.clone(),
+            patch_type,
+        }
+    }
+}
+
+/// the static name of the decoded tags and attributes. The html and svg names are found in the
+/// lookup, the rest are leaked only once, up to [`MAX_LEAKED_NAMES`]
+fn intern_name(name: &str) -> Result<&'static str, Error> {
+    NAMES.with(|names| {
+        let mut names = names.borrow_mut();
+        if let Some(interned) = names.interned.get(name) {
+            return Ok(*interned);
+        }
+        let interned = match lookup::match_tag(name).or_else(|| lookup::match_attribute(name)) {
+            Some(known) => known,
+            None if names.leaked < MAX_LEAKED_NAMES => {
+                names.leaked += 1;
+                Box::leak(name.to_string().into_boxed_str())
+            }
+            None => return Err(Error::WireName(name.to_string())),
+        };
+        names.interned.insert(interned);
+        Ok(interned)
+    })
+}
+
+impl<'a> NodePatch<'a, WireNode> {
+    fn from_wire(patch: &'a WirePatch) -> Self {
+        let nodes_path =
+            |nodes_path: &'a [Vec<usize>]| nodes_path.iter().map(Vec::as_slice).collect();
+        let patch_type = match &patch.patch_type {
+            WirePatchType::InsertBeforeNode { nodes } => NodePatchType::Insert {
+                nodes: nodes.clone(),
+                after: false,
+            },
+            WirePatchType::InsertAfterNode { nodes } => NodePatchType::Insert {
+                nodes: nodes.clone(),
+                after: true,
+            },
+            WirePatchType::AppendChildren { children } => {
+                NodePatchType::AppendChildren(children.clone())
+            }
+            WirePatchType::RemoveNode => NodePatchType::RemoveNode,
+            WirePatchType::MoveBeforeNode { nodes_path: paths } => NodePatchType::Move {
+                nodes_path: nodes_path(paths),
+                after: false,
+            },
+            WirePatchType::MoveAfterNode { nodes_path: paths } => NodePatchType::Move {
+                nodes_path: nodes_path(paths),
+                after: true,
+            },
+            WirePatchType::ReplaceNode { replacement } => {
+                NodePatchType::ReplaceNode(replacement.clone())
+            }
+            WirePatchType::AddAttributes { attrs } => NodePatchType::AddAttributes(attrs.clone()),
+            WirePatchType::RemoveAttributes { attrs } => {
+                NodePatchType::RemoveAttributes(attrs.clone())
+            }
+        };
+        Self {
+            tag: patch.tag.as_deref(),
+            patch_path: &patch.patch_path,
+            patch_type,
+        }
+    }
+}
+
+impl PatchNode for WireNode {
+    type Attribute = WireAttribute;
+
+    fn tag(&self) -> Option<&str> {
+        match self {
+            Self::Element(element) => Some(&element.tag),
+            _ => None,
+        }
+    }
+
+    fn child_nodes_mut(&mut self) -> Option<&mut Vec<Self>> {
+        match self {
+            Self::Element(element) => Some(&mut element.children),
+            Self::NodeList(nodes) | Self::Fragment(nodes) => Some(nodes),
+            Self::Leaf(_) => None,
+        }
+    }
+
+    fn fragment(nodes: Vec<Self>) -> Self {
+        Self::Fragment(nodes)
+    }
+
+    fn attributes_mut(&mut self) -> Option<&mut Vec<Self::Attribute>> {
+        match self {
+            Self::Element(element) => Some(&mut element.attrs),
+            _ => None,
+        }
+    }
+
+    fn same_name(a: &Self::Attribute, b: &Self::Attribute) -> bool {
+        a.name == b.name
+    }
+}
@@ -1,9 +1,18 @@
 use proc_macro2::TokenStream;
 use quote::quote;
-use rstml::node::{KeyedAttributeValue, Node, NodeAttribute, NodeBlock};
+use rstml::node::{KeyedAttributeValue, Node, NodeAttribute, NodeBlock, NodeName};
 use sauron_core::html::lookup;
 use syn::{Expr, ExprForLoop, Stmt};
 
+/// the options of a listener which can be appended to the event name in the `node!` macro
+const LISTENER_MODIFIERS: [&str; 5] = [
+    "passive",
+    "capture",
+    "once",
+    "prevent_default",
+    "stop_propagation",
+];
+
 pub fn to_token_stream(input: proc_macro::TokenStream) -> TokenStream {
     match rstml::parse(input) {
         Ok(nodes) => multiple_nodes(nodes),
@@ -32,6 +41,9 @@ fn single_node(node: Node) -> TokenStream {
     match node {
         Node::Element(elm) => {
             let open_tag = elm.open_tag;
+            if let Some(path) = component_path(&open_tag.name) {
+                return component_to_tokens(path, open_tag.attributes, elm.children);
+            }
             let tag = open_tag.name.to_string();
 
             let self_closing = lookup::is_self_closing(&tag);
@@ -148,11 +160,7 @@ fn attribute_to_tokens(attribute: NodeAttribute) -> TokenStream {
                     let value = value.value;
                     let is_event = attr.starts_with("on_");
                     if is_event {
-                        let event = quote::format_ident!("{attr}");
-                        quote! {
-                            #[allow(unused_braces)]
-                            sauron::html::events::#event(#value)
-                        }
+                        event_to_tokens(&attr, value)
                     } else {
                         quote! {
                             #[allow(unused_braces)]
@@ -170,6 +178,107 @@ fn attribute_to_tokens(attribute: NodeAttribute) -> TokenStream {
     }
 }
 
+/// the options of the listener are appended to the event name, such as
+/// `on_submit:prevent_default={..}`, which is `on_submit(..).prevent_default()`
+fn event_to_tokens(attr: &str, value: Expr) -> TokenStream {
+    let mut parts = attr.split(':');
+    let event = quote::format_ident!("{}", parts.next().expect("must have an event name"));
+    let mut tokens = quote! {
+        #[allow(unused_braces)]
+        sauron::html::events::#event(#value)
+    };
+    for modifier in parts {
+        if !LISTENER_MODIFIERS.contains(&modifier) {
+            let message = format!(
+                "unknown listener option `{modifier}`, expecting one of {LISTENER_MODIFIERS:?}"
+            );
+            return quote! { compile_error!(#message) };
+        }
+        let modifier = quote::format_ident!("{modifier}");
+        tokens = quote! {
+            sauron::html::attributes::ListenerModifiers::#modifier(#tokens)
+        };
+    }
+    tokens
+}
+
+/// the path of the component when the tag is capitalized, such as `<FuiButton />`
+fn component_path(name: &NodeName) -> Option<&syn::Path> {
+    match name {
+        NodeName::Path(expr_path) => {
+            let last = expr_path.path.segments.last()?;
+            let is_capitalized = last
+                .ident
+                .to_string()
+                .starts_with(|ch: char| ch.is_ascii_uppercase());
+            is_capitalized.then_some(&expr_path.path)
+        }
+        _ => None,
+    }
+}
+
+/// A component is created with its `Default`, then each attribute is called as its builder
+/// method, such that `<FuiButton label="Ok" on_click={..} />` is
+/// `FuiButton::default().label("Ok").on_click(..)`.
+/// The `key` is set in the host element, while `map_msg` maps the external msgs of the
+/// component into the msgs of the parent.
+fn component_to_tokens(
+    path: &syn::Path,
+    attributes: Vec<NodeAttribute>,
+    children: Vec<Node>,
+) -> TokenStream {
+    if !children.is_empty() {
+        return quote! {
+            compile_error!("components can not have children")
+        };
+    }
+    let mut host_attributes = TokenStream::new();
+    let mut builder_calls = TokenStream::new();
+    let mut map_msg = quote! { |xmsg| xmsg };
+    for attribute in attributes {
+        match attribute {
+            NodeAttribute::Block(block) => host_attributes.extend(quote! {
+                #[allow(unused_braces)]
+                #block,
+            }),
+            NodeAttribute::Attribute(attribute) => {
+                let name = attribute.key.to_string();
+                let method = quote::format_ident!("{name}");
+                match attribute.possible_value {
+                    KeyedAttributeValue::Value(value) => {
+                        let value = value.value;
+                        match name.as_str() {
+                            "key" => host_attributes.extend(quote! {
+                                #[allow(unused_braces)]
+                                sauron::html::attributes::key(#value),
+                            }),
+                            "map_msg" => map_msg = quote! { #value },
+                            _ => builder_calls.extend(quote! {
+                                .#method(#value)
+                            }),
+                        }
+                    }
+                    KeyedAttributeValue::None => builder_calls.extend(quote! {
+                        .#method()
+                    }),
+                    KeyedAttributeValue::Binding(binding) => {
+                        return quote! {
+                            compile_error!("Function binding is not supported! {:?}",#binding)
+                        }
+                    }
+                }
+            }
+        }
+    }
+    quote! {
+        {
+            #[allow(unused_braces)]
+            let component = #path::default()#builder_calls;
+            sauron::html::component([#host_attributes], component, #map_msg)
+        }
+    }
+}
+
 fn braced_for_loop(block: &syn::Block) -> Option<&ExprForLoop> {
     let len = block.stmts.len();
     if len != 1 {
@@ -1,5 +1,5 @@
 #![deny(warnings)]
-use sauron::{dom::spawn_local, dom::Http, js_sys::TypeError, jss, *};
+use sauron::{dom::spawn_local, dom::Http, dom::HttpError, jss, *};
 use serde::Deserialize;
 
 #[macro_use]
@@ -14,7 +14,7 @@ pub enum Msg {
     PrevPage,
     ReceivedData(Data),
     JsonError(serde_json::Error),
-    RequestError(TypeError),
+    RequestError(HttpError),
 }
 
 pub struct App {
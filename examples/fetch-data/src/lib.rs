@@ -1,10 +1,8 @@
 #![deny(warnings)]
-use sauron::dom::spawn_local;
-use sauron::dom::Http;
+use sauron::dom::{Http, HttpError, Response};
 use sauron::html::attributes::*;
 use sauron::html::events::*;
 use sauron::html::*;
-use sauron::js_sys::TypeError;
 use sauron::{jss, text, wasm_bindgen, Application, Cmd, Node, Program};
 use serde::Deserialize;
 
@@ -18,9 +16,7 @@ const PER_PAGE: i32 = 4;
 pub enum Msg {
     NextPage,
     PrevPage,
-    ReceivedData(Data),
-    JsonError(serde_json::Error),
-    RequestError(TypeError),
+    ReceivedData(Result<Data, HttpError>),
 }
 
 pub struct App {
@@ -58,18 +54,7 @@ impl App {
 
     fn fetch_page(&self) -> Cmd<Self, Msg> {
         let url = format!("{}?page={}&per_page={}", DATA_URL, self.page, PER_PAGE);
-        Cmd::new(|mut program| {
-            spawn_local(async move {
-                let msg = match Http::fetch_text(&url).await {
-                    Ok(v) => match serde_json::from_str(&v) {
-                        Ok(data1) => Msg::ReceivedData(data1),
-                        Err(err) => Msg::JsonError(err),
-                    },
-                    Err(e) => Msg::RequestError(e),
-                };
-                program.dispatch(msg);
-            })
-        })
+        Cmd::http(Http::get(url), Response::json, Msg::ReceivedData)
     }
 }
 
@@ -155,21 +140,14 @@ impl Application<Msg> for App {
                 }
                 self.fetch_page()
             }
-            Msg::ReceivedData(data1) => {
+            Msg::ReceivedData(Ok(data1)) => {
                 self.data = data1;
+                self.error = None;
                 Cmd::none()
             }
-            Msg::JsonError(err) => {
+            Msg::ReceivedData(Err(err)) => {
                 trace!("Error fetching users! {:#?}", err);
-                self.error = Some(format!("There was an error fetching the page: {:?}", err));
-                Cmd::none()
-            }
-            Msg::RequestError(type_error) => {
-                trace!("Error requesting the page: {:?}", type_error);
-                self.error = Some(format!(
-                    "There was an error fetching the page: {:?}",
-                    type_error
-                ));
+                self.error = Some(format!("There was an error fetching the page: {}", err));
                 Cmd::none()
             }
         }
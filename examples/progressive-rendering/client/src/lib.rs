@@ -1,4 +1,4 @@
-use sauron::js_sys::TypeError;
+use sauron::dom::HttpError;
 use sauron::*;
 use sauron::jss;
 use serde::{Deserialize, Serialize};
@@ -24,7 +24,7 @@ pub enum Msg {
     EditName(String),
     ReceivedData(Data),
     JsonError(serde_json::Error),
-    RequestError(TypeError),
+    RequestError(HttpError),
     QueryAPI,
 }
 
@@ -207,13 +207,13 @@ pub fn main(serialized_state: String) {
         };
 
     /* If there's a window (i.e., if this is running in the browser)
-     * then mount the app by swapping out the <main> tag */
+     * then attach the app to the server rendered <main> tag */
     match web_sys::window() {
         Some(window) => {
-            trace!("found window, will try to replace <main>");
+            trace!("found window, will try to hydrate <main>");
             let document =
                 window.document().expect("should have a document on window");
-            Program::replace_mount(
+            Program::hydrate_mount(
                 app,
                 &document.query_selector_all("main").unwrap().get(0).unwrap(),
             );
@@ -1,6 +1,6 @@
 #![deny(warnings)]
 #![deny(clippy::all)]
-use sauron::{html::*, *};
+use sauron::{dom::Sub, html::*, *};
 
 pub enum Msg {
     WindowResized(i32, i32),
@@ -13,21 +13,15 @@ pub struct App {
 }
 
 impl Application<Msg> for App {
-    fn init(&mut self) -> Cmd<Self, Msg> {
-        Cmd::batch([
-            Cmd::from(Program::<Self, Msg>::on_resize_task(|w, h| {
-                log::info!("This will trigger only once..");
-                Msg::WindowResized(w, h)
-            })),
-            Cmd::new(|program| program.on_resize(|w, h| Msg::WindowResized(w, h))),
-        ])
+    fn subscriptions(&self) -> Sub<Msg> {
+        Sub::on_resize(Msg::WindowResized)
     }
 
     fn view(&self) -> Node<Msg> {
         sauron::html::main(
             [],
             [
-                h1([], [text("Usage of task")]),
+                h1([], [text("Usage of subscriptions")]),
                 ol(
                     [],
                     [
@@ -0,0 +1,219 @@
+#![deny(warnings)]
+#![cfg(not(target_arch = "wasm32"))]
+//! The patches of random old and new trees are applied to the old tree, which must turn it into
+//! the new tree
+use proptest::{collection::vec, prelude::*, sample::select};
+use sauron::{
+    html::{attributes::*, html_element},
+    vdom::{apply_patches, diff, WireEvents, WireNode, WirePatch},
+    *,
+};
+
+const TAGS: &[&str] = &["div", "p", "ul"];
+const WORDS: &[&str] = &["", "a", "b", "ab"];
+
+/// the element has the attributes in the order of their names, since the order of the added
+/// attributes is not kept in the patches
+fn normalized(mut node: Node<()>) -> Node<()> {
+    if let Node::Element(element) = &mut node {
+        element.attrs.sort_by_key(|att| att.name);
+        element.children = std::mem::take(&mut element.children)
+            .into_iter()
+            .map(normalized)
+            .collect();
+    }
+    node
+}
+
+fn assert_patched(old: &Node<()>, new: &Node<()>) -> Result<(), TestCaseError> {
+    let patches = diff(old, new);
+    let mut patched = old.clone();
+    apply_patches(&mut patched, &patches)
+        .map_err(|error| TestCaseError::fail(format!("{error}: {patches:#?}")))?;
+    prop_assert_eq!(
+        normalized(patched),
+        normalized(new.clone()),
+        "{:#?}",
+        patches
+    );
+    Ok(())
+}
+
+fn attributes() -> impl Strategy<Value = Vec<Attribute<()>>> {
+    (
+        prop::option::of(select(WORDS)),
+        vec(select(WORDS), 0..3),
+        any::<bool>(),
+    )
+        .prop_map(|(title_value, classes, hidden)| {
+            let mut attrs: Vec<_> = title_value.map(title).into_iter().collect();
+            attrs.extend(classes.into_iter().map(class));
+            if hidden {
+                attrs.push(attr("hidden", true));
+            }
+            attrs
+        })
+}
+
+/// a random tree of elements and texts
+fn node() -> impl Strategy<Value = Node<()>> {
+    let leaf = select(WORDS).prop_map(text);
+    leaf.prop_recursive(4, 64, 6, |inner| {
+        (select(TAGS), attributes(), vec(inner, 0..5))
+            .prop_map(|(tag, attrs, children)| html_element(None, tag, attrs, children, false))
+    })
+}
+
+/// a random tree, with keyed children in a random order
+fn keyed_node() -> impl Strategy<Value = Node<()>> {
+    let leaf = select(WORDS).prop_map(text);
+    leaf.prop_recursive(4, 64, 6, |inner| {
+        let keyed = (
+            Just((0..8).collect::<Vec<u8>>()).prop_shuffle(),
+            0..8usize,
+            vec(inner.clone(), 8),
+        )
+            .prop_map(|(keys, len, children)| {
+                keys.into_iter()
+                    .take(len)
+                    .zip(children)
+                    .map(|(k, child)| li([key(k)], [child]))
+                    .collect::<Vec<_>>()
+            });
+        prop_oneof![
+            (select(TAGS), attributes(), vec(inner, 0..5))
+                .prop_map(|(tag, attrs, children)| html_element(None, tag, attrs, children, false)),
+            (select(TAGS), attributes(), keyed)
+                .prop_map(|(tag, attrs, children)| html_element(None, tag, attrs, children, false)),
+        ]
+    })
+}
+
+fn keyed_list(keys: &[u8], words: &[&'static str]) -> Node<()> {
+    ul(
+        [class("list")],
+        keys.iter()
+            .map(|k| li([key(*k)], [text(words[*k as usize % words.len()])])),
+    )
+}
+
+/// a keyed list and the list with a block of its children removed or a block of new children
+/// inserted, the texts of the children are changed at random
+fn keyed_insert_or_remove() -> impl Strategy<Value = (Node<()>, Node<()>)> {
+    (
+        1..8u8,
+        0..8u8,
+        0..4u8,
+        any::<bool>(),
+        vec(select(WORDS), 8),
+        vec(select(WORDS), 8),
+    )
+        .prop_map(|(len, at, count, insert, old_words, new_words)| {
+            let old_keys: Vec<u8> = (0..len).collect();
+            let at = at.min(len) as usize;
+            let mut new_keys = old_keys.clone();
+            if insert {
+                new_keys.splice(at..at, (0..count).map(|k| 100 + k));
+            } else {
+                let end = (at + count as usize).min(len as usize);
+                new_keys.drain(at..end);
+            }
+            (
+                keyed_list(&old_keys, &old_words),
+                keyed_list(&new_keys, &new_words),
+            )
+        })
+}
+
+/// a keyed list with its children in a random order
+fn shuffled_keyed_list() -> impl Strategy<Value = Node<()>> {
+    (
+        Just((0..10).collect::<Vec<u8>>()).prop_shuffle(),
+        0..10usize,
+        vec(select(WORDS), 10),
+    )
+        .prop_map(|(keys, len, words)| keyed_list(&keys[..len], &words))
+}
+
+proptest! {
+    #![proptest_config(ProptestConfig::with_cases(512))]
+
+    #[test]
+    fn patches_turn_the_old_tree_into_the_new_tree(old in node(), new in node()) {
+        assert_patched(&old, &new)?;
+    }
+
+    #[test]
+    fn patches_insert_and_remove_the_keyed_children((old, new) in keyed_insert_or_remove()) {
+        assert_patched(&old, &new)?;
+    }
+
+    #[test]
+    fn patches_reorder_the_keyed_children(old in shuffled_keyed_list(), new in shuffled_keyed_list()) {
+        assert_patched(&old, &new)?;
+    }
+
+    #[test]
+    fn patches_turn_the_old_keyed_tree_into_the_new_tree(old in keyed_node(), new in keyed_node()) {
+        assert_patched(&old, &new)?;
+    }
+
+    #[test]
+    fn wire_patches_turn_the_old_tree_into_the_new_tree(old in node(), new in node()) {
+        let mut events = WireEvents::new();
+        let mut mirror = WireNode::encode(&old, &mut events).expect("must encode");
+        let patches = WirePatch::encode_all(&diff(&old, &new), &mut events).expect("must encode");
+        mirror.apply_patches(&patches).map_err(|error| TestCaseError::fail(error.to_string()))?;
+        // the values of the decoded attributes are owned, which are compared with the decoded new tree
+        let expected = WireNode::encode(&new, &mut WireEvents::new()).expect("must encode");
+        prop_assert_eq!(normalized(mirror.decode(|_, _| ()).expect("must decode")), normalized(expected.decode(|_, _| ()).expect("must decode")));
+    }
+}
+
+#[test]
+fn patches_change_and_reorder_the_middle_of_the_keyed_children() {
+    let cases: &[(&[u8], &[u8])] = &[
+        (&[0, 1, 2], &[0, 1, 3]),
+        (&[0, 1, 2], &[0, 3, 2]),
+        (&[0, 1, 2], &[2, 0, 1]),
+        (&[0, 1, 2], &[1, 2, 0]),
+        (&[0, 1, 2, 3], &[3, 1, 2, 0]),
+        (&[0, 1, 2, 3], &[0, 4, 2, 1, 5]),
+        (&[0, 1, 2], &[3, 4]),
+    ];
+    for (old_keys, new_keys) in cases {
+        let old = keyed_list(old_keys, &["a", "b"]);
+        let new = keyed_list(new_keys, &["b", "ab"]);
+        if let Err(error) = assert_patched(&old, &new) {
+            panic!("{old_keys:?} into {new_keys:?}: {error}");
+        }
+    }
+}
+
+#[test]
+fn patches_change_the_keyed_children_in_between_the_children_without_keys() {
+    let old: Node<()> = ul(
+        [],
+        [text("a"), li([key(1)], [text("b")]), text("c"), text("d")],
+    );
+    let new: Node<()> = ul([], [text("a"), text("d")]);
+    assert_patched(&old, &new).expect("must turn into the new list");
+}
+
+#[test]
+fn patches_of_another_tree_leave_the_node_unchanged() {
+    let old: Node<()> = div([], [p([], [text("a")]), p([], [text("b")])]);
+    let new: Node<()> = div([], [p([], [text("a")])]);
+    let mut other: Node<()> = div([], []);
+    assert!(apply_patches(&mut other, &diff(&old, &new)).is_err());
+    assert_eq!(other, div([], []));
+}
+
+#[test]
+fn added_attributes_of_the_same_name_are_kept() {
+    let old: Node<()> = div([class("a")], []);
+    let new: Node<()> = div([class("b"), class("c")], []);
+    let mut patched = old.clone();
+    apply_patches(&mut patched, &diff(&old, &new)).expect("must apply");
+    assert_eq!(patched, new);
+}
@@ -0,0 +1,120 @@
+#![deny(warnings)]
+use sauron::{
+    dom::{delay, MemoryDom, MountAction, MountTarget, Priority},
+    html::*,
+    *,
+};
+use std::{cell::RefCell, rc::Rc};
+use wasm_bindgen_test::*;
+
+wasm_bindgen_test_configure!(run_in_browser);
+
+/// an app which emits the cmd at init and records the msgs it receives
+struct Recorder {
+    cmd: Option<Cmd<Self, i32>>,
+    received: Rc<RefCell<Vec<i32>>>,
+}
+
+impl Application<i32> for Recorder {
+    fn init(&mut self) -> Cmd<Self, i32> {
+        self.cmd.take().unwrap_or_else(Cmd::none)
+    }
+
+    fn update(&mut self, msg: i32) -> Cmd<Self, i32> {
+        self.received.borrow_mut().push(msg);
+        Cmd::none()
+    }
+
+    fn view(&self) -> Node<i32> {
+        div([], [])
+    }
+}
+
+/// run the cmd in a program, returning the msgs it dispatched
+async fn run(cmd: Cmd<Recorder, i32>) -> Vec<i32> {
+    let received = Rc::new(RefCell::new(vec![]));
+    let _program = Program::mount_to_body(Recorder {
+        cmd: Some(cmd),
+        received: Rc::clone(&received),
+    });
+    delay(100).await;
+    let received = received.borrow().clone();
+    received
+}
+
+fn delayed(msg: i32, timeout: i32) -> Task<i32> {
+    Task::new(async move {
+        delay(timeout).await;
+        msg
+    })
+}
+
+#[wasm_bindgen_test]
+async fn batch_runs_concurrently() {
+    let cmd = Cmd::batch([Cmd::from(delayed(1, 30)), Cmd::from(delayed(2, 0))]);
+    assert_eq!(run(cmd).await, vec![2, 1]);
+}
+
+#[wasm_bindgen_test]
+async fn sequence_waits_for_the_previous_cmd() {
+    let cmd = Cmd::sequence([
+        Cmd::from(delayed(1, 30)),
+        Cmd::batch_msg([2, 3]),
+        Cmd::from(delayed(4, 0)),
+    ]);
+    assert_eq!(run(cmd).await, vec![1, 2, 3, 4]);
+}
+
+#[wasm_bindgen_test]
+async fn and_then_waits_for_the_previous_cmd() {
+    let cmd = Cmd::from(delayed(1, 30)).and_then(Cmd::from(delayed(2, 0)));
+    assert_eq!(run(cmd).await, vec![1, 2]);
+}
+
+#[wasm_bindgen_test]
+async fn map_msg_lifts_the_child_cmd() {
+    let child_cmd: MsgCmd<&'static str> = MsgCmd::sequence([
+        MsgCmd::from(Task::new(async { "hello" })),
+        MsgCmd::batch_msg(["world!"]),
+    ]);
+    let cmd = Cmd::from(child_cmd.map_msg(|msg: &'static str| msg.len() as i32));
+    assert_eq!(run(cmd).await, vec![5, 6]);
+}
+
+#[wasm_bindgen_test]
+async fn aborted_cmd_does_not_dispatch() {
+    let (search, handle) = Cmd::from(delayed(1, 30)).abortable();
+    let abort = Cmd::from(Task::new(async move {
+        handle.abort();
+        2
+    }));
+    assert_eq!(run(Cmd::batch([search, abort])).await, vec![2]);
+}
+
+#[test]
+fn mapped_msgs_keep_their_priority() {
+    let received = Rc::new(RefCell::new(vec![]));
+    let child_cmd: MsgCmd<&'static str> = MsgCmd::sequence([
+        MsgCmd::batch_msg(["hello"]).with_priority(Priority::Background),
+        // `with-ric` and `with-raf` change the priority of the msgs dispatched without one
+        MsgCmd::batch_msg(["hi"]).with_priority(Priority::UserInput),
+    ]);
+    let dom = MemoryDom::new();
+    let mut program = Program::with_backend(
+        Recorder {
+            cmd: Some(Cmd::from(
+                child_cmd.map_msg(|msg: &'static str| msg.len() as i32),
+            )),
+            received: Rc::clone(&received),
+        },
+        dom.clone(),
+        &dom.body(),
+        MountAction::Append,
+        MountTarget::MountNode,
+    );
+    program.mount().expect("must mount");
+    dom.run_until_stalled();
+    assert_eq!(*received.borrow(), vec![2]);
+    dom.settle();
+    assert_eq!(*received.borrow(), vec![2, 5]);
+}
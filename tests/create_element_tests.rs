@@ -21,7 +21,7 @@ wasm_bindgen_test_configure!(run_in_browser);
 fn nested_divs() {
     let vdiv: Node<()> = div(vec![], vec![div(vec![], vec![div(vec![], vec![])])]); // <div> <div> <div></div> </div> </div>
     let program = simple_program();
-    let created_node = program.create_dom_node(&vdiv);
+    let created_node = program.create_dom_node(&vdiv).expect("must create");
     let div: Element = created_node.unchecked_into();
 
     assert_eq!(&div.inner_html(), "<div><div></div></div>");
@@ -36,7 +36,9 @@ fn svg_element() {
             vec![circle(vec![cx("50"), cy("50"), r("50")], vec![])],
         )],
     );
-    let created_node = simple_program().create_dom_node(&vdiv);
+    let created_node = simple_program()
+        .create_dom_node(&vdiv)
+        .expect("must create");
     let div: Element = created_node.unchecked_into();
 
     assert_eq!(
@@ -49,7 +51,9 @@ fn svg_element() {
 fn div_with_attributes() {
     let vdiv: Node<()> = div(vec![id("id-here"), class("two classes")], vec![]);
 
-    let created_node = simple_program().create_dom_node(&vdiv);
+    let created_node = simple_program()
+        .create_dom_node(&vdiv)
+        .expect("must create");
     let div: Element = created_node.unchecked_into();
 
     assert_eq!(&div.id(), "id-here");
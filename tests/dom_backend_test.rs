@@ -0,0 +1,329 @@
+#![deny(warnings)]
+use sauron::{
+    dom::{DomBackend, MemoryDom, MountAction, MountTarget, Priority},
+    html::{attributes::*, events::*, *},
+    *,
+};
+
+#[derive(Debug, Clone, PartialEq)]
+enum Msg {
+    Increment,
+    Rename(String),
+    Reverse,
+    Reorder(Vec<usize>),
+    Loaded(usize),
+    Mounted,
+}
+
+#[derive(Default)]
+struct App {
+    count: usize,
+    name: String,
+    items: Vec<usize>,
+    mounted: usize,
+}
+
+impl Application<Msg> for App {
+    fn update(&mut self, msg: Msg) -> Cmd<Self, Msg> {
+        match msg {
+            Msg::Increment => self.count += 1,
+            Msg::Rename(name) => self.name = name,
+            Msg::Reverse => self.items.reverse(),
+            Msg::Reorder(items) => self.items = items,
+            Msg::Loaded(n) => self.items.extend(0..n),
+            Msg::Mounted => self.mounted += 1,
+        }
+        Cmd::none()
+    }
+
+    fn view(&self) -> Node<Msg> {
+        div(
+            [class("app")],
+            [
+                button(
+                    [class("inc"), on_synthetic("click", |_| Msg::Increment)],
+                    [text("+")],
+                ),
+                // the listener of the web event is skipped in the memory dom
+                button([class("web"), on_click(|_| Msg::Increment)], [text("+")]),
+                span([class("count")], [text(self.count)]),
+                input(
+                    [
+                        class("name"),
+                        value(&self.name),
+                        on_synthetic("input", |e| Msg::Rename(e.value.unwrap_or_default())),
+                    ],
+                    [],
+                ),
+                span([class("greeting")], [text(format!("Hello {}", self.name))]),
+                ul(
+                    [class("items")],
+                    self.items.iter().map(|item| li([key(*item)], [text(item)])),
+                ),
+                view_if(
+                    self.count > 0,
+                    p(
+                        [class("banner"), on_synthetic("mount", |_| Msg::Mounted)],
+                        [text("clicked")],
+                    ),
+                ),
+            ],
+        )
+    }
+}
+
+fn mount(dom: &MemoryDom, action: MountAction) -> Program<App, Msg, MemoryDom> {
+    let mut program = Program::with_backend(
+        App::default(),
+        dom.clone(),
+        &dom.body(),
+        action,
+        MountTarget::MountNode,
+    );
+    program.mount().expect("must mount");
+    program
+}
+
+fn items(dom: &MemoryDom) -> Vec<String> {
+    dom.query_selector_all("ul.items li")
+        .iter()
+        .map(|li| li.text_content())
+        .collect()
+}
+
+#[test]
+fn mount_appends_the_view() {
+    let dom = MemoryDom::new();
+    let existing = dom.body();
+    let _program = mount(&dom, MountAction::Append);
+    assert_eq!(dom.body(), existing);
+    let app = dom.query_selector("body div.app").expect("must be mounted");
+    assert_eq!(
+        app.query_selector("span.count").unwrap().text_content(),
+        "0"
+    );
+    assert_eq!(app.query_selector("ul.items").unwrap().inner_html(), "");
+}
+
+#[test]
+fn clear_append_and_replace_the_mount_node() {
+    let dom = MemoryDom::new();
+    let _first = mount(&dom, MountAction::Append);
+    let _second = mount(&dom, MountAction::ClearAppend);
+    assert_eq!(dom.query_selector_all("div.app").len(), 1);
+
+    let dom = MemoryDom::new();
+    let mut program = mount(&dom, MountAction::Replace);
+    assert!(dom.query_selector("body").is_none());
+    assert_eq!(
+        dom.document_element().children()[1].tag_name().as_deref(),
+        Some("div")
+    );
+
+    // the replaced mount node is restored
+    program.unmount();
+    assert!(dom.query_selector("div.app").is_none());
+    assert!(dom.query_selector("body").is_some());
+}
+
+#[test]
+fn events_update_the_view() {
+    let dom = MemoryDom::new();
+    let program = mount(&dom, MountAction::Append);
+
+    dom.click("button.inc");
+    dom.click("button.inc");
+    dom.click("button.web");
+    dom.settle();
+    assert_eq!(program.app().count, 2);
+    assert_eq!(
+        dom.query_selector("span.count").unwrap().text_content(),
+        "2"
+    );
+    // the mount listener of the created banner is called once
+    assert_eq!(program.app().mounted, 1);
+    let banner = dom.query_selector("p.banner").unwrap();
+    assert!(banner.attribute("data-vdom-id").is_some());
+    assert_eq!(banner.text_content(), "clicked");
+
+    dom.input("input.name", "sauron");
+    dom.settle();
+    assert_eq!(
+        dom.query_selector("span.greeting").unwrap().text_content(),
+        "Hello sauron"
+    );
+    assert_eq!(
+        dom.query_selector("input.name").unwrap().property("value"),
+        Some(Value::from("sauron"))
+    );
+}
+
+#[test]
+fn keyed_children_are_patched_in_place() {
+    let dom = MemoryDom::new();
+    let mut program = mount(&dom, MountAction::Append);
+    program.dispatch(Msg::Loaded(4));
+    dom.settle();
+    assert_eq!(items(&dom), ["0", "1", "2", "3"]);
+
+    let first = dom.query_selector("ul.items li").unwrap();
+    program.dispatch(Msg::Reverse);
+    dom.settle();
+    assert_eq!(items(&dom), ["3", "2", "1", "0"]);
+    // the keyed node is moved instead of recreated
+    assert_eq!(dom.query_selector_all("ul.items li")[3], first);
+}
+
+#[test]
+fn keyed_children_are_moved_and_inserted_in_the_middle() {
+    let dom = MemoryDom::new();
+    let mut program = mount(&dom, MountAction::Append);
+    program.dispatch(Msg::Loaded(4));
+    dom.settle();
+    let first = dom.query_selector("ul.items li").unwrap();
+
+    // the children after the last child in place are moved after it in their order
+    program.dispatch(Msg::Reorder(vec![2, 3, 1, 0]));
+    dom.settle();
+    assert_eq!(items(&dom), ["2", "3", "1", "0"]);
+    assert_eq!(dom.query_selector_all("ul.items li")[3], first);
+
+    program.dispatch(Msg::Reorder(vec![2, 5, 0, 4, 3]));
+    dom.settle();
+    assert_eq!(items(&dom), ["2", "5", "0", "4", "3"]);
+    assert_eq!(dom.query_selector_all("ul.items li")[2], first);
+}
+
+#[test]
+fn background_patches_are_applied_in_frames() {
+    let dom = MemoryDom::new();
+    let mut program = mount(&dom, MountAction::Append);
+    program.set_frame_budget(0.0);
+    program.dispatch_with_priority(Msg::Increment, Priority::Background);
+    assert_eq!(dom.run_animation_frames(), 0);
+
+    let is_patched = || {
+        dom.query_selector("span.count").unwrap().text_content() == "1"
+            && dom.query_selector("p.banner").is_some()
+    };
+    assert_eq!(dom.run_idle_callbacks(), 1);
+    assert_eq!(program.app().count, 1);
+    // a patch is applied in each slice of the frame budget, the rest are pending
+    assert!(!is_patched());
+    let mut frames = 0;
+    while dom.run_animation_frames() > 0 {
+        frames += 1;
+    }
+    assert!(frames > 0);
+    assert!(is_patched());
+    // the mount msg is dispatched with the default priority, which waits for an idle callback
+    // with `with-ric`
+    dom.settle();
+    assert_eq!(program.app().mounted, 1);
+}
+
+#[test]
+fn unmount_removes_the_view_and_listeners() {
+    let dom = MemoryDom::new();
+    let mut program = mount(&dom, MountAction::Append);
+    let button = dom.query_selector("button.inc").unwrap();
+    program.unmount();
+    assert!(dom.query_selector("div.app").is_none());
+    assert!(program.node_closures.borrow().is_empty());
+
+    // the detached button no longer dispatches into the program
+    dom.fire_event(&button, "click", Default::default());
+    dom.settle();
+    assert_eq!(program.app().count, 0);
+}
+
+/// a view whose adjacent text nodes are rendered with a separator comment in between
+struct Greeting {
+    count: usize,
+}
+
+impl Application<Msg> for Greeting {
+    fn update(&mut self, msg: Msg) -> Cmd<Self, Msg> {
+        if msg == Msg::Increment {
+            self.count += 1;
+        }
+        Cmd::none()
+    }
+
+    fn view(&self) -> Node<Msg> {
+        fragment([
+            text("Hello"),
+            text("world"),
+            button(
+                [on_synthetic("click", |_| Msg::Increment)],
+                [text(self.count)],
+            ),
+        ])
+    }
+}
+
+#[test]
+fn hydration_removes_the_separators_of_the_text_nodes() {
+    let dom = MemoryDom::new();
+    let view = Greeting { count: 0 }.view();
+    assert!(view
+        .render_to_string()
+        .starts_with("Hello<!--separator-->world<button"));
+    // the nodes parsed from the server rendered html
+    let container = dom.create_element("div", None).unwrap();
+    let button = dom.create_element("button", None).unwrap();
+    dom.append_child(&button, &dom.create_text_node("0"))
+        .unwrap();
+    for node in [
+        dom.create_text_node("Hello"),
+        dom.create_comment("separator"),
+        dom.create_text_node("world"),
+        button,
+    ] {
+        dom.append_child(&container, &node).unwrap();
+    }
+    dom.append_child(&dom.body(), &container).unwrap();
+
+    let mut program = Program::with_backend(
+        Greeting { count: 0 },
+        dom.clone(),
+        &container,
+        MountAction::Hydrate,
+        MountTarget::MountNode,
+    );
+    program.mount().expect("must hydrate");
+    assert_eq!(container.children().len(), 3);
+    assert!(container.inner_html().starts_with("Helloworld<button"));
+
+    // the patches find the button at its index in the view
+    dom.click("button");
+    dom.settle();
+    assert_eq!(program.app().count, 1);
+    assert_eq!(container.text_content(), "Helloworld1");
+}
+
+/// a view with a doctype, which is only used in rendering
+struct Page;
+
+impl Application<Msg> for Page {
+    fn update(&mut self, _msg: Msg) -> Cmd<Self, Msg> {
+        Cmd::none()
+    }
+
+    fn view(&self) -> Node<Msg> {
+        fragment([doctype("html"), html([], [])])
+    }
+}
+
+#[test]
+fn doctype_in_the_view_fails_the_mount() {
+    let dom = MemoryDom::new();
+    let mut program = Program::with_backend(
+        Page,
+        dom.clone(),
+        &dom.body(),
+        MountAction::Append,
+        MountTarget::MountNode,
+    );
+    assert_eq!(program.mount(), Err(Error::InvalidNode("a doctype")));
+}
@@ -0,0 +1,145 @@
+#![deny(warnings)]
+use sauron::{
+    dom::{self, util::document, TestProgram},
+    html::{attributes::*, error_boundary, try_error_boundary, ViewError},
+    *,
+};
+use wasm_bindgen_test::*;
+
+wasm_bindgen_test_configure!(run_in_browser);
+
+#[derive(Debug, Clone, PartialEq)]
+enum Msg {
+    Select(usize),
+    Failed(String),
+}
+
+#[derive(Default)]
+struct Dashboard {
+    widgets: Vec<&'static str>,
+    selected: usize,
+    errors: Vec<String>,
+}
+
+impl Application<Msg> for Dashboard {
+    fn update(&mut self, msg: Msg) -> Cmd<Self, Msg> {
+        match msg {
+            Msg::Select(selected) => self.selected = selected,
+            Msg::Failed(error) => {
+                self.errors.push(error);
+                return Cmd::none().no_render();
+            }
+        }
+        Cmd::none()
+    }
+
+    fn view(&self) -> Node<Msg> {
+        div(
+            [id("dashboard")],
+            [
+                h1([], [text("dashboard")]),
+                error_boundary(fallback, || {
+                    span([class("selected")], [text(self.widgets[self.selected])])
+                }),
+                try_error_boundary(fallback, || {
+                    self.widgets
+                        .first()
+                        .map(|widget| span([class("first")], [text(widget)]))
+                        .ok_or("there are no widgets")
+                }),
+            ],
+        )
+    }
+
+    fn on_error(&self, error: Error) -> Option<Msg> {
+        Some(Msg::Failed(error.to_string()))
+    }
+}
+
+fn fallback(error: &ViewError) -> Node<Msg> {
+    p([class("broken")], [text(&error.message)])
+}
+
+#[test]
+fn failed_views_render_the_fallback() {
+    let mut program = TestProgram::new(Dashboard {
+        widgets: vec!["clock", "weather"],
+        ..Default::default()
+    });
+    assert_eq!(program.text_content(".selected").as_deref(), Some("clock"));
+    assert_eq!(program.text_content(".first").as_deref(), Some("clock"));
+    assert!(program.app().errors.is_empty());
+
+    program.dispatch(Msg::Select(5));
+    assert_eq!(program.text_content("h1").as_deref(), Some("dashboard"));
+    assert_eq!(
+        program.text_content(".broken").as_deref(),
+        Some("index out of bounds: the len is 2 but the index is 5")
+    );
+    assert_eq!(
+        program.app().errors,
+        ["the view panicked: index out of bounds: the len is 2 but the index is 5"]
+    );
+}
+
+#[test]
+fn returned_errors_render_the_fallback() {
+    let program = TestProgram::new(Dashboard {
+        widgets: vec![],
+        ..Default::default()
+    });
+    assert_eq!(
+        program
+            .find_all(".broken")
+            .iter()
+            .map(|node| node.render_to_string())
+            .collect::<Vec<_>>(),
+        [
+            r#"<p class="broken">index out of bounds: the len is 0 but the index is 0</p>"#,
+            r#"<p class="broken">there are no widgets</p>"#,
+        ]
+    );
+    assert_eq!(
+        program.app().errors,
+        [
+            "the view panicked: index out of bounds: the len is 0 but the index is 0",
+            "the view failed: there are no widgets",
+        ]
+    );
+}
+
+#[test]
+fn failures_outside_of_a_program_are_not_reported() {
+    let broken = Dashboard::default();
+    for _ in 0..3 {
+        assert!(broken
+            .view()
+            .render_to_string()
+            .contains("there are no widgets"));
+    }
+    let program = TestProgram::new(Dashboard {
+        widgets: vec!["clock"],
+        ..Default::default()
+    });
+    assert!(program.app().errors.is_empty());
+}
+
+#[wasm_bindgen_test]
+async fn program_reports_the_failed_views() {
+    console_error_panic_hook::set_once();
+    let mut program = Program::mount_to_body(Dashboard {
+        widgets: vec!["clock"],
+        ..Default::default()
+    });
+    program.dispatch(Msg::Select(1));
+    dom::delay(50).await;
+    let broken = document()
+        .query_selector("#dashboard .broken")
+        .expect("must query")
+        .expect("must render the fallback");
+    assert_eq!(
+        broken.text_content().as_deref(),
+        Some("index out of bounds: the len is 1 but the index is 1")
+    );
+    assert_eq!(program.app().errors.len(), 1);
+}
@@ -0,0 +1,109 @@
+#![deny(warnings)]
+use sauron::{
+    dom::{delay, util::document},
+    html::{attributes::*, events::*, *},
+    *,
+};
+use wasm_bindgen::JsCast;
+use wasm_bindgen_test::*;
+
+wasm_bindgen_test_configure!(run_in_browser);
+
+#[derive(Debug, PartialEq)]
+enum Msg {
+    Break,
+    Failed(Error),
+}
+
+#[derive(Default)]
+struct App {
+    broken: bool,
+    errors: Vec<Error>,
+}
+
+impl Application<Msg> for App {
+    fn update(&mut self, msg: Msg) -> Cmd<Self, Msg> {
+        match msg {
+            Msg::Break => self.broken = true,
+            Msg::Failed(error) => self.errors.push(error),
+        }
+        Cmd::none()
+    }
+
+    fn view(&self) -> Node<Msg> {
+        let invalid = self.broken.then(|| attr("bad name", "x"));
+        div(
+            [id("error-app")],
+            [
+                button([class("break"), on_click(|_| Msg::Break)], [text("break")]),
+                span(invalid, [text("target")]),
+            ],
+        )
+    }
+
+    fn on_error(&self, error: Error) -> Option<Msg> {
+        Some(Msg::Failed(error))
+    }
+}
+
+struct Silent;
+
+impl Application<()> for Silent {
+    fn update(&mut self, _msg: ()) -> Cmd<Self, ()> {
+        Cmd::none()
+    }
+
+    fn view(&self) -> Node<()> {
+        text("silent")
+    }
+}
+
+#[test]
+fn errors_are_displayed_with_their_context() {
+    assert_eq!(
+        Error::SetAttribute {
+            tag: "span".to_string(),
+            name: "bad name".to_string(),
+            reason: "invalid character".to_string(),
+        }
+        .to_string(),
+        "unable to set the attribute `bad name` of `<span>`: invalid character"
+    );
+    assert_eq!(
+        Error::TagMismatch {
+            path: vec![0, 1],
+            expected: "div".to_string(),
+            found: "span".to_string(),
+        }
+        .to_string(),
+        "expecting a `<div>` at [0, 1], but found `<span>`"
+    );
+    assert_eq!(
+        Error::PendingPatches(2).to_string(),
+        "there are still 2 pending patches"
+    );
+}
+
+#[test]
+fn on_error_defaults_to_no_msg() {
+    assert_eq!(Silent.on_error(Error::NotMounted), None);
+}
+
+#[wasm_bindgen_test]
+async fn invalid_attribute_is_passed_to_on_error() {
+    console_error_panic_hook::set_once();
+    let program = Program::mount_to_body(App::default());
+    let button: web_sys::HtmlElement = document()
+        .query_selector("#error-app .break")
+        .unwrap()
+        .expect("must be mounted")
+        .unchecked_into();
+    button.click();
+    delay(50).await;
+
+    assert!(program.app().broken);
+    assert!(matches!(
+        program.app().errors.as_slice(),
+        [Error::SetAttribute { tag, name, .. }] if tag == "span" && name == "bad name"
+    ));
+}
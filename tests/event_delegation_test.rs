@@ -0,0 +1,125 @@
+#![deny(warnings)]
+use sauron::{
+    dom::{delay, util::document},
+    html::{attributes::*, events::*, *},
+    *,
+};
+use wasm_bindgen::JsCast;
+use wasm_bindgen_test::*;
+
+wasm_bindgen_test_configure!(run_in_browser);
+
+#[derive(Debug, PartialEq)]
+enum Msg {
+    Row(usize),
+    Table,
+    Stopped,
+    Added,
+    After,
+}
+
+#[derive(Default)]
+struct Table {
+    rows: usize,
+    received: Vec<Msg>,
+}
+
+impl Application<Msg> for Table {
+    fn update(&mut self, msg: Msg) -> Cmd<Self, Msg> {
+        if msg == Msg::Added {
+            self.rows += 1;
+        }
+        self.received.push(msg);
+        Cmd::none()
+    }
+
+    fn view(&self) -> Node<Msg> {
+        div(
+            [id("delegation-table"), on_click(|_| Msg::Table)],
+            [
+                ul(
+                    [],
+                    (0..self.rows).map(|row| {
+                        li(
+                            [class("row"), on_click(move |_| Msg::Row(row))],
+                            [span([], [text(row)])],
+                        )
+                    }),
+                ),
+                button(
+                    [
+                        class("stop"),
+                        on_click(|event| {
+                            event.stop_propagation();
+                            Msg::Stopped
+                        }),
+                    ],
+                    [text("stop")],
+                ),
+                button([class("add"), on_click(|_| Msg::Added)], [text("add")]),
+                // the fragment and the safe html are unrolled into several DOM nodes
+                fragment([span([], [text("one")]), span([], [text("two")])]),
+                safe_html("<i>three</i><i>four</i>"),
+                button([class("after"), on_click(|_| Msg::After)], [text("after")]),
+            ],
+        )
+    }
+}
+
+fn click(selector: &str) {
+    let element: web_sys::HtmlElement = document()
+        .query_selector(selector)
+        .unwrap()
+        .unwrap_or_else(|| panic!("must have {selector}"))
+        .unchecked_into();
+    element.click();
+}
+
+#[wasm_bindgen_test]
+async fn events_bubble_up_to_the_listeners_of_the_ancestors() {
+    console_error_panic_hook::set_once();
+    let mut program = Program::mount_to_body(Table {
+        rows: 2,
+        ..Default::default()
+    });
+
+    click("#delegation-table .row:nth-child(2) span");
+    delay(50).await;
+    assert_eq!(program.app().received, [Msg::Row(1), Msg::Table]);
+
+    click("#delegation-table .stop");
+    delay(50).await;
+    assert_eq!(program.app().received[2..], [Msg::Stopped]);
+
+    click("#delegation-table .add");
+    delay(50).await;
+    click("#delegation-table .row:nth-child(3)");
+    delay(50).await;
+    assert_eq!(
+        program.app().received[3..],
+        [Msg::Added, Msg::Table, Msg::Row(2), Msg::Table]
+    );
+
+    #[cfg(feature = "with-event-delegation")]
+    assert!(document()
+        .query_selector("#delegation-table [data-vdom-id]")
+        .unwrap()
+        .is_none());
+    program.unmount();
+}
+
+#[wasm_bindgen_test]
+async fn listeners_after_the_fragments_are_found() {
+    console_error_panic_hook::set_once();
+    let mut program = Program::mount_to_body(Table::default());
+
+    click("#delegation-table .after");
+    delay(50).await;
+    assert_eq!(program.app().received, [Msg::After, Msg::Table]);
+
+    // the nodes of the safe html are not in the view, only the ancestors are called
+    click("#delegation-table i");
+    delay(50).await;
+    assert_eq!(program.app().received[2..], [Msg::Table]);
+    program.unmount();
+}
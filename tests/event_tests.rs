@@ -301,3 +301,30 @@ fn remove_event_from_replaced_node() {
         "There should only be 0 left after replacing it with a different tag"
     );
 }
+
+#[wasm_bindgen_test]
+fn synthetic_event_has_the_values_of_the_keyboard_and_mouse_events() {
+    let init = web_sys::KeyboardEventInit::new();
+    init.set_key("Enter");
+    init.set_code("NumpadEnter");
+    init.set_shift_key(true);
+    let keydown = web_sys::KeyboardEvent::new_with_keyboard_event_init_dict("keydown", &init)
+        .expect("must create the keyboard event");
+    let synthetic = dom::SyntheticEvent::from_web_event(&keydown);
+    assert_eq!(synthetic.key.as_deref(), Some("Enter"));
+    assert_eq!(synthetic.code.as_deref(), Some("NumpadEnter"));
+    assert!(synthetic.shift_key && !synthetic.ctrl_key);
+
+    let init = web_sys::MouseEventInit::new();
+    init.set_client_x(10);
+    init.set_client_y(20);
+    init.set_button(2);
+    init.set_ctrl_key(true);
+    let click = web_sys::MouseEvent::new_with_mouse_event_init_dict("click", &init)
+        .expect("must create the mouse event");
+    let synthetic = dom::SyntheticEvent::from_web_event(&click);
+    assert_eq!((synthetic.client_x, synthetic.client_y), (10, 20));
+    assert_eq!(synthetic.button, 2);
+    assert!(synthetic.ctrl_key && !synthetic.shift_key);
+    assert_eq!(synthetic.key, None);
+}
@@ -0,0 +1,137 @@
+#![deny(warnings)]
+use futures::executor::block_on;
+use sauron::dom::{AbortHandle, Http, HttpError, Method, MockTransport, Response};
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+struct User {
+    id: u32,
+    name: String,
+}
+
+#[derive(Debug, PartialEq)]
+enum Msg {
+    ReceivedUser(Result<User, HttpError>),
+    ReceivedText(Result<String, HttpError>),
+}
+
+#[test]
+fn request_builder() {
+    let request = Http::post("/users")
+        .header("Authorization", "Bearer token")
+        .json(&User {
+            id: 1,
+            name: "Alice".to_string(),
+        })
+        .timeout(Duration::from_secs(5));
+
+    assert_eq!(request.method(), Method::Post);
+    assert_eq!(request.url(), "/users");
+    assert_eq!(
+        request.headers(),
+        [
+            ("Authorization".to_string(), "Bearer token".to_string()),
+            ("Content-Type".to_string(), "application/json".to_string()),
+        ]
+    );
+    assert_eq!(
+        request.body_bytes(),
+        Some(&br#"{"id":1,"name":"Alice"}"#[..])
+    );
+    assert_eq!(request.timeout_duration(), Some(Duration::from_secs(5)));
+}
+
+#[test]
+fn form_body() {
+    let request = Http::put("/users/1").form(&[("name", "Alice Smith"), ("id", "1")]);
+    assert_eq!(request.body_bytes(), Some(&b"name=Alice+Smith&id=1"[..]));
+}
+
+#[test]
+fn decode_json_response() {
+    let transport = MockTransport::new();
+    transport.respond(
+        Method::Get,
+        "/users/1",
+        Response::new(200, r#"{"id":1,"name":"Alice"}"#),
+    );
+    let task = Http::get("/users/1").task_with(&transport, Response::json, Msg::ReceivedUser);
+
+    assert_eq!(
+        block_on(async { task.await }),
+        Msg::ReceivedUser(Ok(User {
+            id: 1,
+            name: "Alice".to_string()
+        }))
+    );
+    assert_eq!(transport.requests().len(), 1);
+    assert_eq!(transport.requests()[0].url(), "/users/1");
+}
+
+#[test]
+fn decode_error() {
+    let transport = MockTransport::new();
+    transport.respond(Method::Get, "/users/1", Response::new(200, "not json"));
+    let task = Http::get("/users/1").task_with(&transport, Response::json, Msg::ReceivedUser);
+
+    assert!(matches!(
+        block_on(async { task.await }),
+        Msg::ReceivedUser(Err(HttpError::Decode(_)))
+    ));
+}
+
+#[test]
+fn non_utf8_text_is_a_decode_error() {
+    let transport = MockTransport::new();
+    transport.respond(Method::Get, "/", Response::new(200, vec![0xff, 0xfe]));
+    let task = Http::get("/").task_with(&transport, Response::text, Msg::ReceivedText);
+
+    assert!(matches!(
+        block_on(async { task.await }),
+        Msg::ReceivedText(Err(HttpError::Decode(_)))
+    ));
+}
+
+#[test]
+fn unsuccessful_status_is_an_error() {
+    let transport = MockTransport::new();
+    let not_found = Response::new(404, "not found").header("Content-Type", "text/plain");
+    transport.respond(Method::Get, "/users/2", not_found.clone());
+    let task = Http::get("/users/2").task_with(&transport, Response::json, Msg::ReceivedUser);
+
+    assert_eq!(
+        block_on(async { task.await }),
+        Msg::ReceivedUser(Err(HttpError::Status(not_found)))
+    );
+}
+
+#[test]
+fn network_error() {
+    let transport = MockTransport::new();
+    transport.fail(
+        Method::Delete,
+        "/users/1",
+        HttpError::Network("offline".to_string()),
+    );
+    let response = block_on(Http::delete("/users/1").send_with(&transport));
+    assert_eq!(response, Err(HttpError::Network("offline".to_string())));
+
+    let response = block_on(Http::get("/unknown").send_with(&transport));
+    assert!(matches!(response, Err(HttpError::Network(_))));
+}
+
+#[test]
+fn aborted_request() {
+    let transport = MockTransport::new();
+    transport.respond(Method::Get, "/search?q=a", Response::new(200, "a"));
+    let abort_handle = AbortHandle::default();
+    let request = Http::get("/search?q=a").abort_handle(&abort_handle);
+    abort_handle.abort();
+
+    assert_eq!(
+        block_on(request.send_with(&transport)),
+        Err(HttpError::Aborted)
+    );
+    assert!(transport.requests().is_empty());
+}
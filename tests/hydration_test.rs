@@ -0,0 +1,77 @@
+#![deny(warnings)]
+use sauron::{html::attributes::*, html::events::*, html::*, *};
+use wasm_bindgen::JsCast;
+use wasm_bindgen_test::*;
+use web_sys::{Element, HtmlElement};
+
+wasm_bindgen_test_configure!(run_in_browser);
+
+struct Counter {
+    count: i32,
+}
+
+enum Msg {
+    Increment,
+}
+
+impl Application<Msg> for Counter {
+    fn update(&mut self, msg: Msg) -> Cmd<Self, Msg> {
+        match msg {
+            Msg::Increment => self.count += 1,
+        }
+        Cmd::none()
+    }
+
+    fn view(&self) -> Node<Msg> {
+        main(
+            [id("counter")],
+            [
+                label([], [text("Count:"), text(self.count)]),
+                button([id("increment"), on_click(|_| Msg::Increment)], [text("+")]),
+            ],
+        )
+    }
+}
+
+/// render the app in a container, as if it was rendered in the server
+fn server_render(html: &str) -> Element {
+    let container = document().create_element("div").unwrap();
+    container.set_inner_html(html);
+    document().body().unwrap().append_child(&container).unwrap();
+    container.first_element_child().expect("must have the root")
+}
+
+#[wasm_bindgen_test]
+fn hydrate_pretty_rendered_html() {
+    console_error_panic_hook::set_once();
+    let mount = server_render(&Counter { count: 1 }.view().render_to_string_pretty());
+    let button = mount.query_selector("#increment").unwrap().unwrap();
+
+    let _program = Program::hydrate_mount(Counter { count: 1 }, &mount);
+
+    // the existing nodes are reused
+    assert_eq!(
+        Some(button.clone()),
+        mount.query_selector("#increment").unwrap()
+    );
+    // the whitespace added by the pretty rendering are removed
+    assert_eq!(
+        mount.inner_html(),
+        Counter { count: 1 }.view().render_to_string()[r#"<main id="counter">"#.len()..]
+            .trim_end_matches("</main>")
+    );
+
+    button.unchecked_ref::<HtmlElement>().click();
+    let label = mount.query_selector("label").unwrap().unwrap();
+    assert_eq!(label.text_content().unwrap(), "Count:2");
+}
+
+#[wasm_bindgen_test]
+fn hydrate_mismatched_html_keeps_the_existing_nodes() {
+    console_error_panic_hook::set_once();
+    let mount = server_render(r#"<main id="counter"><p>Loading</p></main>"#);
+
+    let _program = Program::hydrate_mount(Counter { count: 1 }, &mount);
+
+    assert_eq!(mount.inner_html(), "<p>Loading</p>");
+}
@@ -0,0 +1,136 @@
+#![deny(warnings)]
+#![cfg(feature = "with-inspector")]
+use sauron::{
+    dom::{delay, util::document, InspectedNode, Inspector, InspectorMsg, Measurements},
+    html::{attributes::*, events::*, *},
+    *,
+};
+use wasm_bindgen::JsCast;
+use wasm_bindgen_test::*;
+
+wasm_bindgen_test_configure!(run_in_browser);
+
+#[derive(Debug)]
+enum Msg {
+    Increment,
+}
+
+#[derive(Default)]
+struct Counter {
+    count: i32,
+}
+
+impl Application<Msg> for Counter {
+    fn update(&mut self, msg: Msg) -> Cmd<Self, Msg> {
+        match msg {
+            Msg::Increment => self.count += 1,
+        }
+        Cmd::none()
+    }
+
+    fn view(&self) -> Node<Msg> {
+        div(
+            [id("inspected"), class("counter")],
+            [
+                span([], [text(self.count)]),
+                button([on_click(|_| Msg::Increment)], [text("+")]),
+            ],
+        )
+    }
+}
+
+#[test]
+fn inspect_flattens_the_view_with_its_attributes_and_listeners() {
+    let tree = Inspector::inspect(&Counter::default().view());
+    assert_eq!(
+        tree,
+        [
+            InspectedNode {
+                depth: 0,
+                label: "<div>".to_string(),
+                attributes: vec![
+                    ("id".to_string(), "inspected".to_string()),
+                    ("class".to_string(), "counter".to_string()),
+                ],
+                listeners: vec![],
+            },
+            InspectedNode {
+                depth: 1,
+                label: "<span>".to_string(),
+                attributes: vec![],
+                listeners: vec![],
+            },
+            InspectedNode {
+                depth: 2,
+                label: "\"0\"".to_string(),
+                attributes: vec![],
+                listeners: vec![],
+            },
+            InspectedNode {
+                depth: 1,
+                label: "<button>".to_string(),
+                attributes: vec![],
+                listeners: vec!["click".to_string()],
+            },
+            InspectedNode {
+                depth: 2,
+                label: "\"+\"".to_string(),
+                attributes: vec![],
+                listeners: vec![],
+            },
+        ]
+    );
+}
+
+#[test]
+fn inspector_shows_the_selected_node_and_the_last_msgs() {
+    let mut inspector = Inspector::default();
+    let tree = Inspector::inspect(&Counter::default().view());
+    for msg in [
+        InspectorMsg::Toggle,
+        InspectorMsg::ViewUpdated(tree),
+        InspectorMsg::Select(3),
+        InspectorMsg::MsgDispatched("Increment".to_string()),
+        InspectorMsg::Measured(Measurements {
+            total_patches: 1,
+            total_time: 2.0,
+            ..Default::default()
+        }),
+    ] {
+        let _ = inspector.update(msg);
+    }
+    let html = inspector.view().render_to_string();
+    assert!(html.contains(r#"<li class="selected""#));
+    assert!(html.contains(r#"<ul class="listeners"><li>click</li></ul>"#));
+    assert!(html.contains(r#"<ul class="msgs"><li>Increment</li></ul>"#));
+    assert!(html.contains(r#"title="1 patches in 2.0ms""#));
+}
+
+#[wasm_bindgen_test]
+async fn inspector_is_mounted_in_a_shadow_root() {
+    console_error_panic_hook::set_once();
+    let mut program = Program::mount_to_body(Counter::default());
+    program.enable_inspector().expect("must enable");
+    let host = document()
+        .get_element_by_id("sauron-inspector")
+        .expect("must append the host");
+    let shadow = host.shadow_root().expect("must have a shadow root");
+
+    let toggle: web_sys::HtmlElement = shadow
+        .query_selector(".toggle")
+        .unwrap()
+        .expect("must mount the inspector")
+        .unchecked_into();
+    toggle.click();
+    program.dispatch(Msg::Increment);
+    delay(50).await;
+    let msgs = shadow
+        .query_selector(".msgs")
+        .unwrap()
+        .expect("must be open");
+    assert_eq!(msgs.text_content().as_deref(), Some("Increment"));
+    assert!(shadow.query_selector(".flash").unwrap().is_some());
+
+    program.unmount();
+    assert!(document().get_element_by_id("sauron-inspector").is_none());
+}
@@ -0,0 +1,130 @@
+#![deny(warnings)]
+use sauron::{
+    dom::{self, util::document, TestProgram},
+    html::{attributes::*, lazy},
+    vdom::PatchType,
+    *,
+};
+use std::cell::Cell;
+use wasm_bindgen_test::*;
+
+wasm_bindgen_test_configure!(run_in_browser);
+
+#[derive(Debug, Clone, PartialEq)]
+enum Msg {
+    Add(&'static str),
+    Tick,
+}
+
+#[derive(Default)]
+struct Inbox {
+    messages: Vec<&'static str>,
+    ticks: usize,
+    /// the number of times the list of messages is built
+    builds: Cell<usize>,
+}
+
+impl Application<Msg> for Inbox {
+    fn update(&mut self, msg: Msg) -> Cmd<Self, Msg> {
+        match msg {
+            Msg::Add(message) => self.messages.push(message),
+            Msg::Tick => self.ticks += 1,
+        }
+        Cmd::none()
+    }
+
+    fn view(&self) -> Node<Msg> {
+        div(
+            [id("inbox")],
+            [
+                span([class("ticks")], [text(self.ticks)]),
+                lazy(self.messages.clone(), || {
+                    self.builds.set(self.builds.get() + 1);
+                    ul(
+                        [class("messages"), attr("lazy", "user")],
+                        self.messages.iter().map(|message| li([], [text(message)])),
+                    )
+                }),
+            ],
+        )
+    }
+}
+
+#[test]
+fn lazy_subtree_is_built_only_when_the_deps_change() {
+    let mut program = TestProgram::new(Inbox::default());
+    assert_eq!(program.app().builds.get(), 1);
+
+    program.dispatch(Msg::Add("hello"));
+    assert_eq!(program.app().builds.get(), 2);
+    assert_eq!(program.text_content(".messages").as_deref(), Some("hello"));
+
+    program.dispatch(Msg::Tick);
+    program.dispatch(Msg::Tick);
+    assert_eq!(program.app().builds.get(), 2);
+    assert_eq!(program.text_content(".ticks").as_deref(), Some("2"));
+    // the reused subtree keeps its children and is not diffed
+    assert_eq!(program.text_content(".messages").as_deref(), Some("hello"));
+    assert_eq!(program.patches().len(), 1);
+
+    program.dispatch(Msg::Add("world"));
+    assert_eq!(program.app().builds.get(), 3);
+    assert_eq!(
+        program.text_content(".messages").as_deref(),
+        Some("helloworld")
+    );
+}
+
+#[test]
+fn lazy_subtree_is_built_everytime_outside_of_a_program() {
+    let inbox = Inbox {
+        messages: vec!["hello"],
+        ..Default::default()
+    };
+    let html = inbox.view().render_to_string();
+    assert_eq!(html, inbox.view().render_to_string());
+    assert_eq!(inbox.builds.get(), 2);
+    assert!(html.contains("<li>hello</li>"));
+}
+
+#[test]
+fn lazy_marker_is_not_rendered_nor_patched() {
+    let mut program = TestProgram::new(Inbox::default());
+    program.dispatch(Msg::Add("hello"));
+    // the rebuilt subtree only patches its new children
+    let patches = program.patches();
+    assert_eq!(patches.len(), 1);
+    assert!(!matches!(
+        patches[0].patch_type,
+        PatchType::AddAttributes { .. }
+    ));
+    // the lazy attribute of the app is kept
+    let html = program.html();
+    assert!(
+        html.contains(r#"<ul class="messages" lazy="user">"#),
+        "{html}"
+    );
+    assert!(!html.contains("sauron-lazy"), "{html}");
+}
+
+#[wasm_bindgen_test]
+async fn program_reuses_the_lazy_subtree() {
+    console_error_panic_hook::set_once();
+    let mut program = Program::mount_to_body(Inbox::default());
+    program.dispatch(Msg::Add("hello"));
+    dom::delay(0).await;
+    program.dispatch(Msg::Tick);
+    dom::delay(0).await;
+    assert_eq!(program.app().builds.get(), 2);
+
+    let messages = document()
+        .query_selector("#inbox .messages")
+        .expect("must query")
+        .expect("must render the messages");
+    assert_eq!(messages.text_content().as_deref(), Some("hello"));
+
+    program.dispatch(Msg::Add("world"));
+    dom::delay(0).await;
+    assert_eq!(messages.text_content().as_deref(), Some("helloworld"));
+    program.unmount();
+}
@@ -0,0 +1,83 @@
+#![deny(warnings)]
+use sauron::{dom::delay, html::attributes::*, html::events::*, html::*, *};
+use std::{cell::RefCell, rc::Rc};
+use wasm_bindgen_test::*;
+
+wasm_bindgen_test_configure!(run_in_browser);
+
+/// an app which shows the widget when visible, recording its lifecycle events
+struct Lifecycle {
+    visible: bool,
+    count: i32,
+    events: Rc<RefCell<Vec<String>>>,
+}
+
+enum Msg {
+    Toggle,
+    Increment,
+    Event(&'static str),
+}
+
+impl Application<Msg> for Lifecycle {
+    fn update(&mut self, msg: Msg) -> Cmd<Self, Msg> {
+        match msg {
+            Msg::Toggle => self.visible = !self.visible,
+            Msg::Increment => self.count += 1,
+            Msg::Event(event) => {
+                self.events.borrow_mut().push(event.to_string());
+                return Cmd::none().no_render();
+            }
+        }
+        Cmd::none()
+    }
+
+    fn view(&self) -> Node<Msg> {
+        div(
+            [id("lifecycle")],
+            [if self.visible {
+                div(
+                    [
+                        class("widget"),
+                        on_will_unmount(|me| {
+                            assert!(me.target_node.is_connected());
+                            Msg::Event("will_unmount")
+                        }),
+                        on_unmount(|me| {
+                            assert!(!me.target_node.is_connected());
+                            Msg::Event("unmount")
+                        }),
+                        on_updated(|_| Msg::Event("updated")),
+                    ],
+                    [
+                        span([on_unmount(|_| Msg::Event("child_unmount"))], []),
+                        text(self.count),
+                    ],
+                )
+            } else {
+                comment("hidden")
+            }],
+        )
+    }
+}
+
+#[wasm_bindgen_test]
+async fn unmount_and_updated_events() {
+    console_error_panic_hook::set_once();
+    let events = Rc::new(RefCell::new(vec![]));
+    let mut program = Program::mount_to_body(Lifecycle {
+        visible: true,
+        count: 0,
+        events: Rc::clone(&events),
+    });
+
+    program.dispatch(Msg::Increment);
+    delay(50).await;
+    assert_eq!(*events.borrow(), ["updated"]);
+
+    program.dispatch(Msg::Toggle);
+    delay(50).await;
+    assert_eq!(
+        *events.borrow(),
+        ["updated", "will_unmount", "child_unmount", "unmount"]
+    );
+}
@@ -0,0 +1,161 @@
+#![deny(warnings)]
+use sauron::{
+    dom::{delay, util::document, TestProgram},
+    html::{
+        attributes::{ListenerModifiers, ListenerOptions, *},
+        events::*,
+        *,
+    },
+    *,
+};
+use wasm_bindgen::JsCast;
+use wasm_bindgen_test::*;
+
+wasm_bindgen_test_configure!(run_in_browser);
+
+#[derive(Debug, Clone, PartialEq)]
+enum Msg {
+    Outer,
+    Inner,
+    Once,
+}
+
+#[derive(Default)]
+struct App {
+    received: Vec<Msg>,
+    /// the listeners are made with `on_synthetic`, such that they are called in the
+    /// `TestProgram`
+    headless: bool,
+}
+
+impl Application<Msg> for App {
+    fn update(&mut self, msg: Msg) -> Cmd<Self, Msg> {
+        self.received.push(msg);
+        Cmd::none()
+    }
+
+    fn view(&self) -> Node<Msg> {
+        if self.headless {
+            return div(
+                [
+                    id("listener-options"),
+                    on_synthetic("click", |_| Msg::Outer),
+                ],
+                [
+                    a(
+                        [
+                            class("inner"),
+                            href("#navigated"),
+                            on_synthetic("click", |_| Msg::Inner)
+                                .prevent_default()
+                                .stop_propagation(),
+                        ],
+                        [text("inner")],
+                    ),
+                    button(
+                        [class("once"), on_synthetic("click", |_| Msg::Once).once()],
+                        [text("once")],
+                    ),
+                ],
+            );
+        }
+        node! {
+            <div id="listener-options" on_click={|_| Msg::Outer}>
+                <a class="inner" href="#navigated" on_click:prevent_default:stop_propagation={|_| Msg::Inner}>
+                    "inner"
+                </a>
+                <button class="once" on_click:once={|_| Msg::Once}>"once"</button>
+            </div>
+        }
+    }
+}
+
+fn listener_options(attr: &Attribute<Msg>) -> ListenerOptions {
+    *attr.value()[0]
+        .as_event_listener()
+        .expect("must be a listener")
+        .options()
+}
+
+#[test]
+fn modifiers_set_the_options_of_the_listener() {
+    let attr = on_submit(|_| Msg::Outer).prevent_default().passive();
+    assert_eq!(
+        listener_options(&attr),
+        ListenerOptions {
+            prevent_default: true,
+            passive: true,
+            ..Default::default()
+        }
+    );
+    assert_eq!(
+        listener_options(&on_click(|_| Msg::Outer)),
+        Default::default()
+    );
+    assert_ne!(
+        on_click(|_| Msg::Outer).capture(),
+        on_click(|_| Msg::Outer).once()
+    );
+
+    let mapped: Attribute<Option<Msg>> = on_click(|_| Msg::Outer).stop_propagation().map_msg(Some);
+    let options = *mapped.value()[0].as_event_listener().unwrap().options();
+    assert!(options.stop_propagation);
+}
+
+#[test]
+fn node_macro_appends_the_options_to_the_event_name() {
+    let node: Node<Msg> = node! {
+        <form on_submit:prevent_default:stop_propagation={|_| Msg::Outer}></form>
+    };
+    let attr = node.attributes().unwrap()[0].clone();
+    assert_eq!(
+        listener_options(&attr),
+        ListenerOptions {
+            prevent_default: true,
+            stop_propagation: true,
+            ..Default::default()
+        }
+    );
+}
+
+#[test]
+fn stop_propagation_stops_the_bubbling_in_test_program() {
+    let mut program = TestProgram::new(App {
+        headless: true,
+        ..App::default()
+    });
+    program.click(".inner");
+    assert_eq!(program.app().received, [Msg::Inner]);
+    program.click(".once");
+    assert_eq!(program.app().received, [Msg::Inner, Msg::Once, Msg::Outer]);
+}
+
+#[wasm_bindgen_test]
+async fn options_are_applied_to_the_dom_listeners() {
+    console_error_panic_hook::set_once();
+    let program = Program::mount_to_body(App::default());
+    let click = |selector: &str| {
+        let element: web_sys::HtmlElement = document()
+            .query_selector(selector)
+            .unwrap()
+            .expect("must be mounted")
+            .unchecked_into();
+        element.click();
+    };
+
+    click("#listener-options .inner");
+    delay(50).await;
+    assert_eq!(program.app().received, [Msg::Inner]);
+    assert_ne!(
+        web_sys::window().unwrap().location().hash().unwrap(),
+        "#navigated"
+    );
+
+    click("#listener-options .once");
+    click("#listener-options .once");
+    delay(50).await;
+    assert_eq!(
+        program.app().received,
+        [Msg::Inner, Msg::Once, Msg::Outer, Msg::Outer]
+    );
+}
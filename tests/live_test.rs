@@ -0,0 +1,467 @@
+#![deny(warnings)]
+use futures::channel::oneshot;
+use sauron::{
+    dom::{
+        live::{ClientMessage, LiveProgram, LiveSessions, ServerMessage},
+        SyntheticEvent,
+    },
+    html::{attributes::*, events::on_synthetic},
+    vdom::{EventId, WireNode, WireValue},
+    *,
+};
+use std::{cell::Cell, time::Duration};
+
+#[derive(Debug, Clone, PartialEq)]
+enum Msg {
+    Increment,
+    Rename(String),
+}
+
+#[derive(Default)]
+struct Counter {
+    count: i32,
+    name: String,
+}
+
+impl Application<Msg> for Counter {
+    fn update(&mut self, msg: Msg) -> Cmd<Self, Msg> {
+        match msg {
+            Msg::Increment => self.count += 1,
+            Msg::Rename(name) => self.name = name,
+        }
+        Cmd::none()
+    }
+
+    fn view(&self) -> Node<Msg> {
+        div(
+            [id("counter")],
+            [
+                input(
+                    [on_synthetic("input", |event| {
+                        Msg::Rename(event.value.unwrap_or_default())
+                    })],
+                    [],
+                ),
+                span([class("name")], [text(&self.name)]),
+                button(
+                    [on_synthetic("click", |_| Msg::Increment)],
+                    [text(self.count)],
+                ),
+            ],
+        )
+    }
+}
+
+fn join(session: Option<&str>, version: u64) -> ClientMessage {
+    ClientMessage::Join {
+        session: session.map(ToString::to_string),
+        version,
+    }
+}
+
+/// the ids of the event listeners of the view, in document order
+fn event_ids(node: &WireNode) -> Vec<EventId> {
+    let mut ids = vec![];
+    if let WireNode::Element(element) = node {
+        for value in element.attrs.iter().flat_map(|att| &att.value) {
+            if let WireValue::EventListener { id, .. } = value {
+                ids.push(*id);
+            }
+        }
+    }
+    for child in node.children() {
+        ids.extend(event_ids(child));
+    }
+    ids
+}
+
+fn rendered_view(replies: &[ServerMessage]) -> &WireNode {
+    match replies {
+        [ServerMessage::Render { view, .. }] => view,
+        _ => panic!("expecting a render, got: {replies:?}"),
+    }
+}
+
+#[test]
+fn events_of_the_client_are_replied_with_patches() {
+    let mut live = LiveProgram::new(Counter::default());
+    let replies = live.handle("session", join(None, 0)).expect("must join");
+    let ids = event_ids(rendered_view(&replies));
+    assert_eq!(live.version(), 1);
+
+    let click = ClientMessage::Event {
+        id: ids[1],
+        event: SyntheticEvent::default(),
+    };
+    let replies = live.handle("session", click).expect("must handle");
+    let [ServerMessage::Patch { version, patches }] = replies.as_slice() else {
+        panic!("expecting a patch, got: {replies:?}");
+    };
+    assert_eq!(*version, 2);
+    assert!(!patches.is_empty());
+    assert_eq!(live.app().count, 1);
+
+    let input = ClientMessage::Event {
+        id: ids[0],
+        event: SyntheticEvent::with_value("live"),
+    };
+    live.handle("session", input).expect("must handle");
+    assert_eq!(live.app().name, "live");
+    assert_eq!(live.version(), 3);
+}
+
+#[test]
+fn events_of_unknown_listeners_are_ignored() {
+    let mut live = LiveProgram::new(Counter::default());
+    live.handle("session", join(None, 0)).expect("must join");
+    let replies = live
+        .handle(
+            "session",
+            ClientMessage::Event {
+                id: EventId(404),
+                event: SyntheticEvent::default(),
+            },
+        )
+        .expect("must handle");
+    assert!(replies.is_empty());
+    assert_eq!(live.version(), 1);
+}
+
+#[test]
+fn resync_renders_the_whole_view() {
+    let mut live = LiveProgram::new(Counter::default());
+    let replies = live.handle("session", join(None, 0)).expect("must join");
+    let ids = event_ids(rendered_view(&replies));
+    let click = ClientMessage::Event {
+        id: ids[1],
+        event: SyntheticEvent::default(),
+    };
+    live.handle("session", click).expect("must handle");
+
+    let replies = live
+        .handle("session", ClientMessage::Resync)
+        .expect("must resync");
+    let ServerMessage::Render { version, view, .. } = &replies[0] else {
+        panic!("expecting a render, got: {replies:?}");
+    };
+    assert_eq!(*version, 3);
+    let button = &view.children()[2];
+    assert_eq!(
+        button.children(),
+        [WireNode::Leaf(sauron::vdom::Leaf::Text("1".into()))]
+    );
+}
+
+#[test]
+fn reconnecting_client_keeps_its_session() {
+    let mut sessions = LiveSessions::new(Counter::default);
+    let (session, replies) = sessions.handle(None, join(None, 0)).expect("must join");
+    let ids = event_ids(rendered_view(&replies));
+    let click = ClientMessage::Event {
+        id: ids[1],
+        event: SyntheticEvent::default(),
+    };
+    sessions
+        .handle(Some(&session), click)
+        .expect("must handle the click");
+    sessions.leave(&session);
+
+    // the client which has the latest view is not sent anything
+    let (rejoined, replies) = sessions
+        .handle(None, join(Some(&session), 2))
+        .expect("must rejoin");
+    assert_eq!(rejoined, session);
+    assert!(replies.is_empty());
+
+    // the client which missed a patch is sent the whole view
+    let (_, replies) = sessions
+        .handle(None, join(Some(&session), 1))
+        .expect("must rejoin");
+    rendered_view(&replies);
+    let live = sessions.get(&session).expect("must have the session");
+    assert_eq!(live.app().count, 1);
+}
+
+#[test]
+fn expired_session_is_replaced_with_a_new_session() {
+    let mut sessions = LiveSessions::new(Counter::default).session_ttl(Duration::ZERO);
+    let (session, _) = sessions.handle(None, join(None, 0)).expect("must join");
+    let (other, _) = sessions.handle(None, join(None, 0)).expect("must join");
+    assert_ne!(session, other);
+    assert_eq!(sessions.len(), 2);
+
+    sessions.leave(&session);
+    let (rejoined, replies) = sessions
+        .handle(None, join(Some(&session), 1))
+        .expect("must join");
+    assert_ne!(rejoined, session);
+    rendered_view(&replies);
+    assert!(sessions.get(&session).is_none());
+    assert_eq!(sessions.len(), 2);
+}
+
+/// an app which panics when it is clicked, and shows the value sent to it later
+struct Fragile {
+    loading: Option<oneshot::Receiver<i32>>,
+    loaded: Option<i32>,
+}
+
+impl Application<Option<i32>> for Fragile {
+    fn init(&mut self) -> Cmd<Self, Option<i32>> {
+        match self.loading.take() {
+            Some(loading) => Cmd::from(Task::new(async move { loading.await.ok() })),
+            None => Cmd::none(),
+        }
+    }
+
+    fn update(&mut self, msg: Option<i32>) -> Cmd<Self, Option<i32>> {
+        match msg {
+            Some(loaded) => self.loaded = Some(loaded),
+            None => panic!("clicked a fragile app"),
+        }
+        Cmd::none()
+    }
+
+    fn view(&self) -> Node<Option<i32>> {
+        button(
+            [on_synthetic("click", |_| None)],
+            [text(self.loaded.map(|v| v.to_string()).unwrap_or_default())],
+        )
+    }
+}
+
+#[test]
+fn panicking_session_is_removed_alone() {
+    let mut sessions = LiveSessions::new(|| Fragile {
+        loading: None,
+        loaded: None,
+    });
+    let (fragile, replies) = sessions.handle(None, join(None, 0)).expect("must join");
+    let ids = event_ids(rendered_view(&replies));
+    let (other, _) = sessions.handle(None, join(None, 0)).expect("must join");
+
+    let click = ClientMessage::Event {
+        id: ids[0],
+        event: SyntheticEvent::default(),
+    };
+    let error = sessions
+        .handle(Some(&fragile), click)
+        .expect_err("must fail the panicking session");
+    assert!(matches!(error, Error::SessionPanicked { session, .. } if session == fragile));
+    assert!(sessions.get(&fragile).is_none());
+    assert!(sessions.get(&other).is_some());
+
+    // the client of the removed session joins a new session
+    let (rejoined, replies) = sessions
+        .handle(None, join(Some(&fragile), 1))
+        .expect("must join");
+    assert_ne!(rejoined, fragile);
+    rendered_view(&replies);
+}
+
+#[test]
+fn results_of_the_tasks_are_pushed_when_polled() {
+    let (loaded, loading) = oneshot::channel();
+    let loading = Cell::new(Some(loading));
+    let mut sessions = LiveSessions::new(move || Fragile {
+        loading: loading.take(),
+        loaded: None,
+    });
+    let (session, _) = sessions.handle(None, join(None, 0)).expect("must join");
+    assert!(sessions.poll().is_empty());
+
+    loaded.send(42).expect("must send");
+    let pushed = sessions.poll();
+    let [(pushed_session, messages)] = pushed.as_slice() else {
+        panic!("expecting the patches of the session, got: {pushed:?}");
+    };
+    assert_eq!(*pushed_session, session);
+    assert!(matches!(
+        messages.as_slice(),
+        [ServerMessage::Patch { version: 2, .. }]
+    ));
+    let live = sessions.get(&session).expect("must have the session");
+    assert_eq!(live.app().loaded, Some(42));
+    assert!(sessions.poll().is_empty());
+}
+
+/// an app which runs a command on the `Program` when it is clicked, the errors passed to
+/// `on_error` are recorded
+#[derive(Default)]
+struct Scroller {
+    errors: Vec<String>,
+}
+
+impl Application<Option<String>> for Scroller {
+    fn update(&mut self, msg: Option<String>) -> Cmd<Self, Option<String>> {
+        match msg {
+            Some(error) => {
+                self.errors.push(error);
+                Cmd::none()
+            }
+            // such as focusing an element, which needs the DOM
+            None => Cmd::new(|_program| ()),
+        }
+    }
+
+    fn view(&self) -> Node<Option<String>> {
+        div(
+            [],
+            [
+                button([on_synthetic("click", |_| None)], [text(self.errors.len())]),
+                // the listener of the web event can not be called on the server
+                button([on_click(|_| None)], [text("web")]),
+            ],
+        )
+    }
+
+    fn on_error(&self, error: Error) -> Option<Option<String>> {
+        Some(Some(error.to_string()))
+    }
+}
+
+#[test]
+fn commands_on_the_program_are_passed_to_on_error() {
+    let mut sessions = LiveSessions::new(Scroller::default);
+    let (session, replies) = sessions.handle(None, join(None, 0)).expect("must join");
+    let ids = event_ids(rendered_view(&replies));
+
+    let click = ClientMessage::Event {
+        id: ids[0],
+        event: SyntheticEvent::default(),
+    };
+    let (_, replies) = sessions.handle(Some(&session), click).expect("must handle");
+    assert!(matches!(
+        replies.as_slice(),
+        [ServerMessage::Patch { version: 2, .. }]
+    ));
+    let live = sessions.get(&session).expect("must have the session");
+    assert_eq!(
+        live.app().errors,
+        [Error::Unsupported("a command which operates on the Program").to_string()]
+    );
+}
+
+#[test]
+fn listeners_of_the_web_events_are_passed_to_on_error() {
+    let mut sessions = LiveSessions::new(Scroller::default);
+    let (session, replies) = sessions.handle(None, join(None, 0)).expect("must join");
+    let ids = event_ids(rendered_view(&replies));
+
+    let click = ClientMessage::Event {
+        id: ids[1],
+        event: SyntheticEvent::default(),
+    };
+    sessions.handle(Some(&session), click).expect("must handle");
+    let live = sessions.get(&session).expect("must have the session");
+    assert_eq!(
+        live.app().errors,
+        [Error::Unsupported("a listener which reads the web event").to_string()]
+    );
+}
+
+#[cfg(feature = "with-live-server")]
+#[test]
+fn live_session_over_a_web_socket() {
+    use sauron::dom::live::LiveServer;
+    use std::net::TcpListener;
+    use tungstenite::Message;
+
+    let listener = TcpListener::bind("127.0.0.1:0").expect("must bind");
+    let address = listener.local_addr().expect("must have an address");
+    std::thread::spawn(move || LiveServer::new(Counter::default).serve(listener));
+
+    let connect = || {
+        let (socket, _) =
+            tungstenite::connect(format!("ws://{address}/live")).expect("must connect");
+        socket
+    };
+    let send = |socket: &mut tungstenite::WebSocket<_>, msg: &ClientMessage| {
+        let json = serde_json::to_string(msg).expect("must serialize");
+        socket.send(Message::Text(json)).expect("must send");
+    };
+    let receive = |socket: &mut tungstenite::WebSocket<_>| -> ServerMessage {
+        let text = socket
+            .read()
+            .expect("must read")
+            .into_text()
+            .expect("must be text");
+        serde_json::from_str(&text).expect("must deserialize")
+    };
+
+    let mut socket = connect();
+    send(&mut socket, &join(None, 0));
+    let ServerMessage::Render { session, view, .. } = receive(&mut socket) else {
+        panic!("expecting a render");
+    };
+    let ids = event_ids(&view);
+    let click = ClientMessage::Event {
+        id: ids[1],
+        event: SyntheticEvent::default(),
+    };
+    send(&mut socket, &click);
+    let ServerMessage::Patch { version, .. } = receive(&mut socket) else {
+        panic!("expecting a patch");
+    };
+    assert_eq!(version, 2);
+    socket.close(None).expect("must close");
+
+    // the reconnected client which missed a patch is sent the whole view of its session
+    let mut socket = connect();
+    send(&mut socket, &join(Some(&session), 1));
+    let ServerMessage::Render {
+        session: rejoined,
+        version,
+        view,
+    } = receive(&mut socket)
+    else {
+        panic!("expecting a render");
+    };
+    assert_eq!(rejoined, session);
+    assert_eq!(version, 3);
+    assert_eq!(
+        view.children()[2].children(),
+        [WireNode::Leaf(sauron::vdom::Leaf::Text("1".into()))]
+    );
+}
+
+#[cfg(feature = "with-live-server")]
+#[test]
+fn results_of_the_tasks_are_pushed_over_the_web_socket() {
+    use sauron::dom::live::LiveServer;
+    use std::net::TcpListener;
+    use tungstenite::Message;
+
+    let listener = TcpListener::bind("127.0.0.1:0").expect("must bind");
+    let address = listener.local_addr().expect("must have an address");
+    let (loaded, loading) = oneshot::channel();
+    let loading = Cell::new(Some(loading));
+    let server = LiveServer::new(move || Fragile {
+        loading: loading.take(),
+        loaded: None,
+    })
+    .poll_interval(Duration::from_millis(10));
+    std::thread::spawn(move || server.serve(listener));
+
+    let (mut socket, _) =
+        tungstenite::connect(format!("ws://{address}/live")).expect("must connect");
+    let json = serde_json::to_string(&join(None, 0)).expect("must serialize");
+    socket.send(Message::Text(json)).expect("must send");
+    let mut receive = || -> ServerMessage {
+        let text = socket
+            .read()
+            .expect("must read")
+            .into_text()
+            .expect("must be text");
+        serde_json::from_str(&text).expect("must deserialize")
+    };
+    let ServerMessage::Render { version: 1, .. } = receive() else {
+        panic!("expecting a render");
+    };
+
+    loaded.send(42).expect("must send");
+    let ServerMessage::Patch { version, .. } = receive() else {
+        panic!("expecting a patch");
+    };
+    assert_eq!(version, 2);
+}
@@ -0,0 +1,151 @@
+#![deny(warnings)]
+use futures::executor::block_on;
+use sauron::{
+    dom::{self, util::document, MemoryStorage, PersistError, Persistence, Storage, WebStorage},
+    html::{attributes::*, *},
+    *,
+};
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+use wasm_bindgen_test::*;
+
+wasm_bindgen_test_configure!(run_in_browser);
+
+enum Msg {
+    Add(String),
+}
+
+#[derive(Debug, Default, PartialEq, Serialize, Deserialize)]
+struct Todos {
+    items: Vec<String>,
+}
+
+impl Application<Msg> for Todos {
+    fn update(&mut self, msg: Msg) -> Cmd<Self, Msg> {
+        match msg {
+            Msg::Add(item) => self.items.push(item),
+        }
+        Cmd::none()
+    }
+
+    fn view(&self) -> Node<Msg> {
+        ul(
+            [id("persisted-todos")],
+            self.items.iter().map(|item| li([], [text(item)])),
+        )
+    }
+}
+
+fn todos(items: &[&str]) -> Todos {
+    Todos {
+        items: items.iter().map(|item| item.to_string()).collect(),
+    }
+}
+
+#[test]
+fn saved_state_is_restored() {
+    let storage = MemoryStorage::new();
+    let persistence = Persistence::new("todos", storage.clone()).version(3);
+    block_on(persistence.save(&todos(&["buy milk"]))).expect("must save");
+    assert_eq!(
+        storage.value("todos").as_deref(),
+        Some(r#"{"version":3,"state":{"items":["buy milk"]}}"#)
+    );
+    let restored: Option<Todos> = block_on(persistence.restore()).expect("must restore");
+    assert_eq!(restored, Some(todos(&["buy milk"])));
+
+    block_on(persistence.clear()).expect("must clear");
+    let restored: Option<Todos> = block_on(persistence.restore()).expect("must restore");
+    assert_eq!(restored, None);
+}
+
+#[test]
+fn older_versions_are_migrated_one_version_at_a_time() {
+    let storage = MemoryStorage::new();
+    storage.insert("todos", r#"{"version":0,"state":"buy milk"}"#);
+    let persistence = Persistence::new("todos", storage)
+        .version(2)
+        .migrate(|version, state| match version {
+            0 => Ok(serde_json::json!([state])),
+            1 => Ok(serde_json::json!({ "items": state })),
+            _ => Err(format!("unknown version {version}")),
+        });
+    let restored: Option<Todos> = block_on(persistence.restore()).expect("must restore");
+    assert_eq!(restored, Some(todos(&["buy milk"])));
+}
+
+#[test]
+fn restoring_fails_on_newer_versions_and_failed_migrations() {
+    let storage = MemoryStorage::new();
+    storage.insert("todos", r#"{"version":5,"state":{"items":[]}}"#);
+    let persistence = Persistence::new("todos", storage.clone()).version(1);
+    assert_eq!(
+        block_on(persistence.restore::<Todos>()),
+        Err(PersistError::NewerVersion {
+            stored: 5,
+            current: 1
+        })
+    );
+
+    storage.insert("todos", r#"{"version":0,"state":{"items":[]}}"#);
+    assert_eq!(
+        block_on(persistence.restore::<Todos>()),
+        Err(PersistError::Migration {
+            from: 0,
+            reason: "there is no migration".to_string()
+        })
+    );
+
+    storage.insert("todos", "not json");
+    assert!(matches!(
+        block_on(persistence.restore::<Todos>()),
+        Err(PersistError::Deserialize(_))
+    ));
+}
+
+#[wasm_bindgen_test]
+async fn program_saves_the_state_after_the_updates() {
+    console_error_panic_hook::set_once();
+    let storage = MemoryStorage::new();
+    let persistence =
+        Persistence::new("todos", storage.clone()).throttle(Duration::from_millis(20));
+    let mut program = Program::mount_to_body(Todos::default());
+    program.persist(persistence.clone());
+
+    program.dispatch(Msg::Add("buy milk".to_string()));
+    program.dispatch(Msg::Add("walk the dog".to_string()));
+    // the save is throttled
+    assert_eq!(storage.value("todos"), None);
+    dom::delay(50).await;
+    let restored: Option<Todos> = persistence.restore().await.expect("must restore");
+    assert_eq!(restored, Some(todos(&["buy milk", "walk the dog"])));
+
+    // the scheduled save is done when unmounted
+    program.dispatch(Msg::Add("read".to_string()));
+    program.unmount();
+    dom::delay(0).await;
+    let restored: Option<Todos> = persistence.restore().await.expect("must restore");
+    assert_eq!(restored, Some(todos(&["buy milk", "walk the dog", "read"])));
+    assert!(document().get_element_by_id("persisted-todos").is_none());
+}
+
+#[wasm_bindgen_test]
+async fn web_storage_reads_and_writes_the_values() {
+    let storage = WebStorage::Session;
+    storage
+        .set("sauron-persist-test", "saved".to_string())
+        .await
+        .expect("must set");
+    assert_eq!(
+        storage.get("sauron-persist-test").await.expect("must get"),
+        Some("saved".to_string())
+    );
+    storage
+        .remove("sauron-persist-test")
+        .await
+        .expect("must remove");
+    assert_eq!(
+        storage.get("sauron-persist-test").await.expect("must get"),
+        None
+    );
+}
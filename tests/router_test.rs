@@ -0,0 +1,160 @@
+#![deny(warnings)]
+use sauron::dom::{router::WILDCARD, RoutePattern, Router};
+
+#[derive(Debug, PartialEq)]
+enum Route {
+    Home,
+    Users,
+    User(u32),
+    UserPost { user: u32, post: String },
+    Search { query: String, page: usize },
+    Files(String),
+    NotFound(String),
+}
+
+fn router() -> Router<Route> {
+    Router::new()
+        .route("/", |_| Some(Route::Home))
+        .route("/users", |_| Some(Route::Users))
+        .route("/users/:id", |params| {
+            Some(Route::User(params.parse("id")?))
+        })
+        .route("/users/:id/posts/:slug", |params| {
+            Some(Route::UserPost {
+                user: params.parse("id")?,
+                post: params.param("slug")?.to_string(),
+            })
+        })
+        .route("/search", |params| {
+            Some(Route::Search {
+                query: params.query("q")?.to_string(),
+                page: params.query_parse("page").unwrap_or(1),
+            })
+        })
+        .route("/files/*path", |params| {
+            Some(Route::Files(params.param("path")?.to_string()))
+        })
+        .route("/*", |params| {
+            Some(Route::NotFound(params.param(WILDCARD)?.to_string()))
+        })
+}
+
+#[test]
+fn static_routes() {
+    let router = router();
+    assert_eq!(router.recognize("/"), Some(Route::Home));
+    assert_eq!(router.recognize(""), Some(Route::Home));
+    assert_eq!(router.recognize("/users"), Some(Route::Users));
+    assert_eq!(router.recognize("/users/"), Some(Route::Users));
+}
+
+#[test]
+fn typed_params() {
+    let router = router();
+    assert_eq!(router.recognize("/users/42"), Some(Route::User(42)));
+    assert_eq!(
+        router.recognize("/users/7/posts/hello-world"),
+        Some(Route::UserPost {
+            user: 7,
+            post: "hello-world".to_string()
+        })
+    );
+}
+
+#[test]
+fn params_that_fail_to_parse_falls_through_to_the_next_route() {
+    let router = router();
+    assert_eq!(
+        router.recognize("/users/abc"),
+        Some(Route::NotFound("users/abc".to_string()))
+    );
+}
+
+#[test]
+fn params_are_percent_decoded() {
+    let router = router();
+    assert_eq!(
+        router.recognize("/users/1/posts/caf%C3%A9%20au%20lait"),
+        Some(Route::UserPost {
+            user: 1,
+            post: "café au lait".to_string()
+        })
+    );
+}
+
+#[test]
+fn query_strings() {
+    let router = router();
+    assert_eq!(
+        router.recognize("/search?q=rust+wasm&page=3"),
+        Some(Route::Search {
+            query: "rust wasm".to_string(),
+            page: 3
+        })
+    );
+    assert_eq!(
+        router.recognize("/search?page=2&q=a%26b"),
+        Some(Route::Search {
+            query: "a&b".to_string(),
+            page: 2
+        })
+    );
+    // the query `q` is required
+    assert_eq!(
+        router.recognize("/search?page=2"),
+        Some(Route::NotFound("search".to_string()))
+    );
+}
+
+#[test]
+fn fragment_is_ignored_in_matching() {
+    let router = router();
+    assert_eq!(router.recognize("/users/42#profile"), Some(Route::User(42)));
+    let params = RoutePattern::new("/users/:id")
+        .match_url("/users/42?tab=posts#latest")
+        .expect("must match");
+    assert_eq!(params.param("id"), Some("42"));
+    assert_eq!(params.query("tab"), Some("posts"));
+    assert_eq!(params.fragment(), Some("latest"));
+}
+
+#[test]
+fn wildcards() {
+    let router = router();
+    assert_eq!(
+        router.recognize("/files/docs/2023/report.pdf"),
+        Some(Route::Files("docs/2023/report.pdf".to_string()))
+    );
+    assert_eq!(
+        router.recognize("/files"),
+        Some(Route::Files("".to_string()))
+    );
+    assert_eq!(
+        router.recognize("/some/unknown/page"),
+        Some(Route::NotFound("some/unknown/page".to_string()))
+    );
+}
+
+#[test]
+fn routes_are_matched_in_order() {
+    let router = Router::new()
+        .route("/users/new", |_| Some("new"))
+        .route("/users/:id", |_| Some("user"));
+    assert_eq!(router.recognize("/users/new"), Some("new"));
+    assert_eq!(router.recognize("/users/1"), Some("user"));
+    assert_eq!(router.recognize("/posts/1"), None);
+}
+
+#[test]
+fn pattern_does_not_match_extra_segments() {
+    let pattern = RoutePattern::new("/users/:id");
+    assert!(pattern.match_path("/users/1").is_some());
+    assert!(pattern.match_path("/users/1/posts").is_none());
+    assert!(pattern.match_path("/users").is_none());
+}
+
+#[test]
+#[should_panic]
+fn wildcard_must_be_the_last_segment() {
+    RoutePattern::new("/files/*path/edit");
+}
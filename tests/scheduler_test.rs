@@ -0,0 +1,132 @@
+#![deny(warnings)]
+use sauron::{
+    dom::{self, util::document, Priority},
+    html::attributes::*,
+    *,
+};
+use std::{cell::RefCell, rc::Rc};
+use wasm_bindgen_test::*;
+
+wasm_bindgen_test_configure!(run_in_browser);
+
+#[derive(Debug, Clone, PartialEq)]
+enum Msg {
+    Typed(char),
+    Loaded(usize),
+}
+
+#[derive(Default)]
+struct Search {
+    query: String,
+    results: usize,
+    updates: Rc<RefCell<Vec<Msg>>>,
+    views: Rc<RefCell<usize>>,
+}
+
+impl Application<Msg> for Search {
+    fn update(&mut self, msg: Msg) -> Cmd<Self, Msg> {
+        self.updates.borrow_mut().push(msg.clone());
+        match msg {
+            Msg::Typed(c) => self.query.push(c),
+            Msg::Loaded(results) => self.results = results,
+        }
+        Cmd::none()
+    }
+
+    fn view(&self) -> Node<Msg> {
+        *self.views.borrow_mut() += 1;
+        div(
+            [id("search")],
+            [
+                input([value(&self.query)], []),
+                ul(
+                    [id("search-results")],
+                    (0..self.results).map(|i| li([key(i)], [text(i)])),
+                ),
+            ],
+        )
+    }
+}
+
+#[test]
+fn priorities_are_ordered_from_user_input_to_background() {
+    assert!(Priority::UserInput < Priority::Animation);
+    assert!(Priority::Animation < Priority::Background);
+    // `with-ric` and `with-raf` change the priority of the msgs dispatched without one
+    let default = if cfg!(feature = "with-ric") {
+        Priority::Background
+    } else if cfg!(feature = "with-raf") {
+        Priority::Animation
+    } else {
+        Priority::UserInput
+    };
+    assert_eq!(Priority::default(), default);
+}
+
+#[wasm_bindgen_test]
+async fn user_input_is_updated_before_background_msgs() {
+    console_error_panic_hook::set_once();
+    let updates = Rc::new(RefCell::new(vec![]));
+    let mut program = Program::mount_to_body(Search {
+        updates: Rc::clone(&updates),
+        ..Default::default()
+    });
+    program.dispatch_with_priority(Msg::Loaded(3), Priority::Background);
+    program.dispatch_with_priority(Msg::Typed('a'), Priority::Animation);
+    program.dispatch(Msg::Typed('b'));
+    dom::delay(100).await;
+    assert_eq!(
+        *updates.borrow(),
+        [Msg::Typed('b'), Msg::Typed('a'), Msg::Loaded(3)]
+    );
+    assert_eq!(program.app().query, "ba");
+    program.unmount();
+}
+
+#[wasm_bindgen_test]
+async fn background_patches_are_applied_across_frames() {
+    console_error_panic_hook::set_once();
+    let mut program = Program::mount_to_body(Search::default());
+    // each frame applies at least one patch
+    program.set_frame_budget(0.0);
+    program.dispatch_with_priority(Msg::Loaded(200), Priority::Background);
+    dom::delay(500).await;
+    let results = document()
+        .get_element_by_id("search-results")
+        .expect("must be mounted");
+    assert_eq!(results.child_element_count(), 200);
+    program.unmount();
+}
+
+#[wasm_bindgen_test]
+async fn dispatches_in_the_same_tick_are_rendered_once() {
+    console_error_panic_hook::set_once();
+    let views = Rc::new(RefCell::new(0));
+    let mut program = Program::mount_to_body(Search {
+        views: Rc::clone(&views),
+        ..Default::default()
+    });
+    let views_before = *views.borrow();
+    for c in "abc".chars() {
+        program.dispatch(Msg::Typed(c));
+    }
+    dom::delay(50).await;
+    assert_eq!(program.app().query, "abc");
+    assert_eq!(*views.borrow() - views_before, 1);
+    program.unmount();
+}
+
+#[wasm_bindgen_test]
+async fn dispatch_sync_patches_the_dom_right_away() {
+    console_error_panic_hook::set_once();
+    let mut program = Program::mount_to_body(Search::default());
+    program.dispatch(Msg::Typed('a'));
+    program.dispatch_sync(Msg::Loaded(2));
+    // the queued msg is updated along with the synchronous one
+    assert_eq!(program.app().query, "a");
+    let results = document()
+        .get_element_by_id("search-results")
+        .expect("must be mounted");
+    assert_eq!(results.child_element_count(), 2);
+    program.unmount();
+}
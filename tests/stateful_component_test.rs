@@ -0,0 +1,174 @@
+#![deny(warnings)]
+use sauron::{
+    dom::{delay, util::document},
+    html::{attributes::*, events::*, *},
+    *,
+};
+use std::rc::Rc;
+use wasm_bindgen::JsCast;
+use wasm_bindgen_test::*;
+
+wasm_bindgen_test_configure!(run_in_browser);
+
+/// a button which counts its own clicks
+struct FuiButton<XMSG> {
+    label: String,
+    clicks: u32,
+    on_click: Vec<Rc<dyn Fn(u32) -> XMSG>>,
+}
+
+enum ButtonMsg {
+    Click,
+}
+
+impl<XMSG> Default for FuiButton<XMSG> {
+    fn default() -> Self {
+        Self {
+            label: String::new(),
+            clicks: 0,
+            on_click: vec![],
+        }
+    }
+}
+
+impl<XMSG> FuiButton<XMSG> {
+    fn label(mut self, label: &str) -> Self {
+        self.label = label.to_string();
+        self
+    }
+
+    fn on_click(mut self, f: impl Fn(u32) -> XMSG + 'static) -> Self {
+        self.on_click.push(Rc::new(f));
+        self
+    }
+}
+
+impl<XMSG> Component<ButtonMsg, XMSG> for FuiButton<XMSG>
+where
+    XMSG: 'static,
+{
+    fn update(&mut self, msg: ButtonMsg) -> Effects<ButtonMsg, XMSG> {
+        match msg {
+            ButtonMsg::Click => {
+                self.clicks += 1;
+                Effects::with_external(self.on_click.iter().map(|f| f(self.clicks)))
+            }
+        }
+    }
+
+    fn view(&self) -> Node<ButtonMsg> {
+        button(
+            [on_click(|_| ButtonMsg::Click)],
+            [text!("{} {}", self.label, self.clicks)],
+        )
+    }
+}
+
+#[derive(Debug, PartialEq)]
+enum Msg {
+    Clicked(u32),
+}
+
+#[derive(Default)]
+struct Parent {
+    clicked: Vec<u32>,
+}
+
+impl Application<Msg> for Parent {
+    fn update(&mut self, msg: Msg) -> Cmd<Self, Msg> {
+        match msg {
+            Msg::Clicked(clicks) => self.clicked.push(clicks),
+        }
+        Cmd::none()
+    }
+
+    fn view(&self) -> Node<Msg> {
+        div(
+            [id("parent")],
+            [
+                node! { <FuiButton key="ok" label="Ok" on_click={Msg::Clicked} /> },
+                span([class("clicked")], [text(self.clicked.len())]),
+            ],
+        )
+    }
+}
+
+#[test]
+fn node_macro_creates_the_component() {
+    let expected: Node<Msg> = component(
+        [key("ok")],
+        FuiButton::default().label("Ok").on_click(Msg::Clicked),
+        |xmsg| xmsg,
+    );
+    let node: Node<Msg> = node! { <FuiButton key="ok" label="Ok" on_click={Msg::Clicked} /> };
+    assert_eq!(node, expected);
+}
+
+#[test]
+fn initial_view_is_rendered_in_the_host() {
+    let html = Parent::default().view().render_to_string();
+    assert!(html.starts_with(r#"<div id="parent"><div class="FuiButton" key="ok"><button"#));
+    assert!(html.ends_with(r#">Ok 0</button></div><span class="clicked">0</span></div>"#));
+}
+
+#[test]
+fn component_is_carried_in_a_named_attribute() {
+    let node: Node<Msg> = component([], FuiButton::default(), |xmsg| xmsg);
+    let attributes = node.attributes().expect("must be an element");
+    assert!(attributes.iter().all(|attr| !attr.name().is_empty()));
+    let host = attributes
+        .iter()
+        .find(|attr| attr.value().iter().any(|v| v.as_component().is_some()))
+        .expect("must carry the component");
+    // the marker is never rendered
+    assert!(!node.render_to_string().contains(host.name()));
+}
+
+#[test]
+fn components_of_different_types_are_not_equal() {
+    struct Other;
+    impl Component<(), ()> for Other {
+        fn update(&mut self, _: ()) -> Effects<(), ()> {
+            Effects::none()
+        }
+        fn view(&self) -> Node<()> {
+            text("other")
+        }
+    }
+    let button: Node<()> = component([], FuiButton::<()>::default(), |xmsg| xmsg);
+    let other: Node<()> = component([], Other, |xmsg| xmsg);
+    assert_eq!(
+        button,
+        component([], FuiButton::<()>::default(), |xmsg| xmsg)
+    );
+    assert_ne!(button, other);
+}
+
+#[wasm_bindgen_test]
+async fn component_keeps_its_state_and_maps_its_msg() {
+    console_error_panic_hook::set_once();
+    let _program = Program::mount_to_body(Parent::default());
+
+    let button: web_sys::HtmlElement = document()
+        .query_selector("#parent .FuiButton button")
+        .unwrap()
+        .expect("must mount the component")
+        .unchecked_into();
+    button.click();
+    delay(50).await;
+    button.click();
+    delay(50).await;
+
+    let button_text = document()
+        .query_selector("#parent .FuiButton button")
+        .unwrap()
+        .unwrap()
+        .text_content();
+    assert_eq!(button_text.as_deref(), Some("Ok 2"));
+    let clicked = document()
+        .query_selector("#parent .clicked")
+        .unwrap()
+        .unwrap()
+        .text_content();
+    assert_eq!(clicked.as_deref(), Some("2"));
+}
@@ -0,0 +1,108 @@
+#![deny(warnings)]
+use sauron::{
+    html::{attributes::*, *},
+    render::{placeholder, AsyncHtmlWriter, HtmlWriter},
+    *,
+};
+use std::io;
+
+/// records each write as a chunk
+#[derive(Default)]
+struct Recorder {
+    chunks: Vec<String>,
+    flushed: usize,
+}
+
+impl io::Write for Recorder {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.chunks.push(String::from_utf8(buf.to_vec()).unwrap());
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.flushed += 1;
+        Ok(())
+    }
+}
+
+fn rows(n: usize) -> Node<()> {
+    table([], (0..n).map(|i| tr([], [td([class("cell")], [text(i)])])))
+}
+
+#[test]
+fn render_to_writer_is_the_same_as_render_to_string() {
+    let view = rows(10);
+    let mut buffer = vec![];
+    view.render_to_writer(&mut buffer).unwrap();
+    assert_eq!(String::from_utf8(buffer).unwrap(), view.render_to_string());
+}
+
+#[test]
+fn html_writer_writes_in_chunks() {
+    let view = rows(100);
+    let mut writer = HtmlWriter::new(Recorder::default()).with_chunk_size(256);
+    writer.write_node(&view).unwrap();
+    let recorder = writer.into_inner().unwrap();
+
+    assert!(recorder.chunks.len() > 1);
+    assert!(recorder.chunks[..recorder.chunks.len() - 1]
+        .iter()
+        .all(|chunk| chunk.len() >= 256));
+    assert_eq!(recorder.chunks.concat(), view.render_to_string());
+    assert_eq!(recorder.flushed, 1);
+}
+
+#[test]
+fn nothing_is_written_until_the_chunk_is_full_or_flushed() {
+    let mut writer = HtmlWriter::new(Recorder::default());
+    writer.write_str("<!doctype html>").unwrap();
+    writer.write_node(&rows(1)).unwrap();
+    let recorder = writer.into_inner().unwrap();
+    assert_eq!(
+        recorder.chunks,
+        ["<!doctype html><table><tr><td class=\"cell\">0</td></tr></table>"]
+    );
+}
+
+#[test]
+fn placeholder_renders_the_fallback_in_between_markers() {
+    let view: Node<()> = div([], [placeholder("rows", text("Loading..."))]);
+    assert_eq!(
+        view.render_to_string(),
+        "<div><!--placeholder:rows-->Loading...<!--/placeholder:rows--></div>"
+    );
+}
+
+#[test]
+fn fill_writes_the_fill_script_once() {
+    let mut writer = HtmlWriter::new(vec![]);
+    writer.fill("a", &text::<_, ()>("first")).unwrap();
+    writer.fill("b", &text::<_, ()>("second")).unwrap();
+    let html = String::from_utf8(writer.into_inner().unwrap()).unwrap();
+
+    assert_eq!(html.matches("function sauronFill").count(), 1);
+    assert!(html.ends_with(
+        r#"<template id="sauron-fill-a">first</template><script>sauronFill("a")</script><template id="sauron-fill-b">second</template><script>sauronFill("b")</script>"#
+    ));
+}
+
+#[test]
+#[should_panic(expected = "placeholder id must only contain")]
+fn placeholder_id_is_validated() {
+    let _: Node<()> = placeholder("</script>", text("Loading..."));
+}
+
+#[test]
+fn async_html_writer_writes_in_chunks() {
+    let view = rows(100);
+    let html = futures::executor::block_on(async {
+        let mut writer = AsyncHtmlWriter::new(vec![]).with_chunk_size(256);
+        writer.write_str("<!doctype html>").await.unwrap();
+        writer.write_node(&view).await.unwrap();
+        writer.fill("rows", &rows(1)).await.unwrap();
+        writer.into_inner().await.unwrap()
+    });
+    let html = String::from_utf8(html).unwrap();
+    assert!(html.starts_with(&format!("<!doctype html>{}", view.render_to_string())));
+    assert!(html.ends_with(r#"<script>sauronFill("rows")</script>"#));
+}
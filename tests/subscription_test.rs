@@ -0,0 +1,162 @@
+#![deny(warnings)]
+use sauron::{
+    dom::{delay, Sub},
+    html::*,
+    *,
+};
+use std::{
+    cell::{Cell, RefCell},
+    rc::Rc,
+};
+use wasm_bindgen_test::*;
+
+wasm_bindgen_test_configure!(run_in_browser);
+
+enum Msg {
+    Tick,
+    Stop,
+    Item(u32),
+}
+
+struct Clock {
+    running: bool,
+    ticks: Rc<Cell<u32>>,
+    items: Rc<Cell<u32>>,
+}
+
+impl Application<Msg> for Clock {
+    fn update(&mut self, msg: Msg) -> Cmd<Self, Msg> {
+        match msg {
+            Msg::Tick => self.ticks.set(self.ticks.get() + 1),
+            Msg::Stop => self.running = false,
+            Msg::Item(item) => self.items.set(self.items.get() + item),
+        }
+        Cmd::none()
+    }
+
+    fn view(&self) -> Node<Msg> {
+        text("clock")
+    }
+
+    fn subscriptions(&self) -> Sub<Msg> {
+        if self.running {
+            Sub::batch([
+                Sub::interval(10, || Msg::Tick),
+                Sub::stream("items", || futures::stream::iter([1, 2, 3]), Msg::Item),
+                // a subscription to the same source is not dropped
+                Sub::stream("items", || futures::stream::iter([10]), Msg::Item),
+            ])
+        } else {
+            Sub::none()
+        }
+    }
+}
+
+#[test]
+fn subscriptions_are_identified_by_their_source() {
+    let sub: Sub<Msg> = Sub::batch([
+        Sub::interval(10, || Msg::Tick),
+        Sub::on_resize(|_, _| Msg::Tick),
+        Sub::media_query("(max-width: 600px)", |_| Msg::Tick),
+        Sub::stream("items", || futures::stream::iter([1]), Msg::Item),
+        Sub::none(),
+    ]);
+    assert_eq!(
+        sub.keys().collect::<Vec<_>>(),
+        [
+            "interval:10",
+            "resize",
+            "media:(max-width: 600px)",
+            "stream:items"
+        ]
+    );
+    let mapped: Sub<Option<Msg>> = sub.map_msg(Some);
+    assert_eq!(mapped.keys().count(), 4);
+    assert!(Sub::<Msg>::none().is_empty());
+}
+
+#[wasm_bindgen_test]
+async fn subscriptions_are_stopped_when_removed() {
+    console_error_panic_hook::set_once();
+    let ticks = Rc::new(Cell::new(0));
+    let items = Rc::new(Cell::new(0));
+    let mut program = Program::mount_to_body(Clock {
+        running: true,
+        ticks: Rc::clone(&ticks),
+        items: Rc::clone(&items),
+    });
+    delay(100).await;
+    assert!(ticks.get() > 0);
+    assert_eq!(items.get(), 16);
+
+    program.dispatch(Msg::Stop);
+    delay(50).await;
+    let stopped_at = ticks.get();
+    delay(100).await;
+    assert_eq!(ticks.get(), stopped_at);
+}
+
+enum Feed {
+    Count(u32),
+    Word(String),
+    Switch,
+}
+
+/// an app which subscribes to the feed of the same key, but with a different item type after
+/// it is switched
+#[derive(Default)]
+struct Reader {
+    switched: bool,
+    counts: Rc<Cell<u32>>,
+    words: Rc<RefCell<Vec<String>>>,
+}
+
+/// emits the item every 10ms
+fn every_10ms<T: Clone + 'static>(item: T) -> impl futures::Stream<Item = T> {
+    futures::stream::unfold(item, |item| async move {
+        delay(10).await;
+        Some((item.clone(), item))
+    })
+}
+
+impl Application<Feed> for Reader {
+    fn update(&mut self, msg: Feed) -> Cmd<Self, Feed> {
+        match msg {
+            Feed::Count(count) => self.counts.set(self.counts.get() + count),
+            Feed::Word(word) => self.words.borrow_mut().push(word),
+            Feed::Switch => self.switched = true,
+        }
+        Cmd::none()
+    }
+
+    fn view(&self) -> Node<Feed> {
+        text("reader")
+    }
+
+    fn subscriptions(&self) -> Sub<Feed> {
+        if self.switched {
+            Sub::stream("feed", || every_10ms("word".to_string()), Feed::Word)
+        } else {
+            Sub::stream("feed", || every_10ms(1), Feed::Count)
+        }
+    }
+}
+
+#[wasm_bindgen_test]
+async fn source_of_a_different_payload_is_restarted() {
+    console_error_panic_hook::set_once();
+    let reader = Reader::default();
+    let counts = Rc::clone(&reader.counts);
+    let words = Rc::clone(&reader.words);
+    let mut program = Program::mount_to_body(reader);
+    delay(50).await;
+    assert!(counts.get() > 0);
+
+    program.dispatch(Feed::Switch);
+    delay(50).await;
+    let counted = counts.get();
+    delay(50).await;
+    assert_eq!(counts.get(), counted);
+    assert!(!words.borrow().is_empty());
+    program.unmount();
+}
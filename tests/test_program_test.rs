@@ -0,0 +1,228 @@
+#![deny(warnings)]
+use sauron::{
+    dom::{Http, HttpError, Method, MockTransport, Response, SyntheticEvent, TestProgram},
+    html::{attributes::*, events::*, *},
+    *,
+};
+
+#[derive(Debug, Clone, PartialEq)]
+enum Msg {
+    Add,
+    Edit(String),
+    Toggle(usize, bool),
+    Select(usize),
+    Fetch,
+    Fetched(Result<String, HttpError>),
+    Scroll,
+    Pressed(String, bool),
+    Pointed(i32, i32),
+}
+
+#[derive(Default)]
+struct Todo {
+    draft: String,
+    items: Vec<(String, bool)>,
+    selected: Option<usize>,
+    pressed: Vec<(String, bool)>,
+    pointed: Option<(i32, i32)>,
+    transport: MockTransport,
+}
+
+impl Application<Msg> for Todo {
+    fn update(&mut self, msg: Msg) -> Cmd<Self, Msg> {
+        match msg {
+            Msg::Add => self.items.push((std::mem::take(&mut self.draft), false)),
+            Msg::Edit(draft) => self.draft = draft,
+            Msg::Toggle(idx, done) => self.items[idx].1 = done,
+            Msg::Select(idx) => self.selected = Some(idx),
+            Msg::Fetch => {
+                return Cmd::from(Http::get("/todo").task_with(
+                    &self.transport,
+                    Response::text,
+                    Msg::Fetched,
+                ))
+            }
+            Msg::Fetched(Ok(item)) => self.items.push((item, false)),
+            Msg::Fetched(Err(_)) => (),
+            Msg::Scroll => return Cmd::new(|mut program| program.dispatch(Msg::Add)),
+            Msg::Pressed(key, ctrl) => self.pressed.push((key, ctrl)),
+            Msg::Pointed(x, y) => self.pointed = Some((x, y)),
+        }
+        Cmd::none()
+    }
+
+    fn view(&self) -> Node<Msg> {
+        div(
+            [class("todo")],
+            [
+                input(
+                    [
+                        r#type("text"),
+                        value(&self.draft),
+                        on_synthetic("input", |e| Msg::Edit(e.value.unwrap_or_default())),
+                        on_synthetic("keydown", |e| {
+                            Msg::Pressed(e.key.unwrap_or_default(), e.ctrl_key)
+                        }),
+                    ],
+                    [],
+                ),
+                button(
+                    [
+                        class("add"),
+                        on_synthetic("click", |_| Msg::Add),
+                        on_synthetic("mousedown", |e| Msg::Pointed(e.client_x, e.client_y)),
+                    ],
+                    [text("Add")],
+                ),
+                // the listener of the web event is not called without the browser
+                button([class("web"), on_click(|_| Msg::Add)], [text("Web")]),
+                ul(
+                    [],
+                    self.items.iter().enumerate().map(|(idx, (item, done))| {
+                        li(
+                            [
+                                classes_flag([("done", *done)]),
+                                on_synthetic("click", move |_| Msg::Select(idx)),
+                            ],
+                            [
+                                input(
+                                    [
+                                        r#type("checkbox"),
+                                        checked(*done),
+                                        on_synthetic("input", move |e| {
+                                            Msg::Toggle(idx, e.checked.unwrap_or_default())
+                                        }),
+                                    ],
+                                    [],
+                                ),
+                                span([], [text(item)]),
+                            ],
+                        )
+                    }),
+                ),
+            ],
+        )
+    }
+}
+
+fn with_items(items: &[&str]) -> TestProgram<Todo, Msg> {
+    let mut program = TestProgram::new(Todo::default());
+    for item in items {
+        program.input("input[type=text]", item);
+        program.click("button.add");
+    }
+    program
+}
+
+#[test]
+fn events_update_the_view() {
+    let program = with_items(&["milk"]);
+    assert_eq!(
+        program.dispatched_msgs(),
+        [Msg::Edit("milk".to_string()), Msg::Add]
+    );
+    assert!(program.html().contains("<span>milk</span>"));
+    assert_eq!(program.app().draft, "");
+}
+
+#[test]
+fn checked_event_and_class_selector() {
+    let mut program = with_items(&["milk", "eggs"]);
+    assert!(program.find("li.done").is_none());
+
+    program.check("ul li input[type=checkbox]", true);
+    assert_eq!(program.find_all("li.done").len(), 1);
+    assert_eq!(
+        program.text_content("li.done span").as_deref(),
+        Some("milk")
+    );
+    assert_eq!(program.app().items[0], ("milk".to_string(), true));
+}
+
+#[test]
+fn events_bubble_to_the_ancestors() {
+    let mut program = with_items(&["milk"]);
+    program.click("li span");
+    assert_eq!(program.dispatched_msgs().last(), Some(&Msg::Select(0)));
+    assert_eq!(program.app().selected, Some(0));
+}
+
+#[test]
+fn synthetic_listeners_read_the_synthetic_event() {
+    let mut program = TestProgram::new(Todo::default());
+    program.fire_event(
+        "input[type=text]",
+        "keydown",
+        SyntheticEvent {
+            ctrl_key: true,
+            ..SyntheticEvent::with_key("Enter")
+        },
+    );
+    program.fire_event("input[type=text]", "keydown", SyntheticEvent::default());
+    assert_eq!(
+        program.app().pressed,
+        [("Enter".to_string(), true), (String::new(), false)]
+    );
+
+    program.fire_event(
+        "button.add",
+        "mousedown",
+        SyntheticEvent::with_client_position(10, 20),
+    );
+    assert_eq!(program.app().pointed, Some((10, 20)));
+}
+
+#[test]
+fn listeners_of_the_web_events_are_skipped() {
+    let mut program = TestProgram::new(Todo::default());
+    program.click("button.web");
+    assert!(program.dispatched_msgs().is_empty());
+    assert!(program.app().items.is_empty());
+}
+
+#[test]
+fn find_by_text_returns_the_innermost_element() {
+    let program = with_items(&["milk", "eggs"]);
+    let span = program.find_by_text("eggs").expect("must find the text");
+    assert_eq!(span.tag(), Some(&"span"));
+    assert!(program.find_by_text("bread").is_none());
+}
+
+#[test]
+fn patches_are_the_changes_of_the_last_update() {
+    let mut program = with_items(&[]);
+    assert!(program.patches().is_empty());
+    program.click("button.add");
+    assert_eq!(program.patches().len(), 1);
+}
+
+#[test]
+fn tasks_are_run_to_completion() {
+    let transport = MockTransport::new();
+    transport.respond(Method::Get, "/todo", Response::new(200, "bread"));
+    let mut program = TestProgram::new(Todo {
+        transport: transport.clone(),
+        ..Default::default()
+    });
+    program.dispatch(Msg::Fetch);
+    assert_eq!(
+        program.dispatched_msgs(),
+        [Msg::Fetch, Msg::Fetched(Ok("bread".to_string()))]
+    );
+    assert_eq!(program.text_content("li").as_deref(), Some("bread"));
+    assert_eq!(transport.requests().len(), 1);
+}
+
+#[test]
+fn program_commands_are_skipped() {
+    let mut program = TestProgram::new(Todo::default());
+    program.dispatch(Msg::Scroll);
+    assert_eq!(program.skipped_commands(), 1);
+    assert_eq!(program.dispatched_msgs(), [Msg::Scroll]);
+}
+
+#[test]
+#[should_panic(expected = "no element matches the selector")]
+fn firing_to_a_missing_element_panics() {
+    TestProgram::new(Todo::default()).click("button.remove");
+}
@@ -0,0 +1,117 @@
+#![deny(warnings)]
+#![cfg(feature = "with-time-travel")]
+use sauron::{
+    dom::{util::document, RecordedMsg, Session, TestProgram},
+    html::{attributes::*, *},
+    *,
+};
+use serde::{Deserialize, Serialize};
+use wasm_bindgen_test::*;
+
+wasm_bindgen_test_configure!(run_in_browser);
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+enum Msg {
+    Increment,
+    Add(i32),
+}
+
+#[derive(Debug, Clone, Default)]
+struct Counter {
+    count: i32,
+}
+
+impl Application<Msg> for Counter {
+    fn update(&mut self, msg: Msg) -> Cmd<Self, Msg> {
+        match msg {
+            Msg::Increment => self.count += 1,
+            Msg::Add(n) => self.count += n,
+        }
+        Cmd::none()
+    }
+
+    fn view(&self) -> Node<Msg> {
+        span([id("time-travel-count")], [text(self.count)])
+    }
+}
+
+fn recorded(msg: Msg) -> RecordedMsg<Msg> {
+    RecordedMsg {
+        msg,
+        timestamp: 0.0,
+        measurements: None,
+    }
+}
+
+#[test]
+fn session_round_trips_through_json() {
+    let session = Session {
+        msgs: vec![recorded(Msg::Increment), recorded(Msg::Add(5))],
+    };
+    let json = session.to_json().expect("must serialize");
+    assert_eq!(
+        Session::<Msg>::from_json(&json).expect("must parse"),
+        session
+    );
+}
+
+#[test]
+fn replay_updates_the_app_with_the_recorded_msgs() {
+    let json = r#"{"msgs":[
+        {"msg":"Increment","timestamp":1.0,"measurements":null},
+        {"msg":{"Add":10},"timestamp":2.0,"measurements":null}
+    ]}"#;
+    let session = Session::from_json(json).expect("must parse");
+    let program = TestProgram::replay(Counter::default(), &session);
+    assert_eq!(program.app().count, 11);
+    assert_eq!(program.dispatched_msgs(), [Msg::Increment, Msg::Add(10)]);
+    assert_eq!(
+        program.text_content("#time-travel-count").as_deref(),
+        Some("11")
+    );
+}
+
+#[wasm_bindgen_test]
+async fn steps_back_and_forth_through_the_recorded_msgs() {
+    console_error_panic_hook::set_once();
+    let mut program = Program::mount_to_body(Counter::default());
+    program.enable_time_travel();
+    program.dispatch_multiple([Msg::Increment, Msg::Add(2)]);
+    dom::delay(50).await;
+    let count = || {
+        document()
+            .get_element_by_id("time-travel-count")
+            .expect("must be mounted")
+            .text_content()
+    };
+    assert_eq!(count().as_deref(), Some("3"));
+    assert_eq!(program.time_travel_position(), 2);
+
+    program.step_back().expect("must step back");
+    assert_eq!(program.app().count, 1);
+    assert_eq!(count().as_deref(), Some("1"));
+
+    program.travel_to(0).expect("must travel");
+    assert_eq!(count().as_deref(), Some("0"));
+    program.step_forward().expect("must step forward");
+    program.step_forward().expect("must step forward");
+    assert_eq!(count().as_deref(), Some("3"));
+
+    let session = program.session();
+    assert_eq!(session.msgs.len(), 2);
+    assert!(session
+        .msgs
+        .iter()
+        .all(|recorded| recorded.measurements.is_some()));
+
+    // dispatching a msg in the past discards the msgs after it
+    program.travel_to(1).expect("must travel");
+    program.dispatch(Msg::Add(10));
+    dom::delay(50).await;
+    assert_eq!(count().as_deref(), Some("11"));
+
+    let json = program.export_session().expect("must export");
+    let replayed = TestProgram::replay(Counter::default(), &Session::from_json(&json).unwrap());
+    assert_eq!(replayed.dispatched_msgs(), [Msg::Increment, Msg::Add(10)]);
+    assert_eq!(replayed.app().count, 11);
+}
@@ -0,0 +1,178 @@
+#![deny(warnings)]
+use sauron::{
+    dom::{delay, util::document, Sub},
+    html::{attributes::*, events::*, *},
+    *,
+};
+use std::{cell::Cell, rc::Rc};
+use wasm_bindgen::JsCast;
+use wasm_bindgen_test::*;
+
+wasm_bindgen_test_configure!(run_in_browser);
+
+enum Msg {
+    Click,
+    Tick,
+}
+
+/// the shared clicks, ticks and whether the app is unmounted
+type Counters = (Rc<Cell<u32>>, Rc<Cell<u32>>, Rc<Cell<bool>>);
+
+struct App {
+    clicks: Rc<Cell<u32>>,
+    ticks: Rc<Cell<u32>>,
+    unmounted: Rc<Cell<bool>>,
+}
+
+impl App {
+    fn new() -> Self {
+        Self {
+            clicks: Rc::new(Cell::new(0)),
+            ticks: Rc::new(Cell::new(0)),
+            unmounted: Rc::new(Cell::new(false)),
+        }
+    }
+
+    fn counters(&self) -> Counters {
+        (
+            Rc::clone(&self.clicks),
+            Rc::clone(&self.ticks),
+            Rc::clone(&self.unmounted),
+        )
+    }
+}
+
+impl Application<Msg> for App {
+    fn update(&mut self, msg: Msg) -> Cmd<Self, Msg> {
+        match msg {
+            Msg::Click => self.clicks.set(self.clicks.get() + 1),
+            Msg::Tick => self.ticks.set(self.ticks.get() + 1),
+        }
+        Cmd::none()
+    }
+
+    fn view(&self) -> Node<Msg> {
+        let unmounted = Rc::clone(&self.unmounted);
+        div(
+            [
+                class("unmount-app"),
+                on_unmount(move |_| {
+                    unmounted.set(true);
+                    Msg::Tick
+                }),
+            ],
+            [button([on_click(|_| Msg::Click)], [text("click")])],
+        )
+    }
+
+    fn subscriptions(&self) -> Sub<Msg> {
+        Sub::interval(10, || Msg::Tick)
+    }
+
+    fn stylesheet() -> Vec<String> {
+        vec![jss! {
+            ".unmount-app": {
+                display: "block",
+            }
+        }]
+    }
+}
+
+fn create_mount(id: &str) -> web_sys::Element {
+    let mount = document().create_element("div").unwrap();
+    mount.set_id(id);
+    document().body().unwrap().append_child(&mount).unwrap();
+    mount
+}
+
+#[wasm_bindgen_test]
+async fn unmount_removes_the_view_and_releases_the_listeners() {
+    console_error_panic_hook::set_once();
+    let mount = create_mount("unmount-append");
+    let app = App::new();
+    let (clicks, ticks, unmounted) = app.counters();
+    let styles_before = document()
+        .query_selector_all("head style")
+        .unwrap()
+        .length();
+    let mut program = Program::append_to_mount(app, &mount);
+    assert!(
+        document()
+            .query_selector_all("head style")
+            .unwrap()
+            .length()
+            > styles_before
+    );
+
+    let button: web_sys::HtmlElement = mount
+        .query_selector("button")
+        .unwrap()
+        .expect("must be mounted")
+        .unchecked_into();
+    button.click();
+    delay(50).await;
+    assert_eq!(clicks.get(), 1);
+    assert!(ticks.get() > 0);
+
+    program.unmount();
+    assert!(program.is_unmounted());
+    assert!(unmounted.get());
+    assert_eq!(mount.child_element_count(), 0);
+    assert_eq!(
+        document()
+            .query_selector_all("head style")
+            .unwrap()
+            .length(),
+        styles_before
+    );
+
+    let stopped_at = ticks.get();
+    button.click();
+    program.dispatch(Msg::Click);
+    delay(50).await;
+    assert_eq!(clicks.get(), 1);
+    assert_eq!(ticks.get(), stopped_at);
+    assert!(program.node_closures.borrow().is_empty());
+}
+
+#[wasm_bindgen_test]
+async fn unmount_restores_the_replaced_node() {
+    console_error_panic_hook::set_once();
+    let mount = create_mount("unmount-replace");
+    let mut program = Program::replace_mount(App::new(), &mount);
+    assert!(document().get_element_by_id("unmount-replace").is_none());
+
+    program.unmount();
+    let restored = document()
+        .get_element_by_id("unmount-replace")
+        .expect("must restore the mount node");
+    assert_eq!(restored, mount);
+    assert_eq!(
+        document()
+            .query_selector_all(".unmount-app")
+            .unwrap()
+            .length(),
+        0
+    );
+}
+
+#[wasm_bindgen_test]
+async fn unmount_leaves_the_hydrated_markup() {
+    console_error_panic_hook::set_once();
+    let mount = create_mount("unmount-hydrate");
+    mount.set_inner_html(&App::new().view().render_to_string());
+    let app = App::new();
+    let (clicks, _, _) = app.counters();
+    let root = mount.first_child().expect("must have the rendered view");
+    let mut program = Program::hydrate_mount(app, &root);
+
+    program.unmount();
+    let button: web_sys::HtmlElement = mount
+        .query_selector("button")
+        .unwrap()
+        .expect("must leave the markup")
+        .unchecked_into();
+    button.click();
+    delay(50).await;
+    assert_eq!(clicks.get(), 0);
+}
@@ -0,0 +1,197 @@
+#![deny(warnings)]
+use sauron::{
+    dom::{self, util::document, Event, SyntheticEvent},
+    html::{attributes::*, events::on},
+    vdom::{diff, EventId, WireElement, WireEvents, WireNode, WirePatch, WireValue},
+    *,
+};
+use wasm_bindgen_test::*;
+
+wasm_bindgen_test_configure!(run_in_browser);
+
+#[derive(Debug, Clone, PartialEq)]
+enum Msg {
+    Remove(&'static str),
+    Event(EventId),
+}
+
+fn todo_list(items: &[&'static str], title: &'static str) -> Node<Msg> {
+    div(
+        [id("todos"), class(title)],
+        [
+            h1([], [text(title)]),
+            ul(
+                [],
+                items.iter().map(|&item| {
+                    li(
+                        [key(item)],
+                        [
+                            text(item),
+                            button([on("click", move |_| Msg::Remove(item))], [text("x")]),
+                        ],
+                    )
+                }),
+            ),
+        ],
+    )
+}
+
+/// the node decoded with the listeners which return the id of the event
+fn decoded(node: &WireNode) -> Node<Msg> {
+    node.decode(|id, _| Msg::Event(id)).expect("must decode")
+}
+
+#[test]
+fn patches_survive_serialization() {
+    let old = todo_list(&["a", "b", "c"], "todo");
+    let new = todo_list(&["c", "a", "d"], "done");
+    let mut events = WireEvents::new();
+    let patches = WirePatch::encode_all(&diff(&old, &new), &mut events).expect("must encode");
+    assert!(!patches.is_empty());
+
+    let json = serde_json::to_string(&patches).expect("must serialize");
+    let deserialized: Vec<WirePatch> = serde_json::from_str(&json).expect("must deserialize");
+    assert_eq!(deserialized, patches);
+}
+
+#[test]
+fn applied_patches_turn_the_old_node_into_the_new_node() {
+    let views = [
+        todo_list(&["a", "b", "c"], "todo"),
+        todo_list(&["c", "a", "d"], "done"),
+        todo_list(&[], "empty"),
+        todo_list(&["e", "f"], "todo"),
+        div([], [text("gone")]),
+    ];
+    let mut events = WireEvents::new();
+    let mut mirror = WireNode::encode(&views[0], &mut events).expect("must encode");
+    for pair in views.windows(2) {
+        let patches =
+            WirePatch::encode_all(&diff(&pair[0], &pair[1]), &mut events).expect("must encode");
+        let json = serde_json::to_string(&patches).expect("must serialize");
+        let patches: Vec<WirePatch> = serde_json::from_str(&json).expect("must deserialize");
+        mirror.apply_patches(&patches).expect("must apply");
+
+        let expected = WireNode::encode(&pair[1], &mut WireEvents::new()).expect("must encode");
+        assert_eq!(decoded(&mirror), decoded(&expected));
+    }
+}
+
+#[test]
+fn added_attributes_of_the_same_name_are_all_kept() {
+    let old: Node<Msg> = div([class("todo")], []);
+    let new: Node<Msg> = div([class("todo"), class("done")], []);
+    let mut events = WireEvents::new();
+    let patches = WirePatch::encode_all(&diff(&old, &new), &mut events).expect("must encode");
+    let mut mirror = WireNode::encode(&old, &mut events).expect("must encode");
+    mirror.apply_patches(&patches).expect("must apply");
+
+    let expected = WireNode::encode(&new, &mut WireEvents::new()).expect("must encode");
+    assert_eq!(decoded(&mirror), decoded(&expected));
+}
+
+#[test]
+fn listeners_are_called_by_their_event_id() {
+    let view = todo_list(&["a", "b"], "todo");
+    let mut events = WireEvents::new();
+    let node = WireNode::encode(&view, &mut events).expect("must encode");
+    assert_eq!(events.len(), 2);
+
+    let mut ids = vec![];
+    collect_ids(&node, &mut ids);
+    let event = Event::Synthetic(SyntheticEvent::default());
+    assert_eq!(events.emit(ids[1], event.clone()), Some(Msg::Remove("b")));
+
+    // the listeners of the removed nodes are dropped
+    let patches = WirePatch::encode_all(&diff(&view, &todo_list(&["a"], "todo")), &mut events)
+        .expect("must encode");
+    let mut mirror = node;
+    mirror.apply_patches(&patches).expect("must apply");
+    events.retain_in(&mirror);
+    assert_eq!(events.len(), 1);
+    assert_eq!(events.emit(ids[1], event), None);
+}
+
+#[test]
+fn patches_of_other_nodes_are_rejected() {
+    let old = todo_list(&["a"], "todo");
+    let patches = WirePatch::encode_all(
+        &diff(&old, &todo_list(&["a"], "done")),
+        &mut WireEvents::new(),
+    )
+    .expect("must encode");
+    let mut other = WireNode::encode(&span([], [text("other")]), &mut WireEvents::<Msg>::new())
+        .expect("must encode");
+    assert!(other.apply_patches(&patches).is_err());
+}
+
+#[test]
+fn unknown_names_are_decoded_up_to_a_limit() {
+    let element = |tag: String| {
+        WireNode::Element(WireElement {
+            namespace: None,
+            tag,
+            attrs: vec![],
+            children: vec![],
+            self_closing: false,
+        })
+    };
+    // the html names are found in the lookup, they are not counted
+    for _ in 0..2000 {
+        assert!(decoded(&element("div".to_string())).is_element());
+    }
+    for i in 0..1024 {
+        assert!(decoded(&element(format!("custom-{i}"))).is_element());
+    }
+    // the names which are already decoded are reused
+    assert!(decoded(&element("custom-0".to_string())).is_element());
+    let error = element("custom-1024".to_string())
+        .decode(|id, _| Msg::Event(id))
+        .expect_err("must reject the name");
+    assert_eq!(error, Error::WireName("custom-1024".to_string()));
+}
+
+fn collect_ids(node: &WireNode, ids: &mut Vec<EventId>) {
+    if let WireNode::Element(element) = node {
+        for value in element.attrs.iter().flat_map(|att| &att.value) {
+            if let WireValue::EventListener { id, .. } = value {
+                ids.push(*id);
+            }
+        }
+    }
+    for child in node.children() {
+        collect_ids(child, ids);
+    }
+}
+
+#[wasm_bindgen_test]
+async fn wire_patches_are_applied_into_the_dom() {
+    console_error_panic_hook::set_once();
+    let old = todo_list(&["a", "b"], "todo");
+    let mut program = Program::mount_to_body(WireView(old.clone()));
+    let mut events = WireEvents::new();
+    let patches = WirePatch::encode_all(&diff(&old, &todo_list(&["b", "c"], "done")), &mut events)
+        .expect("must encode");
+    program
+        .apply_wire_patches(&patches, |id, _| Msg::Event(id))
+        .expect("must apply");
+    dom::delay(0).await;
+    let todos = document()
+        .get_element_by_id("todos")
+        .expect("must have the todos");
+    assert_eq!(todos.class_name(), "done");
+    assert_eq!(todos.text_content().as_deref(), Some("donebxcx"));
+}
+
+/// an app which shows a fixed view
+struct WireView(Node<Msg>);
+
+impl Application<Msg> for WireView {
+    fn update(&mut self, _msg: Msg) -> Cmd<Self, Msg> {
+        Cmd::none()
+    }
+
+    fn view(&self) -> Node<Msg> {
+        self.0.clone()
+    }
+}